@@ -0,0 +1,90 @@
+//! Benchmarks of the graph operations and the main algorithms, on
+//! G(n, m) graphs with an average outdegree of 8.
+//!
+//! Run with `cargo bench`; criterion keeps the previous results under
+//! `target/criterion` and reports the change against them.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use graph::generate::gnm;
+use graph::traversal::{bfs, connected_components, dijkstra};
+use graph::GraphMap;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const SIZES: [u32; 3] = [100, 1_000, 10_000];
+
+/// The same graph on every run, so the results can be compared.
+fn generated(vertices: u32) -> GraphMap<u32, u32> {
+    let mut rng = StdRng::seed_from_u64(vertices as u64);
+    gnm(vertices, vertices * 8, &mut rng, |rng| rng.gen_range(1..100)).unwrap()
+}
+
+fn mutation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mutation");
+    for &n in SIZES.iter() {
+        group.bench_with_input(BenchmarkId::new("add_vertex", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut graph = GraphMap::<u32, u32>::new();
+                for v in 0..n {
+                    graph.add_vertex(v);
+                }
+                graph
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("add_edge", n), &n, |b, &n| {
+            let graph = generated(n);
+            let edges: Vec<_> = graph.edges().map(|((&from, &to), &weight)| ((from, to), weight)).collect();
+            b.iter(|| GraphMap::from_edges(edges.iter().copied()))
+        });
+
+        let graph = generated(n);
+        group.bench_with_input(BenchmarkId::new("remove_vertex", n), &graph, |b, graph| {
+            b.iter_batched(
+                || graph.clone(),
+                |mut graph| {
+                    for v in 0..n / 10 {
+                        graph.remove_vertex(v * 10);
+                    }
+                    graph
+                },
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("remove_edge", n), &graph, |b, graph| {
+            let edges: Vec<_> = graph.edges().map(|((&from, &to), _)| (from, to)).step_by(10).collect();
+            b.iter_batched(
+                || graph.clone(),
+                |mut graph| {
+                    for &edge in &edges {
+                        graph.remove_edge(edge);
+                    }
+                    graph
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn traversal(c: &mut Criterion) {
+    let mut group = c.benchmark_group("traversal");
+    for &n in SIZES.iter() {
+        let graph = generated(n);
+        let start = graph.id_of(&0).unwrap();
+        let goal = graph.id_of(&(n - 1)).unwrap();
+        group.bench_with_input(BenchmarkId::new("bfs", n), &graph, |b, graph| {
+            b.iter(|| bfs(graph, black_box(start)))
+        });
+        group.bench_with_input(BenchmarkId::new("dijkstra", n), &graph, |b, graph| {
+            b.iter(|| dijkstra(graph, black_box(start), black_box(goal)))
+        });
+        group.bench_with_input(BenchmarkId::new("connected_components", n), &graph, |b, graph| {
+            b.iter(|| connected_components(graph))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, mutation, traversal);
+criterion_main!(benches);
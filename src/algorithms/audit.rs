@@ -0,0 +1,94 @@
+
+use crate::{Graph, GraphMap, VertexId};
+use std::fmt;
+use std::hash::Hash;
+
+/// Report of the suspicious parts of a graph, see [`Graph::audit`].
+/// `T` identifies the vertices, [`VertexId`] or the vertex data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Audit<T> {
+    /// Vertices with an edge to themselves.
+    pub self_loops: Vec<T>,
+    /// Vertices without any edge.
+    pub isolated: Vec<T>,
+    /// Pairs of vertices joined in both directions, each pair once.
+    /// These are duplicates if the data was meant to be undirected.
+    pub antiparallel: Vec<(T, T)>,
+}
+
+impl<T> Audit<T> {
+    /// True if nothing was found.
+    pub fn is_clean(&self) -> bool {
+        self.self_loops.is_empty() && self.isolated.is_empty() && self.antiparallel.is_empty()
+    }
+
+    fn map<U, F: Fn(T) -> U>(self, f: F) -> Audit<U> {
+        Audit {
+            self_loops: self.self_loops.into_iter().map(&f).collect(),
+            isolated: self.isolated.into_iter().map(&f).collect(),
+            antiparallel: self.antiparallel.into_iter().map(|(a, b)| (f(a), f(b))).collect(),
+        }
+    }
+}
+
+impl<T> fmt::Display for Audit<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "self loops: {}", self.self_loops.len())?;
+        writeln!(f, "isolated vertices: {}", self.isolated.len())?;
+        writeln!(f, "antiparallel pairs: {}", self.antiparallel.len())
+    }
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Looks for self loops, isolated vertices and antiparallel edges,
+    /// which usually come from messy imported data. The lists are sorted.
+    /// Time complexity: O(V log V + E log E)
+    pub fn audit(&self) -> Audit<VertexId> {
+        let mut self_loops = Vec::new();
+        let mut antiparallel = Vec::new();
+        for (&(from, to), _) in self.edges() {
+            if from == to {
+                self_loops.push(from);
+            } else if from < to && self.edges.contains_key(&(to, from)) {
+                antiparallel.push((from, to));
+            }
+        }
+        let mut isolated: Vec<VertexId> = self.vertices()
+            .map(|(id, _)| id)
+            .filter(|&id| self.outbound[&id].is_empty() && self.inbound[&id].is_empty())
+            .collect();
+
+        self_loops.sort_unstable();
+        isolated.sort_unstable();
+        antiparallel.sort_unstable();
+        Audit {
+            self_loops,
+            isolated,
+            antiparallel,
+        }
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::audit`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut graph = GraphMap::<&str, u32>::new();
+    /// graph.add_edge(("a", "b"), 1);
+    /// graph.add_edge(("b", "a"), 1);
+    /// graph.add_edge(("c", "c"), 1);
+    /// graph.add_vertex("d");
+    ///
+    /// let audit = graph.audit();
+    /// assert_eq!(audit.self_loops, vec![&"c"]);
+    /// assert_eq!(audit.isolated, vec![&"d"]);
+    /// assert_eq!(audit.antiparallel.len(), 1);
+    /// ```
+    pub fn audit(&self) -> Audit<&V> {
+        self.graph.audit().map(|id| self.graph.get_vertex(id).unwrap())
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{Graph, GraphMap, VertexId};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Shortest distances from a start vertex, see [`Graph::bellman_ford`]
+/// and [`Graph::shortest_paths_from`].
+/// `T` identifies the vertices, [`VertexId`] or the vertex data.
+/// Only the vertices reachable from the start are present.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShortestPaths<T: Eq + Hash, E> {
+    pub distance: HashMap<T, E>,
+    /// Vertex before every vertex on its shortest path.
+    pub predecessor: HashMap<T, T>,
+}
+
+impl<T: Eq + Hash + Copy, E> ShortestPaths<T, E> {
+
+    /// Shortest path from the start to `target`, both included.
+    pub fn path_to(&self, target: T) -> Option<Vec<T>> {
+        self.distance.get(&target)?;
+        let mut path = vec![target];
+        let mut current = target;
+        while let Some(&p) = self.predecessor.get(&current) {
+            path.push(p);
+            current = p;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    pub(crate) fn map<U: Eq + Hash, F: Fn(T) -> U>(self, f: F) -> ShortestPaths<U, E> {
+        ShortestPaths {
+            distance: self.distance.into_iter().map(|(v, d)| (f(v), d)).collect(),
+            predecessor: self.predecessor.into_iter().map(|(v, p)| (f(v), f(p))).collect(),
+        }
+    }
+}
+
+/// [`ShortestPaths`] between [`VertexId`]s, as given by [`Graph`].
+pub type ShortestPathResult<E> = ShortestPaths<VertexId, E>;
+
+/// Error of [`Graph::bellman_ford`]: a cycle of negative total weight
+/// reachable from the start, so some distances are unbounded. The
+/// vertices are in the order of the edges, the last one going back
+/// to the first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NegativeCycle<T>(pub Vec<T>);
+
+impl<T: fmt::Debug> fmt::Display for NegativeCycle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "negative cycle through {:?}", self.0)
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for NegativeCycle<T> {}
+
+impl<V: std::fmt::Debug, E: Copy + Ord + Add<Output = E> + Default> Graph<V, E> {
+
+    /// Bellman-Ford shortest paths from `start`, which allows negative
+    /// edge weights. Fails with a negative cycle reachable from `start`.
+    /// Time complexity: O(V E)
+    pub fn bellman_ford(&self, start: VertexId) -> Result<ShortestPaths<VertexId, E>, NegativeCycle<VertexId>> {
+        let mut paths = ShortestPaths {
+            distance: HashMap::new(),
+            predecessor: HashMap::new(),
+        };
+        if self.get_vertex(start).is_none() {
+            return Ok(paths);
+        }
+        paths.distance.insert(start, E::default());
+
+        let mut relaxed = None;
+        for _ in 0..self.vertex_count() {
+            relaxed = None;
+            for (&(from, to), &weight) in self.edges() {
+                let through = match paths.distance.get(&from) {
+                    Some(&d) => d + weight,
+                    None => continue,
+                };
+                let better = match paths.distance.get(&to) {
+                    Some(&d) => through < d,
+                    None => true,
+                };
+                if better {
+                    paths.distance.insert(to, through);
+                    paths.predecessor.insert(to, from);
+                    relaxed = Some(to);
+                }
+            }
+            if relaxed.is_none() {
+                return Ok(paths);
+            }
+        }
+
+        // Still relaxing after V rounds: walking back V steps from the
+        // last relaxed vertex lands on the cycle.
+        let mut vertex = relaxed.unwrap();
+        for _ in 0..self.vertex_count() {
+            vertex = paths.predecessor[&vertex];
+        }
+        let mut cycle = vec![vertex];
+        let mut current = paths.predecessor[&vertex];
+        while current != vertex {
+            cycle.push(current);
+            current = paths.predecessor[&current];
+        }
+        cycle.reverse();
+        Err(NegativeCycle(cycle))
+    }
+}
+
+impl<V, E> GraphMap<V, E>
+where
+    V: Eq + Hash + Clone + std::fmt::Debug,
+    E: Copy + Ord + Add<Output = E> + Default,
+{
+
+    /// See [`Graph::bellman_ford`]. Empty if `start` is not in the graph.
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut graph = GraphMap::<&str, i32>::new();
+    /// graph.add_edge(("a", "b"), 4);
+    /// graph.add_edge(("a", "c"), 5);
+    /// graph.add_edge(("c", "b"), -3);
+    ///
+    /// let paths = graph.bellman_ford(&"a").unwrap();
+    /// assert_eq!(paths.distance[&"b"], 2);
+    /// assert_eq!(paths.path_to(&"b"), Some(vec![&"a", &"c", &"b"]));
+    ///
+    /// graph.add_edge(("b", "c"), 1);
+    /// let cycle = graph.bellman_ford(&"a").unwrap_err();
+    /// assert_eq!(cycle.0.len(), 2);
+    /// ```
+    pub fn bellman_ford(&self, start: &V) -> Result<ShortestPaths<&V, E>, NegativeCycle<&V>> {
+        let vertex = |id| self.graph.get_vertex(id).unwrap();
+        match self.map.get(start) {
+            Some(&id) => match self.graph.bellman_ford(id) {
+                Ok(paths) => Ok(paths.map(vertex)),
+                Err(NegativeCycle(cycle)) => Err(NegativeCycle(cycle.into_iter().map(vertex).collect())),
+            },
+            None => Ok(ShortestPaths {
+                distance: HashMap::new(),
+                predecessor: HashMap::new(),
+            }),
+        }
+    }
+}
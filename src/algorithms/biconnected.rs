@@ -0,0 +1,273 @@
+//! Articulation points, bridges, and biconnected and two edge connected
+//! components, with the edge directions ignored.
+
+use crate::{EdgeId, Graph, GraphMap, VertexId};
+use std::hash::Hash;
+
+/// Hopcroft-Tarjan decomposition of an undirected graph with vertices
+/// `0..n`, without loops or duplicate edges. The depth first search
+/// keeps an explicit stack, so deep graphs don't overflow.
+struct Decomposition {
+    articulation_points: Vec<usize>,
+    bridges: Vec<(usize, usize)>,
+    /// Edges of every biconnected component.
+    blocks: Vec<Vec<(usize, usize)>>,
+    /// Vertices of every 2-edge-connected component.
+    two_edge: Vec<Vec<usize>>,
+}
+
+impl Decomposition {
+
+    fn new(adj: &[Vec<usize>]) -> Self {
+        let n = adj.len();
+        let mut decomposition = Decomposition {
+            articulation_points: Vec::new(),
+            bridges: Vec::new(),
+            blocks: Vec::new(),
+            two_edge: Vec::new(),
+        };
+        let mut discovered: Vec<Option<usize>> = vec![None; n];
+        let mut low = vec![0; n];
+        let mut articulation = vec![false; n];
+        let mut time = 0;
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        let mut vertices: Vec<usize> = Vec::new();
+
+        for root in 0..n {
+            if discovered[root].is_some() {
+                continue;
+            }
+            discovered[root] = Some(time);
+            low[root] = time;
+            time += 1;
+            vertices.push(root);
+            let mut root_children = 0;
+            // (vertex, its parent, next neighbour to look at)
+            let mut stack = vec![(root, root, 0)];
+            while let Some(&mut (v, parent, ref mut next)) = stack.last_mut() {
+                if let Some(&w) = adj[v].get(*next) {
+                    *next += 1;
+                    match discovered[w] {
+                        None => {
+                            discovered[w] = Some(time);
+                            low[w] = time;
+                            time += 1;
+                            edges.push((v, w));
+                            vertices.push(w);
+                            stack.push((w, v, 0));
+                        }
+                        Some(d) if w != parent && d < discovered[v].unwrap() => {
+                            edges.push((v, w));
+                            low[v] = low[v].min(d);
+                        }
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+
+                stack.pop();
+                let v_discovered = discovered[v].unwrap();
+                if low[v] == v_discovered {
+                    let at = vertices.iter().rposition(|&u| u == v).unwrap();
+                    decomposition.two_edge.push(vertices.split_off(at));
+                }
+                if v == root {
+                    continue;
+                }
+                low[parent] = low[parent].min(low[v]);
+                let parent_discovered = discovered[parent].unwrap();
+                if low[v] > parent_discovered {
+                    decomposition.bridges.push((parent, v));
+                }
+                if low[v] >= parent_discovered {
+                    let at = edges.iter().rposition(|&e| e == (parent, v)).unwrap();
+                    decomposition.blocks.push(edges.split_off(at));
+                    if parent == root {
+                        root_children += 1;
+                    } else {
+                        articulation[parent] = true;
+                    }
+                }
+            }
+            if root_children > 1 {
+                articulation[root] = true;
+            }
+        }
+        decomposition.articulation_points = (0..n).filter(|&v| articulation[v]).collect();
+        decomposition
+    }
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    fn decomposition(&self) -> (Vec<VertexId>, Decomposition) {
+        let indexed = self.indexed();
+        let decomposition = Decomposition::new(&indexed.undirected());
+        (indexed.ids, decomposition)
+    }
+
+    /// The edge between two vertices as it is in the graph, in either
+    /// direction, or both if both are there.
+    fn undirected_edges(&self, a: VertexId, b: VertexId) -> impl Iterator<Item = EdgeId> + '_ {
+        let both = if a == b { vec![(a, b)] } else { vec![(a, b), (b, a)] };
+        both.into_iter().filter(move |&edge| self.get_edge(edge).is_some())
+    }
+
+    /// Vertices whose removal disconnects their component, with the edge
+    /// directions ignored, in arena order.
+    /// Time complexity: O(V + E log E)
+    pub fn articulation_points(&self) -> Vec<VertexId> {
+        let (ids, decomposition) = self.decomposition();
+        decomposition.articulation_points.into_iter().map(|v| ids[v]).collect()
+    }
+
+    /// Edges whose removal disconnects their component, with the edge
+    /// directions ignored. An edge and its reverse count as one, and
+    /// are never a bridge together. Edges are given as they are in the
+    /// graph.
+    /// Time complexity: O(V + E log E)
+    pub fn bridges(&self) -> Vec<EdgeId> {
+        let (ids, decomposition) = self.decomposition();
+        decomposition.bridges.into_iter()
+            .flat_map(|(a, b)| self.undirected_edges(ids[a], ids[b]).take(1))
+            .collect()
+    }
+
+    /// Splits the edges into biconnected components, the largest sets
+    /// of edges where any two lie on a common simple cycle, with the
+    /// edge directions ignored. A bridge is a component of its own.
+    /// Every vertex shared by two components is an articulation point.
+    /// An edge and its reverse are in the same component, self loops
+    /// are left out, and so are the vertices without edges.
+    /// Time complexity: O(V + E log E)
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// // two triangles sharing the vertex c, and a pendant edge c - f
+    /// let mut graph = Graph::<char, ()>::new();
+    /// let ids: Vec<_> = "abcdef".chars().map(|v| graph.add_vertex(v)).collect();
+    /// for &(a, b) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2), (2, 5)].iter() {
+    ///     graph.add_edge((ids[a], ids[b]), ()).unwrap();
+    /// }
+    ///
+    /// let components = graph.biconnected_components();
+    /// let mut sizes: Vec<usize> = components.iter().map(Vec::len).collect();
+    /// sizes.sort_unstable();
+    /// assert_eq!(sizes, vec![1, 3, 3]);
+    /// assert_eq!(graph.articulation_points(), vec![ids[2]]);
+    /// assert_eq!(graph.bridges(), vec![(ids[2], ids[5])]);
+    /// assert_eq!(graph.two_edge_connected_components().len(), 2);
+    /// ```
+    pub fn biconnected_components(&self) -> Vec<Vec<EdgeId>> {
+        let (ids, decomposition) = self.decomposition();
+        decomposition.blocks.into_iter()
+            .map(|block| {
+                let mut edges: Vec<EdgeId> = block.into_iter()
+                    .flat_map(|(a, b)| self.undirected_edges(ids[a], ids[b]))
+                    .collect();
+                edges.sort_unstable();
+                edges
+            })
+            .collect()
+    }
+
+    /// Splits the vertices into 2-edge-connected components, which stay
+    /// connected after removing any one edge, with the edge directions
+    /// ignored. They are the connected components left once the bridges
+    /// are removed. Every component is sorted.
+    /// Time complexity: O(V + E log E)
+    pub fn two_edge_connected_components(&self) -> Vec<Vec<VertexId>> {
+        let (ids, decomposition) = self.decomposition();
+        decomposition.two_edge.into_iter()
+            .map(|component| {
+                let mut component: Vec<VertexId> = component.into_iter().map(|v| ids[v]).collect();
+                component.sort_unstable();
+                component
+            })
+            .collect()
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    fn edge_values(&self, (from, to): EdgeId) -> (&V, &V) {
+        (&self.graph.arena[from], &self.graph.arena[to])
+    }
+
+    /// See [`Graph::articulation_points`].
+    pub fn articulation_points(&self) -> Vec<&V> {
+        self.graph.articulation_points().into_iter().map(|id| &self.graph.arena[id]).collect()
+    }
+
+    /// See [`Graph::bridges`].
+    pub fn bridges(&self) -> Vec<(&V, &V)> {
+        self.graph.bridges().into_iter().map(|edge| self.edge_values(edge)).collect()
+    }
+
+    /// See [`Graph::biconnected_components`].
+    pub fn biconnected_components(&self) -> Vec<Vec<(&V, &V)>> {
+        self.graph.biconnected_components()
+            .into_iter()
+            .map(|block| block.into_iter().map(|edge| self.edge_values(edge)).collect())
+            .collect()
+    }
+
+    /// See [`Graph::two_edge_connected_components`].
+    pub fn two_edge_connected_components(&self) -> Vec<Vec<&V>> {
+        self.graph.two_edge_connected_components()
+            .into_iter()
+            .map(|component| component.into_iter().map(|id| &self.graph.arena[id]).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+
+    #[test]
+    fn decomposition_of_a_chain_of_cycles() {
+        // square 0-1-2-3, bridge 3-4, triangle 4-5-6 sharing 4 with
+        // triangle 4-7-8, both ways edges on the square, isolated 9
+        let mut graph = GraphMap::<u32, ()>::new();
+        let edges = [(0, 1), (1, 0), (1, 2), (2, 3), (3, 0), (0, 3), (3, 4), (4, 5), (5, 6), (6, 4), (7, 4), (8, 7), (4, 8)];
+        for &edge in edges.iter() {
+            graph.add_edge(edge, ());
+        }
+        graph.add_vertex(9);
+        graph.add_edge((5, 5), ());
+
+        let mut cut: Vec<u32> = graph.articulation_points().into_iter().copied().collect();
+        cut.sort_unstable();
+        assert_eq!(cut, vec![3, 4]);
+        assert_eq!(graph.bridges(), vec![(&3, &4)]);
+
+        let mut blocks: Vec<Vec<(u32, u32)>> = graph.biconnected_components()
+            .into_iter()
+            .map(|block| {
+                let mut block: Vec<(u32, u32)> = block.into_iter().map(|(&a, &b)| (a, b)).collect();
+                block.sort_unstable();
+                block
+            })
+            .collect();
+        blocks.sort_unstable();
+        assert_eq!(blocks, vec![
+            vec![(0, 1), (0, 3), (1, 0), (1, 2), (2, 3), (3, 0)],
+            vec![(3, 4)],
+            vec![(4, 5), (5, 6), (6, 4)],
+            vec![(4, 8), (7, 4), (8, 7)],
+        ]);
+
+        let mut components: Vec<Vec<u32>> = graph.two_edge_connected_components()
+            .into_iter()
+            .map(|component| {
+                let mut component: Vec<u32> = component.into_iter().copied().collect();
+                component.sort_unstable();
+                component
+            })
+            .collect();
+        components.sort_unstable();
+        assert_eq!(components, vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7, 8], vec![9]]);
+    }
+}
@@ -0,0 +1,266 @@
+//! PageRank and centrality measures.
+//!
+//! Every measure follows the direction of the edges, counts every
+//! edge as one step whatever its data, and ignores self loops.
+//! Results are given in a [`VertexMap`].
+
+use crate::property::VertexMap;
+use crate::{Graph, GraphMap, VertexId};
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Options of [`Graph::pagerank`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PageRankConfig {
+    /// Probability of following an edge rather than jumping
+    /// to a random vertex.
+    pub damping: f64,
+    /// Iteration stops when the ranks change by less than this,
+    /// summed over all the vertices.
+    pub tolerance: f64,
+    /// Iteration stops after this many rounds in any case.
+    pub max_iterations: usize,
+}
+
+impl Default for PageRankConfig {
+    /// Damping 0.85, tolerance 1e-6, 100 iterations.
+    fn default() -> Self {
+        PageRankConfig {
+            damping: 0.85,
+            tolerance: 1e-6,
+            max_iterations: 100,
+        }
+    }
+}
+
+/// Outbound neighbours of every vertex, numbered in arena order.
+fn out_adjacency<V: std::fmt::Debug, E>(graph: &Graph<V, E>) -> (Vec<VertexId>, Vec<Vec<usize>>) {
+    let indexed = graph.indexed();
+    let mut adj = vec![Vec::new(); indexed.ids.len()];
+    for &(from, to) in indexed.edges.iter() {
+        adj[from].push(to);
+    }
+    (indexed.ids, adj)
+}
+
+fn by_id(ids: &[VertexId], values: Vec<f64>) -> VertexMap<f64> {
+    ids.iter().copied().zip(values).collect()
+}
+
+/// Breadth first distances from `source`, `usize::MAX` when unreachable.
+fn distances(adj: &[Vec<usize>], source: usize, queue: &mut VecDeque<usize>) -> Vec<usize> {
+    let mut distance = vec![usize::MAX; adj.len()];
+    distance[source] = 0;
+    queue.push_back(source);
+    while let Some(v) = queue.pop_front() {
+        for &w in adj[v].iter() {
+            if distance[w] == usize::MAX {
+                distance[w] = distance[v] + 1;
+                queue.push_back(w);
+            }
+        }
+    }
+    distance
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// PageRank by power iteration. The rank of a vertex without
+    /// outbound edges is spread over all the vertices. The ranks
+    /// sum to 1.
+    /// Time complexity: O((V + E) * iterations)
+    pub fn pagerank(&self, config: PageRankConfig) -> VertexMap<f64> {
+        let (ids, adj) = out_adjacency(self);
+        let n = ids.len();
+        if n == 0 {
+            return VertexMap::new();
+        }
+        let mut rank = vec![1.0 / n as f64; n];
+        let mut next = vec![0.0; n];
+        for _ in 0..config.max_iterations {
+            let dangling: f64 = adj.iter()
+                .zip(rank.iter())
+                .filter(|(targets, _)| targets.is_empty())
+                .map(|(_, &r)| r)
+                .sum();
+            let base = (1.0 - config.damping + config.damping * dangling) / n as f64;
+            next.iter_mut().for_each(|r| *r = base);
+            for (v, targets) in adj.iter().enumerate() {
+                let share = config.damping * rank[v] / targets.len() as f64;
+                for &w in targets.iter() {
+                    next[w] += share;
+                }
+            }
+            let change: f64 = rank.iter().zip(next.iter()).map(|(a, b)| (a - b).abs()).sum();
+            std::mem::swap(&mut rank, &mut next);
+            if change < config.tolerance {
+                break;
+            }
+        }
+        by_id(&ids, rank)
+    }
+
+    /// Betweenness centrality with Brandes' algorithm: for every vertex,
+    /// the fraction of the shortest paths between two other vertices
+    /// going through it, summed over all the pairs. `normalized` divides
+    /// by the number of pairs, `(V - 1)(V - 2)`.
+    /// Time complexity: O(V E)
+    pub fn betweenness_centrality(&self, normalized: bool) -> VertexMap<f64> {
+        let (ids, adj) = out_adjacency(self);
+        let n = ids.len();
+        let mut betweenness = vec![0.0; n];
+        let mut distance = vec![usize::MAX; n];
+        let mut paths = vec![0.0; n];
+        let mut dependency = vec![0.0; n];
+        let mut order = Vec::with_capacity(n);
+        let mut queue = VecDeque::new();
+
+        for source in 0..n {
+            distance.iter_mut().for_each(|d| *d = usize::MAX);
+            paths.iter_mut().for_each(|p| *p = 0.0);
+            dependency.iter_mut().for_each(|d| *d = 0.0);
+            order.clear();
+
+            distance[source] = 0;
+            paths[source] = 1.0;
+            queue.push_back(source);
+            while let Some(v) = queue.pop_front() {
+                order.push(v);
+                for &w in adj[v].iter() {
+                    if distance[w] == usize::MAX {
+                        distance[w] = distance[v] + 1;
+                        queue.push_back(w);
+                    }
+                    if distance[w] == distance[v] + 1 {
+                        paths[w] += paths[v];
+                    }
+                }
+            }
+            // Leaves first, each vertex passes its dependency back to
+            // the vertices before it on the shortest paths.
+            for &w in order.iter().rev() {
+                for &x in adj[w].iter() {
+                    if distance[x] == distance[w] + 1 {
+                        dependency[w] += paths[w] / paths[x] * (1.0 + dependency[x]);
+                    }
+                }
+                if w != source {
+                    betweenness[w] += dependency[w];
+                }
+            }
+        }
+
+        if normalized && n > 2 {
+            let pairs = ((n - 1) * (n - 2)) as f64;
+            betweenness.iter_mut().for_each(|b| *b /= pairs);
+        }
+        by_id(&ids, betweenness)
+    }
+
+    /// Closeness centrality: the inverse of the average distance to the
+    /// vertices reachable from a vertex, scaled by the fraction of the
+    /// graph they make up, so vertices reaching little score low. 0 for
+    /// a vertex reaching nothing.
+    /// Time complexity: O(V E)
+    pub fn closeness_centrality(&self) -> VertexMap<f64> {
+        let (ids, adj) = out_adjacency(self);
+        let n = ids.len();
+        let mut queue = VecDeque::new();
+        let closeness = (0..n)
+            .map(|source| {
+                let distance = distances(&adj, source, &mut queue);
+                let reached: Vec<usize> = distance.into_iter().filter(|&d| d != usize::MAX).collect();
+                let total: usize = reached.iter().sum();
+                if total == 0 {
+                    return 0.0;
+                }
+                let others = (reached.len() - 1) as f64;
+                others / total as f64 * others / (n - 1) as f64
+            })
+            .collect();
+        by_id(&ids, closeness)
+    }
+
+    /// Degree centrality: indegree plus outdegree, divided
+    /// by the number of other vertices.
+    /// Time complexity: O(V)
+    pub fn degree_centrality(&self) -> VertexMap<f64> {
+        let others = self.vertex_count().saturating_sub(1).max(1) as f64;
+        self.vertices()
+            .map(|(id, _)| (id, (self.indegree(id) + self.outdegree(id)) as f64 / others))
+            .collect()
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    fn by_vertex(&self, values: VertexMap<f64>) -> HashMap<&V, f64> {
+        values.into_iter()
+            .map(|(id, value)| (self.graph.get_vertex(id).unwrap(), value))
+            .collect()
+    }
+
+    /// See [`Graph::pagerank`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    /// use graph::algorithms::centrality::PageRankConfig;
+    ///
+    /// // Everyone cites "survey".
+    /// let mut graph = GraphMap::<&str, ()>::new();
+    /// for &paper in ["a", "b", "c"].iter() {
+    ///     graph.add_edge((paper, "survey"), ());
+    /// }
+    /// graph.add_edge(("survey", "a"), ());
+    ///
+    /// let rank = graph.pagerank(PageRankConfig::default());
+    /// assert!(rank[&"survey"] > rank[&"a"]);
+    /// assert!(rank[&"a"] > rank[&"b"]);
+    /// assert!((rank.values().sum::<f64>() - 1.0).abs() < 1e-9);
+    /// ```
+    pub fn pagerank(&self, config: PageRankConfig) -> HashMap<&V, f64> {
+        self.by_vertex(self.graph.pagerank(config))
+    }
+
+    /// See [`Graph::betweenness_centrality`].
+    pub fn betweenness_centrality(&self, normalized: bool) -> HashMap<&V, f64> {
+        self.by_vertex(self.graph.betweenness_centrality(normalized))
+    }
+
+    /// See [`Graph::closeness_centrality`].
+    pub fn closeness_centrality(&self) -> HashMap<&V, f64> {
+        self.by_vertex(self.graph.closeness_centrality())
+    }
+
+    /// See [`Graph::degree_centrality`].
+    pub fn degree_centrality(&self) -> HashMap<&V, f64> {
+        self.by_vertex(self.graph.degree_centrality())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+
+    #[test]
+    fn centrality_of_a_directed_path() {
+        let mut graph = GraphMap::<u32, ()>::new();
+        for i in 0..3 {
+            graph.add_edge((i, i + 1), ());
+        }
+        let betweenness = graph.betweenness_centrality(false);
+        assert_eq!(betweenness[&0], 0.0);
+        assert_eq!(betweenness[&1], 2.0);
+        assert_eq!(betweenness[&2], 2.0);
+        assert_eq!(graph.betweenness_centrality(true)[&1], 2.0 / 6.0);
+
+        let closeness = graph.closeness_centrality();
+        assert_eq!(closeness[&0], 3.0 / 6.0);
+        assert_eq!(closeness[&2], 1.0 / 3.0);
+        assert_eq!(closeness[&3], 0.0);
+
+        let degree = graph.degree_centrality();
+        assert_eq!(degree[&0], 1.0 / 3.0);
+        assert_eq!(degree[&1], 2.0 / 3.0);
+    }
+}
@@ -0,0 +1,117 @@
+//! Maximum clique, with a branch and bound search.
+
+use crate::{Graph, GraphMap, VertexId};
+use std::hash::Hash;
+
+/// Branch and bound search for a maximum clique, after Tomita's MCQ.
+/// The candidates are greedily colored, and a branch is cut as soon as
+/// the clique plus the number of colors left can't beat the best one.
+struct Search<'a> {
+    adjacent: &'a [Vec<bool>],
+    clique: Vec<usize>,
+    best: Vec<usize>,
+}
+
+impl<'a> Search<'a> {
+
+    /// Orders the candidates by greedy color, returning the color
+    /// of every candidate, colors counted from 1.
+    fn color(&self, candidates: &[usize]) -> (Vec<usize>, Vec<usize>) {
+        let mut classes: Vec<Vec<usize>> = Vec::new();
+        for &v in candidates {
+            let class = classes.iter()
+                .position(|class| class.iter().all(|&u| !self.adjacent[v][u]));
+            match class {
+                Some(class) => classes[class].push(v),
+                None => classes.push(vec![v]),
+            }
+        }
+        let mut order = Vec::with_capacity(candidates.len());
+        let mut colors = Vec::with_capacity(candidates.len());
+        for (color, class) in classes.into_iter().enumerate() {
+            for v in class {
+                order.push(v);
+                colors.push(color + 1);
+            }
+        }
+        (order, colors)
+    }
+
+    fn expand(&mut self, candidates: Vec<usize>) {
+        let (mut order, colors) = self.color(&candidates);
+        while let Some(v) = order.pop() {
+            if self.clique.len() + colors[order.len()] <= self.best.len() {
+                return;
+            }
+            self.clique.push(v);
+            let next: Vec<usize> = order.iter().copied().filter(|&u| self.adjacent[v][u]).collect();
+            if next.is_empty() {
+                if self.clique.len() > self.best.len() {
+                    self.best = self.clique.clone();
+                }
+            } else {
+                self.expand(next);
+            }
+            self.clique.pop();
+        }
+    }
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// A largest set of vertices all joined to each other, with the edge
+    /// directions ignored. Exponential in the worst case, but the coloring
+    /// bound keeps it practical up to a few hundred vertices.
+    /// The clique is sorted.
+    /// Time complexity: O(2^V) in the worst case
+    pub fn maximum_clique(&self) -> Vec<VertexId> {
+        let indexed = self.indexed();
+        let n = indexed.ids.len();
+        let adj = indexed.undirected();
+        let mut adjacent = vec![vec![false; n]; n];
+        for (v, list) in adj.iter().enumerate() {
+            for &u in list {
+                adjacent[v][u] = true;
+            }
+        }
+
+        // High degree vertices first, so they get the low colors.
+        let mut candidates: Vec<usize> = (0..n).collect();
+        candidates.sort_by_key(|&v| std::cmp::Reverse(adj[v].len()));
+
+        let mut search = Search {
+            adjacent: &adjacent,
+            clique: Vec::new(),
+            best: Vec::new(),
+        };
+        search.expand(candidates);
+
+        let mut clique: Vec<VertexId> = search.best.into_iter().map(|v| indexed.ids[v]).collect();
+        clique.sort_unstable();
+        clique
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::maximum_clique`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut graph = GraphMap::<u32, ()>::new();
+    /// for &(a, b) in [(0, 1), (0, 2), (1, 2), (1, 3), (2, 3), (0, 3), (3, 4), (4, 5)].iter() {
+    ///     graph.add_edge((a, b), ());
+    /// }
+    ///
+    /// let mut clique: Vec<u32> = graph.maximum_clique().into_iter().copied().collect();
+    /// clique.sort();
+    /// assert_eq!(clique, vec![0, 1, 2, 3]);
+    /// ```
+    pub fn maximum_clique(&self) -> Vec<&V> {
+        self.graph.maximum_clique()
+            .into_iter()
+            .map(|id| self.graph.get_vertex(id).unwrap())
+            .collect()
+    }
+}
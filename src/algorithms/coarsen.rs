@@ -0,0 +1,181 @@
+
+use crate::{Graph, GraphMap, VertexId, EdgeId};
+use std::hash::Hash;
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+/// Result of [`Graph::coarsen`].
+///
+/// Every coarse vertex holds the number of fine vertices merged into it,
+/// and every coarse edge the summed weight of the fine edges it replaces.
+/// Edges inside a coarse vertex are dropped.
+#[derive(Clone, Debug)]
+pub struct Coarsening<K> {
+    pub graph: Graph<usize, f64>,
+    /// Coarse vertex of every fine vertex.
+    pub mapping: HashMap<K, VertexId>,
+}
+
+impl<V: std::fmt::Debug, E: Clone + Into<f64>> Graph<V, E> {
+
+    /// Maximal matching preferring heavy edges, with the edge directions
+    /// ignored and the weights of antiparallel edges added up.
+    /// Vertices are visited from the lowest to the highest degree, and each
+    /// one is matched with its unmatched neighbour behind the heaviest edge.
+    /// Time complexity: O(V log V + E)
+    pub fn heavy_edge_matching(&self) -> Vec<EdgeId> {
+        let mut weights: HashMap<VertexId, HashMap<VertexId, f64>> = HashMap::new();
+        for (&(from, to), weight) in self.edges() {
+            if from == to {
+                continue;
+            }
+            let weight: f64 = weight.clone().into();
+            *weights.entry(from).or_default().entry(to).or_default() += weight;
+            *weights.entry(to).or_default().entry(from).or_default() += weight;
+        }
+        heavy_edge_matching(self.vertices().map(|(id, _)| id), &weights)
+    }
+
+    /// Coarsens the graph by contracting heavy edge matchings, see
+    /// [`Graph::heavy_edge_matching`], until it has at most `target`
+    /// vertices or a matching doesn't shrink it anymore.
+    ///
+    /// This is the first phase of multilevel partitioning and drawing:
+    /// solve the problem on the small graph, then project the solution
+    /// back through the mapping.
+    pub fn coarsen(&self, target: usize) -> Coarsening<VertexId> {
+        let mut graph = Graph::new();
+        let mut mapping = HashMap::new();
+        for (id, _) in self.vertices() {
+            mapping.insert(id, graph.add_vertex(1));
+        }
+        for (&(from, to), weight) in self.edges() {
+            if from != to {
+                graph.insert_edge((mapping[&from], mapping[&to]), weight.clone().into());
+            }
+        }
+
+        while graph.vertex_count() > target {
+            let matching = graph.heavy_edge_matching();
+            if matching.is_empty() {
+                break;
+            }
+            let (coarse, level) = graph.contract_matching(&matching);
+            for coarse_id in mapping.values_mut() {
+                *coarse_id = level[coarse_id];
+            }
+            graph = coarse;
+        }
+
+        Coarsening {
+            graph,
+            mapping,
+        }
+    }
+}
+
+impl Graph<usize, f64> {
+
+    /// Merges the ends of every matched edge into one vertex.
+    fn contract_matching(&self, matching: &[EdgeId]) -> (Graph<usize, f64>, HashMap<VertexId, VertexId>) {
+        let mut coarse = Graph::new();
+        let mut mapping = HashMap::new();
+        for &(a, b) in matching.iter() {
+            let size = self.get_vertex(a).unwrap() + self.get_vertex(b).unwrap();
+            let id = coarse.add_vertex(size);
+            mapping.insert(a, id);
+            mapping.insert(b, id);
+        }
+        for (id, &size) in self.vertices() {
+            mapping.entry(id).or_insert_with(|| coarse.add_vertex(size));
+        }
+        for (&(from, to), &weight) in self.edges() {
+            let edge = (mapping[&from], mapping[&to]);
+            if edge.0 != edge.1 {
+                let total = coarse.get_edge(edge).copied().unwrap_or(0.0) + weight;
+                coarse.insert_edge(edge, total);
+            }
+        }
+        (coarse, mapping)
+    }
+}
+
+fn heavy_edge_matching<I>(vertices: I, weights: &HashMap<VertexId, HashMap<VertexId, f64>>) -> Vec<EdgeId>
+where
+    I: Iterator<Item = VertexId>,
+{
+    let degree = |id: &VertexId| weights.get(id).map_or(0, |adj| adj.len());
+    let mut order: Vec<VertexId> = vertices.collect();
+    order.sort_unstable_by_key(|id| (degree(id), *id));
+
+    let mut matched = HashSet::new();
+    let mut matching = Vec::new();
+    for vertex in order {
+        if matched.contains(&vertex) {
+            continue;
+        }
+        let adjacent = match weights.get(&vertex) {
+            Some(adjacent) => adjacent,
+            None => continue,
+        };
+        let heaviest = adjacent.iter()
+            .filter(|(other, _)| !matched.contains(*other))
+            .max_by(|(a, x), (b, y)| x.partial_cmp(y).unwrap().then(b.cmp(a)));
+        if let Some((&other, _)) = heaviest {
+            matched.insert(vertex);
+            matched.insert(other);
+            matching.push((vertex, other));
+        }
+    }
+    matching
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E: Clone + Into<f64>> GraphMap<V, E> {
+
+    /// Heavy edge matching, see [`Graph::heavy_edge_matching`].
+    pub fn heavy_edge_matching(&self) -> Vec<(&V, &V)> {
+        self.graph.heavy_edge_matching()
+            .into_iter()
+            .map(|(a, b)| (self.graph.get_vertex(a).unwrap(), self.graph.get_vertex(b).unwrap()))
+            .collect()
+    }
+
+    /// Coarsening, see [`Graph::coarsen`], mapping the vertices by their data.
+    pub fn coarsen(&self, target: usize) -> Coarsening<V> {
+        let Coarsening { graph, mapping } = self.graph.coarsen(target);
+        let mapping = mapping.into_iter()
+            .map(|(id, coarse)| (self.graph.get_vertex(id).unwrap().clone(), coarse))
+            .collect();
+        Coarsening {
+            graph,
+            mapping,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+
+    #[test]
+    fn coarsen_merges_heavy_edges() {
+        let mut graph = GraphMap::<u32, u32>::new();
+        graph.add_edge((0, 1), 10);
+        graph.add_edge((1, 2), 1);
+        graph.add_edge((2, 3), 10);
+        graph.add_edge((3, 0), 2);
+
+        let coarsening = graph.coarsen(2);
+        let m = &coarsening.mapping;
+        assert_eq!(coarsening.graph.vertex_count(), 2);
+        assert_eq!(m[&0], m[&1]);
+        assert_eq!(m[&2], m[&3]);
+        assert_eq!(coarsening.graph.get_vertex(m[&0]), Some(&2));
+        assert_eq!(coarsening.graph.get_edge((m[&0], m[&2])), Some(&1.0));
+        assert_eq!(coarsening.graph.get_edge((m[&2], m[&0])), Some(&2.0));
+
+        assert_eq!(graph.coarsen(1).graph.vertex_count(), 1);
+    }
+}
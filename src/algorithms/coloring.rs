@@ -0,0 +1,167 @@
+use crate::{Graph, GraphMap, VertexId};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Colors of the vertices, numbered from 0, so that joined vertices
+/// have different colors. `T` identifies the vertices, [`VertexId`]
+/// or the vertex data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Coloring<T: Eq + Hash> {
+    pub colors: HashMap<T, usize>,
+    /// Number of colors used.
+    pub count: usize,
+}
+
+impl<T: Eq + Hash> Coloring<T> {
+    fn map<U: Eq + Hash, F: Fn(T) -> U>(self, f: F) -> Coloring<U> {
+        Coloring {
+            colors: self.colors.into_iter().map(|(v, c)| (f(v), c)).collect(),
+            count: self.count,
+        }
+    }
+}
+
+/// Smallest color missing from the colored neighbours of `v`.
+fn first_free(adj: &[Vec<usize>], colors: &[Option<usize>], v: usize) -> usize {
+    let mut used: Vec<usize> = adj[v].iter().filter_map(|&u| colors[u]).collect();
+    used.sort_unstable();
+    used.dedup();
+    used.iter().enumerate().find(|&(i, &c)| i != c).map_or(used.len(), |(i, _)| i)
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    fn coloring(&self, ids: Vec<VertexId>, colors: Vec<Option<usize>>) -> Coloring<VertexId> {
+        let colors: HashMap<VertexId, usize> = ids.into_iter()
+            .zip(colors.into_iter().map(Option::unwrap))
+            .collect();
+        Coloring {
+            count: colors.values().map(|&c| c + 1).max().unwrap_or(0),
+            colors,
+        }
+    }
+
+    /// Colors the vertices in arena order, each with the smallest color
+    /// its neighbours don't have, see [`Graph::greedy_coloring_with_order`].
+    /// Time complexity: O(V + E log E)
+    pub fn greedy_coloring(&self) -> Coloring<VertexId> {
+        self.greedy_coloring_with_order(&[])
+    }
+
+    /// Colors the vertices in the given `order`, each with the smallest
+    /// color its neighbours don't have. The vertices missing from `order`
+    /// are colored after it, in arena order. Edge directions and self
+    /// loops are ignored.
+    /// Time complexity: O(V + E log E)
+    pub fn greedy_coloring_with_order(&self, order: &[VertexId]) -> Coloring<VertexId> {
+        let indexed = self.indexed();
+        let adj = indexed.undirected();
+        let index: HashMap<VertexId, usize> = indexed.ids.iter()
+            .enumerate()
+            .map(|(i, &id)| (id, i))
+            .collect();
+        let mut colors = vec![None; indexed.ids.len()];
+        let order = order.iter()
+            .filter_map(|id| index.get(id).copied())
+            .chain(0..indexed.ids.len());
+        for v in order {
+            if colors[v].is_none() {
+                colors[v] = Some(first_free(&adj, &colors, v));
+            }
+        }
+        self.coloring(indexed.ids, colors)
+    }
+
+    /// Brélaz's DSATUR: colors next the vertex with the most different
+    /// colors among its neighbours, then with the highest degree, then
+    /// first in arena order, with the smallest color its neighbours
+    /// don't have. Usually needs fewer colors than the greedy coloring,
+    /// and is optimal on bipartite graphs. Edge directions and self
+    /// loops are ignored.
+    /// Time complexity: O(V^2 + E log E)
+    pub fn dsatur_coloring(&self) -> Coloring<VertexId> {
+        let indexed = self.indexed();
+        let adj = indexed.undirected();
+        let n = indexed.ids.len();
+        let mut colors: Vec<Option<usize>> = vec![None; n];
+        let mut saturation: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for _ in 0..n {
+            let v = (0..n)
+                .filter(|&v| colors[v].is_none())
+                .max_by_key(|&v| (saturation[v].len(), adj[v].len(), std::cmp::Reverse(v)))
+                .unwrap();
+            let color = first_free(&adj, &colors, v);
+            colors[v] = Some(color);
+            for &u in adj[v].iter() {
+                if let Err(at) = saturation[u].binary_search(&color) {
+                    saturation[u].insert(at, color);
+                }
+            }
+        }
+        self.coloring(indexed.ids, colors)
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::greedy_coloring`].
+    pub fn greedy_coloring(&self) -> Coloring<&V> {
+        self.graph.greedy_coloring().map(|id| &self.graph.arena[id])
+    }
+
+    /// See [`Graph::greedy_coloring_with_order`]. Vertices of `order`
+    /// not in the graph are ignored.
+    pub fn greedy_coloring_with_order(&self, order: &[V]) -> Coloring<&V> {
+        let order: Vec<VertexId> = order.iter().filter_map(|v| self.map.get(v).copied()).collect();
+        self.graph.greedy_coloring_with_order(&order).map(|id| &self.graph.arena[id])
+    }
+
+    /// See [`Graph::dsatur_coloring`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// // a cycle of five needs three colors
+    /// let graph = GraphMap::<u32, ()>::from_edges(vec![((0, 1), ()), ((1, 2), ()), ((2, 3), ()), ((3, 4), ()), ((4, 0), ())]);
+    /// let coloring = graph.dsatur_coloring();
+    /// assert_eq!(coloring.count, 3);
+    /// for ((a, b), _) in graph.edges() {
+    ///     assert_ne!(coloring.colors[a], coloring.colors[b]);
+    /// }
+    /// ```
+    pub fn dsatur_coloring(&self) -> Coloring<&V> {
+        self.graph.dsatur_coloring().map(|id| &self.graph.arena[id])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+
+    #[test]
+    fn dsatur_beats_a_bad_greedy_order() {
+        // crown graph: u_i joined to v_j for every i != j, bipartite
+        let n = 5;
+        let mut graph = GraphMap::<(bool, u32), ()>::new();
+        for i in 0..n {
+            for j in 0..n {
+                if i != j {
+                    graph.add_edge(((false, i), (true, j)), ());
+                }
+            }
+        }
+        let order: Vec<(bool, u32)> = (0..n).flat_map(|i| vec![(false, i), (true, i)]).collect();
+
+        let greedy = graph.greedy_coloring_with_order(&order);
+        assert_eq!(greedy.count, n as usize);
+        let dsatur = graph.dsatur_coloring();
+        assert_eq!(dsatur.count, 2);
+        for coloring in [greedy, dsatur].iter() {
+            assert_eq!(coloring.colors.len(), 2 * n as usize);
+            for ((a, b), _) in graph.edges() {
+                assert_ne!(coloring.colors[a], coloring.colors[b]);
+            }
+        }
+        assert_eq!(GraphMap::<u32, ()>::new().greedy_coloring().count, 0);
+    }
+}
@@ -0,0 +1,385 @@
+//! Community detection: Louvain modularity optimization and label
+//! propagation.
+//!
+//! Edge directions are ignored: an edge and its reverse add up to one
+//! undirected edge, and self loops are left out. Both methods find a
+//! partition in near linear time, see [`Graph::girvan_newman`] for a
+//! slower hierarchical one.
+
+use crate::{Graph, GraphMap, VertexId};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Label propagation stops after this many rounds even if some labels
+/// still change, which only happens on rare ties.
+const MAX_ROUNDS: usize = 100;
+
+/// Partition of the vertices into communities, and its modularity.
+/// `T` identifies the vertices, [`VertexId`] or the vertex data.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Partition<T: Eq + Hash> {
+    /// Vertices of every community, in arena order. Communities are
+    /// sorted by their first vertex.
+    pub communities: Vec<Vec<T>>,
+    /// Index in `communities` of the community of every vertex.
+    pub community: HashMap<T, usize>,
+    /// Newman's modularity, from -1/2 to 1, higher when there are more
+    /// edges inside the communities than at random.
+    pub modularity: f64,
+}
+
+impl<T: Eq + Hash + Clone> Partition<T> {
+    fn map<U: Eq + Hash, F: Fn(T) -> U>(self, f: F) -> Partition<U> {
+        Partition {
+            communities: self.communities.into_iter().map(|c| c.into_iter().map(&f).collect()).collect(),
+            community: self.community.into_iter().map(|(v, c)| (f(v), c)).collect(),
+            modularity: self.modularity,
+        }
+    }
+}
+
+/// Numbers the communities from 0 in order of their first vertex,
+/// returning their count.
+fn renumber(community: &mut [usize]) -> usize {
+    let mut numbers = HashMap::new();
+    for c in community.iter_mut() {
+        let next = numbers.len();
+        *c = *numbers.entry(*c).or_insert(next);
+    }
+    numbers.len()
+}
+
+/// Undirected weighted graph with vertices `0..n`, every edge in the
+/// lists of both its ends. Self loops are kept apart, and count twice
+/// in the degree.
+struct Weighted {
+    adj: Vec<Vec<(usize, f64)>>,
+    loops: Vec<f64>,
+}
+
+impl Weighted {
+
+    fn degrees(&self) -> Vec<f64> {
+        self.adj.iter()
+            .zip(self.loops.iter())
+            .map(|(list, &own)| list.iter().map(|&(_, w)| w).sum::<f64>() + 2.0 * own)
+            .collect()
+    }
+
+    fn modularity(&self, community: &[usize], count: usize) -> f64 {
+        let degrees = self.degrees();
+        let total: f64 = degrees.iter().sum();
+        if total == 0.0 {
+            return 0.0;
+        }
+        let mut inside = vec![0.0; count];
+        let mut degree = vec![0.0; count];
+        for (v, list) in self.adj.iter().enumerate() {
+            let c = community[v];
+            degree[c] += degrees[v];
+            inside[c] += 2.0 * self.loops[v];
+            inside[c] += list.iter().filter(|&&(w, _)| community[w] == c).map(|&(_, w)| w).sum::<f64>();
+        }
+        inside.iter()
+            .zip(degree.iter())
+            .map(|(&inside, &degree)| inside / total - (degree / total).powi(2))
+            .sum()
+    }
+
+    /// Moves every vertex to the neighbouring community that raises the
+    /// modularity most, until none moves. Returns false if none moved.
+    fn move_vertices(&self, community: &mut [usize]) -> bool {
+        let degrees = self.degrees();
+        let total: f64 = degrees.iter().sum();
+        if total == 0.0 {
+            return false;
+        }
+        let n = self.adj.len();
+        let mut sum = vec![0.0; n];
+        for v in 0..n {
+            sum[community[v]] += degrees[v];
+        }
+        let mut links = vec![0.0; n];
+        let mut touched = Vec::new();
+        let mut moved = false;
+        loop {
+            let mut improved = false;
+            for v in 0..n {
+                let current = community[v];
+                sum[current] -= degrees[v];
+                touched.push(current);
+                for &(w, weight) in self.adj[v].iter() {
+                    if links[community[w]] == 0.0 {
+                        touched.push(community[w]);
+                    }
+                    links[community[w]] += weight;
+                }
+                let gain = |c: usize| links[c] - sum[c] * degrees[v] / total;
+                let mut best = current;
+                let mut best_gain = gain(current);
+                for &c in touched.iter() {
+                    // Rounding can't make a vertex move back and forth.
+                    if gain(c) > best_gain + 1e-12 {
+                        best = c;
+                        best_gain = gain(c);
+                    }
+                }
+                for c in touched.drain(..) {
+                    links[c] = 0.0;
+                }
+                sum[best] += degrees[v];
+                if best != current {
+                    community[v] = best;
+                    improved = true;
+                    moved = true;
+                }
+            }
+            if !improved {
+                return moved;
+            }
+        }
+    }
+
+    /// Graph with a vertex for every community, the edges inside a
+    /// community becoming a self loop.
+    fn aggregate(&self, community: &[usize], count: usize) -> Weighted {
+        let mut links: Vec<HashMap<usize, f64>> = vec![HashMap::new(); count];
+        let mut loops = vec![0.0; count];
+        for (v, list) in self.adj.iter().enumerate() {
+            let c = community[v];
+            loops[c] += self.loops[v];
+            for &(w, weight) in list.iter() {
+                let d = community[w];
+                if c == d {
+                    // seen from both ends
+                    loops[c] += weight / 2.0;
+                } else {
+                    *links[c].entry(d).or_insert(0.0) += weight;
+                }
+            }
+        }
+        let adj = links.into_iter()
+            .map(|links| {
+                let mut list: Vec<(usize, f64)> = links.into_iter().collect();
+                list.sort_unstable_by_key(|&(d, _)| d);
+                list
+            })
+            .collect();
+        Weighted {
+            adj,
+            loops,
+        }
+    }
+
+    fn louvain(&self) -> Vec<usize> {
+        let mut assignment: Vec<usize> = (0..self.adj.len()).collect();
+        let mut level = None;
+        loop {
+            let graph = level.as_ref().unwrap_or(self);
+            let mut community: Vec<usize> = (0..graph.adj.len()).collect();
+            if !graph.move_vertices(&mut community) {
+                break;
+            }
+            let count = renumber(&mut community);
+            for a in assignment.iter_mut() {
+                *a = community[*a];
+            }
+            level = Some(graph.aggregate(&community, count));
+        }
+        assignment
+    }
+
+    fn label_propagation<R: Rng>(&self, rng: &mut R) -> Vec<usize> {
+        let n = self.adj.len();
+        let mut labels: Vec<usize> = (0..n).collect();
+        let mut order: Vec<usize> = (0..n).collect();
+        let mut counts = vec![0.0; n];
+        let mut touched = Vec::new();
+        for _ in 0..MAX_ROUNDS {
+            order.shuffle(rng);
+            let mut changed = false;
+            for &v in order.iter() {
+                for &(w, weight) in self.adj[v].iter() {
+                    if counts[labels[w]] == 0.0 {
+                        touched.push(labels[w]);
+                    }
+                    counts[labels[w]] += weight;
+                }
+                let most = touched.iter().map(|&l| counts[l]).fold(0.0, f64::max);
+                let candidates: Vec<usize> = touched.iter().copied().filter(|&l| counts[l] == most).collect();
+                for l in touched.drain(..) {
+                    counts[l] = 0.0;
+                }
+                if candidates.is_empty() || candidates.contains(&labels[v]) {
+                    continue;
+                }
+                labels[v] = *candidates.choose(rng).unwrap();
+                changed = true;
+            }
+            if !changed {
+                break;
+            }
+        }
+        labels
+    }
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    fn weighted<F: FnMut(&E) -> f64>(&self, mut weight: F) -> (Vec<VertexId>, Weighted) {
+        let ids: Vec<VertexId> = self.vertices().map(|(id, _)| id).collect();
+        let index: HashMap<VertexId, usize> = ids.iter()
+            .enumerate()
+            .map(|(i, &id)| (id, i))
+            .collect();
+        let mut links: Vec<HashMap<usize, f64>> = vec![HashMap::new(); ids.len()];
+        for (&(from, to), data) in self.edges() {
+            if from != to {
+                let (a, b) = (index[&from], index[&to]);
+                let w = weight(data);
+                *links[a].entry(b).or_insert(0.0) += w;
+                *links[b].entry(a).or_insert(0.0) += w;
+            }
+        }
+        let adj = links.into_iter()
+            .map(|links| {
+                let mut list: Vec<(usize, f64)> = links.into_iter().collect();
+                list.sort_unstable_by_key(|&(b, _)| b);
+                list
+            })
+            .collect();
+        let loops = vec![0.0; ids.len()];
+        (ids, Weighted {
+            adj,
+            loops,
+        })
+    }
+
+    fn partition(ids: Vec<VertexId>, graph: &Weighted, mut community: Vec<usize>) -> Partition<VertexId> {
+        let count = renumber(&mut community);
+        let mut communities = vec![Vec::new(); count];
+        for (&id, &c) in ids.iter().zip(community.iter()) {
+            communities[c].push(id);
+        }
+        Partition {
+            modularity: graph.modularity(&community, count),
+            community: ids.into_iter().zip(community).collect(),
+            communities,
+        }
+    }
+
+    /// Louvain community detection: every vertex moves to the community
+    /// of a neighbour while that raises the modularity, then every
+    /// community is merged into one vertex, and so on until nothing
+    /// moves. Vertices are visited in arena order, so the result is
+    /// always the same. Every edge has weight 1.
+    /// Time complexity: O(E log V) in practice
+    pub fn louvain(&self) -> Partition<VertexId> {
+        self.weighted_louvain(|_| 1.0)
+    }
+
+    /// [`Graph::louvain`] with the weight of every edge given by
+    /// `weight`, which must not be negative.
+    pub fn weighted_louvain<F: FnMut(&E) -> f64>(&self, weight: F) -> Partition<VertexId> {
+        let (ids, graph) = self.weighted(weight);
+        let community = graph.louvain();
+        Self::partition(ids, &graph, community)
+    }
+
+    /// Label propagation: every vertex starts in a community of its own,
+    /// then takes the community most of its neighbours are in, visiting
+    /// the vertices in random order, until no vertex changes. Faster
+    /// than [`Graph::louvain`] but less stable, ties are broken at
+    /// random.
+    /// Time complexity: O(E) for every round
+    pub fn label_propagation<R: Rng>(&self, rng: &mut R) -> Partition<VertexId> {
+        let (ids, graph) = self.weighted(|_| 1.0);
+        let community = graph.label_propagation(rng);
+        Self::partition(ids, &graph, community)
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::louvain`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// // two triangles joined by a bridge
+    /// let mut graph = GraphMap::<u32, ()>::new();
+    /// for &(a, b) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)].iter() {
+    ///     graph.add_edge((a, b), ());
+    /// }
+    ///
+    /// let partition = graph.louvain();
+    /// assert_eq!(partition.communities, vec![vec![&0, &1, &2], vec![&3, &4, &5]]);
+    /// assert_eq!(partition.community[&4], 1);
+    /// assert!((partition.modularity - 5.0 / 14.0).abs() < 1e-9);
+    /// ```
+    pub fn louvain(&self) -> Partition<&V> {
+        self.graph.louvain().map(|id| &self.graph.arena[id])
+    }
+
+    /// See [`Graph::weighted_louvain`].
+    pub fn weighted_louvain<F: FnMut(&E) -> f64>(&self, weight: F) -> Partition<&V> {
+        self.graph.weighted_louvain(weight).map(|id| &self.graph.arena[id])
+    }
+
+    /// See [`Graph::label_propagation`].
+    pub fn label_propagation<R: Rng>(&self, rng: &mut R) -> Partition<&V> {
+        self.graph.label_propagation(rng).map(|id| &self.graph.arena[id])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn cliques_joined_by_single_edges() {
+        // four cliques of five in a ring, then with heavy edges between
+        // them, which pull their ends together when weighted
+        let mut graph = GraphMap::<u32, f64>::new();
+        for clique in 0..4 {
+            for a in 0..5 {
+                for b in a + 1..5 {
+                    graph.add_edge((clique * 5 + a, clique * 5 + b), 1.0);
+                }
+            }
+            graph.add_edge((clique * 5, (clique + 1) % 4 * 5 + 1), 1.0);
+        }
+
+        let expected: Vec<Vec<u32>> = (0..4).map(|c| (c * 5..c * 5 + 5).collect()).collect();
+        let sorted = |communities: &[Vec<&u32>]| -> Vec<Vec<u32>> {
+            communities.iter()
+                .map(|c| {
+                    let mut c: Vec<u32> = c.iter().map(|&&v| v).collect();
+                    c.sort_unstable();
+                    c
+                })
+                .collect()
+        };
+        let louvain = graph.louvain();
+        assert_eq!(sorted(&louvain.communities), expected);
+        assert!(louvain.modularity > 0.6);
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let propagation = graph.label_propagation(&mut rng);
+        assert_eq!(sorted(&propagation.communities), expected);
+        assert!((propagation.modularity - louvain.modularity).abs() < 1e-9);
+
+        for clique in 0..4 {
+            graph.add_edge((clique * 5, (clique + 1) % 4 * 5 + 1), 100.0);
+        }
+        let weighted = graph.weighted_louvain(|&w| w);
+        assert_eq!(weighted.communities.len(), 4);
+        assert_eq!(weighted.community[&0], weighted.community[&6]);
+        assert_eq!(weighted.community[&15], weighted.community[&1]);
+        assert_ne!(weighted.community[&0], weighted.community[&1]);
+        assert!(weighted.modularity > 0.0);
+    }
+}
@@ -0,0 +1,260 @@
+//! Diameter, radius and eccentricities, with the edge directions ignored.
+
+use crate::property::VertexMap;
+use crate::{Graph, GraphMap, VertexId};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Bounds on the diameter, see [`Graph::diameter_bounds`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiameterBounds {
+    pub lower: usize,
+    pub upper: usize,
+}
+
+impl DiameterBounds {
+    /// True if the bounds meet, so the diameter is known.
+    pub fn is_exact(&self) -> bool {
+        self.lower == self.upper
+    }
+}
+
+const UNSEEN: usize = usize::MAX;
+
+/// Breadth first search state reused between runs. Only the visited
+/// vertices are reset, so searching many small components stays linear.
+struct Bfs {
+    distance: Vec<usize>,
+    parent: Vec<usize>,
+    order: Vec<usize>,
+    runs: usize,
+}
+
+impl Bfs {
+
+    fn new(n: usize) -> Self {
+        Bfs {
+            distance: vec![UNSEEN; n],
+            parent: vec![UNSEEN; n],
+            order: Vec::new(),
+            runs: 0,
+        }
+    }
+
+    /// Visits the component of `source`, in order of distance.
+    /// Returns the eccentricity of `source`.
+    fn run(&mut self, adj: &[Vec<usize>], source: usize) -> usize {
+        for &v in self.order.iter() {
+            self.distance[v] = UNSEEN;
+        }
+        self.order.clear();
+        self.runs += 1;
+
+        self.distance[source] = 0;
+        self.parent[source] = source;
+        self.order.push(source);
+        let mut head = 0;
+        while head < self.order.len() {
+            let v = self.order[head];
+            head += 1;
+            for &w in adj[v].iter() {
+                if self.distance[w] == UNSEEN {
+                    self.distance[w] = self.distance[v] + 1;
+                    self.parent[w] = v;
+                    self.order.push(w);
+                }
+            }
+        }
+        self.distance[*self.order.last().unwrap()]
+    }
+
+    fn farthest(&self) -> usize {
+        *self.order.last().unwrap()
+    }
+}
+
+/// iFUB on the component of `start`: a double sweep picks a central
+/// vertex `u`, then the eccentricities of the vertices farthest from
+/// `u` are computed level by level until the bounds meet or `budget`
+/// searches were run.
+fn ifub(adj: &[Vec<usize>], bfs: &mut Bfs, start: usize, budget: usize) -> DiameterBounds {
+    let runs = bfs.runs;
+    bfs.run(adj, start);
+    let a = bfs.farthest();
+    let lower = bfs.run(adj, a);
+
+    // Middle of the path from a to the vertex farthest from it.
+    let mut u = bfs.farthest();
+    for _ in 0..lower / 2 {
+        u = bfs.parent[u];
+    }
+    let eccentricity = bfs.run(adj, u);
+    let mut bounds = DiameterBounds {
+        lower: lower.max(eccentricity),
+        upper: 2 * eccentricity,
+    };
+
+    let mut levels: Vec<Vec<usize>> = vec![Vec::new(); eccentricity + 1];
+    for &v in bfs.order.iter() {
+        levels[bfs.distance[v]].push(v);
+    }
+    let mut i = eccentricity;
+    while bounds.upper > bounds.lower && i > 0 {
+        for &v in levels[i].iter() {
+            if bfs.runs - runs >= budget {
+                return bounds;
+            }
+            bounds.lower = bounds.lower.max(bfs.run(adj, v));
+        }
+        // Vertices at most i - 1 away from u are at most 2 (i - 1) away
+        // from each other, and the farther ones were all searched.
+        bounds.upper = bounds.lower.max(2 * (i - 1));
+        i -= 1;
+    }
+    bounds
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Lower bound on the diameter with a double sweep: the eccentricity
+    /// of the vertex farthest from the highest degree vertex of every
+    /// component. Often exact in practice. Edge directions are ignored.
+    /// Time complexity: O(V + E)
+    pub fn diameter_lower_bound(&self) -> usize {
+        self.diameter_bounds(0).lower
+    }
+
+    /// Bounds on the diameter, the largest distance in edges between two
+    /// vertices of a component, with edge directions ignored. Uses iFUB,
+    /// doing at most `budget` breadth first searches in every component
+    /// besides the first three. The bounds usually meet after a handful
+    /// of searches, even on graphs with millions of vertices.
+    /// Time complexity: O(budget (V + E))
+    pub fn diameter_bounds(&self, budget: usize) -> DiameterBounds {
+        let indexed = self.indexed();
+        let adj = indexed.undirected();
+        let n = adj.len();
+        let mut seen = vec![false; n];
+        let mut bfs = Bfs::new(n);
+        let mut bounds = DiameterBounds {
+            lower: 0,
+            upper: 0,
+        };
+
+        let mut by_degree: Vec<usize> = (0..n).collect();
+        by_degree.sort_unstable_by_key(|&v| std::cmp::Reverse(adj[v].len()));
+        for start in by_degree {
+            if seen[start] {
+                continue;
+            }
+            let component = ifub(&adj, &mut bfs, start, budget.saturating_add(3));
+            for &v in bfs.order.iter() {
+                seen[v] = true;
+            }
+            bounds.lower = bounds.lower.max(component.lower);
+            bounds.upper = bounds.upper.max(component.upper);
+        }
+        bounds
+    }
+
+    /// Exact diameter, see [`Graph::diameter_bounds`].
+    /// Time complexity: O(V (V + E)) in the worst case
+    pub fn diameter(&self) -> usize {
+        self.diameter_bounds(usize::MAX).lower
+    }
+
+    /// Eccentricity of the vertex: the largest distance in edges to
+    /// a vertex of its component, with edge directions ignored.
+    /// `None` if the vertex is not in the graph.
+    /// Time complexity: O(V + E)
+    pub fn eccentricity(&self, vertex: VertexId) -> Option<usize> {
+        let indexed = self.indexed();
+        let source = indexed.ids.iter().position(|&id| id == vertex)?;
+        let adj = indexed.undirected();
+        Some(Bfs::new(adj.len()).run(&adj, source))
+    }
+
+    /// Eccentricity of every vertex, with a breadth first search from
+    /// each. See [`Graph::eccentricity`].
+    /// Time complexity: O(V (V + E))
+    pub fn eccentricities(&self) -> VertexMap<usize> {
+        let indexed = self.indexed();
+        let adj = indexed.undirected();
+        let mut bfs = Bfs::new(adj.len());
+        indexed.ids.iter()
+            .enumerate()
+            .map(|(v, &id)| (id, bfs.run(&adj, v)))
+            .collect()
+    }
+
+    /// Radius: the smallest eccentricity, so 0 if a vertex is isolated.
+    /// 0 for the empty graph.
+    /// Time complexity: O(V (V + E))
+    pub fn radius(&self) -> usize {
+        self.eccentricities().into_iter().map(|(_, e)| e).min().unwrap_or(0)
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::diameter_lower_bound`].
+    pub fn diameter_lower_bound(&self) -> usize {
+        self.graph.diameter_lower_bound()
+    }
+
+    /// See [`Graph::diameter_bounds`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut graph = GraphMap::<u32, ()>::new();
+    /// for v in 0..100 {
+    ///     graph.add_edge((v, v + 1), ());
+    /// }
+    /// graph.add_edge((200, 201), ());
+    ///
+    /// let bounds = graph.diameter_bounds(10);
+    /// assert!(bounds.is_exact());
+    /// assert_eq!(bounds.lower, 100);
+    /// assert_eq!(graph.diameter(), 100);
+    /// ```
+    pub fn diameter_bounds(&self, budget: usize) -> DiameterBounds {
+        self.graph.diameter_bounds(budget)
+    }
+
+    /// See [`Graph::diameter`].
+    pub fn diameter(&self) -> usize {
+        self.graph.diameter()
+    }
+
+    /// See [`Graph::eccentricity`].
+    pub fn eccentricity(&self, vertex: &V) -> Option<usize> {
+        self.graph.eccentricity(*self.map.get(vertex)?)
+    }
+
+    /// See [`Graph::eccentricities`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut star = GraphMap::<u32, ()>::new();
+    /// for leaf in 1..5 {
+    ///     star.add_edge((0, leaf), ());
+    /// }
+    /// let eccentricity = star.eccentricities();
+    /// assert_eq!(eccentricity[&0], 1);
+    /// assert_eq!(eccentricity[&3], 2);
+    /// assert_eq!(star.radius(), 1);
+    /// assert_eq!(star.diameter(), 2);
+    /// ```
+    pub fn eccentricities(&self) -> HashMap<&V, usize> {
+        self.graph.eccentricities().into_iter()
+            .map(|(id, e)| (self.graph.get_vertex(id).unwrap(), e))
+            .collect()
+    }
+
+    /// See [`Graph::radius`].
+    pub fn radius(&self) -> usize {
+        self.graph.radius()
+    }
+}
@@ -0,0 +1,305 @@
+//! Maximum flow and minimum cut, with the edge data as capacities.
+//!
+//! Both algorithms work on a residual network where every edge has
+//! an arc with its capacity and a reverse arc starting empty, so
+//! opposite edges between two vertices are independent.
+
+use crate::{Graph, GraphMap, VertexId};
+use std::collections::{
+    HashMap,
+    HashSet,
+    VecDeque,
+};
+use std::hash::Hash;
+use std::ops::{Add, Sub};
+
+/// Maximum flow from a source to a sink, and a minimum cut.
+/// `T` identifies the vertices, [`VertexId`] or the vertex data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaxFlow<T: Eq + Hash, E> {
+    /// Total flow, equal to the capacity of the cut.
+    pub value: E,
+    /// Flow through every edge.
+    pub flow: HashMap<(T, T), E>,
+    /// Vertices still reachable from the source in the residual network,
+    /// the source side of a minimum cut.
+    pub source_side: HashSet<T>,
+    /// Edges going from the source side to the rest, all saturated.
+    pub cut: Vec<(T, T)>,
+}
+
+impl<T: Eq + Hash, E> MaxFlow<T, E> {
+    fn map<U: Eq + Hash, F: Fn(T) -> U>(self, f: F) -> MaxFlow<U, E> {
+        MaxFlow {
+            value: self.value,
+            flow: self.flow.into_iter().map(|((a, b), flow)| ((f(a), f(b)), flow)).collect(),
+            source_side: self.source_side.into_iter().map(&f).collect(),
+            cut: self.cut.into_iter().map(|(a, b)| (f(a), f(b))).collect(),
+        }
+    }
+}
+
+struct Arc<E> {
+    to: usize,
+    capacity: E,
+}
+
+/// Residual network. Arc `2i` is an edge and `2i + 1` its reverse.
+struct Network<E> {
+    ids: Vec<VertexId>,
+    index: HashMap<VertexId, usize>,
+    arcs: Vec<Arc<E>>,
+    adj: Vec<Vec<usize>>,
+    /// Edges of the graph, and their arc.
+    edges: Vec<(VertexId, VertexId, usize)>,
+    capacities: Vec<E>,
+}
+
+impl<E: Copy + Ord + Add<Output = E> + Sub<Output = E> + Default> Network<E> {
+
+    fn new<V: std::fmt::Debug>(graph: &Graph<V, E>) -> Self {
+        let ids: Vec<VertexId> = graph.vertices().map(|(id, _)| id).collect();
+        let index: HashMap<VertexId, usize> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+        let mut network = Network {
+            adj: vec![Vec::new(); ids.len()],
+            ids,
+            index,
+            arcs: Vec::with_capacity(2 * graph.edge_count()),
+            edges: Vec::with_capacity(graph.edge_count()),
+            capacities: Vec::with_capacity(graph.edge_count()),
+        };
+        for (&(from, to), &capacity) in graph.edges() {
+            let (u, v) = (network.index[&from], network.index[&to]);
+            let arc = network.arcs.len();
+            network.arcs.push(Arc { to: v, capacity });
+            network.arcs.push(Arc { to: u, capacity: E::default() });
+            network.adj[u].push(arc);
+            network.adj[v].push(arc + 1);
+            network.edges.push((from, to, arc));
+            network.capacities.push(capacity);
+        }
+        network
+    }
+
+    fn push(&mut self, arc: usize, amount: E) {
+        self.arcs[arc].capacity = self.arcs[arc].capacity - amount;
+        self.arcs[arc ^ 1].capacity = self.arcs[arc ^ 1].capacity + amount;
+    }
+
+    /// Breadth first distances from `source` over arcs with capacity left,
+    /// and the arc used to reach every vertex.
+    fn bfs(&self, source: usize) -> (Vec<usize>, Vec<usize>) {
+        let mut level = vec![usize::MAX; self.ids.len()];
+        let mut parent = vec![usize::MAX; self.ids.len()];
+        let mut queue = VecDeque::new();
+        level[source] = 0;
+        queue.push_back(source);
+        while let Some(u) = queue.pop_front() {
+            for &arc in self.adj[u].iter() {
+                let Arc { to, capacity } = self.arcs[arc];
+                if capacity > E::default() && level[to] == usize::MAX {
+                    level[to] = level[u] + 1;
+                    parent[to] = arc;
+                    queue.push_back(to);
+                }
+            }
+        }
+        (level, parent)
+    }
+
+    /// Augments along shortest paths, one at a time.
+    fn edmonds_karp(&mut self, source: usize, sink: usize) -> E {
+        let mut total = E::default();
+        loop {
+            let (level, parent) = self.bfs(source);
+            if level[sink] == usize::MAX {
+                return total;
+            }
+            let mut path = Vec::new();
+            let mut v = sink;
+            while v != source {
+                path.push(parent[v]);
+                v = self.arcs[parent[v] ^ 1].to;
+            }
+            let amount = path.iter().map(|&arc| self.arcs[arc].capacity).min().unwrap();
+            for arc in path {
+                self.push(arc, amount);
+            }
+            total = total + amount;
+        }
+    }
+
+    /// Augments along blocking flows of the level graph.
+    fn dinic(&mut self, source: usize, sink: usize) -> E {
+        let mut total = E::default();
+        loop {
+            let (mut level, _) = self.bfs(source);
+            if level[sink] == usize::MAX {
+                return total;
+            }
+            let mut next = vec![0; self.ids.len()];
+            while let Some(amount) = self.augment(source, sink, &mut level, &mut next) {
+                total = total + amount;
+            }
+        }
+    }
+
+    /// Finds and saturates one path of the level graph, skipping the arcs
+    /// already tried with `next`. Vertices found to be dead ends leave the
+    /// level graph.
+    fn augment(&mut self, source: usize, sink: usize, level: &mut [usize], next: &mut [usize]) -> Option<E> {
+        let mut path: Vec<usize> = Vec::new();
+        let mut u = source;
+        loop {
+            if u == sink {
+                let amount = path.iter().map(|&arc| self.arcs[arc].capacity).min().unwrap();
+                for &arc in path.iter() {
+                    self.push(arc, amount);
+                }
+                return Some(amount);
+            }
+            let mut advanced = false;
+            while next[u] < self.adj[u].len() {
+                let arc = self.adj[u][next[u]];
+                let Arc { to, capacity } = self.arcs[arc];
+                if capacity > E::default() && level[to] == level[u] + 1 {
+                    path.push(arc);
+                    u = to;
+                    advanced = true;
+                    break;
+                }
+                next[u] += 1;
+            }
+            if advanced {
+                continue;
+            }
+            level[u] = usize::MAX;
+            match path.pop() {
+                Some(arc) => {
+                    u = self.arcs[arc ^ 1].to;
+                    next[u] += 1;
+                }
+                None => return None,
+            }
+        }
+    }
+
+    /// Runs `algorithm` from `source` to `sink`, and reads the flow
+    /// and the cut off the residual network.
+    fn run(mut self, source: VertexId, sink: VertexId, algorithm: fn(&mut Self, usize, usize) -> E) -> MaxFlow<VertexId, E> {
+        let mut value = E::default();
+        let mut source_side = HashSet::new();
+        if let (Some(&s), Some(&t)) = (self.index.get(&source), self.index.get(&sink)) {
+            if s != t {
+                value = algorithm(&mut self, s, t);
+            }
+            let (level, _) = self.bfs(s);
+            source_side = self.ids.iter()
+                .zip(level)
+                .filter(|&(_, level)| level != usize::MAX)
+                .map(|(&id, _)| id)
+                .collect();
+        }
+        let mut flow = HashMap::with_capacity(self.edges.len());
+        let mut cut = Vec::new();
+        for (&(from, to, arc), &capacity) in self.edges.iter().zip(self.capacities.iter()) {
+            flow.insert((from, to), capacity - self.arcs[arc].capacity);
+            if source_side.contains(&from) && !source_side.contains(&to) {
+                cut.push((from, to));
+            }
+        }
+        MaxFlow {
+            value,
+            flow,
+            source_side,
+            cut,
+        }
+    }
+}
+
+impl<V, E> Graph<V, E>
+where
+    V: std::fmt::Debug,
+    E: Copy + Ord + Add<Output = E> + Sub<Output = E> + Default,
+{
+
+    /// Maximum flow from `source` to `sink` with the Edmonds-Karp
+    /// algorithm, using the edges as capacities. If either vertex is
+    /// missing, or they are the same, the flow is zero.
+    /// Time complexity: O(V E^2)
+    pub fn edmonds_karp(&self, source: VertexId, sink: VertexId) -> MaxFlow<VertexId, E> {
+        Network::new(self).run(source, sink, Network::edmonds_karp)
+    }
+
+    /// Maximum flow from `source` to `sink` with Dinic's algorithm,
+    /// see [`Graph::edmonds_karp`].
+    /// Time complexity: O(V^2 E)
+    pub fn dinic(&self, source: VertexId, sink: VertexId) -> MaxFlow<VertexId, E> {
+        Network::new(self).run(source, sink, Network::dinic)
+    }
+}
+
+impl<V, E> GraphMap<V, E>
+where
+    V: Eq + Hash + Clone + std::fmt::Debug,
+    E: Copy + Ord + Add<Output = E> + Sub<Output = E> + Default,
+{
+
+    /// See [`Graph::edmonds_karp`]. `None` if either vertex is missing.
+    pub fn edmonds_karp(&self, source: &V, sink: &V) -> Option<MaxFlow<&V, E>> {
+        let (&s, &t) = (self.map.get(source)?, self.map.get(sink)?);
+        Some(self.graph.edmonds_karp(s, t).map(|id| self.graph.get_vertex(id).unwrap()))
+    }
+
+    /// See [`Graph::dinic`]. `None` if either vertex is missing.
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut graph = GraphMap::<&str, u32>::new();
+    /// graph.add_edge(("s", "a"), 3);
+    /// graph.add_edge(("s", "b"), 2);
+    /// graph.add_edge(("a", "b"), 5);
+    /// graph.add_edge(("a", "t"), 2);
+    /// graph.add_edge(("b", "t"), 3);
+    ///
+    /// let flow = graph.dinic(&"s", &"t").unwrap();
+    /// assert_eq!(flow.value, 5);
+    /// assert_eq!(flow.flow[&(&"a", &"b")], 1);
+    /// assert_eq!(flow.source_side.len(), 1);
+    /// assert_eq!(flow.cut.len(), 2);
+    /// ```
+    pub fn dinic(&self, source: &V, sink: &V) -> Option<MaxFlow<&V, E>> {
+        let (&s, &t) = (self.map.get(source)?, self.map.get(sink)?);
+        Some(self.graph.dinic(s, t).map(|id| self.graph.get_vertex(id).unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+
+    #[test]
+    fn algorithms_agree_on_a_grid() {
+        let mut graph = Graph::<usize, u32>::new();
+        let ids: Vec<_> = (0..16).map(|i| graph.add_vertex(i)).collect();
+        for i in 0..16 {
+            if i % 4 != 3 {
+                graph.add_edge((ids[i], ids[i + 1]), (i * 7 % 5 + 1) as u32).unwrap();
+                graph.add_edge((ids[i + 1], ids[i]), (i * 3 % 4 + 1) as u32).unwrap();
+            }
+            if i < 12 {
+                graph.add_edge((ids[i], ids[i + 4]), (i * 5 % 6 + 1) as u32).unwrap();
+            }
+        }
+        let slow = graph.edmonds_karp(ids[0], ids[15]);
+        let fast = graph.dinic(ids[0], ids[15]);
+        assert_eq!(slow.value, fast.value);
+        let cut: u32 = fast.cut.iter().map(|&edge| graph.get_edge(edge).unwrap()).sum();
+        assert_eq!(cut, fast.value);
+        for (&(from, to), &flow) in fast.flow.iter() {
+            assert!(flow <= *graph.get_edge((from, to)).unwrap());
+        }
+        assert_eq!(graph.dinic(ids[3], ids[3]).value, 0);
+    }
+}
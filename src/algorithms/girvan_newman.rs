@@ -0,0 +1,242 @@
+use crate::{Graph, GraphMap, VertexId};
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+/// Communities found by [`Graph::girvan_newman`], as a dendrogram:
+/// every level splits one community of the level before it in two.
+/// `T` identifies the vertices, [`VertexId`] or the vertex data.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dendrogram<T> {
+    /// Partitions from the connected components down to single
+    /// vertices. Communities are sorted by their first vertex.
+    pub levels: Vec<Vec<Vec<T>>>,
+    /// Modularity of every level.
+    pub modularity: Vec<f64>,
+}
+
+impl<T> Dendrogram<T> {
+
+    /// Level with the highest modularity, the first one on ties.
+    pub fn best(&self) -> &[Vec<T>] {
+        let mut best = 0;
+        for (level, &modularity) in self.modularity.iter().enumerate() {
+            if modularity > self.modularity[best] {
+                best = level;
+            }
+        }
+        self.levels.get(best).map_or(&[], |level| level.as_slice())
+    }
+
+    fn map<U, F: Fn(T) -> U>(self, f: F) -> Dendrogram<U> {
+        Dendrogram {
+            levels: self.levels.into_iter()
+                .map(|level| level.into_iter().map(|c| c.into_iter().map(&f).collect()).collect())
+                .collect(),
+            modularity: self.modularity,
+        }
+    }
+}
+
+/// Betweenness of every edge of an undirected graph, with Brandes'
+/// algorithm. `betweenness[v][i]` belongs to the edge to `adj[v][i]`,
+/// and counts the shortest paths through it in both directions.
+fn edge_betweenness(adj: &[Vec<usize>]) -> Vec<Vec<f64>> {
+    let n = adj.len();
+    let mut betweenness: Vec<Vec<f64>> = adj.iter().map(|list| vec![0.0; list.len()]).collect();
+    let mut distance = vec![usize::MAX; n];
+    let mut paths = vec![0.0; n];
+    let mut dependency = vec![0.0; n];
+    let mut order = Vec::with_capacity(n);
+    let mut queue = VecDeque::new();
+
+    for source in 0..n {
+        distance.iter_mut().for_each(|d| *d = usize::MAX);
+        paths.iter_mut().for_each(|p| *p = 0.0);
+        dependency.iter_mut().for_each(|d| *d = 0.0);
+        order.clear();
+
+        distance[source] = 0;
+        paths[source] = 1.0;
+        queue.push_back(source);
+        while let Some(v) = queue.pop_front() {
+            order.push(v);
+            for &w in adj[v].iter() {
+                if distance[w] == usize::MAX {
+                    distance[w] = distance[v] + 1;
+                    queue.push_back(w);
+                }
+                if distance[w] == distance[v] + 1 {
+                    paths[w] += paths[v];
+                }
+            }
+        }
+
+        for &w in order.iter().rev() {
+            for (i, &v) in adj[w].iter().enumerate() {
+                if distance[v] != usize::MAX && distance[v] + 1 == distance[w] {
+                    let share = paths[v] / paths[w] * (1.0 + dependency[w]);
+                    betweenness[w][i] += share;
+                    dependency[v] += share;
+                }
+            }
+        }
+    }
+
+    // Each path was found from both ends, and each edge is in both lists.
+    for (v, list) in adj.iter().enumerate() {
+        for (i, &w) in list.iter().enumerate() {
+            if v < w {
+                let j = adj[w].binary_search(&v).unwrap();
+                let total = betweenness[v][i] + betweenness[w][j];
+                betweenness[v][i] = total;
+                betweenness[w][j] = total;
+            }
+        }
+    }
+    betweenness
+}
+
+/// Connected components, as the community of every vertex numbered
+/// in order of their first vertex.
+fn components(adj: &[Vec<usize>]) -> (Vec<usize>, usize) {
+    let mut community = vec![usize::MAX; adj.len()];
+    let mut count = 0;
+    for start in 0..adj.len() {
+        if community[start] != usize::MAX {
+            continue;
+        }
+        community[start] = count;
+        let mut stack = vec![start];
+        while let Some(v) = stack.pop() {
+            for &w in adj[v].iter() {
+                if community[w] == usize::MAX {
+                    community[w] = count;
+                    stack.push(w);
+                }
+            }
+        }
+        count += 1;
+    }
+    (community, count)
+}
+
+/// Newman's modularity of a partition of the undirected graph `adj`.
+fn modularity(adj: &[Vec<usize>], community: &[usize], count: usize) -> f64 {
+    let twice_edges: usize = adj.iter().map(|list| list.len()).sum();
+    if twice_edges == 0 {
+        return 0.0;
+    }
+    let mut inside = vec![0; count];
+    let mut degrees = vec![0; count];
+    for (v, list) in adj.iter().enumerate() {
+        degrees[community[v]] += list.len();
+        inside[community[v]] += list.iter().filter(|&&w| community[w] == community[v]).count();
+    }
+    let m = twice_edges as f64;
+    inside.iter()
+        .zip(degrees.iter())
+        .map(|(&inside, &degree)| inside as f64 / m - (degree as f64 / m).powi(2))
+        .sum()
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Girvan–Newman community detection, with the edge directions
+    /// ignored: the edge with the highest betweenness is removed until
+    /// none are left, and the partition is recorded every time a
+    /// component splits. Modularity is measured on the whole graph.
+    /// Time complexity: O(E² V)
+    pub fn girvan_newman(&self) -> Dendrogram<VertexId> {
+        let indexed = self.indexed();
+        let original = indexed.undirected();
+        let mut adj = original.clone();
+        let mut levels = Vec::new();
+        let mut scores = Vec::new();
+
+        let mut record = |adj: &[Vec<usize>]| {
+            let (community, count) = components(adj);
+            let mut level = vec![Vec::new(); count];
+            for (v, &c) in community.iter().enumerate() {
+                level[c].push(indexed.ids[v]);
+            }
+            levels.push(level);
+            scores.push(modularity(&original, &community, count));
+            count
+        };
+
+        let mut count = record(&adj);
+        while adj.iter().any(|list| !list.is_empty()) {
+            let betweenness = edge_betweenness(&adj);
+            let mut heaviest = (0, 0);
+            let mut highest = f64::NEG_INFINITY;
+            for (v, list) in adj.iter().enumerate() {
+                for (i, &w) in list.iter().enumerate() {
+                    // Tiny differences are rounding, keep the first edge.
+                    if v < w && betweenness[v][i] > highest + 1e-9 {
+                        highest = betweenness[v][i];
+                        heaviest = (v, w);
+                    }
+                }
+            }
+            let (v, w) = heaviest;
+            adj[v].retain(|&x| x != w);
+            adj[w].retain(|&x| x != v);
+
+            if components(&adj).1 > count {
+                count = record(&adj);
+            }
+        }
+
+        Dendrogram {
+            levels,
+            modularity: scores,
+        }
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::girvan_newman`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// // Two triangles joined by a bridge.
+    /// let mut graph = GraphMap::<u32, ()>::new();
+    /// for &(a, b) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)].iter() {
+    ///     graph.add_edge((a, b), ());
+    /// }
+    ///
+    /// let dendrogram = graph.girvan_newman();
+    /// let mut best: Vec<Vec<u32>> = dendrogram.best()
+    ///     .iter()
+    ///     .map(|c| {
+    ///         let mut c: Vec<u32> = c.iter().map(|&&v| v).collect();
+    ///         c.sort();
+    ///         c
+    ///     })
+    ///     .collect();
+    /// best.sort();
+    /// assert_eq!(best, vec![vec![0, 1, 2], vec![3, 4, 5]]);
+    /// ```
+    pub fn girvan_newman(&self) -> Dendrogram<&V> {
+        self.graph.girvan_newman().map(|id| self.graph.get_vertex(id).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{edge_betweenness, modularity};
+
+    #[test]
+    fn betweenness_of_a_path() {
+        let adj = vec![vec![1], vec![0, 2], vec![1, 3], vec![2]];
+        let betweenness = edge_betweenness(&adj);
+        assert_eq!(betweenness[0], vec![6.0]);
+        assert_eq!(betweenness[1], vec![6.0, 8.0]);
+        assert_eq!(betweenness[3], vec![6.0]);
+
+        assert_eq!(modularity(&adj, &[0, 0, 0, 0], 1), 0.0);
+        assert!(modularity(&adj, &[0, 0, 1, 1], 2) > 0.0);
+    }
+}
@@ -0,0 +1,174 @@
+
+use crate::{Graph, GraphMap, VertexId};
+use super::Indexed;
+use std::hash::Hash;
+use std::collections::HashMap;
+
+/// Graphlet degree vector of a vertex: how many times it touches
+/// each of the 15 orbits of the 2, 3 and 4 vertex graphlets.
+///
+/// | graphlet | shape | orbits |
+/// |---|---|---|
+/// | G0 | edge | 0 |
+/// | G1 | path of 3 | 1 (end), 2 (middle) |
+/// | G2 | triangle | 3 |
+/// | G3 | path of 4 | 4 (end), 5 (middle) |
+/// | G4 | star | 6 (leaf), 7 (center) |
+/// | G5 | 4-cycle | 8 |
+/// | G6 | triangle with a tail | 9 (tail end), 10 (triangle, degree 2), 11 (degree 3) |
+/// | G7 | 4-cycle with a chord | 12 (degree 2), 13 (degree 3) |
+/// | G8 | 4-clique | 14 |
+pub type Orbits = [u64; 15];
+
+/// Number of orbits that affect each orbit, used to weight
+/// the orbits when comparing signatures.
+const ORBIT_DEPENDENCIES: [u32; 15] = [1, 2, 2, 2, 3, 4, 3, 3, 4, 3, 4, 4, 4, 4, 3];
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Graphlet degree vector of every vertex, with edge directions ignored.
+    /// Every connected induced subgraph with up to 4 vertices is
+    /// enumerated once (ESU algorithm), so this is only practical for
+    /// sparse graphs.
+    pub fn graphlet_degree_vectors(&self) -> HashMap<VertexId, Orbits> {
+        let (ids, orbits, _) = self.count_graphlets();
+        ids.into_iter().zip(orbits).collect()
+    }
+
+    /// Number of occurrences of every graphlet G0 to G8,
+    /// see [`Orbits`] for the numbering.
+    pub fn graphlet_counts(&self) -> [u64; 9] {
+        self.count_graphlets().2
+    }
+
+    fn count_graphlets(&self) -> (Vec<VertexId>, Vec<Orbits>, [u64; 9]) {
+        let indexed = self.indexed();
+        let adj = indexed.undirected();
+        let Indexed { ids, .. } = indexed;
+
+        let mut counter = Counter {
+            adj: &adj,
+            orbits: vec![[0; 15]; ids.len()],
+            graphlets: [0; 9],
+        };
+        for (root, neighbours) in adj.iter().enumerate() {
+            let extension = neighbours.iter().copied().filter(|&u| u > root).collect();
+            counter.extend(&mut vec![root], extension, root);
+        }
+
+        let Counter { orbits, graphlets, .. } = counter;
+        (ids, orbits, graphlets)
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// Graphlet degree vectors, see [`Graph::graphlet_degree_vectors`].
+    pub fn graphlet_degree_vectors(&self) -> HashMap<V, Orbits> {
+        self.graph.graphlet_degree_vectors()
+            .into_iter()
+            .map(|(id, orbits)| (self.graph.get_vertex(id).unwrap().clone(), orbits))
+            .collect()
+    }
+
+    /// Graphlet counts, see [`Graph::graphlet_counts`].
+    pub fn graphlet_counts(&self) -> [u64; 9] {
+        self.graph.graphlet_counts()
+    }
+}
+
+/// Similarity between two graphlet degree vectors, in `[0, 1]`.
+/// Orbits that depend on fewer other orbits weigh more, and the
+/// difference on each orbit is measured on a log scale.
+pub fn signature_similarity(a: &Orbits, b: &Orbits) -> f64 {
+    let mut distance = 0.0;
+    let mut total_weight = 0.0;
+    for i in 0..15 {
+        let weight = 1.0 - (ORBIT_DEPENDENCIES[i] as f64).ln() / 73f64.ln();
+        let (x, y) = (a[i] as f64, b[i] as f64);
+        distance += weight * ((x + 1.0).ln() - (y + 1.0).ln()).abs() / (x.max(y) + 2.0).ln();
+        total_weight += weight;
+    }
+    1.0 - distance / total_weight
+}
+
+struct Counter<'a> {
+    adj: &'a [Vec<usize>],
+    orbits: Vec<Orbits>,
+    graphlets: [u64; 9],
+}
+
+impl<'a> Counter<'a> {
+
+    fn adjacent(&self, a: usize, b: usize) -> bool {
+        self.adj[a].binary_search(&b).is_ok()
+    }
+
+    /// ESU: grows `sub` with vertices from `extension`, only ever adding
+    /// vertices greater than `root` that were not already next to `sub`,
+    /// so every subgraph is reached exactly once.
+    fn extend(&mut self, sub: &mut Vec<usize>, mut extension: Vec<usize>, root: usize) {
+        if sub.len() >= 2 {
+            self.classify(sub);
+        }
+        if sub.len() == 4 {
+            return;
+        }
+        while let Some(w) = extension.pop() {
+            let mut next = extension.clone();
+            for &u in self.adj[w].iter() {
+                if u > root && !sub.contains(&u) && !sub.iter().any(|&s| self.adjacent(s, u)) {
+                    next.push(u);
+                }
+            }
+            sub.push(w);
+            self.extend(sub, next, root);
+            sub.pop();
+        }
+    }
+
+    fn classify(&mut self, sub: &[usize]) {
+        let degree: Vec<usize> = sub.iter()
+            .map(|&a| sub.iter().filter(|&&b| self.adjacent(a, b)).count())
+            .collect();
+        let edges = degree.iter().sum::<usize>() / 2;
+        let max_degree = *degree.iter().max().unwrap();
+
+        // graphlet, then the orbit of a vertex of degree 1, 2, 3
+        let (graphlet, orbit_by_degree): (usize, [usize; 4]) = match (sub.len(), edges, max_degree) {
+            (2, _, _) => (0, [0, 0, 0, 0]),
+            (3, 2, _) => (1, [0, 1, 2, 0]),
+            (3, _, _) => (2, [0, 0, 3, 0]),
+            (4, 3, 2) => (3, [0, 4, 5, 0]),
+            (4, 3, _) => (4, [0, 6, 0, 7]),
+            (4, 4, 2) => (5, [0, 0, 8, 0]),
+            (4, 4, _) => (6, [0, 9, 10, 11]),
+            (4, 5, _) => (7, [0, 0, 12, 13]),
+            _ => (8, [0, 0, 0, 14]),
+        };
+
+        self.graphlets[graphlet] += 1;
+        for (&v, &d) in sub.iter().zip(degree.iter()) {
+            self.orbits[v][orbit_by_degree[d]] += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+
+    #[test]
+    fn orbits_of_paw() {
+        let mut graph = GraphMap::<u32, ()>::new();
+        for &(a, b) in [(0, 1), (1, 2), (2, 0), (2, 3)].iter() {
+            graph.add_edge((a, b), ());
+        }
+        let counts = graph.graphlet_counts();
+        assert_eq!(counts, [4, 2, 1, 0, 0, 0, 1, 0, 0]);
+
+        let gdv = graph.graphlet_degree_vectors();
+        assert_eq!(gdv[&3], [1, 2, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0]);
+        assert_eq!(gdv[&2], [3, 0, 2, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,361 @@
+//! Graph equality and isomorphism.
+//!
+//! [`GraphMap::structural_eq`] compares two graphs under their labels.
+//! The isomorphism search ignores labels and ids and looks for a
+//! bijection between the vertices keeping the edges, with the VF2
+//! algorithm of Cordella et al. Edge directions and self loops count.
+
+use crate::{Graph, GraphMap, VertexId};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+const NONE: usize = usize::MAX;
+
+/// One of the two graphs, with its vertices numbered in arena
+/// order, and the state of the search on it.
+struct Side<'a, V, E> {
+    ids: Vec<VertexId>,
+    data: Vec<&'a V>,
+    succ: Vec<Vec<usize>>,
+    pred: Vec<Vec<usize>>,
+    edges: HashMap<(usize, usize), &'a E>,
+    /// Vertex of the other graph matched to every vertex.
+    core: Vec<usize>,
+    /// Depth at which every vertex entered the outbound and inbound
+    /// terminal sets, 0 if it hasn't.
+    out: Vec<usize>,
+    inbound: Vec<usize>,
+}
+
+impl<'a, V: std::fmt::Debug, E> Side<'a, V, E> {
+
+    fn new(graph: &'a Graph<V, E>) -> Self {
+        let (ids, data): (Vec<VertexId>, Vec<&V>) = graph.vertices().unzip();
+        let index: HashMap<VertexId, usize> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+        let n = ids.len();
+        let mut side = Side {
+            ids,
+            data,
+            succ: vec![Vec::new(); n],
+            pred: vec![Vec::new(); n],
+            edges: HashMap::with_capacity(graph.edge_count()),
+            core: vec![NONE; n],
+            out: vec![0; n],
+            inbound: vec![0; n],
+        };
+        for (&(from, to), weight) in graph.edges() {
+            let (from, to) = (index[&from], index[&to]);
+            side.edges.insert((from, to), weight);
+            if from != to {
+                side.succ[from].push(to);
+                side.pred[to].push(from);
+            }
+        }
+        side
+    }
+
+    fn degrees(&self) -> Vec<(usize, usize)> {
+        let mut degrees: Vec<(usize, usize)> = self.succ.iter()
+            .zip(self.pred.iter())
+            .map(|(succ, pred)| (succ.len(), pred.len()))
+            .collect();
+        degrees.sort_unstable();
+        degrees
+    }
+
+    fn in_out(&self, v: usize) -> bool {
+        self.out[v] > 0 && self.core[v] == NONE
+    }
+
+    fn in_in(&self, v: usize) -> bool {
+        self.inbound[v] > 0 && self.core[v] == NONE
+    }
+
+    /// Counts of the unmatched `neighbours` in the outbound terminal set,
+    /// in the inbound one, and in neither, and of the matched ones.
+    fn classify(&self, v: usize, neighbours: &[usize]) -> [usize; 4] {
+        let mut counts = [0; 4];
+        for &w in neighbours.iter().filter(|&&w| w != v) {
+            if self.core[w] != NONE {
+                counts[3] += 1;
+                continue;
+            }
+            if self.out[w] > 0 {
+                counts[0] += 1;
+            }
+            if self.inbound[w] > 0 {
+                counts[1] += 1;
+            }
+            if self.out[w] == 0 && self.inbound[w] == 0 {
+                counts[2] += 1;
+            }
+        }
+        counts
+    }
+
+    fn push(&mut self, v: usize, other: usize, depth: usize) {
+        self.core[v] = other;
+        if self.out[v] == 0 {
+            self.out[v] = depth;
+        }
+        if self.inbound[v] == 0 {
+            self.inbound[v] = depth;
+        }
+        for &w in self.succ[v].iter() {
+            if self.out[w] == 0 {
+                self.out[w] = depth;
+            }
+        }
+        for &w in self.pred[v].iter() {
+            if self.inbound[w] == 0 {
+                self.inbound[w] = depth;
+            }
+        }
+    }
+
+    fn pop(&mut self, v: usize, depth: usize) {
+        self.core[v] = NONE;
+        for w in std::iter::once(v).chain(self.succ[v].iter().copied()).chain(self.pred[v].iter().copied()) {
+            if self.out[w] == depth {
+                self.out[w] = 0;
+            }
+            if self.inbound[w] == depth {
+                self.inbound[w] = 0;
+            }
+        }
+    }
+}
+
+struct Matcher<'a, V1, E1, V2, E2, FV, FE> {
+    first: Side<'a, V1, E1>,
+    second: Side<'a, V2, E2>,
+    vertex_match: FV,
+    edge_match: FE,
+}
+
+impl<'a, V1, E1, V2, E2, FV, FE> Matcher<'a, V1, E1, V2, E2, FV, FE>
+where
+    V1: std::fmt::Debug,
+    V2: std::fmt::Debug,
+    FV: FnMut(&V1, &V2) -> bool,
+    FE: FnMut(&E1, &E2) -> bool,
+{
+
+    /// Next pairs to try: the first vertex of a terminal set of the first
+    /// graph against every vertex of the same set of the second.
+    fn candidates(&self) -> Option<(usize, Vec<usize>)> {
+        type Member<'s, V, E> = fn(&Side<'s, V, E>, usize) -> bool;
+        let sets: [(Member<V1, E1>, Member<V2, E2>); 3] = [
+            (Side::in_out, Side::in_out),
+            (Side::in_in, Side::in_in),
+            (|side, v| side.core[v] == NONE, |side, v| side.core[v] == NONE),
+        ];
+        for &(first, second) in sets.iter() {
+            let n = (0..self.first.ids.len()).find(|&v| first(&self.first, v));
+            let ms: Vec<usize> = (0..self.second.ids.len()).filter(|&m| second(&self.second, m)).collect();
+            match n {
+                Some(n) if !ms.is_empty() => return Some((n, ms)),
+                None if ms.is_empty() => continue,
+                _ => return None,
+            }
+        }
+        None
+    }
+
+    fn edge_matches(&mut self, edge: (usize, usize), other: (usize, usize)) -> bool {
+        match (self.first.edges.get(&edge), self.second.edges.get(&other)) {
+            (Some(a), Some(b)) => (self.edge_match)(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    fn feasible(&mut self, n: usize, m: usize) -> bool {
+        if !(self.vertex_match)(self.first.data[n], self.second.data[m]) {
+            return false;
+        }
+        if !self.edge_matches((n, n), (m, m)) {
+            return false;
+        }
+        if self.first.classify(n, &self.first.succ[n]) != self.second.classify(m, &self.second.succ[m])
+            || self.first.classify(n, &self.first.pred[n]) != self.second.classify(m, &self.second.pred[m])
+        {
+            return false;
+        }
+        // Same numbers of matched neighbours on both sides, so checking
+        // those of `n` is enough.
+        for i in 0..self.first.succ[n].len() {
+            let s = self.first.succ[n][i];
+            let t = self.first.core[s];
+            if t != NONE && !self.edge_matches((n, s), (m, t)) {
+                return false;
+            }
+        }
+        for i in 0..self.first.pred[n].len() {
+            let p = self.first.pred[n][i];
+            let q = self.first.core[p];
+            if q != NONE && !self.edge_matches((p, n), (q, m)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn search(&mut self, depth: usize) -> bool {
+        if depth > self.first.ids.len() {
+            return true;
+        }
+        let (n, ms) = match self.candidates() {
+            Some(candidates) => candidates,
+            None => return false,
+        };
+        for m in ms {
+            if !self.feasible(n, m) {
+                continue;
+            }
+            self.first.push(n, m, depth);
+            self.second.push(m, n, depth);
+            if self.search(depth + 1) {
+                return true;
+            }
+            self.first.pop(n, depth);
+            self.second.pop(m, depth);
+        }
+        false
+    }
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Finds an isomorphism to `other`: a bijection between the vertices
+    /// such that every edge maps to an edge, and the other way around.
+    /// The data is ignored.
+    /// Time complexity: O(V! V) in the worst case, close to O(V^2) in practice.
+    pub fn find_isomorphism<V2: std::fmt::Debug, E2>(&self, other: &Graph<V2, E2>) -> Option<HashMap<VertexId, VertexId>> {
+        self.find_isomorphism_matching(other, |_, _| true, |_, _| true)
+    }
+
+    /// Whether the graphs are isomorphic, see [`Graph::find_isomorphism`].
+    pub fn is_isomorphic<V2: std::fmt::Debug, E2>(&self, other: &Graph<V2, E2>) -> bool {
+        self.find_isomorphism(other).is_some()
+    }
+
+    /// Like [`Graph::find_isomorphism`], but vertices can only map to
+    /// vertices for which `vertex_match` is true, and edges to edges
+    /// for which `edge_match` is true.
+    pub fn find_isomorphism_matching<V2, E2, FV, FE>(&self, other: &Graph<V2, E2>, vertex_match: FV, edge_match: FE) -> Option<HashMap<VertexId, VertexId>>
+    where
+        V2: std::fmt::Debug,
+        FV: FnMut(&V, &V2) -> bool,
+        FE: FnMut(&E, &E2) -> bool,
+    {
+        let mut matcher = Matcher {
+            first: Side::new(self),
+            second: Side::new(other),
+            vertex_match,
+            edge_match,
+        };
+        if self.edge_count() != other.edge_count() || matcher.first.degrees() != matcher.second.degrees() {
+            return None;
+        }
+        if !matcher.search(1) {
+            return None;
+        }
+        let Matcher { first, second, .. } = matcher;
+        Some(first.core.iter()
+            .enumerate()
+            .map(|(n, &m)| (first.ids[n], second.ids[m]))
+            .collect())
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// Whether both graphs have the same vertices, and the same
+    /// edges with equal data.
+    /// Time complexity: O(V + E)
+    pub fn structural_eq(&self, other: &GraphMap<V, E>) -> bool
+    where
+        E: PartialEq,
+    {
+        self.vertex_count() == other.vertex_count()
+            && self.edge_count() == other.edge_count()
+            && self.map.keys().all(|v| other.map.contains_key(v))
+            && self.edges().all(|((from, to), weight)| other.get_edge((from.clone(), to.clone())) == Some(weight))
+    }
+
+    /// See [`Graph::find_isomorphism`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut square = GraphMap::<u32, ()>::new();
+    /// let mut renamed = GraphMap::<&str, ()>::new();
+    /// for &(a, b) in [(0, 1), (1, 2), (2, 3), (3, 0)].iter() {
+    ///     square.add_edge((a, b), ());
+    /// }
+    /// for &(a, b) in [("w", "x"), ("y", "z"), ("x", "y"), ("z", "w")].iter() {
+    ///     renamed.add_edge((a, b), ());
+    /// }
+    ///
+    /// let mapping = square.find_isomorphism(&renamed).unwrap();
+    /// assert!(mapping.iter().all(|(&a, &b)| renamed.contains_edge((b, mapping[&((a + 1) % 4)]))));
+    ///
+    /// renamed.remove_edge(("z", "w"));
+    /// renamed.add_edge(("w", "z"), ());
+    /// assert!(!square.is_isomorphic(&renamed));
+    /// ```
+    pub fn find_isomorphism<'a, V2, E2>(&'a self, other: &'a GraphMap<V2, E2>) -> Option<HashMap<&'a V, &'a V2>>
+    where
+        V2: Eq + Hash + Clone + std::fmt::Debug,
+    {
+        let mapping = self.graph.find_isomorphism(&other.graph)?;
+        Some(mapping.into_iter()
+            .map(|(a, b)| (self.graph.get_vertex(a).unwrap(), other.graph.get_vertex(b).unwrap()))
+            .collect())
+    }
+
+    /// See [`Graph::is_isomorphic`].
+    pub fn is_isomorphic<V2, E2>(&self, other: &GraphMap<V2, E2>) -> bool
+    where
+        V2: Eq + Hash + Clone + std::fmt::Debug,
+    {
+        self.graph.is_isomorphic(&other.graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+    use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+    #[test]
+    fn shuffled_graphs_are_isomorphic() {
+        let mut rng = StdRng::seed_from_u64(9);
+        for _ in 0..20 {
+            let mut graph = GraphMap::<u32, u32>::new();
+            for v in 0..12 {
+                graph.add_vertex(v);
+            }
+            for _ in 0..30 {
+                graph.add_edge((rng.gen_range(0..12), rng.gen_range(0..12)), rng.gen_range(0..3));
+            }
+            let mut relabel: Vec<u32> = (100..112).collect();
+            relabel.shuffle(&mut rng);
+            let mut shuffled = GraphMap::<u32, u32>::new();
+            for &v in relabel.iter() {
+                shuffled.add_vertex(v);
+            }
+            for ((&a, &b), &w) in graph.edges() {
+                shuffled.add_edge((relabel[a as usize], relabel[b as usize]), w);
+            }
+
+            let mapping = graph.graph.find_isomorphism_matching(&shuffled.graph, |_, _| true, |a, b| a == b).unwrap();
+            for (&(from, to), weight) in graph.graph.edges() {
+                assert_eq!(shuffled.graph.get_edge((mapping[&from], mapping[&to])), Some(weight));
+            }
+            assert!(!graph.structural_eq(&shuffled));
+            assert!(graph.structural_eq(&graph.clone()));
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{Graph, GraphMap};
+use std::hash::Hash;
+
+/// Backtracking search for a subdivision of `pattern` in `graph`, both
+/// undirected adjacency lists: pattern vertices are mapped to distinct
+/// branch vertices, then pattern edges are routed one by one along paths
+/// that share no vertex besides their ends.
+struct Search<'a> {
+    graph: &'a [Vec<usize>],
+    pattern: &'a [Vec<usize>],
+    pattern_edges: Vec<(usize, usize)>,
+    branch: Vec<usize>,
+    used: Vec<bool>,
+}
+
+impl<'a> Search<'a> {
+
+    fn assign(&mut self, order: &[usize]) -> bool {
+        let p = match order.first() {
+            Some(&p) => p,
+            None => return self.route(0),
+        };
+        for v in 0..self.graph.len() {
+            if self.used[v] || self.graph[v].len() < self.pattern[p].len() {
+                continue;
+            }
+            self.used[v] = true;
+            self.branch[p] = v;
+            if self.assign(&order[1..]) {
+                return true;
+            }
+            self.used[v] = false;
+        }
+        false
+    }
+
+    fn route(&mut self, edge: usize) -> bool {
+        match self.pattern_edges.get(edge) {
+            Some(&(a, b)) => {
+                let (from, to) = (self.branch[a], self.branch[b]);
+                self.extend(from, to, edge)
+            }
+            None => true,
+        }
+    }
+
+    /// Tries every path from `v` to `to` through unused vertices,
+    /// routing the next edges after each one.
+    fn extend(&mut self, v: usize, to: usize, edge: usize) -> bool {
+        for i in 0..self.graph[v].len() {
+            let w = self.graph[v][i];
+            if w == to {
+                if self.route(edge + 1) {
+                    return true;
+                }
+            } else if !self.used[w] {
+                self.used[w] = true;
+                if self.extend(w, to, edge) {
+                    return true;
+                }
+                self.used[w] = false;
+            }
+        }
+        false
+    }
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Checks whether the graph contains a subdivision of `pattern`, that
+    /// is whether `pattern` is a topological minor of it, with the edge
+    /// directions ignored. The graph contains a subdivision of K5 or K3,3
+    /// exactly when it is not planar.
+    /// The search is exponential, and meant for patterns of a few vertices
+    /// in graphs of a few dozen.
+    pub fn contains_topological_minor<W: std::fmt::Debug, F>(&self, pattern: &Graph<W, F>) -> bool {
+        let graph = self.indexed().undirected();
+        let pattern = pattern.indexed().undirected();
+        let pattern_edges: Vec<(usize, usize)> = pattern.iter()
+            .enumerate()
+            .flat_map(|(a, list)| list.iter().filter(move |&&b| a < b).map(move |&b| (a, b)))
+            .collect();
+        if pattern.len() > graph.len() {
+            return false;
+        }
+
+        // Constrained pattern vertices first.
+        let mut order: Vec<usize> = (0..pattern.len()).collect();
+        order.sort_by_key(|&p| std::cmp::Reverse(pattern[p].len()));
+
+        let mut search = Search {
+            graph: &graph,
+            pattern: &pattern,
+            pattern_edges,
+            branch: vec![0; pattern.len()],
+            used: vec![false; graph.len()],
+        };
+        search.assign(&order)
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::contains_topological_minor`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut k4 = GraphMap::<u32, ()>::new();
+    /// for a in 0..4 {
+    ///     for b in a + 1..4 {
+    ///         k4.add_edge((a, b), ());
+    ///     }
+    /// }
+    ///
+    /// // A wheel with a subdivided spoke still contains K4.
+    /// let mut wheel = GraphMap::<u32, ()>::new();
+    /// for &(a, b) in [(1, 2), (2, 3), (3, 1), (0, 1), (0, 2), (0, 4), (4, 3)].iter() {
+    ///     wheel.add_edge((a, b), ());
+    /// }
+    /// assert!(wheel.contains_topological_minor(&k4));
+    /// wheel.remove_edge((0, 4));
+    /// assert!(!wheel.contains_topological_minor(&k4));
+    /// ```
+    pub fn contains_topological_minor<W: Eq + Hash + Clone + std::fmt::Debug, F>(&self, pattern: &GraphMap<W, F>) -> bool {
+        self.graph.contains_topological_minor(&pattern.graph)
+    }
+}
@@ -0,0 +1,91 @@
+//! Graph algorithms, each module adds its methods
+//! directly on [`Graph`](crate::Graph) and [`GraphMap`](crate::GraphMap).
+pub mod motifs;
+pub mod graphlets;
+pub mod rewire;
+pub mod coarsen;
+pub mod properties;
+pub mod audit;
+pub mod summary;
+pub mod girvan_newman;
+pub mod clique;
+pub mod planarity;
+pub mod minor;
+pub mod diameter;
+pub mod mst;
+pub mod bellman_ford;
+pub mod flow;
+pub mod centrality;
+pub mod isomorphism;
+pub mod tsp;
+pub mod coloring;
+pub mod biconnected;
+pub mod reachability;
+pub mod community;
+
+use super::{Graph, VertexId};
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+/// Vertices numbered `0..n` in arena order, so they can be kept
+/// in plain vectors, with the edges as sorted index pairs.
+/// Self loops are left out of the edges.
+pub(crate) struct Indexed {
+    pub ids: Vec<VertexId>,
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl Indexed {
+
+    /// Sorted neighbours of every vertex with the edge
+    /// directions ignored.
+    pub fn undirected(&self) -> Vec<Vec<usize>> {
+        let mut adj = vec![Vec::new(); self.ids.len()];
+        for &(from, to) in self.edges.iter() {
+            adj[from].push(to);
+            adj[to].push(from);
+        }
+        for list in adj.iter_mut() {
+            list.sort_unstable();
+            list.dedup();
+        }
+        adj
+    }
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Numbers the vertices in arena order, see [`Indexed`].
+    pub(crate) fn indexed(&self) -> Indexed {
+        let ids: Vec<VertexId> = self.vertices().map(|(id, _)| id).collect();
+        let index: HashMap<VertexId, usize> = ids.iter()
+            .enumerate()
+            .map(|(i, &id)| (id, i))
+            .collect();
+        let mut edges: Vec<(usize, usize)> = self.edges()
+            .map(|(&(from, to), _)| (index[&from], index[&to]))
+            .filter(|(from, to)| from != to)
+            .collect();
+        edges.sort_unstable();
+        Indexed {
+            ids,
+            edges,
+        }
+    }
+
+    /// Neighbours of every vertex with the edge directions ignored,
+    /// without self loops.
+    pub(crate) fn undirected_adjacency(&self) -> HashMap<VertexId, HashSet<VertexId>> {
+        self.vertices()
+            .map(|(id, _)| {
+                let adj = self.neighbors(id)
+                    .unwrap()
+                    .filter(|&other| other != id)
+                    .collect();
+                (id, adj)
+            })
+            .collect()
+    }
+}
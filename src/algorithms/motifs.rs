@@ -0,0 +1,183 @@
+//! Small subgraph patterns: triangles, two paths, squares and feed
+//! forward loops.
+
+use crate::{Graph, GraphMap, VertexId};
+use std::hash::Hash;
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// All triangles, with the edge directions ignored.
+    /// Every triangle is reported once.
+    ///
+    /// Edges are oriented from the lower to the higher degree vertex,
+    /// so each vertex only looks at O(sqrt(E)) neighbours.
+    /// Time complexity: O(E * sqrt(E))
+    pub fn triangles(&self) -> Vec<(VertexId, VertexId, VertexId)> {
+        let forward = self.degree_oriented();
+        let mut triangles = Vec::new();
+
+        for (&u, out_u) in forward.iter() {
+            for &v in out_u.iter() {
+                for &w in forward[&v].iter() {
+                    if out_u.contains(&w) {
+                        triangles.push((u, v, w));
+                    }
+                }
+            }
+        }
+        triangles
+    }
+
+    /// Number of triangles, see [`Graph::triangles`].
+    pub fn triangle_count(&self) -> usize {
+        let forward = self.degree_oriented();
+        forward.values()
+            .map(|out_u| {
+                out_u.iter()
+                    .map(|v| forward[v].iter().filter(|w| out_u.contains(w)).count())
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// All directed paths `a -> b -> c` over 3 distinct vertices.
+    pub fn two_paths(&self) -> Vec<(VertexId, VertexId, VertexId)> {
+        let mut paths = Vec::new();
+        for (&b, inbound) in self.inbound.iter() {
+            for &a in inbound.iter().filter(|&&a| a != b) {
+                for &c in self.outbound[&b].iter() {
+                    if c != a && c != b {
+                        paths.push((a, b, c));
+                    }
+                }
+            }
+        }
+        paths
+    }
+
+    /// All cycles of length 4 `a - b - c - d - a`, with the edge
+    /// directions ignored. Every square is reported once, starting
+    /// from its highest degree vertex.
+    pub fn squares(&self) -> Vec<(VertexId, VertexId, VertexId, VertexId)> {
+        let adj = self.undirected_adjacency();
+        let rank = |v: VertexId| (adj[&v].len(), v);
+        let mut squares = Vec::new();
+
+        for (&u, adj_u) in adj.iter() {
+            // wedges u - v - w where u is the highest ranked vertex
+            let mut wedges = HashMap::<VertexId, Vec<VertexId>>::new();
+            for &v in adj_u.iter().filter(|&&v| rank(v) < rank(u)) {
+                for &w in adj[&v].iter().filter(|&&w| rank(w) < rank(u)) {
+                    wedges.entry(w).or_default().push(v);
+                }
+            }
+            for (w, middle) in wedges {
+                for i in 0..middle.len() {
+                    for j in (i + 1)..middle.len() {
+                        squares.push((u, middle[i], w, middle[j]));
+                    }
+                }
+            }
+        }
+        squares
+    }
+
+    /// All feed-forward loops `a -> b -> c` with the shortcut `a -> c`,
+    /// over 3 distinct vertices.
+    pub fn feed_forward_loops(&self) -> Vec<(VertexId, VertexId, VertexId)> {
+        let mut loops = Vec::new();
+        for &(a, c) in self.edges.keys() {
+            if a == c {
+                continue;
+            }
+            // b is in outbound(a) and in inbound(c), scan the smaller set
+            let (small, large) = if self.outdegree(a) <= self.indegree(c) {
+                (&self.outbound[&a], &self.inbound[&c])
+            } else {
+                (&self.inbound[&c], &self.outbound[&a])
+            };
+            for &b in small.iter() {
+                if b != a && b != c && large.contains(&b) {
+                    loops.push((a, b, c));
+                }
+            }
+        }
+        loops
+    }
+
+    /// Undirected adjacency keeping only the edges going from
+    /// the lower to the higher `(degree, id)` vertex.
+    fn degree_oriented(&self) -> HashMap<VertexId, HashSet<VertexId>> {
+        let adj = self.undirected_adjacency();
+        let rank = |v: VertexId| (adj[&v].len(), v);
+        adj.iter()
+            .map(|(&u, neighbours)| {
+                let forward = neighbours.iter()
+                    .copied()
+                    .filter(|&v| rank(u) < rank(v))
+                    .collect();
+                (u, forward)
+            })
+            .collect()
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    fn vertex_data(&self, id: VertexId) -> &V {
+        self.graph.get_vertex(id).unwrap()
+    }
+
+    /// All triangles, see [`Graph::triangles`].
+    pub fn triangles(&self) -> Vec<(&V, &V, &V)> {
+        self.graph.triangles()
+            .into_iter()
+            .map(|(a, b, c)| (self.vertex_data(a), self.vertex_data(b), self.vertex_data(c)))
+            .collect()
+    }
+
+    /// All directed 2-paths, see [`Graph::two_paths`].
+    pub fn two_paths(&self) -> Vec<(&V, &V, &V)> {
+        self.graph.two_paths()
+            .into_iter()
+            .map(|(a, b, c)| (self.vertex_data(a), self.vertex_data(b), self.vertex_data(c)))
+            .collect()
+    }
+
+    /// All 4-cycles, see [`Graph::squares`].
+    pub fn squares(&self) -> Vec<(&V, &V, &V, &V)> {
+        self.graph.squares()
+            .into_iter()
+            .map(|(a, b, c, d)| (self.vertex_data(a), self.vertex_data(b), self.vertex_data(c), self.vertex_data(d)))
+            .collect()
+    }
+
+    /// All feed-forward loops, see [`Graph::feed_forward_loops`].
+    pub fn feed_forward_loops(&self) -> Vec<(&V, &V, &V)> {
+        self.graph.feed_forward_loops()
+            .into_iter()
+            .map(|(a, b, c)| (self.vertex_data(a), self.vertex_data(b), self.vertex_data(c)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+
+    #[test]
+    fn motifs_of_small_graph() {
+        let mut graph = GraphMap::<u32, ()>::new();
+        for &(a, b) in [(0, 1), (1, 2), (0, 2), (2, 3), (3, 0), (3, 4)].iter() {
+            graph.add_edge((a, b), ());
+        }
+        assert_eq!(graph.triangles().len(), 2);
+        assert_eq!(graph.squares().len(), 1);
+        assert_eq!(graph.feed_forward_loops(), vec![(&0, &1, &2)]);
+        assert_eq!(graph.two_paths().len(), 7);
+    }
+}
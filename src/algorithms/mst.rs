@@ -0,0 +1,208 @@
+//! Minimum spanning trees and forests, with Kruskal's and Prim's algorithms.
+
+use crate::{Graph, GraphMap, VertexId};
+use crate::undirected::UndirectedGraph;
+use std::cmp::Reverse;
+use std::hash::Hash;
+use std::ops::Add;
+use std::collections::{
+    BinaryHeap,
+    HashMap,
+    HashSet,
+};
+
+/// Edges of a minimum spanning tree or forest, and their total weight.
+/// `T` identifies the vertices, [`VertexId`] or the vertex data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpanningTree<T, E> {
+    pub edges: Vec<(T, T)>,
+    pub weight: E,
+}
+
+impl<T, E: Default> SpanningTree<T, E> {
+    fn empty() -> Self {
+        SpanningTree {
+            edges: Vec::new(),
+            weight: E::default(),
+        }
+    }
+}
+
+impl<T, E> SpanningTree<T, E> {
+    fn map<U, F: Fn(T) -> U>(self, f: F) -> SpanningTree<U, E> {
+        SpanningTree {
+            edges: self.edges.into_iter().map(|(a, b)| (f(a), f(b))).collect(),
+            weight: self.weight,
+        }
+    }
+}
+
+/// Kruskal's algorithm on a list of undirected edges.
+fn kruskal<E, I>(edges: I) -> SpanningTree<VertexId, E>
+where
+    E: Copy + Ord + Add<Output = E> + Default,
+    I: Iterator<Item = (VertexId, VertexId, E)>,
+{
+    let mut edges: Vec<(VertexId, VertexId, E)> = edges.collect();
+    edges.sort_by_key(|&(_, _, weight)| weight);
+
+    let mut parent: HashMap<VertexId, VertexId> = HashMap::new();
+    fn find(parent: &mut HashMap<VertexId, VertexId>, v: VertexId) -> VertexId {
+        let mut root = v;
+        while let Some(&p) = parent.get(&root) {
+            root = p;
+        }
+        let mut current = v;
+        while let Some(&p) = parent.get(&current) {
+            parent.insert(current, root);
+            current = p;
+        }
+        root
+    }
+
+    let mut tree = SpanningTree::empty();
+    for (from, to, weight) in edges {
+        let a = find(&mut parent, from);
+        let b = find(&mut parent, to);
+        if a != b {
+            parent.insert(a, b);
+            tree.edges.push((from, to));
+            tree.weight = tree.weight + weight;
+        }
+    }
+    tree
+}
+
+/// Prim's algorithm from `start`, with `neighbours` listing the
+/// undirected edges of a vertex.
+fn prim<E, F>(start: VertexId, neighbours: F) -> SpanningTree<VertexId, E>
+where
+    E: Copy + Ord + Add<Output = E> + Default,
+    F: Fn(VertexId) -> Vec<(VertexId, E)>,
+{
+    let mut tree = SpanningTree::empty();
+    let mut reached = HashSet::new();
+    let mut heap = BinaryHeap::new();
+    reached.insert(start);
+    for (to, weight) in neighbours(start) {
+        heap.push(Reverse((weight, start, to)));
+    }
+    while let Some(Reverse((weight, from, to))) = heap.pop() {
+        if !reached.insert(to) {
+            continue;
+        }
+        tree.edges.push((from, to));
+        tree.weight = tree.weight + weight;
+        for (next, weight) in neighbours(to) {
+            if !reached.contains(&next) {
+                heap.push(Reverse((weight, to, next)));
+            }
+        }
+    }
+    tree
+}
+
+impl<V: std::fmt::Debug, E: Copy + Ord + Add<Output = E> + Default> Graph<V, E> {
+
+    /// Minimum spanning forest with Kruskal's algorithm, with the edge
+    /// directions ignored. Edges are given as they are in the graph.
+    /// Time complexity: O(E log E)
+    pub fn kruskal(&self) -> SpanningTree<VertexId, E> {
+        kruskal(self.edges().map(|(&(from, to), &weight)| (from, to, weight)))
+    }
+
+    /// Minimum spanning tree of the component of `start` with Prim's
+    /// algorithm, with the edge directions ignored. Edges are given
+    /// going away from `start`, so they may be reversed.
+    /// Time complexity: O(E log E)
+    pub fn prim(&self, start: VertexId) -> SpanningTree<VertexId, E> {
+        if self.get_vertex(start).is_none() {
+            return SpanningTree::empty();
+        }
+        prim(start, |v| {
+            let out = self.adj_out(v).unwrap();
+            let inc = self.adj_in(v).unwrap();
+            out.chain(inc).map(|(other, &weight)| (other, weight)).collect()
+        })
+    }
+}
+
+impl<V: std::fmt::Debug, E: Copy + Ord + Add<Output = E> + Default> UndirectedGraph<V, E> {
+
+    /// Minimum spanning forest with Kruskal's algorithm.
+    /// Time complexity: O(E log E)
+    pub fn kruskal(&self) -> SpanningTree<VertexId, E> {
+        kruskal(self.edges().map(|(&(a, b), &weight)| (a, b, weight)))
+    }
+
+    /// Minimum spanning tree of the component of `start`
+    /// with Prim's algorithm.
+    /// Time complexity: O(E log E)
+    ///
+    /// ```
+    /// use graph::undirected::UndirectedGraph;
+    ///
+    /// let mut graph = UndirectedGraph::<&str, u32>::new();
+    /// let a = graph.add_vertex("a");
+    /// let b = graph.add_vertex("b");
+    /// let c = graph.add_vertex("c");
+    /// graph.add_edge((a, b), 4).unwrap();
+    /// graph.add_edge((b, c), 1).unwrap();
+    /// graph.add_edge((c, a), 2).unwrap();
+    ///
+    /// let tree = graph.prim(a);
+    /// assert_eq!(tree.edges, vec![(a, c), (c, b)]);
+    /// assert_eq!(tree.weight, 3);
+    /// assert_eq!(graph.kruskal().weight, 3);
+    /// ```
+    pub fn prim(&self, start: VertexId) -> SpanningTree<VertexId, E> {
+        if self.get_vertex(start).is_none() {
+            return SpanningTree::empty();
+        }
+        prim(start, |v| self.adj(v).unwrap().map(|(other, &weight)| (other, weight)).collect())
+    }
+}
+
+impl<V, E> GraphMap<V, E>
+where
+    V: Eq + Hash + Clone + std::fmt::Debug,
+    E: Copy + Ord + Add<Output = E> + Default,
+{
+
+    /// See [`Graph::kruskal`].
+    pub fn kruskal(&self) -> SpanningTree<&V, E> {
+        self.graph.kruskal().map(|id| self.graph.get_vertex(id).unwrap())
+    }
+
+    /// See [`Graph::prim`]. Empty if `start` is not in the graph.
+    pub fn prim(&self, start: &V) -> SpanningTree<&V, E> {
+        match self.map.get(start) {
+            Some(&id) => self.graph.prim(id).map(|id| self.graph.get_vertex(id).unwrap()),
+            None => SpanningTree::empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+
+    #[test]
+    fn kruskal_and_prim_agree() {
+        let mut graph = GraphMap::<u32, u32>::new();
+        let edges = [(0, 1, 7), (0, 3, 5), (1, 2, 8), (1, 3, 9), (1, 4, 7), (2, 4, 5),
+            (3, 4, 15), (3, 5, 6), (4, 5, 8), (4, 6, 9), (5, 6, 11), (8, 7, 1)];
+        for &(a, b, w) in edges.iter() {
+            graph.add_edge((a, b), w);
+        }
+
+        let forest = graph.kruskal();
+        assert_eq!(forest.weight, 39 + 1);
+        assert_eq!(forest.edges.len(), 7);
+
+        let tree = graph.prim(&3);
+        assert_eq!(tree.weight, 39);
+        assert_eq!(tree.edges.len(), 6);
+        assert_eq!(graph.prim(&7).edges, vec![(&7, &8)]);
+    }
+}
@@ -0,0 +1,697 @@
+//! Planarity testing with the left-right algorithm, giving an embedding
+//! or a Kuratowski subgraph.
+
+use crate::{Graph, GraphMap, VertexId};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Result of [`Graph::planarity`].
+/// `T` identifies the vertices, [`VertexId`] or the vertex data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Planarity<T> {
+    /// A combinatorial embedding: the neighbours of every vertex,
+    /// in clockwise order around it.
+    Planar(Vec<(T, Vec<T>)>),
+    /// The edges of a subdivision of K5 or K3,3 in the graph,
+    /// which proves that it is not planar.
+    NonPlanar(Vec<(T, T)>),
+}
+
+impl<T> Planarity<T> {
+
+    pub fn is_planar(&self) -> bool {
+        matches!(self, Planarity::Planar(_))
+    }
+
+    fn map<U, F: Fn(T) -> U>(self, f: F) -> Planarity<U> {
+        match self {
+            Planarity::Planar(rotation) => Planarity::Planar(
+                rotation.into_iter()
+                    .map(|(v, around)| (f(v), around.into_iter().map(&f).collect()))
+                    .collect()
+            ),
+            Planarity::NonPlanar(edges) => Planarity::NonPlanar(
+                edges.into_iter().map(|(a, b)| (f(a), f(b))).collect()
+            ),
+        }
+    }
+}
+
+const NONE: usize = usize::MAX;
+
+#[derive(Clone, Copy)]
+struct Interval {
+    low: Option<usize>,
+    high: Option<usize>,
+}
+
+impl Interval {
+    const EMPTY: Interval = Interval { low: None, high: None };
+
+    fn is_empty(&self) -> bool {
+        self.low.is_none() && self.high.is_none()
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ConflictPair {
+    left: Interval,
+    right: Interval,
+}
+
+impl ConflictPair {
+    fn swap(&mut self) {
+        std::mem::swap(&mut self.left, &mut self.right);
+    }
+}
+
+/// Left-right planarity test, following Brandes' description of the
+/// algorithm of de Fraysseix and Rosenstiehl. Vertices are `0..n` and
+/// edges are undirected, without loops or duplicates. The depth first
+/// searches keep explicit stacks, so deep graphs don't overflow.
+struct LeftRight {
+    adj: Vec<Vec<usize>>,
+    height: Vec<usize>,
+    parent_edge: Vec<Option<usize>>,
+    roots: Vec<usize>,
+    /// Oriented edges, the ones going out of every vertex in the order
+    /// they were oriented, and sorted by nesting depth.
+    edges: Vec<(usize, usize)>,
+    ids: HashMap<(usize, usize), usize>,
+    oriented: Vec<Vec<usize>>,
+    out: Vec<Vec<usize>>,
+    lowpt: Vec<usize>,
+    lowpt2: Vec<usize>,
+    nesting_depth: Vec<isize>,
+    reference: Vec<Option<usize>>,
+    side: Vec<isize>,
+    stack: Vec<ConflictPair>,
+    stack_bottom: Vec<usize>,
+    lowpt_edge: Vec<usize>,
+}
+
+impl LeftRight {
+
+    fn new(n: usize, edges: &[(usize, usize)]) -> Self {
+        let mut adj = vec![Vec::new(); n];
+        for &(a, b) in edges {
+            adj[a].push(b);
+            adj[b].push(a);
+        }
+        let m = edges.len();
+        LeftRight {
+            adj,
+            height: vec![NONE; n],
+            parent_edge: vec![None; n],
+            roots: Vec::new(),
+            edges: Vec::with_capacity(m),
+            ids: HashMap::with_capacity(m),
+            oriented: vec![Vec::new(); n],
+            out: Vec::new(),
+            lowpt: Vec::with_capacity(m),
+            lowpt2: Vec::with_capacity(m),
+            nesting_depth: Vec::with_capacity(m),
+            reference: vec![None; m],
+            side: vec![1; m],
+            stack: Vec::new(),
+            stack_bottom: vec![0; m],
+            lowpt_edge: vec![NONE; m],
+        }
+    }
+
+    /// Orients the edges along a depth first search, and computes the
+    /// lowpoints and nesting depths.
+    fn orient(&mut self) {
+        for root in 0..self.adj.len() {
+            if self.height[root] != NONE {
+                continue;
+            }
+            self.height[root] = 0;
+            self.roots.push(root);
+            let mut frames = vec![(root, 0)];
+            while let Some(&mut (v, ref mut next)) = frames.last_mut() {
+                if *next == self.adj[v].len() {
+                    frames.pop();
+                    if let Some(e) = self.parent_edge[v] {
+                        self.finish_orientation(e);
+                    }
+                    continue;
+                }
+                let w = self.adj[v][*next];
+                *next += 1;
+                if self.ids.contains_key(&(v, w)) || self.ids.contains_key(&(w, v)) {
+                    continue;
+                }
+                let vw = self.edges.len();
+                self.edges.push((v, w));
+                self.ids.insert((v, w), vw);
+                self.oriented[v].push(vw);
+                self.lowpt.push(self.height[v]);
+                self.lowpt2.push(self.height[v]);
+                self.nesting_depth.push(0);
+                if self.height[w] == NONE {
+                    self.parent_edge[w] = Some(vw);
+                    self.height[w] = self.height[v] + 1;
+                    frames.push((w, 0));
+                } else {
+                    self.lowpt[vw] = self.height[w];
+                    self.finish_orientation(vw);
+                }
+            }
+        }
+    }
+
+    fn finish_orientation(&mut self, vw: usize) {
+        let v = self.edges[vw].0;
+        self.nesting_depth[vw] = 2 * self.lowpt[vw] as isize;
+        if self.lowpt2[vw] < self.height[v] {
+            // Chordal edge.
+            self.nesting_depth[vw] += 1;
+        }
+        if let Some(e) = self.parent_edge[v] {
+            if self.lowpt[vw] < self.lowpt[e] {
+                self.lowpt2[e] = self.lowpt[e].min(self.lowpt2[vw]);
+                self.lowpt[e] = self.lowpt[vw];
+            } else if self.lowpt[vw] > self.lowpt[e] {
+                self.lowpt2[e] = self.lowpt2[e].min(self.lowpt[vw]);
+            } else {
+                self.lowpt2[e] = self.lowpt2[e].min(self.lowpt2[vw]);
+            }
+        }
+    }
+
+    fn sort_by_nesting_depth(&mut self) {
+        let nesting_depth = &self.nesting_depth;
+        self.out = self.oriented.iter()
+            .map(|edges| {
+                let mut edges = edges.clone();
+                edges.sort_by_key(|&e| nesting_depth[e]);
+                edges
+            })
+            .collect();
+    }
+
+    fn conflicting(&self, interval: &Interval, edge: usize) -> bool {
+        match interval.high {
+            Some(high) => self.lowpt[high] > self.lowpt[edge],
+            None => false,
+        }
+    }
+
+    fn lowest(&self, pair: &ConflictPair) -> usize {
+        match (pair.left.low, pair.right.low) {
+            (Some(left), Some(right)) => self.lowpt[left].min(self.lowpt[right]),
+            (Some(left), None) => self.lowpt[left],
+            (None, Some(right)) => self.lowpt[right],
+            (None, None) => NONE,
+        }
+    }
+
+    /// Checks the constraints of the depth first search tree. False
+    /// if the edges can't be split in a left and a right side.
+    fn test(&mut self) -> bool {
+        for i in 0..self.roots.len() {
+            let mut frames = vec![(self.roots[i], 0)];
+            while let Some(&mut (v, ref mut next)) = frames.last_mut() {
+                if *next == self.out[v].len() {
+                    frames.pop();
+                    if let Some(e) = self.parent_edge[v] {
+                        self.remove_back_edges(e);
+                        let (parent, _) = self.edges[e];
+                        let index = frames.last().unwrap().1 - 1;
+                        if !self.integrate(parent, e, index) {
+                            return false;
+                        }
+                    }
+                    continue;
+                }
+                let index = *next;
+                let ei = self.out[v][index];
+                *next += 1;
+                let w = self.edges[ei].1;
+                self.stack_bottom[ei] = self.stack.len();
+                if self.parent_edge[w] == Some(ei) {
+                    frames.push((w, 0));
+                } else {
+                    self.lowpt_edge[ei] = ei;
+                    self.stack.push(ConflictPair {
+                        left: Interval::EMPTY,
+                        right: Interval { low: Some(ei), high: Some(ei) },
+                    });
+                    if !self.integrate(v, ei, index) {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Adds the return edges of `ei`, the `index`th edge out of `v`,
+    /// to the constraints of the parent edge of `v`.
+    fn integrate(&mut self, v: usize, ei: usize, index: usize) -> bool {
+        if self.lowpt[ei] < self.height[v] {
+            let e = self.parent_edge[v].unwrap();
+            if index == 0 {
+                self.lowpt_edge[e] = self.lowpt_edge[ei];
+            } else if !self.add_constraints(ei, e) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn add_constraints(&mut self, ei: usize, e: usize) -> bool {
+        let mut pair = ConflictPair {
+            left: Interval::EMPTY,
+            right: Interval::EMPTY,
+        };
+        // Merge the return edges of ei into the right interval.
+        loop {
+            let mut q = self.stack.pop().unwrap();
+            if !q.left.is_empty() {
+                q.swap();
+            }
+            if !q.left.is_empty() {
+                return false;
+            }
+            let low = q.right.low.unwrap();
+            if self.lowpt[low] > self.lowpt[e] {
+                if pair.right.is_empty() {
+                    pair.right = q.right;
+                } else if let Some(right_low) = pair.right.low {
+                    self.reference[right_low] = q.right.high;
+                }
+                pair.right.low = q.right.low;
+            } else {
+                self.reference[low] = Some(self.lowpt_edge[e]);
+            }
+            if self.stack.len() == self.stack_bottom[ei] {
+                break;
+            }
+        }
+        // Merge the conflicting return edges of the previous siblings
+        // into the left interval.
+        while let Some(&top) = self.stack.last() {
+            if !self.conflicting(&top.left, ei) && !self.conflicting(&top.right, ei) {
+                break;
+            }
+            let mut q = self.stack.pop().unwrap();
+            if self.conflicting(&q.right, ei) {
+                q.swap();
+            }
+            if self.conflicting(&q.right, ei) {
+                return false;
+            }
+            if let Some(right_low) = pair.right.low {
+                self.reference[right_low] = q.right.high;
+            }
+            if q.right.low.is_some() {
+                pair.right.low = q.right.low;
+            }
+            if pair.left.is_empty() {
+                pair.left = q.left;
+            } else if let Some(left_low) = pair.left.low {
+                self.reference[left_low] = q.left.high;
+            }
+            pair.left.low = q.left.low;
+        }
+        if !pair.left.is_empty() || !pair.right.is_empty() {
+            self.stack.push(pair);
+        }
+        true
+    }
+
+    /// Drops the back edges that end at the parent of `e`.
+    fn remove_back_edges(&mut self, e: usize) {
+        let u = self.edges[e].0;
+        while let Some(top) = self.stack.last() {
+            if self.lowest(top) != self.height[u] {
+                break;
+            }
+            let pair = self.stack.pop().unwrap();
+            if let Some(low) = pair.left.low {
+                self.side[low] = -1;
+            }
+        }
+        if let Some(mut pair) = self.stack.pop() {
+            while let Some(high) = pair.left.high {
+                if self.edges[high].1 != u {
+                    break;
+                }
+                pair.left.high = self.reference[high];
+            }
+            if pair.left.high.is_none() {
+                if let Some(low) = pair.left.low {
+                    self.reference[low] = pair.right.low;
+                    self.side[low] = -1;
+                    pair.left.low = None;
+                }
+            }
+            while let Some(high) = pair.right.high {
+                if self.edges[high].1 != u {
+                    break;
+                }
+                pair.right.high = self.reference[high];
+            }
+            if pair.right.high.is_none() {
+                if let Some(low) = pair.right.low {
+                    self.reference[low] = pair.left.low;
+                    self.side[low] = -1;
+                    pair.right.low = None;
+                }
+            }
+            self.stack.push(pair);
+        }
+        // The side of e is the side of its highest return edge.
+        if self.lowpt[e] < self.height[u] {
+            let top = self.stack.last().unwrap();
+            let (left, right) = (top.left.high, top.right.high);
+            self.reference[e] = match (left, right) {
+                (Some(l), Some(r)) if self.lowpt[l] > self.lowpt[r] => left,
+                (Some(_), None) => left,
+                _ => right,
+            };
+        }
+    }
+
+    /// Side of the edge, following the chain of references.
+    fn sign(&mut self, e: usize) -> isize {
+        let mut chain = Vec::new();
+        let mut current = e;
+        while let Some(next) = self.reference[current] {
+            chain.push(current);
+            current = next;
+        }
+        for &edge in chain.iter().rev() {
+            let next = self.reference[edge].take().unwrap();
+            self.side[edge] *= self.side[next];
+        }
+        self.side[e]
+    }
+
+    /// Clockwise order of the neighbours around every vertex.
+    fn embed(&mut self) -> Vec<Vec<usize>> {
+        for e in 0..self.edges.len() {
+            self.nesting_depth[e] *= self.sign(e);
+        }
+        self.sort_by_nesting_depth();
+
+        let n = self.adj.len();
+        let mut rotation = Rotation {
+            first: vec![None; n],
+            cw: HashMap::new(),
+            ccw: HashMap::new(),
+        };
+        for v in 0..n {
+            let mut previous = None;
+            for &e in self.out[v].iter() {
+                let w = self.edges[e].1;
+                rotation.insert_cw(v, w, previous);
+                previous = Some(w);
+            }
+        }
+
+        let mut left_ref = vec![NONE; n];
+        let mut right_ref = vec![NONE; n];
+        for &root in self.roots.iter() {
+            let mut frames = vec![(root, 0)];
+            while let Some(&mut (v, ref mut next)) = frames.last_mut() {
+                if *next == self.out[v].len() {
+                    frames.pop();
+                    continue;
+                }
+                let ei = self.out[v][*next];
+                *next += 1;
+                let w = self.edges[ei].1;
+                if self.parent_edge[w] == Some(ei) {
+                    rotation.insert_first(w, v);
+                    left_ref[v] = w;
+                    right_ref[v] = w;
+                    frames.push((w, 0));
+                } else if self.side[ei] == 1 {
+                    rotation.insert_cw(w, v, Some(right_ref[w]));
+                } else {
+                    rotation.insert_ccw(w, v, Some(left_ref[w]));
+                    left_ref[w] = v;
+                }
+            }
+        }
+        (0..n).map(|v| rotation.around(v)).collect()
+    }
+}
+
+/// Cyclic order of the neighbours of every vertex, as linked lists.
+struct Rotation {
+    first: Vec<Option<usize>>,
+    cw: HashMap<(usize, usize), usize>,
+    ccw: HashMap<(usize, usize), usize>,
+}
+
+impl Rotation {
+
+    /// Puts `w` right after `reference` in clockwise order around `v`.
+    fn insert_cw(&mut self, v: usize, w: usize, reference: Option<usize>) {
+        match reference {
+            Some(reference) => {
+                let next = self.cw[&(v, reference)];
+                self.cw.insert((v, reference), w);
+                self.cw.insert((v, w), next);
+                self.ccw.insert((v, w), reference);
+                self.ccw.insert((v, next), w);
+            }
+            None => {
+                self.cw.insert((v, w), w);
+                self.ccw.insert((v, w), w);
+                self.first[v] = Some(w);
+            }
+        }
+    }
+
+    /// Puts `w` right before `reference` in clockwise order around `v`.
+    fn insert_ccw(&mut self, v: usize, w: usize, reference: Option<usize>) {
+        match reference {
+            Some(reference) => {
+                let previous = self.ccw[&(v, reference)];
+                self.insert_cw(v, w, Some(previous));
+                if self.first[v] == Some(reference) {
+                    self.first[v] = Some(w);
+                }
+            }
+            None => self.insert_cw(v, w, None),
+        }
+    }
+
+    fn insert_first(&mut self, v: usize, w: usize) {
+        let first = self.first[v];
+        self.insert_ccw(v, w, first);
+        self.first[v] = Some(w);
+    }
+
+    fn around(&self, v: usize) -> Vec<usize> {
+        let mut around = Vec::new();
+        if let Some(first) = self.first[v] {
+            let mut current = first;
+            loop {
+                around.push(current);
+                current = self.cw[&(v, current)];
+                if current == first {
+                    break;
+                }
+            }
+        }
+        around
+    }
+}
+
+/// Clockwise neighbours of every vertex, or `None` if the graph is not
+/// planar. Edges must be undirected, without loops or duplicates.
+fn embedding(n: usize, edges: &[(usize, usize)]) -> Option<Vec<Vec<usize>>> {
+    // Euler's formula bounds the edges of a simple planar graph.
+    if n > 2 && edges.len() > 3 * n - 6 {
+        return None;
+    }
+    let mut lr = LeftRight::new(n, edges);
+    lr.orient();
+    lr.sort_by_nesting_depth();
+    if !lr.test() {
+        return None;
+    }
+    Some(lr.embed())
+}
+
+/// Undirected edges of the graph, each once and without self loops.
+fn simple_edges(adj: &[Vec<usize>]) -> Vec<(usize, usize)> {
+    adj.iter()
+        .enumerate()
+        .flat_map(|(v, list)| list.iter().filter(move |&&w| v < w).map(move |&w| (v, w)))
+        .collect()
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Checks whether the graph can be drawn in the plane without
+    /// crossing edges, with the left-right planarity test. Edge
+    /// directions, self loops and parallel edges are ignored.
+    /// Time complexity: O(V + E)
+    pub fn is_planar(&self) -> bool {
+        let indexed = self.indexed();
+        embedding(indexed.ids.len(), &simple_edges(&indexed.undirected())).is_some()
+    }
+
+    /// Planar embedding of the graph, or a Kuratowski subgraph when there
+    /// is none, see [`Graph::is_planar`]. The subgraph is found by
+    /// dropping every edge that isn't needed to keep the rest non planar.
+    /// Time complexity: O(V + E) if planar, O(E (V + E)) otherwise
+    pub fn planarity(&self) -> Planarity<VertexId> {
+        let indexed = self.indexed();
+        let n = indexed.ids.len();
+        let mut edges = simple_edges(&indexed.undirected());
+        let id = |v: usize| indexed.ids[v];
+
+        if let Some(rotation) = embedding(n, &edges) {
+            return Planarity::Planar(
+                rotation.into_iter()
+                    .enumerate()
+                    .map(|(v, around)| (id(v), around.into_iter().map(id).collect()))
+                    .collect()
+            );
+        }
+        let mut i = 0;
+        while i < edges.len() {
+            let edge = edges.remove(i);
+            if embedding(n, &edges).is_some() {
+                edges.insert(i, edge);
+                i += 1;
+            }
+        }
+        Planarity::NonPlanar(edges.into_iter().map(|(a, b)| (id(a), id(b))).collect())
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::is_planar`].
+    pub fn is_planar(&self) -> bool {
+        self.graph.is_planar()
+    }
+
+    /// See [`Graph::planarity`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    /// use graph::algorithms::planarity::Planarity;
+    ///
+    /// let mut graph = GraphMap::<u32, ()>::new();
+    /// for a in 0..5 {
+    ///     for b in a + 1..5 {
+    ///         graph.add_edge((a, b), ());
+    ///     }
+    /// }
+    /// graph.add_edge((0, 5), ());
+    /// assert!(!graph.is_planar());
+    /// match graph.planarity() {
+    ///     Planarity::NonPlanar(witness) => assert_eq!(witness.len(), 10),
+    ///     Planarity::Planar(_) => unreachable!(),
+    /// }
+    ///
+    /// graph.remove_edge((3, 4));
+    /// assert!(graph.planarity().is_planar());
+    /// ```
+    pub fn planarity(&self) -> Planarity<&V> {
+        self.graph.planarity().map(|id| self.graph.get_vertex(id).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::embedding;
+    use std::collections::HashMap;
+
+    /// Checks Euler's formula V - E + F = 2 on every component with
+    /// edges, with the faces traced by the rotation system.
+    fn is_valid(n: usize, edges: &[(usize, usize)], rotation: &[Vec<usize>]) -> bool {
+        let mut next: HashMap<(usize, usize), usize> = HashMap::new();
+        for (v, around) in rotation.iter().enumerate() {
+            for (i, &w) in around.iter().enumerate() {
+                next.insert((v, w), around[(i + 1) % around.len()]);
+            }
+        }
+        if next.len() != 2 * edges.len() {
+            return false;
+        }
+
+        let mut parent: Vec<usize> = (0..n).collect();
+        fn find(parent: &mut [usize], mut v: usize) -> usize {
+            while parent[v] != v {
+                parent[v] = parent[parent[v]];
+                v = parent[v];
+            }
+            v
+        }
+        for &(a, b) in edges {
+            let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+            parent[ra] = rb;
+        }
+
+        // V - E + F of every component, indexed by its root.
+        let mut euler = vec![0isize; n];
+        for v in 0..n {
+            let root = find(&mut parent, v);
+            euler[root] += 1;
+        }
+        let mut seen = std::collections::HashSet::new();
+        for &(a, b) in edges {
+            let root = find(&mut parent, a);
+            euler[root] -= 1;
+            for &start in [(a, b), (b, a)].iter() {
+                if seen.contains(&start) {
+                    continue;
+                }
+                euler[root] += 1;
+                let mut half = start;
+                while seen.insert(half) {
+                    let (v, w) = half;
+                    half = (w, next[&(w, v)]);
+                }
+            }
+        }
+        (0..n).all(|v| rotation[v].is_empty() || euler[find(&mut parent, v)] == 2)
+    }
+
+    #[test]
+    fn known_graphs() {
+        let grid: Vec<(usize, usize)> = (0..16)
+            .flat_map(|v| {
+                let mut edges = Vec::new();
+                if v % 4 < 3 { edges.push((v, v + 1)); }
+                if v < 12 { edges.push((v, v + 4)); }
+                edges
+            })
+            .collect();
+        let rotation = embedding(16, &grid).unwrap();
+        assert!(is_valid(16, &grid, &rotation));
+
+        // The grid, a triangle next to it, and an isolated vertex.
+        let mut apart = grid.clone();
+        apart.extend_from_slice(&[(16, 17), (17, 18), (18, 16)]);
+        let rotation = embedding(20, &apart).unwrap();
+        assert!(is_valid(20, &apart, &rotation));
+
+        let mut k33 = Vec::new();
+        for a in 0..3 {
+            for b in 3..6 {
+                k33.push((a, b));
+            }
+        }
+        assert!(embedding(6, &k33).is_none());
+        k33.pop();
+        let rotation = embedding(6, &k33).unwrap();
+        assert!(is_valid(6, &k33, &rotation));
+
+        // Petersen graph.
+        let petersen = [(0, 1), (1, 2), (2, 3), (3, 4), (4, 0), (0, 5), (1, 6), (2, 7), (3, 8), (4, 9),
+            (5, 7), (7, 9), (9, 6), (6, 8), (8, 5)];
+        assert!(embedding(10, &petersen).is_none());
+    }
+}
@@ -0,0 +1,177 @@
+//! Structural checks: acyclicity, forests, trees and connectivity.
+
+use crate::{Graph, GraphMap, VertexId};
+use std::hash::Hash;
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Checks that there is no directed cycle, self loops included.
+    /// Time complexity: O(V + E)
+    pub fn is_dag(&self) -> bool {
+        let mut indegree: HashMap<VertexId, usize> = self.vertices()
+            .map(|(id, _)| (id, self.indegree(id)))
+            .collect();
+        let mut ready: Vec<VertexId> = indegree.iter()
+            .filter(|(_, &degree)| degree == 0)
+            .map(|(&id, _)| id)
+            .collect();
+        let mut removed = 0;
+
+        while let Some(vertex) = ready.pop() {
+            removed += 1;
+            for &to in self.outbound[&vertex].iter() {
+                let degree = indegree.get_mut(&to).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.push(to);
+                }
+            }
+        }
+        removed == self.vertex_count()
+    }
+
+    /// Checks that there is no cycle with the edge directions ignored.
+    /// Self loops and pairs of antiparallel edges count as cycles.
+    /// Time complexity: O(E log V)
+    pub fn is_forest(&self) -> bool {
+        let mut parent: HashMap<VertexId, VertexId> = HashMap::new();
+        fn find(parent: &mut HashMap<VertexId, VertexId>, v: VertexId) -> VertexId {
+            let mut root = v;
+            while let Some(&p) = parent.get(&root) {
+                root = p;
+            }
+            let mut current = v;
+            while let Some(&p) = parent.get(&current) {
+                parent.insert(current, root);
+                current = p;
+            }
+            root
+        }
+
+        for (&(from, to), _) in self.edges() {
+            let a = find(&mut parent, from);
+            let b = find(&mut parent, to);
+            if a == b {
+                return false;
+            }
+            parent.insert(a, b);
+        }
+        true
+    }
+
+    /// Checks that the graph is a forest, see [`Graph::is_forest`],
+    /// with a single component. The empty graph is not a tree.
+    pub fn is_tree(&self) -> bool {
+        self.vertex_count() > 0
+            && self.edge_count() + 1 == self.vertex_count()
+            && self.is_forest()
+    }
+
+    /// Same as [`Graph::is_weakly_connected`].
+    pub fn is_connected(&self) -> bool {
+        self.is_weakly_connected()
+    }
+
+    /// Checks that every vertex can be reached from every other
+    /// with the edge directions ignored. The empty graph is connected.
+    /// Time complexity: O(V + E)
+    pub fn is_weakly_connected(&self) -> bool {
+        match self.vertices().next() {
+            Some((start, _)) => self.reach(start, true, true).len() == self.vertex_count(),
+            None => true,
+        }
+    }
+
+    /// Checks that every vertex can be reached from every other
+    /// following the edge directions. The empty graph is connected.
+    /// Time complexity: O(V + E)
+    pub fn is_strongly_connected(&self) -> bool {
+        match self.vertices().next() {
+            Some((start, _)) => {
+                self.reach(start, true, false).len() == self.vertex_count()
+                    && self.reach(start, false, true).len() == self.vertex_count()
+            }
+            None => true,
+        }
+    }
+
+    /// Vertices reachable from `start` going forward along the edges,
+    /// backward, or both.
+    pub(crate) fn reach(&self, start: VertexId, forward: bool, backward: bool) -> HashSet<VertexId> {
+        let mut seen = HashSet::new();
+        let mut stack = vec![start];
+        seen.insert(start);
+        while let Some(vertex) = stack.pop() {
+            let out = self.outbound[&vertex].iter().filter(|_| forward);
+            let inc = self.inbound[&vertex].iter().filter(|_| backward);
+            for &next in out.chain(inc) {
+                if seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        seen
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::is_dag`].
+    pub fn is_dag(&self) -> bool {
+        self.graph.is_dag()
+    }
+
+    /// See [`Graph::is_forest`].
+    pub fn is_forest(&self) -> bool {
+        self.graph.is_forest()
+    }
+
+    /// See [`Graph::is_tree`].
+    pub fn is_tree(&self) -> bool {
+        self.graph.is_tree()
+    }
+
+    /// See [`Graph::is_weakly_connected`].
+    pub fn is_connected(&self) -> bool {
+        self.graph.is_connected()
+    }
+
+    /// See [`Graph::is_weakly_connected`].
+    pub fn is_weakly_connected(&self) -> bool {
+        self.graph.is_weakly_connected()
+    }
+
+    /// See [`Graph::is_strongly_connected`].
+    pub fn is_strongly_connected(&self) -> bool {
+        self.graph.is_strongly_connected()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+
+    #[test]
+    fn predicates() {
+        let mut graph = GraphMap::<u32, ()>::new();
+        graph.add_edge((0, 1), ());
+        graph.add_edge((0, 2), ());
+        graph.add_edge((2, 3), ());
+        assert!(graph.is_dag() && graph.is_tree() && graph.is_connected());
+        assert!(!graph.is_strongly_connected());
+
+        graph.add_vertex(4);
+        assert!(graph.is_forest() && !graph.is_tree() && !graph.is_connected());
+
+        graph.add_edge((3, 0), ());
+        assert!(!graph.is_dag() && !graph.is_forest());
+
+        graph.remove_vertex(4);
+        graph.add_edge((1, 0), ());
+        assert!(graph.is_strongly_connected());
+    }
+}
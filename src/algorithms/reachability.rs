@@ -0,0 +1,297 @@
+//! Reachability between all pairs of vertices: the transitive closure,
+//! and a [`ReachabilityIndex`] answering `can_reach` in constant time.
+//!
+//! Both work on the strongly connected components, whose vertices all
+//! reach the same ones, so a graph made of a few big cycles is as
+//! cheap as a small DAG. Every component keeps a bitset of the ones
+//! it reaches, which takes C^2 / 8 bytes for C components.
+
+use crate::{Graph, GraphMap, VertexId};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Strongly connected components of the graph with vertices `0..n`,
+/// with Tarjan's algorithm. Components are numbered in reverse
+/// topological order, so every edge between two components goes to a
+/// lower number. The depth first search keeps an explicit stack, so
+/// deep graphs don't overflow.
+fn strong_components(adj: &[Vec<usize>]) -> (Vec<usize>, usize) {
+    let n = adj.len();
+    let mut index: Vec<Option<usize>> = vec![None; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut component = vec![0; n];
+    let mut count = 0;
+    let mut time = 0;
+
+    for root in 0..n {
+        if index[root].is_some() {
+            continue;
+        }
+        index[root] = Some(time);
+        low[root] = time;
+        time += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        // (vertex, next neighbour to look at)
+        let mut calls = vec![(root, 0)];
+        while let Some(&mut (v, ref mut next)) = calls.last_mut() {
+            if let Some(&w) = adj[v].get(*next) {
+                *next += 1;
+                match index[w] {
+                    None => {
+                        index[w] = Some(time);
+                        low[w] = time;
+                        time += 1;
+                        stack.push(w);
+                        on_stack[w] = true;
+                        calls.push((w, 0));
+                    }
+                    Some(i) if on_stack[w] => low[v] = low[v].min(i),
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+            if Some(low[v]) == index[v] {
+                loop {
+                    let u = stack.pop().unwrap();
+                    on_stack[u] = false;
+                    component[u] = count;
+                    if u == v {
+                        break;
+                    }
+                }
+                count += 1;
+            }
+        }
+    }
+    (component, count)
+}
+
+/// Answers whether a vertex can reach another along the edges, in
+/// constant time, after a linear-ish precomputation. A vertex always
+/// reaches itself. Built from a snapshot of the graph: it doesn't see
+/// the changes made afterwards, build a new index for those.
+/// `T` identifies the vertices, [`VertexId`] or the vertex data.
+///
+/// ```
+/// use graph::GraphMap;
+///
+/// let graph = GraphMap::<&str, ()>::from_edges(vec![(("a", "b"), ()), (("b", "c"), ()), (("c", "b"), ()), (("d", "c"), ())]);
+/// let index = graph.reachability_index();
+/// assert!(index.can_reach(&"a", &"c"));
+/// assert!(index.can_reach(&"c", &"b"));
+/// assert!(!index.can_reach(&"c", &"a"));
+/// assert!(!index.can_reach(&"a", &"d"));
+/// assert_eq!(index.component_count(), 3);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ReachabilityIndex<T: Eq + Hash> {
+    component: HashMap<T, usize>,
+    members: Vec<Vec<T>>,
+    /// Bitset of the components reachable from every component,
+    /// the component itself included.
+    reach: Vec<Vec<u64>>,
+}
+
+impl<T: Eq + Hash> ReachabilityIndex<T> {
+
+    fn reaches(&self, from: usize, to: usize) -> bool {
+        self.reach[from][to / 64] & (1 << (to % 64)) != 0
+    }
+
+    /// Checks that `to` can be reached from `from`, false if either
+    /// is not in the graph.
+    /// Time complexity: O(1)
+    pub fn can_reach(&self, from: &T, to: &T) -> bool {
+        match (self.component.get(from), self.component.get(to)) {
+            (Some(&from), Some(&to)) => self.reaches(from, to),
+            _ => false,
+        }
+    }
+
+    /// Vertices reachable from `from`, itself included. Empty if it
+    /// is not in the graph.
+    /// Time complexity: O(V)
+    pub fn reachable_from(&self, from: &T) -> Vec<&T> {
+        let from = match self.component.get(from) {
+            Some(&from) => from,
+            None => return Vec::new(),
+        };
+        (0..self.members.len())
+            .filter(|&c| self.reaches(from, c))
+            .flat_map(|c| self.members[c].iter())
+            .collect()
+    }
+
+    /// Checks that the two vertices reach each other.
+    pub fn same_component(&self, a: &T, b: &T) -> bool {
+        match (self.component.get(a), self.component.get(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Number of strongly connected components.
+    pub fn component_count(&self) -> usize {
+        self.members.len()
+    }
+
+    fn map<U: Eq + Hash, F: Fn(T) -> U>(self, f: F) -> ReachabilityIndex<U> {
+        ReachabilityIndex {
+            component: self.component.into_iter().map(|(v, c)| (f(v), c)).collect(),
+            members: self.members.into_iter().map(|m| m.into_iter().map(&f).collect()).collect(),
+            reach: self.reach,
+        }
+    }
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Builds a [`ReachabilityIndex`] of the graph.
+    /// Time complexity: O(V + E C / 64), for C strongly connected components
+    pub fn reachability_index(&self) -> ReachabilityIndex<VertexId> {
+        let ids: Vec<VertexId> = self.vertices().map(|(id, _)| id).collect();
+        let index: HashMap<VertexId, usize> = ids.iter()
+            .enumerate()
+            .map(|(i, &id)| (id, i))
+            .collect();
+        let mut adj = vec![Vec::new(); ids.len()];
+        for &(from, to) in self.edges.keys() {
+            adj[index[&from]].push(index[&to]);
+        }
+        let (component, count) = strong_components(&adj);
+
+        let mut members = vec![Vec::new(); count];
+        let mut successors = vec![Vec::new(); count];
+        for (v, &c) in component.iter().enumerate() {
+            members[c].push(ids[v]);
+            successors[c].extend(adj[v].iter().map(|&w| component[w]).filter(|&d| d != c));
+        }
+        // `div_ceil` needs Rust 1.73.
+        #[allow(clippy::manual_div_ceil)]
+        let words = (count + 63) / 64;
+        let mut reach: Vec<Vec<u64>> = Vec::with_capacity(count);
+        for (c, successors) in successors.iter().enumerate() {
+            let mut bits = vec![0; words];
+            bits[c / 64] |= 1 << (c % 64);
+            for &d in successors.iter() {
+                for (word, other) in bits.iter_mut().zip(reach[d].iter()) {
+                    *word |= other;
+                }
+            }
+            reach.push(bits);
+        }
+
+        ReachabilityIndex {
+            component: ids.into_iter().zip(component).collect(),
+            members,
+            reach,
+        }
+    }
+
+    /// Graph with the same vertices and ids, and an edge from every
+    /// vertex to every vertex it reaches with a path of at least one
+    /// edge. So a vertex gets a self loop only if it is on a cycle.
+    /// Time complexity: O(V + E C / 64 + V^2), for C strongly connected components
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// let mut graph = Graph::<&str, u32>::new();
+    /// let a = graph.add_vertex("a");
+    /// let b = graph.add_vertex("b");
+    /// let c = graph.add_vertex("c");
+    /// graph.add_edge((a, b), 1).unwrap();
+    /// graph.add_edge((b, c), 2).unwrap();
+    ///
+    /// let closure = graph.transitive_closure();
+    /// assert_eq!(closure.edge_count(), 3);
+    /// assert!(closure.get_edge((a, c)).is_some());
+    /// assert!(closure.get_edge((c, a)).is_none());
+    /// ```
+    pub fn transitive_closure(&self) -> Graph<V, ()>
+    where
+        V: Clone,
+    {
+        let index = self.reachability_index();
+        let mut closure = Graph {
+            arena: self.arena.clone(),
+            inbound: self.inbound.keys().map(|&id| (id, Default::default())).collect(),
+            outbound: self.outbound.keys().map(|&id| (id, Default::default())).collect(),
+            edges: Default::default(),
+        };
+        for (from, _) in self.vertices() {
+            let on_cycle = index.members[index.component[&from]].len() > 1
+                || self.get_edge((from, from)).is_some();
+            for &to in index.reachable_from(&from) {
+                if to != from || on_cycle {
+                    closure.insert_edge((from, to), ());
+                }
+            }
+        }
+        closure
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::reachability_index`].
+    pub fn reachability_index(&self) -> ReachabilityIndex<V> {
+        self.graph.reachability_index().map(|id| self.graph.arena[id].clone())
+    }
+
+    /// See [`Graph::transitive_closure`].
+    pub fn transitive_closure(&self) -> GraphMap<V, ()> {
+        GraphMap {
+            graph: self.graph.transitive_closure(),
+            map: self.map.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+
+    #[test]
+    fn index_agrees_with_the_closure() {
+        // a long chain, so the components need several words, with
+        // a cycle 10 -> 20 -> 10 and a self loop on 100
+        let mut graph = GraphMap::<u32, ()>::new();
+        for i in 0..150 {
+            graph.add_edge((i, i + 1), ());
+        }
+        graph.add_edge((20, 10), ());
+        graph.add_edge((100, 100), ());
+        graph.add_vertex(500);
+
+        let index = graph.reachability_index();
+        assert_eq!(index.component_count(), 152 - 10);
+        assert!(index.can_reach(&0, &150));
+        assert!(index.can_reach(&20, &11));
+        assert!(!index.can_reach(&21, &20));
+        assert!(index.same_component(&10, &20));
+        assert!(index.can_reach(&500, &500));
+        assert!(!index.can_reach(&0, &500));
+        assert_eq!(index.reachable_from(&140).len(), 11);
+
+        let closure = graph.transitive_closure();
+        assert_eq!(closure.vertex_count(), graph.vertex_count());
+        for ((from, to), _) in closure.edges() {
+            assert!(index.can_reach(from, to));
+        }
+        assert!(closure.get_edge((15, 15)).is_some());
+        assert!(closure.get_edge((100, 100)).is_some());
+        assert!(closure.get_edge((101, 101)).is_none());
+        // pairs i < j, plus the pairs back inside the cycle and the loops
+        let expected = 151 * 150 / 2 + 11 * 10 / 2 + 11 + 1;
+        assert_eq!(closure.edge_count(), expected);
+    }
+}
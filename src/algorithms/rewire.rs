@@ -0,0 +1,84 @@
+//! Degree preserving randomization with double edge swaps.
+
+use crate::{Graph, GraphMap, EdgeId};
+use rand::Rng;
+use std::hash::Hash;
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Randomizes the graph with double edge swaps, keeping the in and
+    /// out degree of every vertex. Each swap picks two edges `a -> b` and
+    /// `c -> d` and replaces them with `a -> d` and `c -> b`, the edge data
+    /// staying with the source. Swaps that would create a self loop or a
+    /// duplicate edge are rejected.
+    ///
+    /// Stops after `swaps` successful swaps, or after `100 * swaps`
+    /// attempts on graphs where few swaps are possible.
+    /// Returns the number of swaps done.
+    pub fn rewire<R: Rng>(&mut self, swaps: usize, rng: &mut R) -> usize {
+        let mut edges: Vec<EdgeId> = self.edges.keys().copied().collect();
+        // Sorted so the same seed gives the same graph.
+        edges.sort_unstable();
+        if edges.len() < 2 {
+            return 0;
+        }
+
+        let mut done = 0;
+        let mut attempts = 0;
+        while done < swaps && attempts < swaps.saturating_mul(100) {
+            attempts += 1;
+            let i = rng.gen_range(0..edges.len());
+            let j = rng.gen_range(0..edges.len());
+            let (a, b) = edges[i];
+            let (c, d) = edges[j];
+            if a == c || b == d || a == d || c == b {
+                continue;
+            }
+            if self.edges.contains_key(&(a, d)) || self.edges.contains_key(&(c, b)) {
+                continue;
+            }
+
+            let ab = self.try_remove_edge((a, b)).expect("listed edges are in the graph");
+            let cd = self.try_remove_edge((c, d)).expect("listed edges are in the graph");
+            self.insert_edge((a, d), ab);
+            self.insert_edge((c, b), cd);
+            edges[i] = (a, d);
+            edges[j] = (c, b);
+            done += 1;
+        }
+        done
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// Degree preserving randomization, see [`Graph::rewire`].
+    pub fn rewire<R: Rng>(&mut self, swaps: usize, rng: &mut R) -> usize {
+        self.graph.rewire(swaps, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn rewire_keeps_degrees() {
+        let mut graph = GraphMap::<u32, u32>::new();
+        for i in 0..20 {
+            graph.add_edge((i, (i + 1) % 20), i);
+            graph.add_edge((i, (i + 7) % 20), i);
+        }
+        let degrees = |g: &GraphMap<u32, u32>| -> Vec<(usize, usize)> {
+            (0..20).map(|v| (g.indegree(v), g.outdegree(v))).collect()
+        };
+        let before = degrees(&graph);
+
+        let mut rng = StdRng::seed_from_u64(3);
+        assert!(graph.rewire(50, &mut rng) > 0);
+        assert_eq!(degrees(&graph), before);
+        assert_eq!(graph.edge_count(), 40);
+        assert!(graph.edges().all(|((a, b), _)| a != b));
+    }
+}
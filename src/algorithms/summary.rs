@@ -0,0 +1,164 @@
+
+use crate::{Graph, GraphMap};
+use std::fmt;
+use std::hash::Hash;
+use std::collections::HashSet;
+
+/// Overview of a graph, see [`Graph::summary`].
+/// Degrees count both the inbound and the outbound edges.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphSummary {
+    /// Number of vertices.
+    pub order: usize,
+    /// Number of edges.
+    pub size: usize,
+    /// Edges over the possible `V * (V - 1)` directed edges.
+    pub density: f64,
+    pub min_degree: usize,
+    pub max_degree: usize,
+    pub mean_degree: f64,
+    /// Fraction of the edges between distinct vertices whose
+    /// reverse edge is also in the graph.
+    pub reciprocity: f64,
+    pub self_loops: usize,
+    /// Number of weakly connected components.
+    pub components: usize,
+    pub is_dag: bool,
+}
+
+impl fmt::Display for GraphSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "vertices: {}", self.order)?;
+        writeln!(f, "edges: {}", self.size)?;
+        writeln!(f, "density: {:.6}", self.density)?;
+        writeln!(f, "degree: min {}, max {}, mean {:.3}", self.min_degree, self.max_degree, self.mean_degree)?;
+        writeln!(f, "reciprocity: {:.6}", self.reciprocity)?;
+        writeln!(f, "self loops: {}", self.self_loops)?;
+        writeln!(f, "components: {}", self.components)?;
+        writeln!(f, "dag: {}", self.is_dag)
+    }
+}
+
+/// Histograms of the degrees, see [`Graph::degree_distribution`].
+/// `indegree[d]` is the number of vertices with indegree `d`, and so
+/// on, up to the largest degree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DegreeDistribution {
+    pub indegree: Vec<usize>,
+    pub outdegree: Vec<usize>,
+    /// Indegree plus outdegree.
+    pub total: Vec<usize>,
+}
+
+fn count(histogram: &mut Vec<usize>, degree: usize) {
+    if histogram.len() <= degree {
+        histogram.resize(degree + 1, 0);
+    }
+    histogram[degree] += 1;
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Number of vertices of every in, out and total degree.
+    /// Time complexity: O(V)
+    pub fn degree_distribution(&self) -> DegreeDistribution {
+        let mut distribution = DegreeDistribution {
+            indegree: Vec::new(),
+            outdegree: Vec::new(),
+            total: Vec::new(),
+        };
+        for (id, _) in self.vertices() {
+            let (indegree, outdegree) = (self.indegree(id), self.outdegree(id));
+            count(&mut distribution.indegree, indegree);
+            count(&mut distribution.outdegree, outdegree);
+            count(&mut distribution.total, indegree + outdegree);
+        }
+        distribution
+    }
+
+    /// Computes a [`GraphSummary`].
+    /// Time complexity: O(V + E)
+    pub fn summary(&self) -> GraphSummary {
+        let order = self.vertex_count();
+        let size = self.edge_count();
+        let degrees: Vec<usize> = self.vertices()
+            .map(|(id, _)| self.indegree(id) + self.outdegree(id))
+            .collect();
+
+        let self_loops = self.edges().filter(|(&(from, to), _)| from == to).count();
+        let reciprocated = self.edges()
+            .filter(|(&(from, to), _)| from != to && self.get_edge((to, from)).is_some())
+            .count();
+        let between = size - self_loops;
+
+        let mut seen = HashSet::new();
+        let mut components = 0;
+        for (id, _) in self.vertices() {
+            if !seen.contains(&id) {
+                components += 1;
+                seen.extend(self.reach(id, true, true));
+            }
+        }
+
+        GraphSummary {
+            order,
+            size,
+            density: if order > 1 { size as f64 / (order * (order - 1)) as f64 } else { 0.0 },
+            min_degree: degrees.iter().copied().min().unwrap_or(0),
+            max_degree: degrees.iter().copied().max().unwrap_or(0),
+            mean_degree: if order > 0 { 2.0 * size as f64 / order as f64 } else { 0.0 },
+            reciprocity: if between > 0 { reciprocated as f64 / between as f64 } else { 0.0 },
+            self_loops,
+            components,
+            is_dag: self.is_dag(),
+        }
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::summary`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut graph = GraphMap::<u32, u32>::new();
+    /// graph.add_edge((0, 1), 1);
+    /// graph.add_edge((1, 2), 1);
+    /// graph.add_vertex(3);
+    ///
+    /// let summary = graph.summary();
+    /// assert_eq!((summary.order, summary.size, summary.components), (4, 2, 2));
+    /// assert_eq!((summary.min_degree, summary.max_degree), (0, 2));
+    /// assert!(summary.is_dag);
+    ///
+    /// graph.add_edge((1, 0), 1);
+    /// graph.add_edge((2, 2), 1);
+    /// let summary = graph.summary();
+    /// assert_eq!(summary.reciprocity, 2.0 / 3.0);
+    /// assert_eq!(summary.self_loops, 1);
+    /// assert!(!summary.is_dag);
+    /// ```
+    pub fn summary(&self) -> GraphSummary {
+        self.graph.summary()
+    }
+
+    /// See [`Graph::degree_distribution`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut graph = GraphMap::<u32, ()>::new();
+    /// graph.add_edge((0, 1), ());
+    /// graph.add_edge((1, 0), ());
+    /// graph.add_edge((1, 2), ());
+    /// graph.add_edge((2, 2), ());
+    ///
+    /// let distribution = graph.degree_distribution();
+    /// assert_eq!(distribution.outdegree, vec![0, 2, 1]);
+    /// assert_eq!(distribution.total, vec![0, 0, 1, 2]);
+    /// ```
+    pub fn degree_distribution(&self) -> DegreeDistribution {
+        self.graph.degree_distribution()
+    }
+}
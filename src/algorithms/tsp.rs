@@ -0,0 +1,313 @@
+//! Travelling salesman tours: exact with Held-Karp for small graphs,
+//! and nearest neighbour improved by 2-opt for bigger ones.
+//!
+//! The graphs are meant to be complete, but a missing edge is simply
+//! never used, and there is no tour when every closed walk needs one.
+//! Edges are directed, so the cost of a tour may change when it is
+//! walked backwards.
+
+use crate::{Graph, GraphMap, VertexId};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Graphs up to this many vertices get an exact tour from [`Graph::tsp`].
+pub const EXACT_LIMIT: usize = 12;
+
+/// A closed tour visiting every vertex once. The first vertex is not
+/// repeated at the end, the cost includes the edge back to it.
+/// `T` identifies the vertices, [`VertexId`] or the vertex data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tour<T, E> {
+    pub vertices: Vec<T>,
+    pub cost: E,
+}
+
+impl<T, E> Tour<T, E> {
+    fn map<U, F: Fn(T) -> U>(self, f: F) -> Tour<U, E> {
+        Tour {
+            vertices: self.vertices.into_iter().map(f).collect(),
+            cost: self.cost,
+        }
+    }
+}
+
+/// Vertices in arena order and the weights between them by index.
+struct Costs<E> {
+    ids: Vec<VertexId>,
+    weight: Vec<Vec<Option<E>>>,
+}
+
+impl<E: Copy + Ord + Add<Output = E> + Default> Costs<E> {
+
+    /// Cost of walking `path`, `None` if an edge is missing.
+    fn path(&self, path: &[usize]) -> Option<E> {
+        path.windows(2).try_fold(E::default(), |sum, pair| Some(sum + self.weight[pair[0]][pair[1]]?))
+    }
+
+    /// Cost of the closed tour through `order`.
+    fn tour(&self, order: &[usize]) -> Option<E> {
+        let back = self.weight[*order.last()?][order[0]]?;
+        Some(self.path(order)? + back)
+    }
+
+    fn to_tour(&self, order: Vec<usize>) -> Option<Tour<VertexId, E>> {
+        Some(Tour {
+            cost: self.tour(&order)?,
+            vertices: order.into_iter().map(|i| self.ids[i]).collect(),
+        })
+    }
+
+    fn held_karp(&self) -> Option<Vec<usize>> {
+        let n = self.ids.len();
+        if n <= 1 {
+            return Some((0..n).collect());
+        }
+        assert!(n < usize::BITS as usize, "too many vertices for an exact tour");
+        // best[mask][last]: cheapest path from vertex 0 through the
+        // vertices of `mask`, which always has bit 0, ending at `last`
+        let full = 1usize << n;
+        let mut best: Vec<Vec<Option<(E, usize)>>> = vec![vec![None; n]; full];
+        best[1][0] = Some((E::default(), 0));
+        for mask in (1..full).step_by(2) {
+            for last in 0..n {
+                let cost = match best[mask][last] {
+                    Some((cost, _)) => cost,
+                    None => continue,
+                };
+                for next in 1..n {
+                    if mask & (1 << next) != 0 {
+                        continue;
+                    }
+                    if let Some(weight) = self.weight[last][next] {
+                        let entry = &mut best[mask | (1 << next)][next];
+                        if !matches!(*entry, Some((old, _)) if old <= cost + weight) {
+                            *entry = Some((cost + weight, last));
+                        }
+                    }
+                }
+            }
+        }
+
+        let (_, mut last) = (1..n)
+            .filter_map(|last| {
+                let (cost, _) = best[full - 1][last]?;
+                Some((cost + self.weight[last][0]?, last))
+            })
+            .min()?;
+        let mut mask = full - 1;
+        let mut order = Vec::with_capacity(n);
+        while last != 0 {
+            order.push(last);
+            let (_, previous) = best[mask][last].unwrap();
+            mask &= !(1 << last);
+            last = previous;
+        }
+        order.push(0);
+        order.reverse();
+        Some(order)
+    }
+
+    fn nearest_neighbor(&self, start: usize) -> Option<Vec<usize>> {
+        let n = self.ids.len();
+        let mut visited = vec![false; n];
+        let mut order = vec![start];
+        visited[start] = true;
+        let mut current = start;
+        while order.len() < n {
+            let (_, next) = (0..n)
+                .filter(|&next| !visited[next])
+                .filter_map(|next| Some((self.weight[current][next]?, next)))
+                .min()?;
+            visited[next] = true;
+            order.push(next);
+            current = next;
+        }
+        Some(order)
+    }
+
+    /// Reverses segments of the tour while that makes it cheaper.
+    fn two_opt(&self, order: &mut [usize]) {
+        let n = order.len();
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 1..n.saturating_sub(1) {
+                for k in i + 1..n {
+                    let after = order[(k + 1) % n];
+                    let mut old = vec![order[i - 1]];
+                    old.extend_from_slice(&order[i..=k]);
+                    old.push(after);
+                    let mut new = old.clone();
+                    new[1..=k - i + 1].reverse();
+                    if let (Some(old), Some(new)) = (self.path(&old), self.path(&new)) {
+                        if new < old {
+                            order[i..=k].reverse();
+                            improved = true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<V: std::fmt::Debug, E: Copy + Ord + Add<Output = E> + Default> Graph<V, E> {
+
+    fn costs(&self) -> Costs<E> {
+        let ids: Vec<VertexId> = self.vertices().map(|(id, _)| id).collect();
+        let index: HashMap<VertexId, usize> = ids.iter()
+            .enumerate()
+            .map(|(i, &id)| (id, i))
+            .collect();
+        let mut weight = vec![vec![None; ids.len()]; ids.len()];
+        for (&(from, to), &w) in self.edges() {
+            if from != to {
+                weight[index[&from]][index[&to]] = Some(w);
+            }
+        }
+        Costs {
+            ids,
+            weight,
+        }
+    }
+
+    /// Cheapest tour with the Held-Karp dynamic program, `None` if
+    /// there is no tour. Only for small graphs, see [`EXACT_LIMIT`].
+    /// Time complexity: O(2^V V^2)
+    pub fn tsp_exact(&self) -> Option<Tour<VertexId, E>> {
+        let costs = self.costs();
+        let order = costs.held_karp()?;
+        costs.to_tour(order)
+    }
+
+    /// Tour going to the cheapest unvisited vertex every time, starting
+    /// at `start`. `None` if it gets stuck or `start` is not in the graph.
+    /// Time complexity: O(V^2)
+    pub fn tsp_nearest_neighbor(&self, start: VertexId) -> Option<Tour<VertexId, E>> {
+        let costs = self.costs();
+        let start = costs.ids.iter().position(|&id| id == start)?;
+        let order = costs.nearest_neighbor(start)?;
+        costs.to_tour(order)
+    }
+
+    /// Improves `tour` with 2-opt moves, reversing a part of the tour
+    /// as long as that makes it cheaper. The first vertex stays first.
+    /// Time complexity: O(V^3) for every round of improvements
+    pub fn tsp_two_opt(&self, tour: Tour<VertexId, E>) -> Tour<VertexId, E> {
+        let costs = self.costs();
+        let mut order: Vec<usize> = tour.vertices.iter()
+            .map(|id| costs.ids.iter().position(|other| other == id).expect("vertex of the tour not in the graph"))
+            .collect();
+        costs.two_opt(&mut order);
+        costs.to_tour(order).unwrap_or(tour)
+    }
+
+    /// Exact tour for graphs up to [`EXACT_LIMIT`] vertices, otherwise
+    /// the best nearest neighbour tour from every start, improved with
+    /// 2-opt. `None` if no tour was found.
+    pub fn tsp(&self) -> Option<Tour<VertexId, E>> {
+        let costs = self.costs();
+        let n = costs.ids.len();
+        let order = if n <= EXACT_LIMIT {
+            costs.held_karp()?
+        } else {
+            (0..n)
+                .filter_map(|start| {
+                    let mut order = costs.nearest_neighbor(start)?;
+                    costs.tour(&order)?;
+                    costs.two_opt(&mut order);
+                    Some((costs.tour(&order)?, order))
+                })
+                .min_by_key(|&(cost, _)| cost)?
+                .1
+        };
+        costs.to_tour(order)
+    }
+}
+
+impl<V, E> GraphMap<V, E>
+where
+    V: Eq + Hash + Clone + std::fmt::Debug,
+    E: Copy + Ord + Add<Output = E> + Default,
+{
+
+    /// See [`Graph::tsp_exact`].
+    pub fn tsp_exact(&self) -> Option<Tour<&V, E>> {
+        Some(self.graph.tsp_exact()?.map(|id| &self.graph.arena[id]))
+    }
+
+    /// See [`Graph::tsp_nearest_neighbor`].
+    pub fn tsp_nearest_neighbor(&self, start: &V) -> Option<Tour<&V, E>> {
+        let start = *self.map.get(start)?;
+        Some(self.graph.tsp_nearest_neighbor(start)?.map(|id| &self.graph.arena[id]))
+    }
+
+    /// See [`Graph::tsp_two_opt`].
+    pub fn tsp_two_opt(&self, tour: Tour<&V, E>) -> Tour<&V, E> {
+        let tour = tour.map(|v| self.map[v]);
+        self.graph.tsp_two_opt(tour).map(|id| &self.graph.arena[id])
+    }
+
+    /// See [`Graph::tsp`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// // four corners of a square, the diagonals cost more
+    /// let mut graph = GraphMap::<char, u32>::new();
+    /// for &(a, b, w) in [('a', 'b', 1), ('b', 'c', 1), ('c', 'd', 1), ('d', 'a', 1), ('a', 'c', 3), ('b', 'd', 3)].iter() {
+    ///     graph.add_edge((a, b), w);
+    ///     graph.add_edge((b, a), w);
+    /// }
+    ///
+    /// let tour = graph.tsp().unwrap();
+    /// assert_eq!(tour.cost, 4);
+    /// assert_eq!(tour.vertices.len(), 4);
+    ///
+    /// let greedy = graph.tsp_nearest_neighbor(&'a').unwrap();
+    /// assert_eq!(graph.tsp_two_opt(greedy).cost, 4);
+    /// ```
+    pub fn tsp(&self) -> Option<Tour<&V, E>> {
+        Some(self.graph.tsp()?.map(|id| &self.graph.arena[id]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+
+    #[test]
+    fn heuristics_close_to_exact() {
+        // points of a 3 x 4 grid, Manhattan distances
+        let points: Vec<(i32, i32)> = (0..12).map(|i| (i % 4, i / 4)).collect();
+        let mut graph = GraphMap::<usize, i32>::new();
+        for (a, &(xa, ya)) in points.iter().enumerate() {
+            for (b, &(xb, yb)) in points.iter().enumerate() {
+                if a != b {
+                    graph.add_edge((a, b), (xa - xb).abs() + (ya - yb).abs());
+                }
+            }
+        }
+
+        let exact = graph.tsp_exact().unwrap();
+        assert_eq!(exact.cost, 12);
+        let mut visited: Vec<usize> = exact.vertices.iter().map(|&&v| v).collect();
+        visited.sort_unstable();
+        assert_eq!(visited, (0..12).collect::<Vec<_>>());
+
+        let greedy = graph.tsp_nearest_neighbor(&5).unwrap();
+        assert_eq!(greedy.vertices[0], &5);
+        let improved = graph.tsp_two_opt(greedy.clone());
+        assert!(improved.cost <= greedy.cost);
+        assert!(improved.cost >= exact.cost);
+
+        graph.remove_edge((0, 5));
+        graph.remove_edge((5, 0));
+        assert_eq!(graph.tsp().unwrap().cost, 12);
+        let mut path = GraphMap::<u32, u32>::from_edges(vec![((1, 2), 1), ((2, 3), 1)]);
+        assert_eq!(path.tsp(), None);
+        path.add_edge((3, 1), 1);
+        assert_eq!(path.tsp().unwrap().cost, 3);
+    }
+}
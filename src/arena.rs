@@ -0,0 +1,232 @@
+//! Vertex ids, and the arena holding the vertex data under them.
+//!
+//! The graphs keep their vertices in a generational arena, so the id of
+//! a removed vertex is never confused with a vertex added later in the
+//! same slot. [`VertexId`] wraps the index of the arena, which stays an
+//! implementation detail.
+
+use generational_arena::Index;
+use std::fmt;
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops;
+
+/// Id of a vertex, given by the graph when the vertex is added. Only
+/// valid in the graph that made it.
+///
+/// Displays as `<slot>.<generation>`: the slot in the arena and the
+/// number of vertices that held that slot before. Ordered by slot, then
+/// by generation.
+///
+/// ```
+/// use graph::{Graph, VertexId};
+///
+/// let mut graph = Graph::<&str, ()>::new();
+/// let a = graph.add_vertex("a");
+/// graph.remove_vertex(a);
+/// let b = graph.add_vertex("b");
+/// assert_eq!(a.to_string(), "0.0");
+/// assert_eq!(b.to_string(), "0.1");
+/// assert!(a < b);
+/// assert_eq!(<(usize, u64)>::from(b), (0, 1));
+/// assert_eq!(VertexId::from((0, 1)), b);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VertexId(Index);
+
+impl From<(usize, u64)> for VertexId {
+    /// Id from its slot and generation.
+    fn from((slot, generation): (usize, u64)) -> Self {
+        VertexId(Index::from_raw_parts(slot, generation))
+    }
+}
+
+impl From<VertexId> for (usize, u64) {
+    /// Slot and generation of the id.
+    fn from(id: VertexId) -> Self {
+        id.0.into_raw_parts()
+    }
+}
+
+impl fmt::Display for VertexId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (slot, generation) = self.0.into_raw_parts();
+        write!(f, "{}.{}", slot, generation)
+    }
+}
+
+impl fmt::Debug for VertexId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "VertexId({})", self)
+    }
+}
+
+/// Id wrapping an index of the arena.
+pub(crate) trait Key: Copy {
+    fn from_index(index: Index) -> Self;
+    fn index(self) -> Index;
+}
+
+impl Key for VertexId {
+    fn from_index(index: Index) -> Self {
+        VertexId(index)
+    }
+
+    fn index(self) -> Index {
+        self.0
+    }
+}
+
+/// Layout of a slot of the arena crate, for [`Arena::footprint`].
+#[allow(dead_code)]
+enum Slot<T> {
+    Free(Option<usize>),
+    Occupied(u64, T),
+}
+
+/// Generational arena indexed by `K`.
+#[derive(Clone, Debug)]
+pub(crate) struct Arena<T, K = VertexId> {
+    inner: generational_arena::Arena<T>,
+    key: PhantomData<K>,
+}
+
+impl<T, K: Key> Arena<T, K> {
+    pub(crate) fn new() -> Self {
+        Arena::with_capacity(0)
+    }
+
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Arena {
+            inner: generational_arena::Arena::with_capacity(capacity),
+            key: PhantomData,
+        }
+    }
+
+    pub(crate) fn insert(&mut self, value: T) -> K {
+        K::from_index(self.inner.insert(value))
+    }
+
+    pub(crate) fn remove(&mut self, key: K) -> Option<T> {
+        self.inner.remove(key.index())
+    }
+
+    pub(crate) fn contains(&self, key: K) -> bool {
+        self.inner.contains(key.index())
+    }
+
+    pub(crate) fn get(&self, key: K) -> Option<&T> {
+        self.inner.get(key.index())
+    }
+
+    pub(crate) fn get_mut(&mut self, key: K) -> Option<&mut T> {
+        self.inner.get_mut(key.index())
+    }
+
+    pub(crate) fn retain<F: FnMut(K, &mut T) -> bool>(&mut self, mut keep: F) {
+        self.inner.retain(|index, value| keep(K::from_index(index), value))
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Number of slots, every slot is below it.
+    pub(crate) fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Bytes taken by the slots, without the heap memory owned by
+    /// the values.
+    pub(crate) fn footprint(&self) -> usize {
+        self.capacity() * mem::size_of::<Slot<T>>()
+    }
+
+    pub(crate) fn iter(&self) -> Iter<'_, T, K> {
+        Iter {
+            inner: self.inner.iter(),
+            key: PhantomData,
+        }
+    }
+}
+
+impl<T, K: Key> ops::Index<K> for Arena<T, K> {
+    type Output = T;
+
+    fn index(&self, key: K) -> &T {
+        &self.inner[key.index()]
+    }
+}
+
+impl<T, K: Key> ops::IndexMut<K> for Arena<T, K> {
+    fn index_mut(&mut self, key: K) -> &mut T {
+        &mut self.inner[key.index()]
+    }
+}
+
+/// Iterator over the values of an [`Arena`] and their keys, in slot order.
+#[derive(Clone, Debug)]
+pub(crate) struct Iter<'a, T, K = VertexId> {
+    inner: generational_arena::Iter<'a, T>,
+    key: PhantomData<K>,
+}
+
+impl<'a, T, K: Key> Iterator for Iter<'a, T, K> {
+    type Item = (K, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(index, value)| (K::from_index(index), value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T, K: Key> DoubleEndedIterator for Iter<'a, T, K> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(index, value)| (K::from_index(index), value))
+    }
+}
+
+impl<'a, T, K: Key> ExactSizeIterator for Iter<'a, T, K> {}
+
+impl<'a, T, K: Key> FusedIterator for Iter<'a, T, K> {}
+
+/// Iterator over the vertices of a graph and their data, in the order
+/// of their slots in the arena.
+#[derive(Clone, Debug)]
+pub struct Vertices<'a, V> {
+    iter: Iter<'a, V>,
+}
+
+impl<'a, V> Vertices<'a, V> {
+    pub(crate) fn new(arena: &'a Arena<V>) -> Self {
+        Vertices {
+            iter: arena.iter(),
+        }
+    }
+}
+
+impl<'a, V> Iterator for Vertices<'a, V> {
+    type Item = (VertexId, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, V> DoubleEndedIterator for Vertices<'a, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<'a, V> ExactSizeIterator for Vertices<'a, V> {}
+
+impl<'a, V> FusedIterator for Vertices<'a, V> {}
@@ -0,0 +1,381 @@
+//! Contraction hierarchies.
+//!
+//! [`preprocess`] contracts the vertices one by one, from the least to the
+//! most important, adding shortcut edges so that the distances between the
+//! remaining vertices stay the same. A query then only needs two small
+//! Dijkstra searches that go up the hierarchy, one from each end, which is
+//! much faster than a plain Dijkstra on road-network like graphs.
+//!
+//! ```
+//! use graph::Graph;
+//! use graph::ch;
+//!
+//! let mut graph = Graph::<&str, u32>::new();
+//! let a = graph.add_vertex("a");
+//! let b = graph.add_vertex("b");
+//! let c = graph.add_vertex("c");
+//! graph.add_edge((a, b), 2).unwrap();
+//! graph.add_edge((b, c), 3).unwrap();
+//! graph.add_edge((a, c), 7).unwrap();
+//!
+//! let hierarchy = ch::preprocess(&graph);
+//! assert_eq!(hierarchy.distance(a, c), Some(5));
+//! assert_eq!(hierarchy.shortest_path(a, c), Some((vec![a, b, c], 5)));
+//! ```
+
+use super::{Graph, VertexId};
+use std::cmp::Reverse;
+use std::ops::Add;
+use std::collections::{
+    BinaryHeap,
+    HashMap,
+};
+
+/// Vertices settled by a witness search before giving up. A missed
+/// witness only costs an unneeded shortcut, never a wrong distance.
+const WITNESS_SETTLE_LIMIT: usize = 500;
+
+/// Preprocessed graph answering shortest path queries, see [`preprocess`].
+#[derive(Clone, Debug)]
+pub struct ContractionHierarchy<E> {
+    ids: Vec<VertexId>,
+    index: HashMap<VertexId, usize>,
+    /// Edges going to a vertex contracted later.
+    up: Vec<Vec<(usize, E)>>,
+    /// Reversed edges coming from a vertex contracted later.
+    down: Vec<Vec<(usize, E)>>,
+    /// Contracted vertex in the middle of every shortcut.
+    middle: HashMap<(usize, usize), usize>,
+}
+
+/// Edges of the graph being contracted, the lightest one for every pair,
+/// with the middle vertex if it is a shortcut.
+struct Overlay<E> {
+    outbound: Vec<HashMap<usize, (E, Option<usize>)>>,
+    inbound: Vec<HashMap<usize, E>>,
+    contracted: Vec<bool>,
+}
+
+impl<E> Overlay<E>
+where
+    E: Copy + Ord + Add<Output = E> + Default,
+{
+    fn add_edge(&mut self, from: usize, to: usize, weight: E, middle: Option<usize>) {
+        match self.outbound[from].get(&to) {
+            Some(&(old, _)) if old <= weight => (),
+            _ => {
+                self.outbound[from].insert(to, (weight, middle));
+                self.inbound[to].insert(from, weight);
+            }
+        }
+    }
+
+    /// Shortcuts needed to contract `vertex`, as `(from, to, weight)`.
+    fn shortcuts(&self, vertex: usize) -> Vec<(usize, usize, E)> {
+        let mut shortcuts = Vec::new();
+        let targets: Vec<(usize, E)> = self.outbound[vertex].iter()
+            .filter(|(&to, _)| !self.contracted[to])
+            .map(|(&to, &(weight, _))| (to, weight))
+            .collect();
+        if targets.is_empty() {
+            return shortcuts;
+        }
+        let longest = targets.iter().map(|&(_, weight)| weight).max().unwrap();
+
+        for (&from, &first) in self.inbound[vertex].iter() {
+            if self.contracted[from] {
+                continue;
+            }
+            let witness = self.witness_search(from, vertex, first + longest);
+            for &(to, second) in targets.iter() {
+                if to == from {
+                    continue;
+                }
+                let through = first + second;
+                match witness.get(&to) {
+                    Some(&distance) if distance <= through => (),
+                    _ => shortcuts.push((from, to, through)),
+                }
+            }
+        }
+        shortcuts
+    }
+
+    /// Distances from `source` avoiding `skip`, up to `limit`.
+    fn witness_search(&self, source: usize, skip: usize, limit: E) -> HashMap<usize, E> {
+        let mut dist = HashMap::new();
+        let mut heap = BinaryHeap::new();
+        let mut settled = 0;
+        dist.insert(source, E::default());
+        heap.push(Reverse((E::default(), source)));
+
+        while let Some(Reverse((d, u))) = heap.pop() {
+            if d > dist[&u] {
+                continue;
+            }
+            if d > limit || settled == WITNESS_SETTLE_LIMIT {
+                break;
+            }
+            settled += 1;
+            for (&v, &(weight, _)) in self.outbound[u].iter() {
+                if v == skip || self.contracted[v] {
+                    continue;
+                }
+                let next = d + weight;
+                if !matches!(dist.get(&v), Some(&old) if old <= next) {
+                    dist.insert(v, next);
+                    heap.push(Reverse((next, v)));
+                }
+            }
+        }
+        dist
+    }
+
+    /// Edge difference heuristic, lower means contract sooner.
+    fn priority(&self, vertex: usize, contracted_neighbours: &[i64]) -> i64 {
+        let removed = self.inbound[vertex].keys()
+            .chain(self.outbound[vertex].keys())
+            .filter(|&&other| !self.contracted[other])
+            .count();
+        self.shortcuts(vertex).len() as i64 - removed as i64 + contracted_neighbours[vertex]
+    }
+}
+
+/// Builds the contraction hierarchy of the graph.
+/// The weights must not be negative, [`Default`] is taken as zero.
+/// Self loops are ignored.
+pub fn preprocess<V, E>(graph: &Graph<V, E>) -> ContractionHierarchy<E>
+where
+    V: std::fmt::Debug,
+    E: Copy + Ord + Add<Output = E> + Default,
+{
+    let ids: Vec<VertexId> = graph.vertices().map(|(id, _)| id).collect();
+    let index: HashMap<VertexId, usize> = ids.iter()
+        .enumerate()
+        .map(|(i, &id)| (id, i))
+        .collect();
+    let n = ids.len();
+
+    let mut overlay = Overlay {
+        outbound: vec![HashMap::new(); n],
+        inbound: vec![HashMap::new(); n],
+        contracted: vec![false; n],
+    };
+    for (&(from, to), &weight) in graph.edges() {
+        if from != to {
+            overlay.add_edge(index[&from], index[&to], weight, None);
+        }
+    }
+
+    let mut contracted_neighbours = vec![0; n];
+    let mut heap: BinaryHeap<Reverse<(i64, usize)>> = (0..n)
+        .map(|v| Reverse((overlay.priority(v, &contracted_neighbours), v)))
+        .collect();
+    let mut rank = vec![0; n];
+    let mut next_rank = 0;
+
+    while let Some(Reverse((_, vertex))) = heap.pop() {
+        if overlay.contracted[vertex] {
+            continue;
+        }
+        // Lazy update: the priority may have gone up since it was pushed.
+        let priority = overlay.priority(vertex, &contracted_neighbours);
+        if let Some(&Reverse((best, _))) = heap.peek() {
+            if priority > best {
+                heap.push(Reverse((priority, vertex)));
+                continue;
+            }
+        }
+
+        for (from, to, weight) in overlay.shortcuts(vertex) {
+            overlay.add_edge(from, to, weight, Some(vertex));
+        }
+        overlay.contracted[vertex] = true;
+        rank[vertex] = next_rank;
+        next_rank += 1;
+        let neighbours: Vec<usize> = overlay.inbound[vertex].keys()
+            .chain(overlay.outbound[vertex].keys())
+            .copied()
+            .collect();
+        for other in neighbours {
+            contracted_neighbours[other] += 1;
+        }
+    }
+
+    let mut up = vec![Vec::new(); n];
+    let mut down = vec![Vec::new(); n];
+    let mut middle = HashMap::new();
+    for (from, edges) in overlay.outbound.into_iter().enumerate() {
+        for (to, (weight, via)) in edges {
+            if rank[from] < rank[to] {
+                up[from].push((to, weight));
+            } else {
+                down[to].push((from, weight));
+            }
+            if let Some(via) = via {
+                middle.insert((from, to), via);
+            }
+        }
+    }
+
+    ContractionHierarchy {
+        ids,
+        index,
+        up,
+        down,
+        middle,
+    }
+}
+
+impl<E> ContractionHierarchy<E>
+where
+    E: Copy + Ord + Add<Output = E> + Default,
+{
+    /// Length of the shortest path, `None` if there is no path.
+    pub fn distance(&self, from: VertexId, to: VertexId) -> Option<E> {
+        self.search(from, to).map(|(_, _, _, distance)| distance)
+    }
+
+    /// Shortest path, both ends included, and its length.
+    /// Returns `None` if there is no path.
+    pub fn shortest_path(&self, from: VertexId, to: VertexId) -> Option<(Vec<VertexId>, E)> {
+        let (meeting, forward, backward, distance) = self.search(from, to)?;
+
+        let mut up_path = vec![meeting];
+        while let Some(&previous) = forward.get(up_path.last().unwrap()) {
+            up_path.push(previous);
+        }
+        up_path.reverse();
+        let mut down_path = vec![meeting];
+        while let Some(&next) = backward.get(down_path.last().unwrap()) {
+            down_path.push(next);
+        }
+        up_path.extend(down_path.into_iter().skip(1));
+
+        let mut path = vec![self.ids[up_path[0]]];
+        for pair in up_path.windows(2) {
+            self.unpack(pair[0], pair[1], &mut path);
+        }
+        Some((path, distance))
+    }
+
+    /// Appends the original vertices of the edge, without `from`.
+    fn unpack(&self, from: usize, to: usize, path: &mut Vec<VertexId>) {
+        match self.middle.get(&(from, to)) {
+            Some(&via) => {
+                self.unpack(from, via, path);
+                self.unpack(via, to, path);
+            }
+            None => path.push(self.ids[to]),
+        }
+    }
+
+    /// Bidirectional search up the hierarchy. Returns the meeting vertex,
+    /// the parents on both sides and the distance.
+    #[allow(clippy::type_complexity)]
+    fn search(&self, from: VertexId, to: VertexId) -> Option<(usize, HashMap<usize, usize>, HashMap<usize, usize>, E)> {
+        let source = *self.index.get(&from)?;
+        let target = *self.index.get(&to)?;
+
+        let mut dist = [HashMap::new(), HashMap::new()];
+        let mut parent = [HashMap::new(), HashMap::new()];
+        let mut heaps = [BinaryHeap::new(), BinaryHeap::new()];
+        dist[0].insert(source, E::default());
+        dist[1].insert(target, E::default());
+        heaps[0].push(Reverse((E::default(), source)));
+        heaps[1].push(Reverse((E::default(), target)));
+        let mut best: Option<(E, usize)> = None;
+
+        loop {
+            let side = match (heaps[0].peek(), heaps[1].peek()) {
+                (None, None) => break,
+                (Some(_), None) => 0,
+                (None, Some(_)) => 1,
+                (Some(Reverse((a, _))), Some(Reverse((b, _)))) => if a <= b { 0 } else { 1 },
+            };
+            let Reverse((d, u)) = heaps[side].pop().unwrap();
+            if let Some((best, _)) = best {
+                if d >= best {
+                    heaps[side].clear();
+                    continue;
+                }
+            }
+            if d > dist[side][&u] {
+                continue;
+            }
+            if let Some(&other) = dist[1 - side].get(&u) {
+                let total = d + other;
+                if !matches!(best, Some((best, _)) if best <= total) {
+                    best = Some((total, u));
+                }
+            }
+            let edges = if side == 0 { &self.up[u] } else { &self.down[u] };
+            for &(v, weight) in edges.iter() {
+                let next = d + weight;
+                if !matches!(dist[side].get(&v), Some(&old) if old <= next) {
+                    dist[side].insert(v, next);
+                    parent[side].insert(v, u);
+                    heaps[side].push(Reverse((next, v)));
+                }
+            }
+        }
+
+        let (distance, meeting) = best?;
+        let [forward, backward] = parent;
+        Some((meeting, forward, backward, distance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+    use std::cmp::Reverse;
+    use std::collections::{BinaryHeap, HashMap};
+
+    fn dijkstra(graph: &Graph<u32, u32>, from: crate::VertexId) -> HashMap<crate::VertexId, u32> {
+        let mut dist = HashMap::new();
+        let mut heap = BinaryHeap::new();
+        dist.insert(from, 0);
+        heap.push(Reverse((0, from)));
+        while let Some(Reverse((d, u))) = heap.pop() {
+            if d > dist[&u] {
+                continue;
+            }
+            for (v, &w) in graph.adj_out(u).unwrap() {
+                if !matches!(dist.get(&v), Some(&old) if old <= d + w) {
+                    dist.insert(v, d + w);
+                    heap.push(Reverse((d + w, v)));
+                }
+            }
+        }
+        dist
+    }
+
+    #[test]
+    fn matches_dijkstra_on_random_graph() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut graph = Graph::<u32, u32>::new();
+        let ids: Vec<_> = (0..60).map(|i| graph.add_vertex(i)).collect();
+        for _ in 0..200 {
+            let from = ids[rng.gen_range(0..60)];
+            let to = ids[rng.gen_range(0..60)];
+            graph.add_edge((from, to), rng.gen_range(1..20)).unwrap();
+        }
+
+        let hierarchy = super::preprocess(&graph);
+        for &from in ids.iter().take(10) {
+            let expected = dijkstra(&graph, from);
+            for &to in ids.iter() {
+                assert_eq!(hierarchy.distance(from, to), expected.get(&to).copied());
+                if let Some((path, distance)) = hierarchy.shortest_path(from, to) {
+                    assert_eq!((path[0], *path.last().unwrap()), (from, to));
+                    let length: u32 = path.windows(2)
+                        .map(|pair| *graph.get_edge((pair[0], pair[1])).unwrap())
+                        .sum();
+                    assert_eq!(length, distance);
+                }
+            }
+        }
+    }
+}
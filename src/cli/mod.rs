@@ -0,0 +1,600 @@
+//! The commands of the `graph` shell, usable without the terminal.
+//!
+//! [`CommandEngine`] holds a `GraphMap<u32, u32>` with the state of a
+//! session (names bound with `let`, checkpoints, autosave), parses one
+//! command per line and returns an [`Output`] instead of printing it.
+//! [`CommandEngine::run_script`] runs a whole file non-interactively,
+//! which is what `graph run <script>` does. An engine made with
+//! [`CommandEngine::persistent`] logs every change to the graph as it
+//! happens, so a crash doesn't lose the edits since the last save.
+//!
+//! ```
+//! use graph::cli::{CommandEngine, Output, Value};
+//! use graph::GraphMap;
+//!
+//! let mut engine = CommandEngine::new(GraphMap::new(), "out.txt", false);
+//! engine.execute("add_edge 1 2 5");
+//! engine.execute("add_edge 2 3 1");
+//! match engine.execute("dijkstra 1 3") {
+//!     Output::Value(Value::Path(path, cost)) => assert_eq!((path, cost), (vec![1, 2, 3], 6)),
+//!     _ => unreachable!(),
+//! }
+//! assert!(engine.execute("add_edge 1").is_error());
+//!
+//! let mut printed = Vec::new();
+//! let failed = engine.run_script("# comment\n\nset output json\nedge_count\n".as_bytes(), &mut printed).unwrap();
+//! assert_eq!(failed, 0);
+//! assert_eq!(String::from_utf8(printed).unwrap(), "{\"ok\":true}\n2\n");
+//! ```
+
+mod output;
+mod trace;
+
+pub use output::{Json, Output, Value};
+
+use crate::io::compressed::{self, Compression};
+use crate::io::dot::DotConfig;
+use crate::io::simple_text::write_graph;
+use crate::io::{read_edge_list, write_edge_list, EdgeListConfig};
+use crate::wal::PersistentGraph;
+use crate::GraphMap;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// One line per command, shown by `help`.
+pub const HELP: &[&str] = &[
+    "add_edge <origin> <dest> <cost>",
+    "remove_edge <origin> <dest>",
+    "get_edge <origin> <dest>",
+    "remove_node <vertex>",
+    "add_vertex <vertex>",
+    "indegree <vertex>",
+    "outdegree <vertex>",
+    "inbound <vertex>",
+    "outbound <vertex>",
+    "vertex_count",
+    "edge_count",
+    "stats",
+    "print_graph",
+    "show matrix, show drawing [<width> <height>], show tree <root>",
+    "contains_edge",
+    "connected_components",
+    "dijkstra <origin> <dest>",
+    "match (a)-[w]->(b) where w > 5 return a, b",
+    "trace bfs <start>",
+    "trace dijkstra <origin> <dest>",
+    "let <name> = <command>",
+    "print <name>, print <name>[<index>]",
+    "save_subgraph <name>[<index>] <file>",
+    "export_dot <file>",
+    "import_edges <file>",
+    "export_edges <file>",
+    "checkpoint",
+    "rollback",
+    "save",
+    "autosave <mutations> <seconds>",
+    "set output <text|json>",
+];
+
+/// Saves the graph after a number of mutations or after some time,
+/// whichever comes first. A limit of 0 disables that trigger.
+#[derive(Clone, Debug)]
+struct Autosave {
+    every_mutations: u32,
+    every: Duration,
+    mutations: u32,
+    last_save: Instant,
+}
+
+impl Autosave {
+    fn new(every_mutations: u32, every: Duration) -> Self {
+        Autosave {
+            every_mutations,
+            every,
+            mutations: 0,
+            last_save: Instant::now(),
+        }
+    }
+
+    fn is_due(&self) -> bool {
+        if self.mutations == 0 {
+            return false;
+        }
+        (self.every_mutations > 0 && self.mutations >= self.every_mutations)
+            || (self.every > Duration::from_secs(0) && self.last_save.elapsed() >= self.every)
+    }
+
+    fn saved(&mut self) {
+        self.mutations = 0;
+        self.last_save = Instant::now();
+    }
+}
+
+/// Writes the graph to a temporary file first and renames it over `path`,
+/// so a crash in the middle of a save doesn't corrupt the previous one.
+pub fn save_graph(path: &Path, graph: &GraphMap<u32, u32>, undirected: bool) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    let mut file = compressed::Writer::new(std::fs::File::create(&tmp)?, Compression::from_path(path))?;
+    write_graph(&mut file, graph, undirected)?;
+    file.finish()?;
+    std::fs::rename(&tmp, path)
+}
+
+fn parse<T: std::str::FromStr>(words: &[&str], i: usize, what: &str) -> Result<T, String> {
+    let word = words.get(i).ok_or_else(|| format!("{} needs more arguments", words[0]))?;
+    word.parse::<T>().map_err(|_| format!("{} is not a {}", word, what))
+}
+
+fn parse_vertex(words: &[&str], i: usize) -> Result<u32, String> {
+    parse(words, i, "vertex")
+}
+
+fn log_error(err: io::Error) -> String {
+    format!("Could not log the change: {}", err)
+}
+
+/// Looks up `name`, `name[i]`, `name[i][j]` and so on.
+fn lookup<'a>(names: &'a HashMap<String, Value>, expr: &str) -> Result<&'a Value, String> {
+    let mut parts = expr.split('[');
+    let name = parts.next().unwrap();
+    let mut value = names.get(name).ok_or_else(|| format!("{} is not defined", name))?;
+    for part in parts {
+        let index = part.strip_suffix(']')
+            .and_then(|i| str::parse::<usize>(i).ok())
+            .ok_or_else(|| format!("invalid index in {}", expr))?;
+        value = match value {
+            Value::List(items) => items.get(index),
+            _ => None,
+        }.ok_or_else(|| format!("{} has no element {}", expr, index))?;
+    }
+    Ok(value)
+}
+
+/// The graph of a session, in memory or logged to disk.
+#[derive(Debug)]
+enum Store {
+    Memory(GraphMap<u32, u32>),
+    Persistent(PersistentGraph<u32, u32>),
+}
+
+impl Store {
+    fn graph(&self) -> &GraphMap<u32, u32> {
+        match self {
+            Store::Memory(graph) => graph,
+            Store::Persistent(graph) => graph.graph(),
+        }
+    }
+
+    fn add_vertex(&mut self, vertex: u32) -> io::Result<()> {
+        match self {
+            Store::Memory(graph) => {
+                graph.add_vertex(vertex);
+                Ok(())
+            }
+            Store::Persistent(graph) => graph.add_vertex(vertex),
+        }
+    }
+
+    fn add_edge(&mut self, edge: (u32, u32), cost: u32) -> io::Result<()> {
+        match self {
+            Store::Memory(graph) => {
+                graph.add_edge(edge, cost);
+                Ok(())
+            }
+            Store::Persistent(graph) => graph.add_edge(edge, cost),
+        }
+    }
+
+    fn remove_vertex(&mut self, vertex: u32) -> io::Result<bool> {
+        match self {
+            Store::Memory(graph) => Ok(graph.remove_vertex(vertex)),
+            Store::Persistent(graph) => graph.remove_vertex(vertex),
+        }
+    }
+
+    fn remove_edge(&mut self, edge: (u32, u32)) -> io::Result<bool> {
+        match self {
+            Store::Memory(graph) => Ok(graph.remove_edge(edge)),
+            Store::Persistent(graph) => graph.remove_edge(edge),
+        }
+    }
+
+    /// Changes the graph in ways the log doesn't have entries for,
+    /// so a persistent graph is compacted afterwards.
+    fn modify<F: FnOnce(&mut GraphMap<u32, u32>)>(&mut self, f: F) -> io::Result<()> {
+        match self {
+            Store::Memory(graph) => {
+                f(graph);
+                Ok(())
+            }
+            Store::Persistent(graph) => {
+                let mut modified = graph.graph().clone();
+                f(&mut modified);
+                graph.replace(modified)
+            }
+        }
+    }
+}
+
+/// State of a shell session, see the [module](self) documentation.
+#[derive(Debug)]
+pub struct CommandEngine {
+    graph: Store,
+    undirected: bool,
+    out_file: PathBuf,
+    names: HashMap<String, Value>,
+    checkpoints: Vec<GraphMap<u32, u32>>,
+    autosave: Autosave,
+    json: bool,
+}
+
+impl CommandEngine {
+
+    /// Session on `graph`, saved to `out_file` by `save` and autosave,
+    /// every 50 mutations or 5 minutes until `autosave` changes it.
+    pub fn new<P: Into<PathBuf>>(graph: GraphMap<u32, u32>, out_file: P, undirected: bool) -> Self {
+        CommandEngine::with_store(Store::Memory(graph), out_file.into(), undirected)
+    }
+
+    /// Session on a graph that logs every change, see [`PersistentGraph`].
+    pub fn persistent<P: Into<PathBuf>>(graph: PersistentGraph<u32, u32>, out_file: P, undirected: bool) -> Self {
+        CommandEngine::with_store(Store::Persistent(graph), out_file.into(), undirected)
+    }
+
+    fn with_store(graph: Store, out_file: PathBuf, undirected: bool) -> Self {
+        CommandEngine {
+            graph,
+            undirected,
+            out_file,
+            names: HashMap::new(),
+            checkpoints: Vec::new(),
+            autosave: Autosave::new(50, Duration::from_secs(300)),
+            json: false,
+        }
+    }
+
+    pub fn graph(&self) -> &GraphMap<u32, u32> {
+        self.graph.graph()
+    }
+
+    pub fn is_undirected(&self) -> bool {
+        self.undirected
+    }
+
+    /// Whether [`CommandEngine::render`] writes JSON, changed by
+    /// `set output <text|json>`.
+    pub fn is_json(&self) -> bool {
+        self.json
+    }
+
+    pub fn set_json(&mut self, json: bool) {
+        self.json = json;
+    }
+
+    /// Runs one command. Commands that change the graph count towards
+    /// the autosave, see [`CommandEngine::autosave`].
+    pub fn execute(&mut self, line: &str) -> Output {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if words.is_empty() {
+            return Output::Error("No such command".to_string());
+        }
+        let (output, mutated) = match self.run(line, &words) {
+            Ok(result) => result,
+            Err(message) => (Output::Error(message), false),
+        };
+        if mutated {
+            self.autosave.mutations += 1;
+        }
+        output
+    }
+
+    /// Saves the graph if the autosave is due. Returns whether it saved.
+    pub fn autosave(&mut self) -> io::Result<bool> {
+        if !self.autosave.is_due() {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Saves the graph to the output file.
+    pub fn save(&mut self) -> io::Result<()> {
+        save_graph(&self.out_file, self.graph(), self.undirected)?;
+        self.autosave.saved();
+        Ok(())
+    }
+
+    /// The output as printed in the current mode: text, or one
+    /// JSON document per line.
+    pub fn render(&self, output: &Output) -> String {
+        if self.json {
+            format!("{}\n", output.to_json())
+        } else {
+            output.to_text(self.undirected)
+        }
+    }
+
+    /// Runs every line of `script` and writes the outputs to `out`,
+    /// going on after the commands that fail. Blank lines and lines
+    /// starting with `#` are skipped. Returns the number of failures.
+    pub fn run_script<R: BufRead, W: Write>(&mut self, script: R, out: &mut W) -> io::Result<usize> {
+        let mut failed = 0;
+        for line in script.lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let output = self.execute(line);
+            if output.is_error() {
+                failed += 1;
+            }
+            out.write_all(self.render(&output).as_bytes())?;
+            if let Err(err) = self.autosave() {
+                failed += 1;
+                let output = Output::Error(format!("Autosave failed: {}", err));
+                out.write_all(self.render(&output).as_bytes())?;
+            }
+        }
+        Ok(failed)
+    }
+
+    /// Runs a `trace bfs <start>` or `trace dijkstra <origin> <dest>`
+    /// command, writing every step of the search to `out` and reading a
+    /// line of `input` before the next one, `q` stopping the trace.
+    /// Errors are written to `out` like the outputs of the other commands.
+    ///
+    /// ```
+    /// use graph::cli::CommandEngine;
+    /// use graph::GraphMap;
+    ///
+    /// let mut engine = CommandEngine::new(GraphMap::new(), "out.txt", false);
+    /// engine.execute("add_edge 1 2 5");
+    ///
+    /// let mut out = Vec::new();
+    /// engine.trace("trace dijkstra 1 2", &mut "\n".as_bytes(), &mut out).unwrap();
+    /// assert!(String::from_utf8(out).unwrap().ends_with("reached 2, path: 1 2, cost 5\n"));
+    ///
+    /// let mut out = Vec::new();
+    /// engine.trace("trace bfs 7", &mut "".as_bytes(), &mut out).unwrap();
+    /// assert_eq!(String::from_utf8(out).unwrap(), "no vertex 7\n");
+    /// ```
+    pub fn trace<R: BufRead, W: Write>(&self, line: &str, input: &mut R, out: &mut W) -> io::Result<()> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let search = match (self.json, words.get(1)) {
+            (true, _) => Err("trace is interactive, switch to text output first".to_string()),
+            (false, Some(&"bfs")) => self.vertex(&words, 2).map(|start| (start, None)),
+            (false, Some(&"dijkstra")) => self.vertex(&words, 2)
+                .and_then(|start| Ok((start, Some(self.vertex(&words, 3)?)))),
+            (false, _) => Err("Can trace bfs or dijkstra".to_string()),
+        };
+        match search {
+            Ok((start, None)) => trace::bfs(self.graph(), start, input, out),
+            Ok((start, Some(end))) => trace::dijkstra(self.graph(), start, end, input, out),
+            Err(message) => out.write_all(self.render(&Output::Error(message)).as_bytes()),
+        }
+    }
+
+    /// Checks that the vertex is in the graph.
+    fn vertex(&self, words: &[&str], i: usize) -> Result<u32, String> {
+        let v = parse_vertex(words, i)?;
+        if self.graph().contains_vertex(&v) {
+            Ok(v)
+        } else {
+            Err(format!("no vertex {}", v))
+        }
+    }
+
+    /// Runs a command that produces a value, for `let`.
+    fn evaluate(&self, words: &[&str]) -> Result<Value, String> {
+        let graph = self.graph();
+        match words[0] {
+            "connected_components" => {
+                Ok(Value::List(graph.connected_components().into_iter().map(|g| Value::Graph(Box::new(g))).collect()))
+            }
+            "shortest_path" | "dijkstra" => {
+                let first = self.vertex(words, 1)?;
+                let second = self.vertex(words, 2)?;
+                match graph.dijkstra(first, second) {
+                    Some((path, cost)) => Ok(Value::Path(path, cost)),
+                    None => Err(format!("no path from {} to {}", first, second)),
+                }
+            }
+            "bfs" => Ok(Value::Vertices(graph.bfs(&self.vertex(words, 1)?))),
+            "outbound" => {
+                let v = self.vertex(words, 1)?;
+                Ok(Value::Vertices(graph.adj_out(v).unwrap().map(|(&u, _)| u).collect()))
+            }
+            "inbound" => {
+                let v = self.vertex(words, 1)?;
+                Ok(Value::Vertices(graph.adj_in(v).unwrap().map(|(&u, _)| u).collect()))
+            }
+            "indegree" => Ok(Value::Number(graph.indegree(self.vertex(words, 1)?))),
+            "outdegree" => Ok(Value::Number(graph.outdegree(self.vertex(words, 1)?))),
+            "vertex_count" => Ok(Value::Number(graph.vertex_count())),
+            "edge_count" => Ok(Value::Number(graph.edge_count())),
+            expr if words.len() == 1 => lookup(&self.names, expr).cloned(),
+            other => Err(format!("{} doesn't produce a value", other)),
+        }
+    }
+
+    /// Runs the command, returning its output and whether it
+    /// changed the graph.
+    fn run(&mut self, line: &str, words: &[&str]) -> Result<(Output, bool), String> {
+        let undirected = self.undirected;
+        let output = match words[0] {
+            "help" => Output::Help(HELP),
+            "add_vertex" => {
+                self.graph.add_vertex(parse_vertex(words, 1)?).map_err(log_error)?;
+                return Ok((Output::Ok, true));
+            }
+            "add_edge" => {
+                let first = parse_vertex(words, 1)?;
+                let second = parse_vertex(words, 2)?;
+                let cost = parse(words, 3, "cost")?;
+                self.graph.add_edge((first, second), cost).map_err(log_error)?;
+                return Ok((Output::Ok, true));
+            }
+            "get_edge" => {
+                let first = parse_vertex(words, 1)?;
+                let second = parse_vertex(words, 2)?;
+                Output::Edge(self.graph().get_edge((first, second)).copied())
+            }
+            "remove_edge" => {
+                let first = parse_vertex(words, 1)?;
+                let second = parse_vertex(words, 2)?;
+                self.graph.remove_edge((first, second)).map_err(log_error)?;
+                return Ok((Output::Ok, true));
+            }
+            "remove_node" => {
+                self.graph.remove_vertex(parse_vertex(words, 1)?).map_err(log_error)?;
+                return Ok((Output::Ok, true));
+            }
+            "indegree" | "outdegree" | "vertex_count" | "edge_count" => Output::Value(self.evaluate(words)?),
+            "stats" => Output::Stats(self.graph().summary(), self.graph().degree_distribution()),
+            "outbound" | "inbound" => {
+                let vertex = self.vertex(words, 1)?;
+                let mut adj: Vec<(u32, u32)> = if words[0] == "outbound" {
+                    self.graph().adj_out(vertex).unwrap().map(|(&v, &w)| (v, w)).collect()
+                } else {
+                    self.graph().adj_in(vertex).unwrap().map(|(&v, &w)| (v, w)).collect()
+                };
+                adj.sort_unstable();
+                Output::Adjacency(adj)
+            }
+            "print_graph" => Output::Graph(Box::new(self.graph().clone())),
+            "show" => {
+                let usage = "Usage: show matrix, show drawing [<width> <height>], show tree <root>";
+                match words.get(1) {
+                    Some(&"matrix") => Output::Rendering(self.graph().render_matrix()),
+                    Some(&"drawing") if words.len() == 2 => Output::Rendering(self.graph().render_drawing(60, 20)),
+                    Some(&"drawing") => {
+                        let width = parse(words, 2, "width")?;
+                        let height = parse(words, 3, "height")?;
+                        Output::Rendering(self.graph().render_drawing(width, height))
+                    }
+                    Some(&"tree") => Output::Rendering(self.graph().render_tree(&self.vertex(words, 2)?).unwrap()),
+                    _ => return Err(usage.to_string()),
+                }
+            }
+            "contains_edge" => {
+                let first = parse_vertex(words, 1)?;
+                let second = parse_vertex(words, 2)?;
+                Output::Bool(self.graph().contains_edge((first, second)))
+            }
+            "connected_components" => Output::Components(self.graph().connected_components()),
+            "dijkstra" => {
+                let first = parse_vertex(words, 1)?;
+                let second = parse_vertex(words, 2)?;
+                match self.graph().dijkstra(first, second) {
+                    Some((path, cost)) => Output::Value(Value::Path(path, cost)),
+                    None => return Err(format!("no path from {} to {}", first, second)),
+                }
+            }
+            "match" | "MATCH" => {
+                let result = self.graph().query(line).map_err(|err| err.to_string())?;
+                Output::Table {
+                    columns: result.columns,
+                    rows: result.rows.iter().map(|row| row.iter().map(|v| v.to_string()).collect()).collect(),
+                }
+            }
+            "let" => {
+                if words.len() < 4 || words[2] != "=" {
+                    return Err("Usage: let <name> = <command>".to_string());
+                }
+                let value = self.evaluate(&words[3..])?;
+                self.names.insert(words[1].to_string(), value);
+                Output::Ok
+            }
+            "print" => {
+                let expr = words.get(1).ok_or("Usage: print <name>")?;
+                Output::Value(lookup(&self.names, expr)?.clone())
+            }
+            "export_dot" => {
+                let file = words.get(1).ok_or("Usage: export_dot <file>")?;
+                let config = DotConfig {
+                    directed: !undirected,
+                    vertex_label: Box::new(|v: &u32| v.to_string()),
+                    edge_label: Some(Box::new(|w: &u32| w.to_string())),
+                    ..DotConfig::default()
+                };
+                std::fs::write(file, self.graph().to_dot_with(&config)).map_err(|err| format!("Error: {}", err))?;
+                Output::Ok
+            }
+            "import_edges" => {
+                let file = words.get(1).ok_or("Usage: import_edges <file>")?;
+                let config = EdgeListConfig {
+                    directed: !undirected,
+                    default_weight: Some(1),
+                    ..EdgeListConfig::default()
+                };
+                let edges = compressed::open(file)
+                    .map_err(crate::io::Error::from)
+                    .and_then(|reader| read_edge_list::<u32, u32, _>(reader, &config))
+                    .map_err(|err| format!("Error: {}", err))?;
+                self.graph.modify(|graph| graph.merge_with(edges, |_, new| new)).map_err(log_error)?;
+                return Ok((Output::Ok, true));
+            }
+            "export_edges" => {
+                let file = words.get(1).ok_or("Usage: export_edges <file>")?;
+                let config = EdgeListConfig {
+                    directed: !undirected,
+                    ..EdgeListConfig::default()
+                };
+                compressed::create(file)
+                    .and_then(|mut writer| {
+                        write_edge_list(&mut writer, self.graph(), &config)?;
+                        writer.finish().map(drop)
+                    })
+                    .map_err(|err| format!("Error: {}", err))?;
+                Output::Ok
+            }
+            "save_subgraph" => {
+                let (expr, file) = match (words.get(1), words.get(2)) {
+                    (Some(expr), Some(file)) => (expr, file),
+                    _ => return Err("Usage: save_subgraph <name> <file>".to_string()),
+                };
+                match lookup(&self.names, expr)? {
+                    Value::Graph(subgraph) => {
+                        save_graph(Path::new(file), subgraph, undirected).map_err(|err| format!("Error: {}", err))?;
+                        Output::Ok
+                    }
+                    _ => return Err(format!("{} is not a graph", expr)),
+                }
+            }
+            "trace" => return Err("trace is interactive, run it in the shell with text output".to_string()),
+            "checkpoint" => {
+                self.checkpoints.push(self.graph().clone());
+                Output::Checkpoint(self.checkpoints.len())
+            }
+            "rollback" => {
+                let checkpoint = self.checkpoints.pop().ok_or("No checkpoint to roll back to")?;
+                self.graph.modify(|graph| *graph = checkpoint).map_err(log_error)?;
+                return Ok((Output::RolledBack(self.checkpoints.len() + 1), true));
+            }
+            "save" => {
+                self.save().map_err(|err| format!("Error: {}", err))?;
+                Output::Ok
+            }
+            "autosave" => {
+                let mutations = parse(words, 1, "number")?;
+                let seconds = parse(words, 2, "number")?;
+                self.autosave.every_mutations = mutations;
+                self.autosave.every = Duration::from_secs(seconds);
+                Output::Ok
+            }
+            "set" => {
+                match (words.get(1), words.get(2)) {
+                    (Some(&"output"), Some(&"json")) => self.json = true,
+                    (Some(&"output"), Some(&"text")) => self.json = false,
+                    _ => return Err("Usage: set output <text|json>".to_string()),
+                }
+                Output::Ok
+            }
+            _ => return Err("No such command".to_string()),
+        };
+        Ok((output, false))
+    }
+}
@@ -0,0 +1,246 @@
+//! Results of the commands, and how they are printed.
+
+use crate::algorithms::summary::{DegreeDistribution, GraphSummary};
+use crate::io::simple_text::write_graph;
+use crate::GraphMap;
+use std::fmt;
+
+/// Result of a command, bound to a name with `let`.
+#[derive(Clone, Debug)]
+pub enum Value {
+    Number(usize),
+    Vertices(Vec<u32>),
+    /// Vertices of a path, and its cost.
+    Path(Vec<u32>, u32),
+    Graph(Box<GraphMap<u32, u32>>),
+    List(Vec<Value>),
+}
+
+impl Value {
+    /// The value in the text output mode.
+    pub fn to_text(&self, undirected: bool) -> String {
+        match self {
+            Value::Number(n) => format!("{}\n", n),
+            Value::Vertices(vertices) => format!("{}\n", join(vertices, " ")),
+            Value::Path(path, cost) => format!("Path: {}\nTotal cost: {}\n", join(path, " "), cost),
+            Value::Graph(graph) => graph_text(graph, undirected),
+            Value::List(items) => {
+                items.iter()
+                    .enumerate()
+                    .map(|(i, item)| format!("[{}]\n{}", i, item.to_text(undirected)))
+                    .collect()
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> Json {
+        match self {
+            Value::Number(n) => Json::Number(*n as f64),
+            Value::Vertices(vertices) => vertices_json(vertices),
+            Value::Path(path, cost) => path_json(path, *cost),
+            Value::Graph(graph) => graph_json(graph),
+            Value::List(items) => Json::Array(items.iter().map(Value::to_json).collect()),
+        }
+    }
+}
+
+/// Structured result of [`CommandEngine::execute`](super::CommandEngine::execute).
+#[derive(Clone, Debug)]
+pub enum Output {
+    /// The command succeeded and has nothing to show.
+    Ok,
+    Value(Value),
+    /// Data of the edge asked for, if it exists.
+    Edge(Option<u32>),
+    Bool(bool),
+    /// Pairs of (vertex, cost), sorted.
+    Adjacency(Vec<(u32, u32)>),
+    Stats(GraphSummary, DegreeDistribution),
+    Components(Vec<GraphMap<u32, u32>>),
+    /// The whole graph, drawn when it is small.
+    Graph(Box<GraphMap<u32, u32>>),
+    /// Text rendering of `show`, see [`crate::render`].
+    Rendering(String),
+    /// Result of a `match` query, with the values as text.
+    Table {
+        columns: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
+    Help(&'static [&'static str]),
+    /// Number of the checkpoint created.
+    Checkpoint(usize),
+    /// Number of the checkpoint restored.
+    RolledBack(usize),
+    Error(String),
+}
+
+impl Output {
+    pub fn is_error(&self) -> bool {
+        matches!(self, Output::Error(_))
+    }
+
+    /// The output in the text mode, empty for [`Output::Ok`].
+    pub fn to_text(&self, undirected: bool) -> String {
+        match self {
+            Output::Ok => String::new(),
+            Output::Value(value) => value.to_text(undirected),
+            Output::Edge(edge) => format!("{:?}\n", edge),
+            Output::Bool(b) => format!("{}\n", b),
+            Output::Adjacency(adj) => adj.iter().map(|(v, w)| format!("{} {}\n", v, w)).collect(),
+            Output::Stats(stats, _) => stats.to_string(),
+            Output::Components(components) => {
+                components.iter().map(|g| format!("Component: \n{}", graph_text(g, undirected))).collect()
+            }
+            Output::Graph(graph) => {
+                if graph.vertex_count() <= 16 {
+                    graph.render_drawing(60, 20) + &graph.render_matrix()
+                } else {
+                    graph_text(graph, undirected)
+                }
+            }
+            Output::Rendering(text) => text.clone(),
+            Output::Table { columns, rows } => {
+                let mut text = format!("{}\n", columns.join("\t"));
+                for row in rows.iter() {
+                    text += &format!("{}\n", row.join("\t"));
+                }
+                text
+            }
+            Output::Help(lines) => lines.iter().map(|line| format!("{}\n", line)).collect(),
+            Output::Checkpoint(number) => format!("Checkpoint {} created\n", number),
+            Output::RolledBack(number) => format!("Rolled back to checkpoint {}\n", number),
+            Output::Error(message) => format!("{}\n", message),
+        }
+    }
+
+    /// The output in the json mode, one document.
+    pub fn to_json(&self) -> Json {
+        match self {
+            Output::Ok => Json::Object(vec![("ok", Json::Bool(true))]),
+            Output::Value(value) => value.to_json(),
+            Output::Edge(edge) => edge.map_or(Json::Null, |w| Json::Number(w as f64)),
+            Output::Bool(b) => Json::Bool(*b),
+            Output::Adjacency(adj) => Json::Array(adj.iter()
+                .map(|&(v, w)| Json::Object(vec![("vertex", Json::Number(v as f64)), ("cost", Json::Number(w as f64))]))
+                .collect()),
+            Output::Stats(stats, distribution) => {
+                let histogram = |counts: &[usize]| Json::Array(counts.iter().map(|&c| Json::Number(c as f64)).collect());
+                Json::Object(vec![
+                    ("vertices", Json::Number(stats.order as f64)),
+                    ("edges", Json::Number(stats.size as f64)),
+                    ("density", Json::Number(stats.density)),
+                    ("min_degree", Json::Number(stats.min_degree as f64)),
+                    ("max_degree", Json::Number(stats.max_degree as f64)),
+                    ("mean_degree", Json::Number(stats.mean_degree)),
+                    ("reciprocity", Json::Number(stats.reciprocity)),
+                    ("self_loops", Json::Number(stats.self_loops as f64)),
+                    ("components", Json::Number(stats.components as f64)),
+                    ("dag", Json::Bool(stats.is_dag)),
+                    ("indegree", histogram(&distribution.indegree)),
+                    ("outdegree", histogram(&distribution.outdegree)),
+                ])
+            }
+            Output::Components(components) => Json::Array(components.iter().map(graph_json).collect()),
+            Output::Graph(graph) => graph_json(graph),
+            Output::Rendering(text) => Json::Object(vec![("rendering", Json::String(text.clone()))]),
+            Output::Table { columns, rows } => Json::Object(vec![
+                ("columns", Json::Array(columns.iter().map(|c| Json::String(c.clone())).collect())),
+                ("rows", Json::Array(rows.iter()
+                    .map(|row| Json::Array(row.iter().map(|v| Json::String(v.clone())).collect()))
+                    .collect())),
+            ]),
+            Output::Help(lines) => {
+                Json::Object(vec![("commands", Json::Array(lines.iter().map(|line| Json::String(line.to_string())).collect()))])
+            }
+            Output::Checkpoint(number) | Output::RolledBack(number) => {
+                Json::Object(vec![("checkpoint", Json::Number(*number as f64))])
+            }
+            Output::Error(message) => Json::Object(vec![("error", Json::String(message.clone()))]),
+        }
+    }
+}
+
+/// Minimal JSON value, for the json output mode.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::String(text) => {
+                write!(f, "\"")?;
+                for c in text.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\t' => write!(f, "\\t")?,
+                        c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+                        c => write!(f, "{}", c)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{}", Json::String(key.to_string()), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn join(vertices: &[u32], separator: &str) -> String {
+    let vertices: Vec<String> = vertices.iter().map(|v| v.to_string()).collect();
+    vertices.join(separator)
+}
+
+fn graph_text(graph: &GraphMap<u32, u32>, undirected: bool) -> String {
+    let mut buffer = Vec::new();
+    write_graph(&mut buffer, graph, undirected).unwrap();
+    String::from_utf8(buffer).unwrap()
+}
+
+fn vertices_json(vertices: &[u32]) -> Json {
+    Json::Array(vertices.iter().map(|&v| Json::Number(v as f64)).collect())
+}
+
+fn path_json(path: &[u32], cost: u32) -> Json {
+    Json::Object(vec![("path", vertices_json(path)), ("cost", Json::Number(cost as f64))])
+}
+
+fn graph_json(graph: &GraphMap<u32, u32>) -> Json {
+    let mut vertices: Vec<u32> = graph.vertices().copied().collect();
+    vertices.sort_unstable();
+    let mut edges: Vec<(u32, u32, u32)> = graph.edges().map(|((&o, &t), &c)| (o, t, c)).collect();
+    edges.sort_unstable();
+    let edges = edges.into_iter()
+        .map(|(o, t, c)| Json::Array(vec![Json::Number(o as f64), Json::Number(t as f64), Json::Number(c as f64)]))
+        .collect();
+    Json::Object(vec![("vertices", vertices_json(&vertices)), ("edges", Json::Array(edges))])
+}
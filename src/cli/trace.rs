@@ -0,0 +1,107 @@
+//! Step by step traces of the searches, for the `trace` command.
+//!
+//! Every step is written to `out`, then a line is read from `input`
+//! before the next one. A line with `q`, or the end of `input`, stops.
+
+use crate::GraphMap;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::io::{self, BufRead, Write};
+
+/// Waits for Enter between two steps of a trace.
+/// Returns false if the user typed `q` to stop.
+fn next_step<R: BufRead>(input: &mut R) -> io::Result<bool> {
+    let mut line = String::new();
+    Ok(input.read_line(&mut line)? > 0 && line.trim() != "q")
+}
+
+/// Outbound edges of a vertex in the graph, sorted.
+fn sorted_out(graph: &GraphMap<u32, u32>, vertex: u32) -> Vec<(u32, u32)> {
+    let mut adj: Vec<(u32, u32)> = graph.adj_out(vertex).unwrap().map(|(&v, &w)| (v, w)).collect();
+    adj.sort_unstable();
+    adj
+}
+
+/// Breadth first search from `start`, which must be in the graph.
+pub(super) fn bfs<R: BufRead, W: Write>(graph: &GraphMap<u32, u32>, start: u32, input: &mut R, out: &mut W) -> io::Result<()> {
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+    visited.insert(start);
+    queue.push_back(start);
+    let mut step = 0;
+    writeln!(out, "(Enter for the next step, q to stop)")?;
+
+    while let Some(current) = queue.pop_front() {
+        step += 1;
+        writeln!(out, "step {}: visit {}", step, current)?;
+        for (v, _) in sorted_out(graph, current) {
+            if visited.insert(v) {
+                writeln!(out, "  discover {}", v)?;
+                queue.push_back(v);
+            } else {
+                writeln!(out, "  skip {} (already seen)", v)?;
+            }
+        }
+        let frontier: Vec<String> = queue.iter().map(|v| v.to_string()).collect();
+        writeln!(out, "  queue: [{}]", frontier.join(", "))?;
+        out.flush()?;
+        if !queue.is_empty() && !next_step(input)? {
+            return Ok(());
+        }
+    }
+    writeln!(out, "done, {} vertices reached", visited.len())
+}
+
+/// Dijkstra from `start` to `end`, both in the graph.
+pub(super) fn dijkstra<R: BufRead, W: Write>(graph: &GraphMap<u32, u32>, start: u32, end: u32, input: &mut R, out: &mut W) -> io::Result<()> {
+    let mut dist = HashMap::<u32, u32>::new();
+    let mut prev = HashMap::<u32, u32>::new();
+    let mut heap = BinaryHeap::new();
+    dist.insert(start, 0);
+    heap.push(Reverse((0, start)));
+    let mut step = 0;
+    writeln!(out, "(Enter for the next step, q to stop)")?;
+
+    while let Some(Reverse((d, current))) = heap.pop() {
+        if d > dist[&current] {
+            writeln!(out, "pop {} at {}, stale entry, skip", current, d)?;
+            continue;
+        }
+        step += 1;
+        writeln!(out, "step {}: settle {} at distance {}", step, current, d)?;
+        if current == end {
+            let mut path = vec![end];
+            while let Some(&p) = prev.get(path.last().unwrap()) {
+                path.push(p);
+            }
+            let path: Vec<String> = path.iter().rev().map(|v| v.to_string()).collect();
+            return writeln!(out, "reached {}, path: {}, cost {}", end, path.join(" "), d);
+        }
+        for (v, w) in sorted_out(graph, current) {
+            let candidate = d + w;
+            match dist.get(&v) {
+                Some(&old) if old <= candidate => {
+                    writeln!(out, "  edge {} -> {} ({}): {} is not better than {}", current, v, w, candidate, old)?;
+                }
+                old => {
+                    match old {
+                        Some(old) => writeln!(out, "  edge {} -> {} ({}): improve {} to {}", current, v, w, old, candidate)?,
+                        None => writeln!(out, "  edge {} -> {} ({}): reach {} at {}", current, v, w, v, candidate)?,
+                    }
+                    dist.insert(v, candidate);
+                    prev.insert(v, current);
+                    heap.push(Reverse((candidate, v)));
+                }
+            }
+        }
+        let mut entries: Vec<(u32, u32)> = heap.iter().map(|Reverse(entry)| *entry).collect();
+        entries.sort_unstable();
+        let entries: Vec<String> = entries.iter().map(|(d, v)| format!("{}:{}", v, d)).collect();
+        writeln!(out, "  heap: [{}]", entries.join(", "))?;
+        out.flush()?;
+        if !heap.is_empty() && !next_step(input)? {
+            return Ok(());
+        }
+    }
+    writeln!(out, "{} is not reachable from {}", end, start)
+}
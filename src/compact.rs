@@ -0,0 +1,241 @@
+//! Graph with plain `u32` vertex ids.
+//!
+//! [`CompactGraph`] stores the vertices in a vector indexed by their id and
+//! the adjacency in one small vector per vertex. Ids are half the size of
+//! a [`VertexId`](crate::VertexId) and there is no hashing on the way to
+//! the neighbours, which helps algorithm heavy code.
+//!
+//! The price is that ids of removed vertices are reused by the next
+//! vertices added, so an old id may silently point to a new vertex, and
+//! removing edges costs O(degree). It fits graphs that are built once and
+//! then mostly read.
+
+use super::GraphError;
+
+/// Vertex id of a [`CompactGraph`].
+pub type CompactId = u32;
+
+/// Graph data structure with `u32` vertex ids reused after removal.
+/// [`V`] is the Vertex data, and [`E`] is the Edge data.
+///
+/// ```
+/// use graph::compact::CompactGraph;
+///
+/// let mut graph = CompactGraph::<&str, u32>::new();
+/// let a = graph.add_vertex("a");
+/// let b = graph.add_vertex("b");
+/// graph.add_edge((a, b), 5).unwrap();
+/// assert_eq!(graph.adj_out(a).collect::<Vec<_>>(), vec![(b, &5)]);
+///
+/// graph.remove_vertex(b);
+/// let c = graph.add_vertex("c");
+/// assert_eq!(c, b);
+/// assert_eq!(graph.edge_count(), 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CompactGraph<V, E> {
+    vertices: Vec<Option<V>>,
+    free: Vec<CompactId>,
+    outbound: Vec<Vec<(CompactId, E)>>,
+    inbound: Vec<Vec<CompactId>>,
+    vertex_count: usize,
+    edge_count: usize,
+}
+
+impl<V, E> CompactGraph<V, E> {
+
+    pub fn new() -> Self {
+        CompactGraph {
+            vertices: Vec::new(),
+            free: Vec::new(),
+            outbound: Vec::new(),
+            inbound: Vec::new(),
+            vertex_count: 0,
+            edge_count: 0,
+        }
+    }
+
+    fn contains(&self, vertex: CompactId) -> bool {
+        matches!(self.vertices.get(vertex as usize), Some(Some(_)))
+    }
+
+    /// Adds a vertex to the graph, and returns its id. Reuses the id
+    /// of the last removed vertex if there is one.
+    /// Panics if the graph already has `u32::MAX` vertex slots.
+    pub fn add_vertex(&mut self, vertex: V) -> CompactId {
+        self.vertex_count += 1;
+        match self.free.pop() {
+            Some(id) => {
+                self.vertices[id as usize] = Some(vertex);
+                id
+            }
+            None => {
+                assert!(self.vertices.len() < CompactId::MAX as usize, "too many vertices for u32 ids");
+                self.vertices.push(Some(vertex));
+                self.outbound.push(Vec::new());
+                self.inbound.push(Vec::new());
+                (self.vertices.len() - 1) as CompactId
+            }
+        }
+    }
+
+    /// Returns the data in the vertex.
+    pub fn get_vertex(&self, vertex: CompactId) -> Option<&V> {
+        self.vertices.get(vertex as usize)?.as_ref()
+    }
+
+    /// Adds an edge, or modifies the existing one, returning the old data.
+    /// Fails if one of the vertices is not in the graph.
+    /// Time complexity: O(outdegree(from))
+    pub fn add_edge(&mut self, edge: (CompactId, CompactId), weight: E) -> Result<Option<E>, GraphError> {
+        let (from, to) = edge;
+        if !self.contains(from) || !self.contains(to) {
+            return Err(GraphError::VertexNotFound);
+        }
+        let row = &mut self.outbound[from as usize];
+        match row.iter_mut().find(|(target, _)| *target == to) {
+            Some(entry) => Ok(Some(std::mem::replace(&mut entry.1, weight))),
+            None => {
+                row.push((to, weight));
+                self.inbound[to as usize].push(from);
+                self.edge_count += 1;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Get the edge.
+    /// Time complexity: O(outdegree(from))
+    pub fn get_edge(&self, edge: (CompactId, CompactId)) -> Option<&E> {
+        let (from, to) = edge;
+        self.outbound.get(from as usize)?
+            .iter()
+            .find(|(target, _)| *target == to)
+            .map(|(_, weight)| weight)
+    }
+
+    /// Removes the vertex and its edges, freeing its id.
+    /// Time complexity: O(sum of the degrees of its neighbours)
+    pub fn remove_vertex(&mut self, vertex: CompactId) -> Option<V> {
+        let data = self.vertices.get_mut(vertex as usize)?.take()?;
+        let outbound = std::mem::take(&mut self.outbound[vertex as usize]);
+        let inbound = std::mem::take(&mut self.inbound[vertex as usize]);
+        // A self loop is in both lists.
+        let self_loop = outbound.iter().any(|&(to, _)| to == vertex);
+        self.edge_count -= outbound.len() + inbound.len() - self_loop as usize;
+
+        for (to, _) in outbound {
+            self.inbound[to as usize].retain(|&from| from != vertex);
+        }
+        for from in inbound {
+            self.outbound[from as usize].retain(|&(to, _)| to != vertex);
+        }
+
+        self.free.push(vertex);
+        self.vertex_count -= 1;
+        Some(data)
+    }
+
+    /// Removes an edge, returning its data.
+    /// Time complexity: O(outdegree(from) + indegree(to))
+    pub fn remove_edge(&mut self, edge: (CompactId, CompactId)) -> Option<E> {
+        let (from, to) = edge;
+        let row = self.outbound.get_mut(from as usize)?;
+        let position = row.iter().position(|(target, _)| *target == to)?;
+        let (_, weight) = row.swap_remove(position);
+        let column = &mut self.inbound[to as usize];
+        let position = column.iter().position(|&source| source == from).unwrap();
+        column.swap_remove(position);
+        self.edge_count -= 1;
+        Some(weight)
+    }
+
+    /// Outbound edges, as pairs of (target, weight).
+    pub fn adj_out(&self, vertex: CompactId) -> impl Iterator<Item = (CompactId, &E)> {
+        self.outbound.get(vertex as usize)
+            .into_iter()
+            .flat_map(|row| row.iter().map(|(to, weight)| (*to, weight)))
+    }
+
+    /// Inbound edges, as pairs of (source, weight).
+    pub fn adj_in(&self, vertex: CompactId) -> impl Iterator<Item = (CompactId, &E)> {
+        self.inbound.get(vertex as usize)
+            .into_iter()
+            .flat_map(move |column| {
+                column.iter().map(move |&from| (from, self.get_edge((from, vertex)).unwrap()))
+            })
+    }
+
+    /// Indegree of the vertex
+    pub fn indegree(&self, vertex: CompactId) -> usize {
+        self.inbound.get(vertex as usize).map_or(0, |column| column.len())
+    }
+
+    /// Outdegree of the vertex
+    pub fn outdegree(&self, vertex: CompactId) -> usize {
+        self.outbound.get(vertex as usize).map_or(0, |row| row.len())
+    }
+
+    /// Number of vertices
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    /// Number of edges
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// Iterator over the vertices, by increasing id.
+    pub fn vertices(&self) -> impl Iterator<Item = (CompactId, &V)> {
+        self.vertices.iter()
+            .enumerate()
+            .filter_map(|(id, data)| Some((id as CompactId, data.as_ref()?)))
+    }
+
+    /// Iterator over the edges, grouped by source.
+    pub fn edges(&self) -> impl Iterator<Item = ((CompactId, CompactId), &E)> {
+        self.outbound.iter()
+            .enumerate()
+            .flat_map(|(from, row)| row.iter().map(move |(to, weight)| ((from as CompactId, *to), weight)))
+    }
+}
+
+impl<V, E> Default for CompactGraph<V, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CompactGraph;
+    use crate::GraphError;
+
+    #[test]
+    fn removal_keeps_counts_and_reuses_ids() {
+        let mut graph = CompactGraph::<u32, u32>::new();
+        let ids: Vec<_> = (0..4).map(|i| graph.add_vertex(i)).collect();
+        assert_eq!(graph.add_edge((ids[0], ids[1]), 1), Ok(None));
+        assert_eq!(graph.add_edge((ids[1], ids[1]), 2), Ok(None));
+        assert_eq!(graph.add_edge((ids[2], ids[1]), 3), Ok(None));
+        assert_eq!(graph.add_edge((ids[1], ids[3]), 4), Ok(None));
+        assert_eq!(graph.add_edge((ids[1], ids[3]), 5), Ok(Some(4)));
+        assert_eq!(graph.edge_count(), 4);
+        assert_eq!(graph.get_edge((ids[1], ids[3])), Some(&5));
+
+        assert_eq!(graph.remove_vertex(ids[1]), Some(1));
+        assert_eq!(graph.add_edge((ids[0], ids[1]), 7), Err(GraphError::VertexNotFound));
+        assert_eq!(graph.edge_count(), 0);
+        assert_eq!(graph.vertex_count(), 3);
+        assert_eq!(graph.outdegree(ids[0]), 0);
+        assert_eq!(graph.indegree(ids[3]), 0);
+
+        let reused = graph.add_vertex(9);
+        assert_eq!(reused, ids[1]);
+        assert_eq!(graph.add_edge((ids[2], reused), 6), Ok(None));
+        assert_eq!(graph.adj_in(reused).collect::<Vec<_>>(), vec![(ids[2], &6)]);
+        assert_eq!(graph.remove_edge((ids[2], reused)), Some(6));
+        assert_eq!(graph.edges().count(), 0);
+    }
+}
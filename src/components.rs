@@ -0,0 +1,199 @@
+//! Connected components kept up to date as edges are added.
+//!
+//! [`ConnectedComponents`] is a union-find over the vertices, with union
+//! by size and path compression, so every operation takes nearly
+//! constant time. Edge directions are ignored. Removals are not
+//! supported: after removing edges, build a new tracker.
+
+use crate::{Graph, GraphMap, VertexId};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Weakly connected components of a growing graph. `T` identifies the
+/// vertices, [`VertexId`] or the vertex data.
+///
+/// ```
+/// use graph::components::ConnectedComponents;
+///
+/// let mut components = ConnectedComponents::new();
+/// for &(a, b) in [(1, 2), (3, 4), (2, 5)].iter() {
+///     components.add_edge(a, b);
+/// }
+/// assert_eq!(components.component_count(), 2);
+/// assert!(components.same_component(&1, &5));
+/// assert!(!components.same_component(&1, &4));
+///
+/// components.add_edge(5, 4);
+/// assert_eq!(components.component_count(), 1);
+/// assert_eq!(components.component_size(&3), Some(5));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConnectedComponents<T: Eq + Hash> {
+    index: HashMap<T, usize>,
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl<T: Eq + Hash> ConnectedComponents<T> {
+
+    pub fn new() -> Self {
+        ConnectedComponents {
+            index: HashMap::new(),
+            parent: Vec::new(),
+            size: Vec::new(),
+            count: 0,
+        }
+    }
+
+    /// Adds a vertex in a component of its own. Returns false
+    /// if it was already there.
+    pub fn add_vertex(&mut self, vertex: T) -> bool {
+        self.get_or_add(vertex).1
+    }
+
+    /// Joins the components of the two vertices, adding them if needed.
+    /// Returns true if they were in different components.
+    /// Time complexity: O(α(V))
+    pub fn add_edge(&mut self, a: T, b: T) -> bool {
+        let (a, _) = self.get_or_add(a);
+        let (b, _) = self.get_or_add(b);
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.count -= 1;
+        true
+    }
+
+    /// Whether the vertices are in the same component. False if
+    /// either is missing.
+    /// Time complexity: O(log V)
+    pub fn same_component(&self, a: &T, b: &T) -> bool {
+        match (self.index.get(a), self.index.get(b)) {
+            (Some(&a), Some(&b)) => self.root(a) == self.root(b),
+            _ => false,
+        }
+    }
+
+    /// Number of vertices in the component of the vertex.
+    pub fn component_size(&self, vertex: &T) -> Option<usize> {
+        let &i = self.index.get(vertex)?;
+        Some(self.size[self.root(i)])
+    }
+
+    /// Number of components
+    pub fn component_count(&self) -> usize {
+        self.count
+    }
+
+    /// Number of vertices
+    pub fn vertex_count(&self) -> usize {
+        self.parent.len()
+    }
+
+    fn get_or_add(&mut self, vertex: T) -> (usize, bool) {
+        if let Some(&i) = self.index.get(&vertex) {
+            return (i, false);
+        }
+        let i = self.parent.len();
+        self.index.insert(vertex, i);
+        self.parent.push(i);
+        self.size.push(1);
+        self.count += 1;
+        (i, true)
+    }
+
+    /// Root without compressing, for the `&self` queries. Union by
+    /// size keeps the trees O(log V) deep.
+    fn root(&self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            i = self.parent[i];
+        }
+        i
+    }
+
+    /// Root, halving the path on the way.
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+}
+
+impl<T: Eq + Hash> Default for ConnectedComponents<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Eq + Hash> Extend<(T, T)> for ConnectedComponents<T> {
+    fn extend<I: IntoIterator<Item = (T, T)>>(&mut self, edges: I) {
+        for (a, b) in edges {
+            self.add_edge(a, b);
+        }
+    }
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Tracker of the components of the graph as it is now, to be fed
+    /// the edges added afterwards.
+    /// Time complexity: O(V + E)
+    pub fn components_tracker(&self) -> ConnectedComponents<VertexId> {
+        let mut components = ConnectedComponents::new();
+        for (id, _) in self.vertices() {
+            components.add_vertex(id);
+        }
+        components.extend(self.edges().map(|(&edge, _)| edge));
+        components
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::components_tracker`].
+    pub fn components_tracker(&self) -> ConnectedComponents<V> {
+        let mut components = ConnectedComponents::new();
+        for vertex in self.vertices() {
+            components.add_vertex(vertex.clone());
+        }
+        components.extend(self.edges().map(|((from, to), _)| (from.clone(), to.clone())));
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+    use crate::traversal::connected_components;
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn tracker_agrees_with_bfs() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut graph = GraphMap::<u32, u32>::new();
+        for v in 0..40 {
+            graph.add_vertex(v);
+        }
+        let mut tracker = graph.components_tracker();
+        for _ in 0..60 {
+            let (a, b) = (rng.gen_range(0..40), rng.gen_range(0..40));
+            graph.add_edge((a, b), 1);
+            tracker.add_edge(a, b);
+            assert_eq!(tracker.component_count(), connected_components(&graph).len());
+        }
+        for component in graph.connected_components() {
+            let first = component.vertices().next().unwrap();
+            assert!(component.vertices().all(|v| tracker.same_component(first, v)));
+            assert_eq!(tracker.component_size(first), Some(component.vertex_count()));
+        }
+    }
+}
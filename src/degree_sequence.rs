@@ -0,0 +1,108 @@
+//! Degree sequences of simple undirected graphs, with no self loops
+//! and no parallel edges.
+
+use super::GraphMap;
+use std::collections::BinaryHeap;
+use std::fmt;
+
+/// Error returned by [`from_degree_sequence`] when no simple graph
+/// has the degree sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotGraphical;
+
+impl fmt::Display for NotGraphical {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the degree sequence is not graphical")
+    }
+}
+
+impl std::error::Error for NotGraphical {}
+
+/// Checks whether some simple undirected graph has exactly these vertex
+/// degrees, in any order, with the Erdős–Gallai theorem: the sum must be
+/// even and, with the degrees sorted in decreasing order, for every `k`
+///
+/// ```text
+/// d1 + ... + dk <= k (k - 1) + min(d(k+1), k) + ... + min(dn, k)
+/// ```
+///
+/// Time complexity: O(n log n)
+///
+/// ```
+/// use graph::degree_sequence::is_graphical;
+///
+/// assert!(is_graphical(&[3, 3, 2, 2, 2]));
+/// assert!(!is_graphical(&[3, 3, 1, 1]));
+/// assert!(!is_graphical(&[2, 1]));
+/// ```
+pub fn is_graphical(degree_sequence: &[usize]) -> bool {
+    let mut degrees = degree_sequence.to_vec();
+    degrees.sort_unstable_by(|a, b| b.cmp(a));
+    let n = degrees.len();
+    if degrees.iter().sum::<usize>() % 2 == 1 || matches!(degrees.first(), Some(&d) if d >= n) {
+        return false;
+    }
+
+    let mut suffix = vec![0; n + 1];
+    for i in (0..n).rev() {
+        suffix[i] = suffix[i + 1] + degrees[i];
+    }
+
+    let mut left = 0;
+    for k in 1..=n {
+        left += degrees[k - 1];
+        // Degrees after position k that are at least k count as k.
+        let at_least_k = degrees.partition_point(|&d| d >= k).max(k);
+        let right = k * (k - 1) + k * (at_least_k - k) + suffix[at_least_k];
+        if left > right {
+            return false;
+        }
+    }
+    true
+}
+
+/// Builds a simple undirected graph where vertex `i` has degree
+/// `degree_sequence[i]`, with the Havel–Hakimi algorithm: the vertex with
+/// the most missing edges is joined to the ones with the most after it.
+/// Every undirected edge is stored in both directions.
+/// Time complexity: O(n log n + E log n)
+///
+/// ```
+/// use graph::degree_sequence::from_degree_sequence;
+///
+/// let graph = from_degree_sequence(&[3, 3, 2, 2, 2]).unwrap();
+/// assert_eq!(graph.outdegree(0), 3);
+/// assert_eq!(graph.edge_count(), 2 * 6);
+/// assert!(from_degree_sequence(&[3, 3, 1, 1]).is_err());
+/// ```
+pub fn from_degree_sequence(degree_sequence: &[usize]) -> Result<GraphMap<usize, ()>, NotGraphical> {
+    if !is_graphical(degree_sequence) {
+        return Err(NotGraphical);
+    }
+
+    let mut graph = GraphMap::new();
+    for vertex in 0..degree_sequence.len() {
+        graph.add_vertex(vertex);
+    }
+    let mut heap: BinaryHeap<(usize, usize)> = degree_sequence.iter()
+        .copied()
+        .enumerate()
+        .filter(|&(_, degree)| degree > 0)
+        .map(|(vertex, degree)| (degree, vertex))
+        .collect();
+
+    while let Some((degree, vertex)) = heap.pop() {
+        let mut partners = Vec::with_capacity(degree);
+        for _ in 0..degree {
+            partners.push(heap.pop().ok_or(NotGraphical)?);
+        }
+        for (other_degree, other) in partners {
+            graph.add_edge((vertex, other), ());
+            graph.add_edge((other, vertex), ());
+            if other_degree > 1 {
+                heap.push((other_degree - 1, other));
+            }
+        }
+    }
+    Ok(graph)
+}
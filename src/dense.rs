@@ -0,0 +1,305 @@
+//! Graph whose vertices are the numbers `0..n`.
+//!
+//! [`DenseGraph`] has no vertex data and no arena: the vertex `v` is the
+//! position `v` of a vector of adjacency lists, so reaching the
+//! neighbours needs neither hashing nor an indirection. It fits the
+//! graphs read from edge lists, whose vertices are already numbered.
+//! Every vertex also has the list of its inbound edges, each pointing
+//! at its place in the outbound list of its source, so the edge data is
+//! stored once.
+//!
+//! It implements the traits of [`graph_ref`](crate::graph_ref), so the
+//! generic algorithms of [`traversal`](crate::traversal) run on it. They
+//! identify the vertex `v` by [`vertex_id`], the [`VertexId`] of slot
+//! `v` and generation 0, and see `v` itself as its vertex data.
+
+use super::{GraphError, GraphMap, VertexId};
+use std::ops::Range;
+
+/// [`VertexId`] of the vertex, for the generic algorithms.
+pub fn vertex_id(vertex: u32) -> VertexId {
+    VertexId::from((vertex as usize, 0))
+}
+
+/// Vertex of an id made by [`vertex_id`], `None` for the other ids.
+pub fn vertex_of(id: VertexId) -> Option<u32> {
+    match <(usize, u64)>::from(id) {
+        (slot, 0) if slot <= u32::MAX as usize => Some(slot as u32),
+        _ => None,
+    }
+}
+
+/// Directed graph on the vertices `0..n`, with adjacency lists.
+/// [`E`] is the Edge data. There is no vertex data: through
+/// [`VertexData`](crate::graph_ref::VertexData), the data of a vertex
+/// is its number.
+///
+/// ```
+/// use graph::dense::{vertex_id, vertex_of, DenseGraph};
+/// use graph::traversal::bfs;
+///
+/// let mut graph = DenseGraph::<u32>::with_vertices(3);
+/// graph.add_edge((0, 1), 5).unwrap();
+/// graph.add_edge((1, 2), 7).unwrap();
+/// assert_eq!(graph.add_edge((1, 2), 8), Ok(Some(7)));
+/// assert!(graph.add_edge((2, 3), 1).is_err());
+///
+/// assert_eq!(graph.adj_out(1), &[(2, 8)]);
+/// let order: Vec<_> = bfs(&graph, vertex_id(0)).into_iter().map(vertex_of).collect();
+/// assert_eq!(order, vec![Some(0), Some(1), Some(2)]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DenseGraph<E> {
+    outbound: Vec<Vec<(u32, E)>>,
+    /// Source of every inbound edge, and its position in `outbound[source]`.
+    inbound: Vec<Vec<(u32, usize)>>,
+    edge_count: usize,
+}
+
+impl<E> DenseGraph<E> {
+
+    pub fn new() -> Self {
+        DenseGraph {
+            outbound: Vec::new(),
+            inbound: Vec::new(),
+            edge_count: 0,
+        }
+    }
+
+    /// Graph with the vertices `0..vertices` and no edges.
+    pub fn with_vertices(vertices: u32) -> Self {
+        DenseGraph {
+            outbound: (0..vertices).map(|_| Vec::new()).collect(),
+            inbound: (0..vertices).map(|_| Vec::new()).collect(),
+            edge_count: 0,
+        }
+    }
+
+    fn contains(&self, vertex: u32) -> bool {
+        (vertex as usize) < self.outbound.len()
+    }
+
+    /// Adds the vertex `n`, and returns it.
+    /// Panics if the graph already has `u32::MAX` vertices.
+    pub fn add_vertex(&mut self) -> u32 {
+        assert!(self.outbound.len() < u32::MAX as usize, "too many vertices for u32 ids");
+        self.outbound.push(Vec::new());
+        self.inbound.push(Vec::new());
+        (self.outbound.len() - 1) as u32
+    }
+
+    /// Adds an edge, or modifies the existing one, returning its previous
+    /// data. Fails if either vertex is not in the graph.
+    /// Time complexity: O(outdegree(from))
+    pub fn add_edge(&mut self, edge: (u32, u32), weight: E) -> Result<Option<E>, GraphError> {
+        let (from, to) = edge;
+        if !self.contains(from) || !self.contains(to) {
+            return Err(GraphError::VertexNotFound);
+        }
+        let targets = &mut self.outbound[from as usize];
+        if let Some((_, existing)) = targets.iter_mut().find(|(target, _)| *target == to) {
+            return Ok(Some(std::mem::replace(existing, weight)));
+        }
+        self.inbound[to as usize].push((from, targets.len()));
+        targets.push((to, weight));
+        self.edge_count += 1;
+        Ok(None)
+    }
+
+    /// Get the edge.
+    /// Time complexity: O(outdegree(from))
+    pub fn get_edge(&self, edge: (u32, u32)) -> Option<&E> {
+        let (from, to) = edge;
+        self.outbound.get(from as usize)?
+            .iter()
+            .find(|(target, _)| *target == to)
+            .map(|(_, weight)| weight)
+    }
+
+    /// Get the edge, mutably.
+    /// Time complexity: O(outdegree(from))
+    pub fn get_edge_mut(&mut self, edge: (u32, u32)) -> Option<&mut E> {
+        let (from, to) = edge;
+        self.outbound.get_mut(from as usize)?
+            .iter_mut()
+            .find(|(target, _)| *target == to)
+            .map(|(_, weight)| weight)
+    }
+
+    /// Removes the edge, returning its data.
+    /// Time complexity: O(outdegree(from) + indegree(to))
+    pub fn remove_edge(&mut self, edge: (u32, u32)) -> Option<E> {
+        let (from, to) = edge;
+        let targets = self.outbound.get_mut(from as usize)?;
+        let position = targets.iter().position(|(target, _)| *target == to)?;
+        let (_, weight) = targets.swap_remove(position);
+        // The last edge of `from` took the place of the removed one.
+        if let Some(&(moved, _)) = targets.get(position) {
+            let last = targets.len();
+            for source in self.inbound[moved as usize].iter_mut() {
+                if *source == (from, last) {
+                    source.1 = position;
+                }
+            }
+        }
+        let sources = &mut self.inbound[to as usize];
+        let source = sources.iter().position(|&(source, _)| source == from).unwrap();
+        sources.swap_remove(source);
+        self.edge_count -= 1;
+        Some(weight)
+    }
+
+    /// Targets and data of the outbound edges of the vertex, empty if
+    /// it is not in the graph.
+    pub fn adj_out(&self, vertex: u32) -> &[(u32, E)] {
+        self.outbound.get(vertex as usize).map_or(&[], Vec::as_slice)
+    }
+
+    /// Sources and data of the inbound edges of the vertex, empty if
+    /// it is not in the graph.
+    pub fn adj_in(&self, vertex: u32) -> impl Iterator<Item = (u32, &E)> + '_ {
+        self.inbound.get(vertex as usize).map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(move |&(from, position)| (from, &self.outbound[from as usize][position].1))
+    }
+
+    pub fn outdegree(&self, vertex: u32) -> usize {
+        self.adj_out(vertex).len()
+    }
+
+    pub fn indegree(&self, vertex: u32) -> usize {
+        self.inbound.get(vertex as usize).map_or(0, Vec::len)
+    }
+
+    /// Number of vertices
+    pub fn vertex_count(&self) -> usize {
+        self.outbound.len()
+    }
+
+    /// Number of edges
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// The vertices, `0..n`.
+    pub fn vertices(&self) -> Range<u32> {
+        0..self.outbound.len() as u32
+    }
+
+    /// Iterator over the edges, grouped by source vertex.
+    pub fn edges(&self) -> impl Iterator<Item = ((u32, u32), &E)> {
+        self.outbound.iter().enumerate().flat_map(|(from, targets)| {
+            targets.iter().map(move |(to, weight)| ((from as u32, *to), weight))
+        })
+    }
+}
+
+impl<E: Clone> DenseGraph<E> {
+
+    /// Copies the graph into a [`GraphMap`] with every vertex from
+    /// 0 to `n`, isolated ones included. See [`GraphMap::to_dense`]
+    /// for the way back.
+    /// Time complexity: O(V + E)
+    pub fn to_graph_map(&self) -> GraphMap<u32, E> {
+        let mut graph = GraphMap::with_capacity(self.vertex_count(), self.edge_count());
+        for vertex in self.vertices() {
+            graph.add_vertex(vertex);
+        }
+        for (edge, weight) in self.edges() {
+            graph.add_edge(edge, weight.clone());
+        }
+        graph
+    }
+}
+
+impl<E: Clone> GraphMap<u32, E> {
+
+    /// Copies the graph into a [`DenseGraph`], numbering the vertices
+    /// `0..n` in increasing order. Also returns the vertex of this graph
+    /// behind every number, so `keys[v]` is the dense vertex `v`. A graph
+    /// already on `0..n` keeps its numbers, and sparse ones like
+    /// `{0, 4_000_000_000}` take two vertices, not four billion.
+    /// Time complexity: O(V log V + E log V)
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let graph = GraphMap::<u32, u32>::from_edges(vec![((0, 1), 1), ((1, 2), 2), ((7, 0), 3)]);
+    /// let (dense, keys) = graph.to_dense();
+    /// assert_eq!(keys, vec![0, 1, 2, 7]);
+    /// assert_eq!(dense.vertex_count(), 4);
+    /// assert_eq!(dense.get_edge((1, 2)), Some(&2));
+    /// assert_eq!(dense.get_edge((3, 0)), Some(&3));
+    /// assert_eq!(dense.adj_in(0).collect::<Vec<_>>(), vec![(3, &3)]);
+    /// ```
+    pub fn to_dense(&self) -> (DenseGraph<E>, Vec<u32>) {
+        let mut keys: Vec<u32> = self.vertices().copied().collect();
+        keys.sort_unstable();
+        let number = |key: &u32| keys.binary_search(key).unwrap() as u32;
+        let mut dense = DenseGraph::with_vertices(keys.len() as u32);
+        for ((from, to), weight) in self.edges() {
+            let (from, to) = (number(from), number(to));
+            dense.inbound[to as usize].push((from, dense.outbound[from as usize].len()));
+            dense.outbound[from as usize].push((to, weight.clone()));
+        }
+        dense.edge_count = self.edge_count();
+        (dense, keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{vertex_id, vertex_of};
+    use crate::graph_ref::VertexData;
+    use crate::traversal::{astar, connected_components, shortest_paths_from};
+    use crate::GraphMap;
+
+    #[test]
+    fn algorithms_agree_with_the_graph_map() {
+        let mut graph = GraphMap::<u32, u32>::new();
+        for i in 0..40 {
+            graph.add_edge((i, (i * 7 + 3) % 40), i % 5 + 1);
+            graph.add_edge((i, (i + 1) % 40), 9);
+        }
+        graph.add_vertex(45);
+        graph.add_vertex(u32::MAX);
+        let (mut dense, keys) = graph.to_dense();
+        assert_eq!(dense.vertex_count(), 42);
+        assert_eq!(keys[40..], [45, u32::MAX]);
+        assert_eq!(dense.edge_count(), graph.edge_count());
+
+        let paths = shortest_paths_from(&dense, vertex_id(0));
+        let start = graph.id_of(&0).unwrap();
+        let expected = shortest_paths_from(&graph, start);
+        for vertex in 0..40 {
+            let id = graph.id_of(&vertex).unwrap();
+            assert_eq!(paths.distance.get(&vertex_id(vertex)), expected.distance.get(&id));
+        }
+        assert_eq!(connected_components(&dense).len(), 3);
+        assert_eq!(dense.with_vertex(vertex_id(41), |&v| v), Some(41));
+        assert_eq!(dense.with_vertex(vertex_id(42), |&v| v), None);
+        let (_, cost) = astar(&dense, vertex_id(0), vertex_id(39), |_| 0).unwrap();
+        assert_eq!(Some(&cost), paths.distance.get(&vertex_id(39)));
+
+        let back = dense.to_graph_map();
+        assert_eq!(back.vertex_count(), 42);
+        for ((&from, &to), weight) in graph.edges() {
+            assert_eq!(back.get_edge((from, to)), Some(weight));
+        }
+
+        for i in (0..40).step_by(3) {
+            assert_eq!(dense.remove_edge((i, (i + 1) % 40)), Some(9));
+        }
+        for vertex in dense.vertices() {
+            let mut inbound: Vec<_> = dense.adj_in(vertex).map(|(from, &w)| (from, w)).collect();
+            let mut expected: Vec<_> = dense.edges()
+                .filter(|&((_, to), _)| to == vertex)
+                .map(|((from, _), &w)| (from, w))
+                .collect();
+            inbound.sort_unstable();
+            expected.sort_unstable();
+            assert_eq!(inbound, expected);
+        }
+        assert_eq!(vertex_of(vertex_id(12)), Some(12));
+    }
+}
@@ -0,0 +1,307 @@
+//! Graph stored in an embedded key-value database.
+//!
+//! Enabled with the `disk` feature. [`DiskGraph`] keeps the vertices, the
+//! adjacency and the edge data in [sled] trees, so it can hold graphs that
+//! don't fit in memory. The most recently used vertices and outbound
+//! adjacency lists are kept in an LRU cache in front of the database.
+//!
+//! Data is written with [`Display`] and read back with [`FromStr`], like
+//! the write-ahead log in [`crate::wal`].
+
+use super::{VertexId, EdgeId};
+use lru::LruCache;
+use sled::transaction::{ConflictableTransactionError, TransactionError};
+use sled::{Db, Transactional, Tree};
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::vec::IntoIter;
+
+/// Graph data structure backed by a sled database.
+///
+/// Mirrors the API of [`crate::Graph`], but every operation can fail
+/// with an I/O error, and the data is returned by value.
+/// Vertex ids are never reused, even after the vertex is removed.
+/// It implements the traits of [`graph_ref`](crate::graph_ref), so the
+/// algorithms of [`crate::traversal`] run on it directly.
+///
+/// ```
+/// use graph::disk::DiskGraph;
+/// use graph::traversal::dijkstra;
+///
+/// let graph = DiskGraph::<String, u32>::temporary(1024).unwrap();
+/// let a = graph.add_vertex(String::from("a")).unwrap();
+/// let b = graph.add_vertex(String::from("b")).unwrap();
+/// let c = graph.add_vertex(String::from("c")).unwrap();
+/// graph.add_edge((a, b), 7).unwrap();
+/// graph.add_edge((b, c), 1).unwrap();
+/// graph.add_edge((a, c), 9).unwrap();
+///
+/// assert_eq!(graph.get_edge((a, b)).unwrap(), Some(7));
+/// assert_eq!(graph.adj_in(b).unwrap().unwrap().collect::<Vec<_>>(), vec![(a, 7)]);
+/// assert_eq!(dijkstra(&graph, a, c), Some((vec![a, b, c], 8)));
+/// ```
+pub struct DiskGraph<V, E> {
+    db: Db,
+    vertices: Tree,
+    outbound: Tree,
+    inbound: Tree,
+    vertex_cache: RefCell<LruCache<VertexId, V>>,
+    adjacency_cache: RefCell<LruCache<VertexId, Vec<(VertexId, E)>>>,
+}
+
+impl<V, E> DiskGraph<V, E>
+where
+    V: Display + FromStr + Clone,
+    E: Display + FromStr + Clone,
+{
+    /// Opens the database at `path`, creating it if it doesn't exist.
+    /// At most `cache_capacity` vertices and adjacency lists are cached.
+    pub fn open<P: AsRef<Path>>(path: P, cache_capacity: usize) -> io::Result<Self> {
+        Self::with_db(sled::open(path)?, cache_capacity)
+    }
+
+    /// Opens a database that is deleted when the graph is dropped.
+    pub fn temporary(cache_capacity: usize) -> io::Result<Self> {
+        Self::with_db(sled::Config::new().temporary(true).open()?, cache_capacity)
+    }
+
+    fn with_db(db: Db, cache_capacity: usize) -> io::Result<Self> {
+        Ok(DiskGraph {
+            vertices: db.open_tree("vertices")?,
+            outbound: db.open_tree("outbound")?,
+            inbound: db.open_tree("inbound")?,
+            db,
+            vertex_cache: RefCell::new(LruCache::new(cache_capacity)),
+            adjacency_cache: RefCell::new(LruCache::new(cache_capacity)),
+        })
+    }
+
+    /// Adds a vertex to the graph, and returns an Id.
+    pub fn add_vertex(&self, vertex: V) -> io::Result<VertexId> {
+        let id = VertexId::from((self.db.generate_id()? as usize, 0));
+        self.vertices.insert(encode_id(id), vertex.to_string().as_bytes())?;
+        self.vertex_cache.borrow_mut().put(id, vertex);
+        Ok(id)
+    }
+
+    /// Returns the data in the vertex.
+    pub fn get_vertex(&self, vertex: VertexId) -> io::Result<Option<V>> {
+        if let Some(data) = self.vertex_cache.borrow_mut().get(&vertex) {
+            return Ok(Some(data.clone()));
+        }
+        let data: V = match self.vertices.get(encode_id(vertex))? {
+            Some(bytes) => decode(&bytes)?,
+            None => return Ok(None),
+        };
+        self.vertex_cache.borrow_mut().put(vertex, data.clone());
+        Ok(Some(data))
+    }
+
+    /// Checks if the vertex is in the graph.
+    pub fn contains_vertex(&self, vertex: VertexId) -> io::Result<bool> {
+        if self.vertex_cache.borrow().contains(&vertex) {
+            return Ok(true);
+        }
+        Ok(self.vertices.contains_key(encode_id(vertex))?)
+    }
+
+    /// Adds an edge, or modifies the existing one.
+    /// Fails with [`io::ErrorKind::InvalidInput`] if one of the
+    /// vertices is not in the graph.
+    pub fn add_edge(&self, edge: EdgeId, weight: E) -> io::Result<()> {
+        let (from, to) = edge;
+        if !self.contains_vertex(from)? || !self.contains_vertex(to)? {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "edge endpoint is not in the graph"));
+        }
+        let data = weight.to_string();
+        (&self.outbound, &self.inbound)
+            .transaction(|(outbound, inbound)| {
+                outbound.insert(&edge_key(from, to)[..], data.as_bytes())?;
+                inbound.insert(&edge_key(to, from)[..], &[][..])?;
+                Ok::<_, ConflictableTransactionError>(())
+            })
+            .map_err(storage_error)?;
+        self.adjacency_cache.borrow_mut().pop(&from);
+        Ok(())
+    }
+
+    /// Get the edge.
+    pub fn get_edge(&self, edge: EdgeId) -> io::Result<Option<E>> {
+        let (from, to) = edge;
+        match self.outbound.get(edge_key(from, to))? {
+            Some(bytes) => Ok(Some(decode(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Removes the vertex and all its edges.
+    /// Time complexity: O(degree(v))
+    pub fn remove_vertex(&self, vertex: VertexId) -> io::Result<()> {
+        let targets = self.neighbors(&self.outbound, vertex)?;
+        let sources = self.neighbors(&self.inbound, vertex)?;
+        (&self.vertices, &self.outbound, &self.inbound)
+            .transaction(|(vertices, outbound, inbound)| {
+                vertices.remove(&encode_id(vertex)[..])?;
+                for &to in targets.iter() {
+                    outbound.remove(&edge_key(vertex, to)[..])?;
+                    inbound.remove(&edge_key(to, vertex)[..])?;
+                }
+                for &from in sources.iter() {
+                    outbound.remove(&edge_key(from, vertex)[..])?;
+                    inbound.remove(&edge_key(vertex, from)[..])?;
+                }
+                Ok::<_, ConflictableTransactionError>(())
+            })
+            .map_err(storage_error)?;
+
+        self.vertex_cache.borrow_mut().pop(&vertex);
+        let mut adjacency = self.adjacency_cache.borrow_mut();
+        adjacency.pop(&vertex);
+        for from in sources {
+            adjacency.pop(&from);
+        }
+        Ok(())
+    }
+
+    /// Remove an edge
+    pub fn remove_edge(&self, edge: EdgeId) -> io::Result<()> {
+        let (from, to) = edge;
+        (&self.outbound, &self.inbound)
+            .transaction(|(outbound, inbound)| {
+                outbound.remove(&edge_key(from, to)[..])?;
+                inbound.remove(&edge_key(to, from)[..])?;
+                Ok::<_, ConflictableTransactionError>(())
+            })
+            .map_err(storage_error)?;
+        self.adjacency_cache.borrow_mut().pop(&from);
+        Ok(())
+    }
+
+    /// Returns an iterator over outbound edges, `None` if the vertex
+    /// is not in the graph.
+    pub fn adj_out(&self, vertex: VertexId) -> io::Result<Option<IntoIter<(VertexId, E)>>> {
+        if let Some(adjacent) = self.adjacency_cache.borrow_mut().get(&vertex) {
+            return Ok(Some(adjacent.clone().into_iter()));
+        }
+        if !self.contains_vertex(vertex)? {
+            return Ok(None);
+        }
+        let mut adjacent = Vec::new();
+        for entry in self.outbound.scan_prefix(encode_id(vertex)) {
+            let (key, value) = entry?;
+            adjacent.push((decode_id(&key[8..]), decode(&value)?));
+        }
+        self.adjacency_cache.borrow_mut().put(vertex, adjacent.clone());
+        Ok(Some(adjacent.into_iter()))
+    }
+
+    /// Returns an iterator over inbound edges, `None` if the vertex
+    /// is not in the graph.
+    pub fn adj_in(&self, vertex: VertexId) -> io::Result<Option<IntoIter<(VertexId, E)>>> {
+        if !self.contains_vertex(vertex)? {
+            return Ok(None);
+        }
+        let mut adjacent = Vec::new();
+        for from in self.neighbors(&self.inbound, vertex)? {
+            if let Some(weight) = self.get_edge((from, vertex))? {
+                adjacent.push((from, weight));
+            }
+        }
+        Ok(Some(adjacent.into_iter()))
+    }
+
+    fn neighbors(&self, tree: &Tree, vertex: VertexId) -> io::Result<Vec<VertexId>> {
+        tree.scan_prefix(encode_id(vertex))
+            .keys()
+            .map(|key| Ok(decode_id(&key?[8..])))
+            .collect()
+    }
+
+    /// Indegree of the vertex
+    pub fn indegree(&self, vertex: VertexId) -> io::Result<usize> {
+        Ok(self.neighbors(&self.inbound, vertex)?.len())
+    }
+
+    /// Outdegree of the vertex
+    pub fn outdegree(&self, vertex: VertexId) -> io::Result<usize> {
+        if let Some(adjacent) = self.adjacency_cache.borrow_mut().get(&vertex) {
+            return Ok(adjacent.len());
+        }
+        Ok(self.neighbors(&self.outbound, vertex)?.len())
+    }
+
+    /// Number of vertices
+    /// Time complexity: O(V)
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Number of edges
+    /// Time complexity: O(E)
+    pub fn edge_count(&self) -> usize {
+        self.outbound.len()
+    }
+
+    /// Iterator over the vertex ids, without reading their data.
+    pub fn vertex_ids(&self) -> impl Iterator<Item = io::Result<VertexId>> + '_ {
+        self.vertices.iter().keys().map(|key| Ok(decode_id(&key?)))
+    }
+
+    /// Iterator over the vertices, read from the database.
+    pub fn vertices(&self) -> impl Iterator<Item = io::Result<(VertexId, V)>> + '_ {
+        self.vertices.iter().map(|entry| {
+            let (key, value) = entry?;
+            Ok((decode_id(&key), decode(&value)?))
+        })
+    }
+
+    /// Iterator over the edges, read from the database.
+    pub fn edges(&self) -> impl Iterator<Item = io::Result<(EdgeId, E)>> + '_ {
+        self.outbound.iter().map(|entry| {
+            let (key, value) = entry?;
+            Ok(((decode_id(&key[..8]), decode_id(&key[8..])), decode(&value)?))
+        })
+    }
+
+    /// Writes all the pending changes to disk.
+    pub fn flush(&self) -> io::Result<()> {
+        self.db.flush()?;
+        Ok(())
+    }
+}
+
+/// Vertex ids are stored big endian, so the keys sort by id.
+fn encode_id(id: VertexId) -> [u8; 8] {
+    (<(usize, u64)>::from(id).0 as u64).to_be_bytes()
+}
+
+fn decode_id(bytes: &[u8]) -> VertexId {
+    let mut raw = [0; 8];
+    raw.copy_from_slice(&bytes[..8]);
+    VertexId::from((u64::from_be_bytes(raw) as usize, 0))
+}
+
+/// Key of an adjacency entry, the source id followed by the target id,
+/// so the adjacency of a vertex is a prefix scan.
+fn edge_key(from: VertexId, to: VertexId) -> [u8; 16] {
+    let mut key = [0; 16];
+    key[..8].copy_from_slice(&encode_id(from));
+    key[8..].copy_from_slice(&encode_id(to));
+    key
+}
+
+fn decode<T: FromStr>(bytes: &[u8]) -> io::Result<T> {
+    std::str::from_utf8(bytes)
+        .ok()
+        .and_then(|text| text.parse().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "stored data can't be parsed"))
+}
+
+fn storage_error(error: TransactionError) -> io::Error {
+    match error {
+        TransactionError::Abort(error) | TransactionError::Storage(error) => error.into(),
+    }
+}
@@ -0,0 +1,28 @@
+//! Errors of the graph operations.
+
+use std::fmt;
+
+/// Error of the `try_*` methods of [`Graph`](crate::Graph) and
+/// [`GraphMap`](crate::GraphMap), instead of a panic or a silent
+/// no-op when the graph doesn't have what they need.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GraphError {
+    /// A vertex given is not in the graph.
+    VertexNotFound,
+    /// The edge given is not in the graph.
+    EdgeNotFound,
+    /// The vertex given is already in the graph.
+    VertexExists,
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GraphError::VertexNotFound => write!(f, "vertex not found"),
+            GraphError::EdgeNotFound => write!(f, "edge not found"),
+            GraphError::VertexExists => write!(f, "vertex already in the graph"),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
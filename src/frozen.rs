@@ -0,0 +1,229 @@
+//! Read-only compressed sparse row form of a graph.
+//!
+//! [`FrozenGraph`] keeps the targets and the weights of all the outbound
+//! edges in two flat arrays, grouped by source vertex, so the neighbours
+//! of a vertex are a contiguous slice. It can't be modified, but it takes
+//! much less memory than [`Graph`] and iterating over the edges doesn't
+//! need any hashing. The inbound edges are indexed the same way, by
+//! their position in the outbound arrays, so following edges backwards
+//! costs as much as forwards.
+
+use super::{Graph, GraphMap, VertexId};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Compressed sparse row graph, built with [`Graph::freeze`].
+#[derive(Clone, Debug)]
+pub struct FrozenGraph<V, E> {
+    ids: Vec<VertexId>,
+    data: Vec<V>,
+    index: HashMap<VertexId, usize>,
+    /// Edges of the `i`th vertex are at `offsets[i]..offsets[i + 1]`.
+    offsets: Vec<usize>,
+    targets: Vec<VertexId>,
+    weights: Vec<E>,
+    /// Inbound edges of the `i`th vertex are at `in_offsets[i]..in_offsets[i + 1]`.
+    in_offsets: Vec<usize>,
+    sources: Vec<VertexId>,
+    /// Position of every inbound edge in `targets` and `weights`.
+    in_edges: Vec<usize>,
+}
+
+impl<V: std::fmt::Debug + Clone, E: Clone> Graph<V, E> {
+
+    /// Copies the graph into a [`FrozenGraph`]. The vertices keep their ids,
+    /// and the neighbours of every vertex are sorted by id.
+    /// Time complexity: O(V + E log E)
+    pub fn freeze(&self) -> FrozenGraph<V, E> {
+        let mut ids: Vec<VertexId> = self.vertices().map(|(id, _)| id).collect();
+        ids.sort_unstable();
+        let index: HashMap<VertexId, usize> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+        let data = ids.iter().map(|&id| self.get_vertex(id).unwrap().clone()).collect();
+
+        let mut offsets = Vec::with_capacity(ids.len() + 1);
+        let mut targets = Vec::with_capacity(self.edge_count());
+        let mut weights = Vec::with_capacity(self.edge_count());
+        offsets.push(0);
+        for &from in ids.iter() {
+            let mut row: Vec<VertexId> = self.outbound[&from].iter().copied().collect();
+            row.sort_unstable();
+            for to in row {
+                targets.push(to);
+                weights.push(self.get_edge((from, to)).unwrap().clone());
+            }
+            offsets.push(targets.len());
+        }
+
+        // Counting sort of the edges by target. Sources come in id
+        // order, so every inbound row is sorted too.
+        let mut in_offsets = vec![0; ids.len() + 1];
+        for to in targets.iter() {
+            in_offsets[index[to] + 1] += 1;
+        }
+        for i in 0..ids.len() {
+            in_offsets[i + 1] += in_offsets[i];
+        }
+        let mut next = in_offsets.clone();
+        let mut sources = vec![VertexId::from((0, 0)); targets.len()];
+        let mut in_edges = vec![0; targets.len()];
+        for (i, &from) in ids.iter().enumerate() {
+            for edge in offsets[i]..offsets[i + 1] {
+                let slot = &mut next[index[&targets[edge]]];
+                sources[*slot] = from;
+                in_edges[*slot] = edge;
+                *slot += 1;
+            }
+        }
+
+        FrozenGraph {
+            ids,
+            data,
+            index,
+            offsets,
+            targets,
+            weights,
+            in_offsets,
+            sources,
+            in_edges,
+        }
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E: Clone> GraphMap<V, E> {
+
+    /// See [`Graph::freeze`]. The frozen graph identifies the
+    /// vertices by the ids they have in this graph.
+    pub fn freeze(&self) -> FrozenGraph<V, E> {
+        self.graph.freeze()
+    }
+}
+
+impl<V, E> FrozenGraph<V, E> {
+
+    fn row(&self, vertex: VertexId) -> std::ops::Range<usize> {
+        match self.index.get(&vertex) {
+            Some(&i) => self.offsets[i]..self.offsets[i + 1],
+            None => 0..0,
+        }
+    }
+
+    fn in_row(&self, vertex: VertexId) -> std::ops::Range<usize> {
+        match self.index.get(&vertex) {
+            Some(&i) => self.in_offsets[i]..self.in_offsets[i + 1],
+            None => 0..0,
+        }
+    }
+
+    /// Returns the data in the vertex.
+    pub fn get_vertex(&self, vertex: VertexId) -> Option<&V> {
+        self.index.get(&vertex).map(|&i| &self.data[i])
+    }
+
+    /// Targets of the outbound edges, sorted by id.
+    /// Empty if the vertex is not in the graph.
+    pub fn neighbors(&self, vertex: VertexId) -> &[VertexId] {
+        &self.targets[self.row(vertex)]
+    }
+
+    /// Weights of the outbound edges, in the same order as
+    /// [`FrozenGraph::neighbors`].
+    pub fn weights(&self, vertex: VertexId) -> &[E] {
+        &self.weights[self.row(vertex)]
+    }
+
+    /// Get the edge.
+    /// Time complexity: O(log outdegree(v))
+    pub fn get_edge(&self, edge: (VertexId, VertexId)) -> Option<&E> {
+        let (from, to) = edge;
+        let row = self.row(from);
+        let position = self.targets[row.clone()].binary_search(&to).ok()?;
+        Some(&self.weights[row.start + position])
+    }
+
+    /// Outdegree of the vertex
+    pub fn outdegree(&self, vertex: VertexId) -> usize {
+        self.row(vertex).len()
+    }
+
+    /// Sources of the inbound edges, sorted by id.
+    /// Empty if the vertex is not in the graph.
+    pub fn in_neighbors(&self, vertex: VertexId) -> &[VertexId] {
+        &self.sources[self.in_row(vertex)]
+    }
+
+    /// Sources and weights of the inbound edges, in the same order as
+    /// [`FrozenGraph::in_neighbors`].
+    pub fn adj_in(&self, vertex: VertexId) -> impl Iterator<Item = (VertexId, &E)> + '_ {
+        let row = self.in_row(vertex);
+        self.sources[row.clone()].iter()
+            .zip(&self.in_edges[row])
+            .map(move |(&from, &edge)| (from, &self.weights[edge]))
+    }
+
+    /// Indegree of the vertex
+    pub fn indegree(&self, vertex: VertexId) -> usize {
+        self.in_row(vertex).len()
+    }
+
+    /// Vertex ids, sorted.
+    pub fn vertices(&self) -> &[VertexId] {
+        &self.ids
+    }
+
+    /// Row offsets: the edges of the `i`th vertex of
+    /// [`FrozenGraph::vertices`] are at `offsets[i]..offsets[i + 1]`
+    /// in [`FrozenGraph::targets`] and [`FrozenGraph::all_weights`].
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// Targets of all the edges, grouped by source.
+    pub fn targets(&self) -> &[VertexId] {
+        &self.targets
+    }
+
+    /// Weights of all the edges, grouped by source.
+    pub fn all_weights(&self) -> &[E] {
+        &self.weights
+    }
+
+    /// Number of vertices
+    pub fn vertex_count(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Number of edges
+    pub fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+
+    #[test]
+    fn slices_match_the_graph() {
+        let mut graph = Graph::<u32, u32>::new();
+        let ids: Vec<_> = (0..5).map(|i| graph.add_vertex(i)).collect();
+        graph.add_edge((ids[0], ids[3]), 3).unwrap();
+        graph.add_edge((ids[0], ids[1]), 1).unwrap();
+        graph.add_edge((ids[2], ids[4]), 4).unwrap();
+
+        let frozen = graph.freeze();
+        assert_eq!(frozen.neighbors(ids[0]), &[ids[1], ids[3]]);
+        assert_eq!(frozen.weights(ids[0]), &[1, 3]);
+        assert!(frozen.neighbors(ids[1]).is_empty());
+        assert_eq!(frozen.get_edge((ids[2], ids[4])), Some(&4));
+        assert_eq!(frozen.get_edge((ids[4], ids[2])), None);
+        assert_eq!(frozen.offsets(), &[0, 2, 2, 3, 3, 3]);
+        assert_eq!(frozen.get_vertex(ids[3]), Some(&3));
+
+        graph.add_edge((ids[2], ids[3]), 5).unwrap();
+        let frozen = graph.freeze();
+        assert_eq!(frozen.in_neighbors(ids[3]), &[ids[0], ids[2]]);
+        assert_eq!(frozen.adj_in(ids[3]).collect::<Vec<_>>(), vec![(ids[0], &3), (ids[2], &5)]);
+        assert_eq!(frozen.indegree(ids[4]), 1);
+        assert_eq!(frozen.indegree(ids[0]), 0);
+    }
+}
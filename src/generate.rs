@@ -0,0 +1,371 @@
+//! Random graph generators.
+//!
+//! Generators take the random number generator and a closure drawing the
+//! weight of each edge, so the same seed always gives the same graph.
+//! [`WeightDistribution`] covers the usual choices of weights.
+//!
+//! All of them build a [`GraphMap`] on the vertices `0..vertices`. The
+//! directed models, [`random_graph`], [`gnp`] and [`gnm`], draw every edge
+//! on its own. The others describe undirected graphs: every edge is added
+//! in both directions, with a single weight.
+
+use super::GraphMap;
+use rand::distributions::Distribution;
+use rand::seq::IteratorRandom;
+use rand::Rng;
+use rand_distr::{Normal, Zipf};
+use std::collections::BTreeSet;
+
+/// Distribution of random edge weights.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WeightDistribution {
+    /// Always the same weight.
+    Constant(f64),
+    /// Uniform in `[low, high)`.
+    Uniform { low: f64, high: f64 },
+    /// Normal with the given mean and standard deviation.
+    Normal { mean: f64, std_dev: f64 },
+    /// Zipf over the ranks `1..=n`, a few heavy weights and many light ones.
+    Zipf { n: u64, exponent: f64 },
+}
+
+impl Distribution<f64> for WeightDistribution {
+    /// Draws a weight.
+    ///
+    /// Panics if the parameters are invalid: an empty uniform range,
+    /// a negative or non finite standard deviation, or a Zipf with
+    /// `n == 0` or a negative exponent.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        match *self {
+            WeightDistribution::Constant(weight) => weight,
+            WeightDistribution::Uniform { low, high } => rng.gen_range(low..high),
+            WeightDistribution::Normal { mean, std_dev } => {
+                Normal::new(mean, std_dev)
+                    .expect("invalid normal weight distribution")
+                    .sample(rng)
+            }
+            WeightDistribution::Zipf { n, exponent } => {
+                Zipf::new(n, exponent)
+                    .expect("invalid zipf weight distribution")
+                    .sample(rng)
+            }
+        }
+    }
+}
+
+/// Random graph on the vertices `0..vertices` with exactly `edges` distinct
+/// edges, self loops included, each weighted by a call to `weight`.
+/// Returns `None` if there are more edges than vertex pairs.
+///
+/// ```
+/// use graph::generate::{random_graph, WeightDistribution};
+/// use rand::{SeedableRng, distributions::Distribution, rngs::StdRng};
+///
+/// let mut rng = StdRng::seed_from_u64(7);
+/// let weights = WeightDistribution::Normal { mean: 10.0, std_dev: 2.0 };
+/// let graph = random_graph(100, 500, &mut rng, |rng| weights.sample(rng)).unwrap();
+///
+/// assert_eq!(graph.edge_count(), 500);
+/// ```
+pub fn random_graph<R, E, F>(vertices: u32, edges: u32, rng: &mut R, mut weight: F) -> Option<GraphMap<u32, E>>
+where
+    R: Rng,
+    F: FnMut(&mut R) -> E,
+{
+    let pairs = vertices as u64 * vertices as u64;
+    if edges as u64 > pairs {
+        return None;
+    }
+
+    let mut graph = GraphMap::new();
+    for v in 0..vertices {
+        graph.add_vertex(v);
+    }
+    for pair in (0..pairs).choose_multiple(rng, edges as usize) {
+        let from = (pair / vertices as u64) as u32;
+        let to = (pair % vertices as u64) as u32;
+        let weight = weight(rng);
+        graph.add_edge((from, to), weight);
+    }
+    Some(graph)
+}
+
+/// Erdős–Rényi G(n, p): every edge between two distinct vertices is
+/// added with probability `p`. Returns `None` if `p` is not in `[0, 1]`.
+/// Time complexity: O(V^2)
+pub fn gnp<R, E, F>(vertices: u32, p: f64, rng: &mut R, mut weight: F) -> Option<GraphMap<u32, E>>
+where
+    R: Rng,
+    F: FnMut(&mut R) -> E,
+{
+    if !(0.0..=1.0).contains(&p) {
+        return None;
+    }
+    let mut graph = empty(vertices);
+    for from in 0..vertices {
+        for to in 0..vertices {
+            if from != to && rng.gen_bool(p) {
+                let weight = weight(rng);
+                graph.add_edge((from, to), weight);
+            }
+        }
+    }
+    Some(graph)
+}
+
+/// Erdős–Rényi G(n, m): exactly `edges` distinct edges, chosen uniformly
+/// among those between two distinct vertices. Unlike [`random_graph`],
+/// there are no self loops. Returns `None` if there are more edges than
+/// pairs of distinct vertices.
+///
+/// ```
+/// use graph::generate::gnm;
+/// use rand::{SeedableRng, rngs::StdRng};
+///
+/// let mut rng = StdRng::seed_from_u64(1);
+/// let graph = gnm(10, 90, &mut rng, |_| 1).unwrap();
+/// assert_eq!(graph.edge_count(), 90);
+/// assert!(graph.edges().all(|((a, b), _)| a != b));
+/// assert!(gnm(10, 91, &mut rng, |_| 1).is_none());
+/// ```
+pub fn gnm<R, E, F>(vertices: u32, edges: u32, rng: &mut R, mut weight: F) -> Option<GraphMap<u32, E>>
+where
+    R: Rng,
+    F: FnMut(&mut R) -> E,
+{
+    let others = vertices.saturating_sub(1) as u64;
+    let pairs = vertices as u64 * others;
+    if edges as u64 > pairs {
+        return None;
+    }
+
+    let mut graph = empty(vertices);
+    for pair in (0..pairs).choose_multiple(rng, edges as usize) {
+        let from = (pair / others) as u32;
+        let rest = (pair % others) as u32;
+        let to = if rest >= from { rest + 1 } else { rest };
+        let weight = weight(rng);
+        graph.add_edge((from, to), weight);
+    }
+    Some(graph)
+}
+
+/// Barabási–Albert preferential attachment. Starts from a complete graph
+/// on `m + 1` vertices, then joins every new vertex to `m` distinct older
+/// ones, chosen with probability proportional to their degree. Returns
+/// `None` unless `0 < m < vertices`.
+///
+/// ```
+/// use graph::generate::barabasi_albert;
+/// use rand::{SeedableRng, rngs::StdRng};
+///
+/// let mut rng = StdRng::seed_from_u64(3);
+/// let graph = barabasi_albert(100, 2, &mut rng, |_| 1u32).unwrap();
+/// // 3 edges in the first triangle, then 2 per vertex, both directions.
+/// assert_eq!(graph.edge_count(), 2 * (3 + 2 * 97));
+/// ```
+pub fn barabasi_albert<R, E, F>(vertices: u32, m: u32, rng: &mut R, mut weight: F) -> Option<GraphMap<u32, E>>
+where
+    R: Rng,
+    E: Clone,
+    F: FnMut(&mut R) -> E,
+{
+    if m == 0 || m >= vertices {
+        return None;
+    }
+    let mut graph = complete(m + 1, rng, &mut weight);
+    // Every vertex once per edge, so a uniform pick is proportional to degree.
+    let mut ends: Vec<u32> = Vec::with_capacity(2 * (vertices as usize) * (m as usize));
+    for a in 0..=m {
+        for _ in 0..m {
+            ends.push(a);
+        }
+    }
+    for v in m + 1..vertices {
+        graph.add_vertex(v);
+        let mut targets = Vec::with_capacity(m as usize);
+        while targets.len() < m as usize {
+            let target = ends[rng.gen_range(0..ends.len())];
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        for target in targets {
+            undirected_edge(&mut graph, (v, target), rng, &mut weight);
+            ends.push(v);
+            ends.push(target);
+        }
+    }
+    Some(graph)
+}
+
+/// Watts–Strogatz small world. Starts from a ring where every vertex is
+/// joined to the `k / 2` nearest on each side, then moves the far end of
+/// every edge to a random vertex with probability `beta`, avoiding self
+/// loops and duplicate edges. Returns `None` if `k` is odd, `k >= vertices`
+/// or `beta` is not in `[0, 1]`.
+// `is_multiple_of` needs Rust 1.87.
+#[allow(clippy::manual_is_multiple_of)]
+pub fn watts_strogatz<R, E, F>(vertices: u32, k: u32, beta: f64, rng: &mut R, mut weight: F) -> Option<GraphMap<u32, E>>
+where
+    R: Rng,
+    E: Clone,
+    F: FnMut(&mut R) -> E,
+{
+    if k % 2 != 0 || k >= vertices || !(0.0..=1.0).contains(&beta) {
+        return None;
+    }
+    let pair = |a: u32, b: u32| (a.min(b), a.max(b));
+    let mut edges = BTreeSet::new();
+    for u in 0..vertices {
+        for j in 1..=k / 2 {
+            edges.insert(pair(u, (u + j) % vertices));
+        }
+    }
+    let mut degree = vec![k; vertices as usize];
+    for u in 0..vertices {
+        for j in 1..=k / 2 {
+            let v = (u + j) % vertices;
+            if !rng.gen_bool(beta) || degree[u as usize] == vertices - 1 {
+                continue;
+            }
+            let w = loop {
+                let w = rng.gen_range(0..vertices);
+                if w != u && !edges.contains(&pair(u, w)) {
+                    break w;
+                }
+            };
+            edges.remove(&pair(u, v));
+            edges.insert(pair(u, w));
+            degree[v as usize] -= 1;
+            degree[w as usize] += 1;
+        }
+    }
+
+    let mut graph = empty(vertices);
+    for edge in edges {
+        undirected_edge(&mut graph, edge, rng, &mut weight);
+    }
+    Some(graph)
+}
+
+/// Complete graph, every pair of distinct vertices joined.
+pub fn complete<R, E, F>(vertices: u32, rng: &mut R, mut weight: F) -> GraphMap<u32, E>
+where
+    R: Rng,
+    E: Clone,
+    F: FnMut(&mut R) -> E,
+{
+    let mut graph = empty(vertices);
+    for a in 0..vertices {
+        for b in a + 1..vertices {
+            undirected_edge(&mut graph, (a, b), rng, &mut weight);
+        }
+    }
+    graph
+}
+
+/// Star, vertex 0 joined to all the others.
+pub fn star<R, E, F>(vertices: u32, rng: &mut R, mut weight: F) -> GraphMap<u32, E>
+where
+    R: Rng,
+    E: Clone,
+    F: FnMut(&mut R) -> E,
+{
+    let mut graph = empty(vertices);
+    for v in 1..vertices {
+        undirected_edge(&mut graph, (0, v), rng, &mut weight);
+    }
+    graph
+}
+
+/// Path, every vertex `i` joined to `i + 1`.
+pub fn path<R, E, F>(vertices: u32, rng: &mut R, mut weight: F) -> GraphMap<u32, E>
+where
+    R: Rng,
+    E: Clone,
+    F: FnMut(&mut R) -> E,
+{
+    let mut graph = empty(vertices);
+    for v in 1..vertices {
+        undirected_edge(&mut graph, (v - 1, v), rng, &mut weight);
+    }
+    graph
+}
+
+/// Cycle, a [`path`] with the last vertex joined to the first.
+/// With fewer than 3 vertices this is just the path.
+///
+/// ```
+/// use graph::generate::cycle;
+/// use rand::{SeedableRng, rngs::StdRng};
+///
+/// let mut rng = StdRng::seed_from_u64(0);
+/// let graph = cycle(5, &mut rng, |rng| rand::Rng::gen_range(rng, 1..10));
+/// assert_eq!(graph.edge_count(), 10);
+/// assert_eq!(graph.get_edge((4, 0)), graph.get_edge((0, 4)));
+/// ```
+pub fn cycle<R, E, F>(vertices: u32, rng: &mut R, mut weight: F) -> GraphMap<u32, E>
+where
+    R: Rng,
+    E: Clone,
+    F: FnMut(&mut R) -> E,
+{
+    let mut graph = path(vertices, rng, &mut weight);
+    if vertices > 2 {
+        undirected_edge(&mut graph, (vertices - 1, 0), rng, &mut weight);
+    }
+    graph
+}
+
+fn empty<E>(vertices: u32) -> GraphMap<u32, E> {
+    let mut graph = GraphMap::with_capacity(vertices as usize, 0);
+    for v in 0..vertices {
+        graph.add_vertex(v);
+    }
+    graph
+}
+
+/// Adds the edge in both directions with the same weight.
+fn undirected_edge<R, E, F>(graph: &mut GraphMap<u32, E>, edge: (u32, u32), rng: &mut R, weight: &mut F)
+where
+    R: Rng,
+    E: Clone,
+    F: FnMut(&mut R) -> E,
+{
+    let (a, b) = edge;
+    let weight = weight(rng);
+    graph.add_edge((b, a), weight.clone());
+    graph.add_edge((a, b), weight);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn generators_have_the_expected_size() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let unit = |_: &mut StdRng| 1u32;
+        assert_eq!(complete(6, &mut rng, unit).edge_count(), 30);
+        assert_eq!(star(6, &mut rng, unit).outdegree(0), 5);
+        assert_eq!(path(6, &mut rng, unit).edge_count(), 10);
+        assert_eq!(cycle(2, &mut rng, unit).edge_count(), 2);
+        assert_eq!(gnp(8, 1.0, &mut rng, unit).unwrap().edge_count(), 56);
+        assert_eq!(gnp(8, 0.0, &mut rng, unit).unwrap().edge_count(), 0);
+        assert!(gnp(8, 1.5, &mut rng, unit).is_none());
+
+        let lattice = watts_strogatz(20, 4, 0.0, &mut rng, unit).unwrap();
+        assert!((0..20).all(|v| lattice.outdegree(v) == 4));
+        let small_world = watts_strogatz(20, 4, 0.5, &mut rng, unit).unwrap();
+        assert_eq!(small_world.edge_count(), 80);
+        assert!(small_world.edges().all(|((a, b), _)| a != b));
+        assert!(watts_strogatz(20, 3, 0.5, &mut rng, unit).is_none());
+
+        let first = barabasi_albert(50, 3, &mut StdRng::seed_from_u64(5), unit).unwrap();
+        let second = barabasi_albert(50, 3, &mut StdRng::seed_from_u64(5), unit).unwrap();
+        assert!(first.edges().all(|((&a, &b), _)| second.contains_edge((a, b))));
+        assert!((0..50).all(|v| first.outdegree(v) >= 3));
+    }
+}
@@ -0,0 +1,401 @@
+//! Read-only access shared by the graph representations.
+//!
+//! The traits are split by what an algorithm needs: [`GraphBase`] for
+//! the vertex set, [`VertexData`] and [`EdgeWeights`] for the data, and
+//! [`Neighbors`] for the adjacency. [`GraphRef`] is all of them together.
+//! They are implemented by [`Graph`], [`GraphMap`], [`FrozenGraph`],
+//! [`UndirectedGraph`], [`DenseGraph`] and, with the `disk` feature,
+//! `DiskGraph`, all identifying their vertices by [`VertexId`], so the
+//! generic algorithms of [`crate::traversal`] run on any of them.
+//! [`DenseGraph`] has no vertex data: it uses the ids of [`vertex_id`],
+//! and the data of a vertex is its number.
+//! Neighbours and data are visited with a closure, which needs no
+//! allocation whatever the storage, and lets a graph that decodes its
+//! data on the fly lend it for the duration of the call.
+
+use crate::dense::{vertex_id, vertex_of, DenseGraph};
+#[cfg(feature = "disk")]
+use crate::disk::DiskGraph;
+use crate::frozen::FrozenGraph;
+use crate::undirected::UndirectedGraph;
+use crate::{EdgeId, Graph, GraphMap, VertexId};
+use std::hash::{BuildHasher, Hash};
+#[cfg(feature = "disk")]
+use std::{fmt::Display, str::FromStr};
+
+/// The vertex set of a graph.
+pub trait GraphBase {
+
+    /// Number of vertices
+    fn vertex_count(&self) -> usize;
+
+    /// Number of edges
+    fn edge_count(&self) -> usize;
+
+    fn contains_vertex(&self, vertex: VertexId) -> bool;
+
+    /// Calls `visit` with the id of every vertex.
+    fn visit_vertices<F: FnMut(VertexId)>(&self, visit: F);
+}
+
+/// Access to the data in the vertices.
+pub trait VertexData: GraphBase {
+    type Vertex;
+
+    /// Calls `visit` with the data in the vertex and returns its result,
+    /// `None` if the vertex is not in the graph.
+    fn with_vertex<R, F: FnOnce(&Self::Vertex) -> R>(&self, vertex: VertexId, visit: F) -> Option<R>;
+}
+
+/// Access to the data in the edges.
+pub trait EdgeWeights: GraphBase {
+    type Edge;
+
+    /// Calls `visit` with the data in the edge and returns its result,
+    /// `None` if the edge is not in the graph.
+    fn with_edge<R, F: FnOnce(&Self::Edge) -> R>(&self, edge: EdgeId, visit: F) -> Option<R>;
+}
+
+/// Adjacency of the vertices. An undirected graph gives every edge
+/// as both outbound and inbound.
+pub trait Neighbors: EdgeWeights {
+
+    /// Calls `visit` with the target and the data of every outbound
+    /// edge. Does nothing if the vertex is not in the graph.
+    fn visit_out<F: FnMut(VertexId, &Self::Edge)>(&self, vertex: VertexId, visit: F);
+
+    /// Calls `visit` with the source and the data of every inbound
+    /// edge. Does nothing if the vertex is not in the graph.
+    fn visit_in<F: FnMut(VertexId, &Self::Edge)>(&self, vertex: VertexId, visit: F);
+}
+
+/// Read access to a whole graph.
+pub trait GraphRef: Neighbors + VertexData {}
+
+impl<G: Neighbors + VertexData> GraphRef for G {}
+
+impl<V: std::fmt::Debug, E, S: BuildHasher + Default> GraphBase for Graph<V, E, S> {
+
+    fn vertex_count(&self) -> usize {
+        self.arena.len()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    fn contains_vertex(&self, vertex: VertexId) -> bool {
+        self.arena.contains(vertex)
+    }
+
+    fn visit_vertices<F: FnMut(VertexId)>(&self, mut visit: F) {
+        for (id, _) in self.arena.iter() {
+            visit(id);
+        }
+    }
+}
+
+impl<V: std::fmt::Debug, E, S: BuildHasher + Default> VertexData for Graph<V, E, S> {
+    type Vertex = V;
+
+    fn with_vertex<R, F: FnOnce(&V) -> R>(&self, vertex: VertexId, visit: F) -> Option<R> {
+        self.arena.get(vertex).map(visit)
+    }
+}
+
+impl<V: std::fmt::Debug, E, S: BuildHasher + Default> EdgeWeights for Graph<V, E, S> {
+    type Edge = E;
+
+    fn with_edge<R, F: FnOnce(&E) -> R>(&self, edge: EdgeId, visit: F) -> Option<R> {
+        self.edges.get(&edge).map(visit)
+    }
+}
+
+impl<V: std::fmt::Debug, E, S: BuildHasher + Default> Neighbors for Graph<V, E, S> {
+
+    fn visit_out<F: FnMut(VertexId, &E)>(&self, vertex: VertexId, mut visit: F) {
+        if let Some(targets) = self.adj_out(vertex) {
+            for (to, weight) in targets {
+                visit(to, weight);
+            }
+        }
+    }
+
+    fn visit_in<F: FnMut(VertexId, &E)>(&self, vertex: VertexId, mut visit: F) {
+        if let Some(sources) = self.adj_in(vertex) {
+            for (from, weight) in sources {
+                visit(from, weight);
+            }
+        }
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E, S: BuildHasher + Default> GraphBase for GraphMap<V, E, S> {
+
+    fn vertex_count(&self) -> usize {
+        self.graph.arena.len()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.graph.edges.len()
+    }
+
+    fn contains_vertex(&self, vertex: VertexId) -> bool {
+        self.graph.arena.contains(vertex)
+    }
+
+    fn visit_vertices<F: FnMut(VertexId)>(&self, visit: F) {
+        self.graph.visit_vertices(visit)
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E, S: BuildHasher + Default> VertexData for GraphMap<V, E, S> {
+    type Vertex = V;
+
+    fn with_vertex<R, F: FnOnce(&V) -> R>(&self, vertex: VertexId, visit: F) -> Option<R> {
+        self.graph.arena.get(vertex).map(visit)
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E, S: BuildHasher + Default> EdgeWeights for GraphMap<V, E, S> {
+    type Edge = E;
+
+    fn with_edge<R, F: FnOnce(&E) -> R>(&self, edge: EdgeId, visit: F) -> Option<R> {
+        self.graph.edges.get(&edge).map(visit)
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E, S: BuildHasher + Default> Neighbors for GraphMap<V, E, S> {
+
+    fn visit_out<F: FnMut(VertexId, &E)>(&self, vertex: VertexId, visit: F) {
+        self.graph.visit_out(vertex, visit)
+    }
+
+    fn visit_in<F: FnMut(VertexId, &E)>(&self, vertex: VertexId, visit: F) {
+        self.graph.visit_in(vertex, visit)
+    }
+}
+
+impl<V, E> GraphBase for FrozenGraph<V, E> {
+
+    fn vertex_count(&self) -> usize {
+        FrozenGraph::vertex_count(self)
+    }
+
+    fn edge_count(&self) -> usize {
+        FrozenGraph::edge_count(self)
+    }
+
+    fn contains_vertex(&self, vertex: VertexId) -> bool {
+        self.get_vertex(vertex).is_some()
+    }
+
+    fn visit_vertices<F: FnMut(VertexId)>(&self, visit: F) {
+        self.vertices().iter().copied().for_each(visit)
+    }
+}
+
+impl<V, E> VertexData for FrozenGraph<V, E> {
+    type Vertex = V;
+
+    fn with_vertex<R, F: FnOnce(&V) -> R>(&self, vertex: VertexId, visit: F) -> Option<R> {
+        self.get_vertex(vertex).map(visit)
+    }
+}
+
+impl<V, E> EdgeWeights for FrozenGraph<V, E> {
+    type Edge = E;
+
+    fn with_edge<R, F: FnOnce(&E) -> R>(&self, edge: EdgeId, visit: F) -> Option<R> {
+        self.get_edge(edge).map(visit)
+    }
+}
+
+impl<V, E> Neighbors for FrozenGraph<V, E> {
+
+    fn visit_out<F: FnMut(VertexId, &E)>(&self, vertex: VertexId, mut visit: F) {
+        for (&to, weight) in self.neighbors(vertex).iter().zip(self.weights(vertex)) {
+            visit(to, weight);
+        }
+    }
+
+    fn visit_in<F: FnMut(VertexId, &E)>(&self, vertex: VertexId, mut visit: F) {
+        for (from, weight) in self.adj_in(vertex) {
+            visit(from, weight);
+        }
+    }
+}
+
+impl<V: std::fmt::Debug, E> GraphBase for UndirectedGraph<V, E> {
+
+    fn vertex_count(&self) -> usize {
+        UndirectedGraph::vertex_count(self)
+    }
+
+    fn edge_count(&self) -> usize {
+        UndirectedGraph::edge_count(self)
+    }
+
+    fn contains_vertex(&self, vertex: VertexId) -> bool {
+        self.get_vertex(vertex).is_some()
+    }
+
+    fn visit_vertices<F: FnMut(VertexId)>(&self, mut visit: F) {
+        for (id, _) in self.vertices() {
+            visit(id);
+        }
+    }
+}
+
+impl<V: std::fmt::Debug, E> VertexData for UndirectedGraph<V, E> {
+    type Vertex = V;
+
+    fn with_vertex<R, F: FnOnce(&V) -> R>(&self, vertex: VertexId, visit: F) -> Option<R> {
+        self.get_vertex(vertex).map(visit)
+    }
+}
+
+impl<V: std::fmt::Debug, E> EdgeWeights for UndirectedGraph<V, E> {
+    type Edge = E;
+
+    fn with_edge<R, F: FnOnce(&E) -> R>(&self, edge: EdgeId, visit: F) -> Option<R> {
+        self.get_edge(edge).map(visit)
+    }
+}
+
+impl<V: std::fmt::Debug, E> Neighbors for UndirectedGraph<V, E> {
+
+    fn visit_out<F: FnMut(VertexId, &E)>(&self, vertex: VertexId, mut visit: F) {
+        if let Some(adj) = self.adj(vertex) {
+            for (other, weight) in adj {
+                visit(other, weight);
+            }
+        }
+    }
+
+    fn visit_in<F: FnMut(VertexId, &E)>(&self, vertex: VertexId, visit: F) {
+        self.visit_out(vertex, visit)
+    }
+}
+
+impl<E> GraphBase for DenseGraph<E> {
+
+    fn vertex_count(&self) -> usize {
+        DenseGraph::vertex_count(self)
+    }
+
+    fn edge_count(&self) -> usize {
+        DenseGraph::edge_count(self)
+    }
+
+    fn contains_vertex(&self, vertex: VertexId) -> bool {
+        vertex_of(vertex).is_some_and(|v| (v as usize) < DenseGraph::vertex_count(self))
+    }
+
+    fn visit_vertices<F: FnMut(VertexId)>(&self, visit: F) {
+        self.vertices().map(vertex_id).for_each(visit)
+    }
+}
+
+impl<E> VertexData for DenseGraph<E> {
+    type Vertex = u32;
+
+    fn with_vertex<R, F: FnOnce(&u32) -> R>(&self, vertex: VertexId, visit: F) -> Option<R> {
+        vertex_of(vertex)
+            .filter(|&v| (v as usize) < DenseGraph::vertex_count(self))
+            .map(|v| visit(&v))
+    }
+}
+
+impl<E> EdgeWeights for DenseGraph<E> {
+    type Edge = E;
+
+    fn with_edge<R, F: FnOnce(&E) -> R>(&self, edge: EdgeId, visit: F) -> Option<R> {
+        let (from, to) = edge;
+        self.get_edge((vertex_of(from)?, vertex_of(to)?)).map(visit)
+    }
+}
+
+impl<E> Neighbors for DenseGraph<E> {
+
+    fn visit_out<F: FnMut(VertexId, &E)>(&self, vertex: VertexId, mut visit: F) {
+        if let Some(vertex) = vertex_of(vertex) {
+            for (to, weight) in self.adj_out(vertex) {
+                visit(vertex_id(*to), weight);
+            }
+        }
+    }
+
+    fn visit_in<F: FnMut(VertexId, &E)>(&self, vertex: VertexId, mut visit: F) {
+        if let Some(vertex) = vertex_of(vertex) {
+            for (from, weight) in self.adj_in(vertex) {
+                visit(vertex_id(from), weight);
+            }
+        }
+    }
+}
+
+/// The traits can't return I/O errors, so the methods of the
+/// [`DiskGraph`] impls panic if the database can't be read.
+#[cfg(feature = "disk")]
+const DISK_READ: &str = "disk graph can't be read";
+
+#[cfg(feature = "disk")]
+impl<V: Display + FromStr + Clone, E: Display + FromStr + Clone> GraphBase for DiskGraph<V, E> {
+
+    fn vertex_count(&self) -> usize {
+        DiskGraph::vertex_count(self)
+    }
+
+    fn edge_count(&self) -> usize {
+        DiskGraph::edge_count(self)
+    }
+
+    fn contains_vertex(&self, vertex: VertexId) -> bool {
+        DiskGraph::contains_vertex(self, vertex).expect(DISK_READ)
+    }
+
+    fn visit_vertices<F: FnMut(VertexId)>(&self, mut visit: F) {
+        for id in self.vertex_ids() {
+            visit(id.expect(DISK_READ));
+        }
+    }
+}
+
+#[cfg(feature = "disk")]
+impl<V: Display + FromStr + Clone, E: Display + FromStr + Clone> VertexData for DiskGraph<V, E> {
+    type Vertex = V;
+
+    fn with_vertex<R, F: FnOnce(&V) -> R>(&self, vertex: VertexId, visit: F) -> Option<R> {
+        self.get_vertex(vertex).expect(DISK_READ).map(|data| visit(&data))
+    }
+}
+
+#[cfg(feature = "disk")]
+impl<V: Display + FromStr + Clone, E: Display + FromStr + Clone> EdgeWeights for DiskGraph<V, E> {
+    type Edge = E;
+
+    fn with_edge<R, F: FnOnce(&E) -> R>(&self, edge: EdgeId, visit: F) -> Option<R> {
+        self.get_edge(edge).expect(DISK_READ).map(|weight| visit(&weight))
+    }
+}
+
+#[cfg(feature = "disk")]
+impl<V: Display + FromStr + Clone, E: Display + FromStr + Clone> Neighbors for DiskGraph<V, E> {
+
+    fn visit_out<F: FnMut(VertexId, &E)>(&self, vertex: VertexId, mut visit: F) {
+        if let Some(targets) = self.adj_out(vertex).expect(DISK_READ) {
+            for (to, weight) in targets {
+                visit(to, &weight);
+            }
+        }
+    }
+
+    fn visit_in<F: FnMut(VertexId, &E)>(&self, vertex: VertexId, mut visit: F) {
+        if let Some(sources) = self.adj_in(vertex).expect(DISK_READ) {
+            for (from, weight) in sources {
+                visit(from, &weight);
+            }
+        }
+    }
+}
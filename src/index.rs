@@ -0,0 +1,297 @@
+
+use super::{Graph, GraphError, VertexId, EdgeId};
+use std::any::Any;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+use std::collections::{
+    BTreeMap,
+    HashMap,
+    HashSet,
+};
+
+/// An index over some projection of the vertex data.
+trait VertexIndex<V> {
+    fn insert(&mut self, id: VertexId, vertex: &V);
+    fn remove(&mut self, id: VertexId, vertex: &V);
+    /// Vertices whose key equals `key`, `None` if `key` has the wrong type.
+    fn lookup(&self, key: &dyn Any) -> Option<Vec<VertexId>>;
+    /// Vertices whose key is in the range, `None` if the index is not
+    /// ordered or the bounds have the wrong type.
+    fn range(&self, bounds: &dyn Any) -> Option<Vec<VertexId>>;
+}
+
+struct HashIndex<V, K> {
+    key: Box<dyn Fn(&V) -> K>,
+    map: HashMap<K, HashSet<VertexId>>,
+}
+
+struct OrderedIndex<V, K> {
+    key: Box<dyn Fn(&V) -> K>,
+    map: BTreeMap<K, HashSet<VertexId>>,
+}
+
+impl<V, K: Eq + Hash + 'static> VertexIndex<V> for HashIndex<V, K> {
+    fn insert(&mut self, id: VertexId, vertex: &V) {
+        self.map.entry((self.key)(vertex)).or_default().insert(id);
+    }
+
+    fn remove(&mut self, id: VertexId, vertex: &V) {
+        let key = (self.key)(vertex);
+        if let Some(set) = self.map.get_mut(&key) {
+            set.remove(&id);
+            if set.is_empty() {
+                self.map.remove(&key);
+            }
+        }
+    }
+
+    fn lookup(&self, key: &dyn Any) -> Option<Vec<VertexId>> {
+        let key = key.downcast_ref::<K>()?;
+        Some(self.map.get(key).map_or_else(Vec::new, |set| set.iter().copied().collect()))
+    }
+
+    fn range(&self, _: &dyn Any) -> Option<Vec<VertexId>> {
+        None
+    }
+}
+
+impl<V, K: Ord + 'static> VertexIndex<V> for OrderedIndex<V, K> {
+    fn insert(&mut self, id: VertexId, vertex: &V) {
+        self.map.entry((self.key)(vertex)).or_default().insert(id);
+    }
+
+    fn remove(&mut self, id: VertexId, vertex: &V) {
+        let key = (self.key)(vertex);
+        if let Some(set) = self.map.get_mut(&key) {
+            set.remove(&id);
+            if set.is_empty() {
+                self.map.remove(&key);
+            }
+        }
+    }
+
+    fn lookup(&self, key: &dyn Any) -> Option<Vec<VertexId>> {
+        let key = key.downcast_ref::<K>()?;
+        Some(self.map.get(key).map_or_else(Vec::new, |set| set.iter().copied().collect()))
+    }
+
+    fn range(&self, bounds: &dyn Any) -> Option<Vec<VertexId>> {
+        let bounds = bounds.downcast_ref::<(Bound<K>, Bound<K>)>()?;
+        let bounds = (bounds.0.as_ref(), bounds.1.as_ref());
+        Some(self.map.range::<K, _>(bounds).flat_map(|(_, set)| set.iter().copied()).collect())
+    }
+}
+
+/// Names an index of an [`IndexedGraph`] and remembers its key type,
+/// so lookups are checked at compile time.
+#[derive(Debug)]
+pub struct IndexHandle<K> {
+    name: String,
+    key: PhantomData<fn() -> K>,
+}
+
+impl<K> IndexHandle<K> {
+    /// Name of the index.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<K> Clone for IndexHandle<K> {
+    fn clone(&self) -> Self {
+        IndexHandle {
+            name: self.name.clone(),
+            key: PhantomData,
+        }
+    }
+}
+
+/// [`Graph`] with secondary indices over the vertex data.
+///
+/// An index is built from a closure projecting the vertex data to a key,
+/// for example a field of a struct. All the mutations go through this
+/// wrapper, so the indices always stay up to date, and lookups by key
+/// don't need to scan all the vertices.
+///
+/// ```
+/// use graph::index::IndexedGraph;
+///
+/// let mut graph = IndexedGraph::<(String, u32), ()>::new();
+/// let name = graph.create_hash_index("name", |v: &(String, u32)| v.0.clone());
+/// let age = graph.create_ordered_index("age", |v: &(String, u32)| v.1);
+///
+/// let ann = graph.add_vertex((String::from("Ann"), 31));
+/// graph.add_vertex((String::from("Bob"), 25));
+///
+/// assert_eq!(graph.find_vertices_where(&name, &String::from("Ann")), Some(vec![ann]));
+/// assert_eq!(graph.find_vertices_in_range(&age, 30..), Some(vec![ann]));
+/// ```
+pub struct IndexedGraph<V, E> {
+    graph: Graph<V, E>,
+    indices: HashMap<String, Box<dyn VertexIndex<V>>>,
+}
+
+impl<V: std::fmt::Debug + 'static, E> IndexedGraph<V, E> {
+
+    pub fn new() -> Self {
+        IndexedGraph::from_graph(Graph::new())
+    }
+
+    /// Wraps an existing graph, with no indices.
+    pub fn from_graph(graph: Graph<V, E>) -> Self {
+        IndexedGraph {
+            graph,
+            indices: HashMap::new(),
+        }
+    }
+
+    /// The underlying graph.
+    pub fn graph(&self) -> &Graph<V, E> {
+        &self.graph
+    }
+
+    /// Consumes the wrapper, dropping the indices.
+    pub fn into_graph(self) -> Graph<V, E> {
+        self.graph
+    }
+
+    /// Creates a hash index named `name`, answering equality lookups
+    /// on the key. Replaces any index with the same name.
+    /// Time complexity: O(V)
+    pub fn create_hash_index<K, F>(&mut self, name: &str, key: F) -> IndexHandle<K>
+    where
+        K: Eq + Hash + 'static,
+        F: Fn(&V) -> K + 'static,
+    {
+        let index = HashIndex {
+            key: Box::new(key),
+            map: HashMap::new(),
+        };
+        self.insert_index(name, Box::new(index))
+    }
+
+    /// Creates an ordered index named `name`, answering both equality
+    /// and range lookups on the key. Replaces any index with the same name.
+    /// Time complexity: O(V log V)
+    pub fn create_ordered_index<K, F>(&mut self, name: &str, key: F) -> IndexHandle<K>
+    where
+        K: Ord + 'static,
+        F: Fn(&V) -> K + 'static,
+    {
+        let index = OrderedIndex {
+            key: Box::new(key),
+            map: BTreeMap::new(),
+        };
+        self.insert_index(name, Box::new(index))
+    }
+
+    fn insert_index<K>(&mut self, name: &str, mut index: Box<dyn VertexIndex<V>>) -> IndexHandle<K> {
+        for (id, vertex) in self.graph.vertices() {
+            index.insert(id, vertex);
+        }
+        self.indices.insert(name.to_string(), index);
+        IndexHandle {
+            name: name.to_string(),
+            key: PhantomData,
+        }
+    }
+
+    /// Removes an index. Returns false if there was no such index.
+    pub fn drop_index(&mut self, name: &str) -> bool {
+        self.indices.remove(name).is_some()
+    }
+
+    /// Names of the indices.
+    pub fn index_names(&self) -> impl Iterator<Item = &str> {
+        self.indices.keys().map(|name| name.as_str())
+    }
+
+    /// Vertices whose key in the index is equal to `key`.
+    /// Handles find their index by name, so after an index is replaced
+    /// the old handles read the new one if its key has the same type.
+    /// Returns `None` if the index was dropped, or replaced by one with
+    /// another key type.
+    ///
+    /// ```
+    /// use graph::index::IndexedGraph;
+    ///
+    /// let mut graph = IndexedGraph::<(String, u32), ()>::new();
+    /// let ann = graph.add_vertex((String::from("Ann"), 31));
+    /// let key = graph.create_hash_index("key", |v: &(String, u32)| v.1);
+    ///
+    /// graph.create_hash_index("key", |v: &(String, u32)| v.1 + 1);
+    /// assert_eq!(graph.find_vertices_where(&key, &32), Some(vec![ann]));
+    ///
+    /// graph.create_hash_index("key", |v: &(String, u32)| v.0.clone());
+    /// assert_eq!(graph.find_vertices_where(&key, &32), None);
+    /// ```
+    pub fn find_vertices_where<K: 'static>(&self, index: &IndexHandle<K>, key: &K) -> Option<Vec<VertexId>> {
+        self.indices.get(&index.name)?.lookup(key)
+    }
+
+    /// Vertices whose key in the ordered index is in `range`, in key order.
+    /// Returns `None` if the index is not ordered, was dropped, or was
+    /// replaced by one with another key type, see
+    /// [`IndexedGraph::find_vertices_where`].
+    pub fn find_vertices_in_range<K: Clone + 'static, R: RangeBounds<K>>(&self, index: &IndexHandle<K>, range: R) -> Option<Vec<VertexId>> {
+        let bounds = (range.start_bound().cloned(), range.end_bound().cloned());
+        self.indices.get(&index.name)?.range(&bounds)
+    }
+
+    /// Adds a vertex to the graph and to every index.
+    pub fn add_vertex(&mut self, vertex: V) -> VertexId {
+        let id = self.graph.add_vertex(vertex);
+        let vertex = self.graph.get_vertex(id).unwrap();
+        for index in self.indices.values_mut() {
+            index.insert(id, vertex);
+        }
+        id
+    }
+
+    /// Removes a vertex from the graph and from every index.
+    pub fn remove_vertex(&mut self, vertex: VertexId) -> bool {
+        let data = match self.graph.get_vertex(vertex) {
+            Some(data) => data,
+            None => return false,
+        };
+        for index in self.indices.values_mut() {
+            index.remove(vertex, data);
+        }
+        self.graph.remove_vertex(vertex);
+        true
+    }
+
+    /// Changes the data of a vertex, updating the indices.
+    /// Returns false if the vertex is not in the graph.
+    pub fn update_vertex<F: FnOnce(&mut V)>(&mut self, vertex: VertexId, update: F) -> bool {
+        let data = match self.graph.arena.get_mut(vertex) {
+            Some(data) => data,
+            None => return false,
+        };
+        for index in self.indices.values_mut() {
+            index.remove(vertex, data);
+        }
+        update(data);
+        for index in self.indices.values_mut() {
+            index.insert(vertex, data);
+        }
+        true
+    }
+
+    /// Adds an edge, or modifies the existing one, see [`Graph::add_edge`].
+    pub fn add_edge(&mut self, edge: EdgeId, weight: E) -> Result<Option<E>, GraphError> {
+        self.graph.add_edge(edge, weight)
+    }
+
+    /// Removes an edge.
+    pub fn remove_edge(&mut self, edge: EdgeId) {
+        self.graph.remove_edge(edge);
+    }
+}
+
+impl<V: std::fmt::Debug + 'static, E> Default for IndexedGraph<V, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,81 @@
+//! Adjacency lists, one vertex per line.
+//!
+//! ```text
+//! # comment
+//! 1: 2 3
+//! 2: 3
+//! 3:
+//! ```
+//!
+//! Every line is `<vertex>: <neighbor>*`, listing the targets of the
+//! outbound edges of the vertex. A vertex may appear on several lines,
+//! and neighbors don't need a line of their own. Names are separated by
+//! whitespace and can't contain `:`. Blank lines and lines starting
+//! with `#` are skipped.
+
+use super::Error;
+use crate::GraphMap;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+/// Reads a graph in the adjacency-list format. The format has no
+/// weights, so the edges hold `()`.
+///
+/// ```
+/// use graph::io::read_adjacency_list;
+///
+/// let graph = read_adjacency_list::<u32, _>("1: 2 3\n2: 3\n4:\n".as_bytes()).unwrap();
+///
+/// assert_eq!(graph.vertex_count(), 4);
+/// assert_eq!(graph.edge_count(), 3);
+/// assert!(graph.contains_edge((2, 3)));
+/// ```
+pub fn read_adjacency_list<V, R>(reader: R) -> Result<GraphMap<V, ()>, Error>
+where
+    V: FromStr + Eq + Hash + Clone + std::fmt::Debug,
+    R: BufRead,
+{
+    let mut graph = GraphMap::new();
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let number = number + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let (vertex, neighbors) = trimmed.split_once(':')
+            .ok_or_else(|| Error::parse(number, "expected `<vertex>: <neighbors>`"))?;
+        let parse = |name: &str| {
+            name.parse::<V>()
+                .map_err(|_| Error::parse(number, format!("invalid vertex `{}`", name)))
+        };
+
+        let vertex = parse(vertex.trim())?;
+        graph.add_vertex(vertex.clone());
+        for neighbor in neighbors.split_whitespace() {
+            graph.add_edge((vertex.clone(), parse(neighbor)?), ());
+        }
+    }
+    Ok(graph)
+}
+
+/// Writes the graph in the format read by [`read_adjacency_list`],
+/// one line per vertex. Edge data is not written.
+pub fn write_adjacency_list<V, E, W>(writer: &mut W, graph: &GraphMap<V, E>) -> std::io::Result<()>
+where
+    V: Display + Eq + Hash + Clone + std::fmt::Debug,
+    W: Write,
+{
+    for vertex in graph.vertices() {
+        write!(writer, "{}:", vertex)?;
+        for (neighbor, _) in graph.adj_out(vertex.clone()).unwrap() {
+            write!(writer, " {}", neighbor)?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,262 @@
+//! Compact binary format, much faster to load than the text formats.
+//!
+//! All numbers are little endian. The structure is stored like a
+//! compressed sparse row matrix: the targets of every vertex's outbound
+//! edges are contiguous, and `offsets[i]..offsets[i + 1]` are those of
+//! vertex `i`. Vertex and edge data are written with [`Display`] and
+//! read with [`FromStr`], as length prefixed UTF-8 strings.
+//!
+//! ```text
+//! header   = magic:8 version:u32 flags:u32 vertices:u64 edges:u64
+//! offsets  = u64 * (vertices + 1)
+//! targets  = u32 * edges
+//! data     = string * vertices, string * edges (in target order)
+//! string   = length:u32 bytes
+//! ```
+//!
+//! Files with a newer version than [`VERSION`] are refused. `flags` is
+//! reserved and written as zero.
+//!
+//! Vertices are written in arena order, and reading adds them in the same
+//! order, so the `i`th vertex gets the `i`th new id.
+
+use crate::{Graph, GraphMap, Map, VertexId};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::io::{self, BufRead, Read, Write};
+use std::str::FromStr;
+
+const MAGIC: &[u8; 8] = b"GRAPHRS\0";
+
+/// Version of the format written by this module.
+pub const VERSION: u32 = 1;
+
+fn invalid<S: Into<String>>(message: S) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn write_string<W: Write, T: Display>(writer: &mut W, value: &T) -> io::Result<()> {
+    let text = value.to_string();
+    let length = u32::try_from(text.len()).map_err(|_| invalid("data longer than 4 GiB"))?;
+    writer.write_all(&length.to_le_bytes())?;
+    writer.write_all(text.as_bytes())
+}
+
+fn read_u32<R: BufRead>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: BufRead>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_string<R: BufRead, T: FromStr>(reader: &mut R, buffer: &mut Vec<u8>) -> io::Result<T> {
+    // Read through `take`, so a bogus length costs no more memory
+    // than the bytes actually in the file.
+    let length = read_u32(reader)?;
+    buffer.clear();
+    reader.by_ref().take(length as u64).read_to_end(buffer)?;
+    if buffer.len() != length as usize {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "string cut short"));
+    }
+    std::str::from_utf8(buffer)
+        .ok()
+        .and_then(|text| text.parse().ok())
+        .ok_or_else(|| invalid(format!("can't parse `{}`", String::from_utf8_lossy(buffer))))
+}
+
+/// Writes the graph in the binary format. Fails if it has more
+/// than `u32::MAX` vertices.
+pub fn write_graph<W, V, E>(writer: &mut W, graph: &Graph<V, E>) -> io::Result<()>
+where
+    W: Write,
+    V: std::fmt::Debug + Display,
+    E: Display,
+{
+    let ids: Vec<VertexId> = graph.vertices().map(|(id, _)| id).collect();
+    if u32::try_from(ids.len()).is_err() {
+        return Err(invalid("more than u32::MAX vertices"));
+    }
+    let position: HashMap<VertexId, u32> = ids.iter()
+        .enumerate()
+        .map(|(i, &id)| (id, i as u32))
+        .collect();
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&0u32.to_le_bytes())?;
+    writer.write_all(&(ids.len() as u64).to_le_bytes())?;
+    writer.write_all(&(graph.edge_count() as u64).to_le_bytes())?;
+
+    let mut rows: Vec<Vec<(u32, &E)>> = Vec::with_capacity(ids.len());
+    let mut offset = 0u64;
+    writer.write_all(&offset.to_le_bytes())?;
+    for &id in ids.iter() {
+        let mut row: Vec<(u32, &E)> = graph.adj_out(id).unwrap()
+            .map(|(to, weight)| (position[&to], weight))
+            .collect();
+        row.sort_unstable_by_key(|&(to, _)| to);
+        offset += row.len() as u64;
+        writer.write_all(&offset.to_le_bytes())?;
+        rows.push(row);
+    }
+    for row in rows.iter() {
+        for &(to, _) in row {
+            writer.write_all(&to.to_le_bytes())?;
+        }
+    }
+
+    for (_, vertex) in graph.vertices() {
+        write_string(writer, vertex)?;
+    }
+    for row in rows.iter() {
+        for &(_, weight) in row {
+            write_string(writer, weight)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads a graph in the binary format, checking the header
+/// and the structure.
+///
+/// ```
+/// use graph::Graph;
+/// use graph::io::binary::{read_graph, write_graph};
+///
+/// let mut graph = Graph::<String, f64>::new();
+/// let a = graph.add_vertex("a".to_string());
+/// let b = graph.add_vertex("b".to_string());
+/// graph.add_edge((a, b), 0.5).unwrap();
+///
+/// let mut bytes = Vec::new();
+/// write_graph(&mut bytes, &graph).unwrap();
+/// let loaded: Graph<String, f64> = read_graph(&bytes[..]).unwrap();
+/// let ids: Vec<_> = loaded.vertices().map(|(id, _)| id).collect();
+/// assert_eq!(loaded.get_vertex(ids[1]).map(String::as_str), Some("b"));
+/// assert_eq!(loaded.get_edge((ids[0], ids[1])), Some(&0.5));
+///
+/// bytes[8] = 99;
+/// assert!(read_graph::<_, String, f64>(&bytes[..]).is_err());
+/// ```
+pub fn read_graph<R, V, E>(mut reader: R) -> io::Result<Graph<V, E>>
+where
+    R: BufRead,
+    V: std::fmt::Debug + FromStr,
+    E: FromStr,
+{
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(invalid("not a binary graph file"));
+    }
+    let version = read_u32(&mut reader)?;
+    if version > VERSION {
+        return Err(invalid(format!("unsupported version {}", version)));
+    }
+    read_u32(&mut reader)?;
+    let vertices = read_u64(&mut reader)?;
+    let edges = read_u64(&mut reader)?;
+    if vertices > u32::MAX as u64 {
+        return Err(invalid("more than u32::MAX vertices"));
+    }
+    let (vertices, edges) = (vertices as usize, edges as usize);
+
+    // The counts are not trusted until the data is there, so the
+    // first allocations are bounded.
+    let bound = 1 << 20;
+    let mut offsets = Vec::with_capacity(vertices.min(bound) + 1);
+    for _ in 0..=vertices {
+        offsets.push(read_u64(&mut reader)? as usize);
+    }
+    if offsets[0] != 0 || offsets[vertices] != edges || offsets.windows(2).any(|w| w[0] > w[1]) {
+        return Err(invalid("malformed offsets"));
+    }
+    let mut targets = Vec::with_capacity(edges.min(bound));
+    for _ in 0..edges {
+        let target = read_u32(&mut reader)? as usize;
+        if target >= vertices {
+            return Err(invalid(format!("edge to missing vertex {}", target)));
+        }
+        targets.push(target);
+    }
+
+    let mut graph = Graph::with_capacity(vertices, edges);
+    let mut buffer = Vec::new();
+    let mut ids = Vec::with_capacity(vertices);
+    for _ in 0..vertices {
+        ids.push(graph.add_vertex(read_string(&mut reader, &mut buffer)?));
+    }
+    for from in 0..vertices {
+        for &to in targets[offsets[from]..offsets[from + 1]].iter() {
+            let weight = read_string(&mut reader, &mut buffer)?;
+            graph.insert_edge((ids[from], ids[to]), weight);
+        }
+    }
+    Ok(graph)
+}
+
+/// Writes the graph in the binary format, see [`write_graph`].
+pub fn write_graph_map<W, V, E>(writer: &mut W, graph: &GraphMap<V, E>) -> io::Result<()>
+where
+    W: Write,
+    V: Eq + Hash + Clone + std::fmt::Debug + Display,
+    E: Display,
+{
+    write_graph(writer, &graph.graph)
+}
+
+/// Reads a graph in the binary format, see [`read_graph`].
+/// Fails if two vertices are the same.
+pub fn read_graph_map<R, V, E>(reader: R) -> io::Result<GraphMap<V, E>>
+where
+    R: BufRead,
+    V: Eq + Hash + Clone + std::fmt::Debug + FromStr,
+    E: FromStr,
+{
+    let graph: Graph<V, E> = read_graph(reader)?;
+    let mut map = Map::with_capacity_and_hasher(graph.vertex_count(), Default::default());
+    for (id, vertex) in graph.vertices() {
+        if map.insert(vertex.clone(), id).is_some() {
+            return Err(invalid(format!("duplicate vertex {:?}", vertex)));
+        }
+    }
+    Ok(GraphMap {
+        graph,
+        map,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_graph, MAGIC, VERSION};
+    use crate::Graph;
+    use std::io;
+
+    #[test]
+    fn huge_string_length_is_not_allocated() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&1u64.to_le_bytes());
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        bytes.extend_from_slice(&[0; 16]);
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        bytes.extend_from_slice(b"abc");
+
+        let error = read_graph::<_, String, u32>(&bytes[..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+
+        // The same file with the right length loads.
+        let end = bytes.len() - 7;
+        bytes[end..end + 4].copy_from_slice(&3u32.to_le_bytes());
+        let graph: Graph<String, u32> = read_graph(&bytes[..]).unwrap();
+        assert_eq!(graph.vertices().map(|(_, v)| v.as_str()).collect::<Vec<_>>(), vec!["abc"]);
+    }
+}
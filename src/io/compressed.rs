@@ -0,0 +1,181 @@
+//! Compressed graph files, read and written like plain ones.
+//!
+//! [`open`] recognizes gzip and zstd streams by their first bytes, so a
+//! reader of any format can load a compressed file unchanged. [`create`]
+//! compresses by the file extension, `.gz` or `.zst`. Gzip needs the
+//! `gzip` feature and zstd the `zstd` feature, without them these files
+//! fail with an error naming the feature.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Compression of a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+
+    /// Compression named by the extension of `path`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    /// Compression of the stream starting with `head`.
+    fn detect(head: &[u8]) -> Self {
+        if head.starts_with(GZIP_MAGIC) {
+            Compression::Gzip
+        } else if head.starts_with(ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+}
+
+#[cfg(not(all(feature = "gzip", feature = "zstd")))]
+fn unsupported(feature: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, format!("compressed files need the `{}` feature", feature))
+}
+
+/// Decompresses `reader` if it starts like a gzip or zstd stream,
+/// otherwise reads it as it is.
+pub fn reader<R: BufRead + 'static>(mut reader: R) -> io::Result<Box<dyn BufRead>> {
+    match Compression::detect(reader.fill_buf()?) {
+        Compression::None => Ok(Box::new(reader)),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Ok(Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader)))),
+        #[cfg(not(feature = "gzip"))]
+        Compression::Gzip => Err(unsupported("gzip")),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Ok(Box::new(BufReader::new(zstd::Decoder::with_buffer(reader)?))),
+        #[cfg(not(feature = "zstd"))]
+        Compression::Zstd => Err(unsupported("zstd")),
+    }
+}
+
+/// Opens a file for reading, decompressing it if needed, see [`reader`].
+pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Box<dyn BufRead>> {
+    reader(BufReader::new(File::open(path)?))
+}
+
+/// Creates a file, compressed by its extension, see [`Compression::from_path`].
+pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Writer<File>> {
+    Writer::new(File::create(&path)?, Compression::from_path(&path))
+}
+
+enum Inner<W: Write> {
+    Plain(BufWriter<W>),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::write::GzEncoder<BufWriter<W>>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Encoder<'static, BufWriter<W>>),
+}
+
+/// Buffered writer compressing what it writes. [`Writer::finish`] must
+/// be called at the end, to write the end of the compressed stream.
+pub struct Writer<W: Write> {
+    inner: Inner<W>,
+}
+
+impl<W: Write> Writer<W> {
+
+    pub fn new(writer: W, compression: Compression) -> io::Result<Self> {
+        let writer = BufWriter::new(writer);
+        let inner = match compression {
+            Compression::None => Inner::Plain(writer),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => Inner::Gzip(flate2::write::GzEncoder::new(writer, flate2::Compression::default())),
+            #[cfg(not(feature = "gzip"))]
+            Compression::Gzip => return Err(unsupported("gzip")),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Inner::Zstd(zstd::Encoder::new(writer, 0)?),
+            #[cfg(not(feature = "zstd"))]
+            Compression::Zstd => return Err(unsupported("zstd")),
+        };
+        Ok(Writer {
+            inner,
+        })
+    }
+
+    /// Ends the compressed stream and flushes everything, returning
+    /// the underlying writer.
+    #[allow(clippy::infallible_destructuring_match)]
+    pub fn finish(self) -> io::Result<W> {
+        let writer = match self.inner {
+            Inner::Plain(writer) => writer,
+            #[cfg(feature = "gzip")]
+            Inner::Gzip(encoder) => encoder.finish()?,
+            #[cfg(feature = "zstd")]
+            Inner::Zstd(encoder) => encoder.finish()?,
+        };
+        writer.into_inner().map_err(|err| err.into_error())
+    }
+}
+
+impl<W: Write> Write for Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.inner {
+            Inner::Plain(writer) => writer.write(buf),
+            #[cfg(feature = "gzip")]
+            Inner::Gzip(encoder) => encoder.write(buf),
+            #[cfg(feature = "zstd")]
+            Inner::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.inner {
+            Inner::Plain(writer) => writer.flush(),
+            #[cfg(feature = "gzip")]
+            Inner::Gzip(encoder) => encoder.flush(),
+            #[cfg(feature = "zstd")]
+            Inner::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{reader, Compression, Writer};
+    use crate::io::simple_text::{read_graph, write_graph};
+    use crate::GraphMap;
+    use std::io::Cursor;
+
+    #[test]
+    fn round_trip_in_every_available_compression() {
+        let graph = GraphMap::<u32, u32>::from_edges(vec![((0, 1), 3), ((1, 2), 4)]);
+        let mut compressions = vec![Compression::None];
+        if cfg!(feature = "gzip") {
+            compressions.push(Compression::Gzip);
+        }
+        if cfg!(feature = "zstd") {
+            compressions.push(Compression::Zstd);
+        }
+        for &compression in compressions.iter() {
+            let mut writer = Writer::new(Vec::new(), compression).unwrap();
+            write_graph(&mut writer, &graph, false).unwrap();
+            let bytes = writer.finish().unwrap();
+            assert_eq!(Compression::detect(&bytes), compression);
+
+            let read = read_graph::<_, u32>(reader(Cursor::new(bytes)).unwrap(), false).unwrap();
+            assert_eq!(read.edge_count(), 2);
+            assert_eq!(read.get_edge((1, 2)), Some(&4));
+        }
+
+        assert_eq!(Compression::from_path("graph.txt.gz"), Compression::Gzip);
+        assert_eq!(Compression::from_path("graph.bin.zst"), Compression::Zstd);
+        assert_eq!(Compression::from_path("graph.txt"), Compression::None);
+    }
+}
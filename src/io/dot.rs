@@ -0,0 +1,160 @@
+//! Export to the Graphviz DOT language, for looking at graphs with
+//! `dot -Tsvg` or any other Graphviz tool.
+
+use crate::layout::Layout;
+use crate::{Graph, GraphMap, VertexId};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Closure making the label of a vertex or an edge.
+pub type Label<'a, T> = Box<dyn Fn(&T) -> String + 'a>;
+
+/// Options of [`to_dot`]. Labels are made by closures, so any vertex
+/// and edge data can be shown.
+pub struct DotConfig<'a, V, E> {
+    /// Name of the graph in the output.
+    pub name: String,
+    /// Write a `digraph` with `->` edges, or a `graph` with `--` edges.
+    /// Undirected output writes a pair of opposite edges once.
+    pub directed: bool,
+    /// Label of every vertex.
+    pub vertex_label: Label<'a, V>,
+    /// Label of every edge, none if `None`.
+    pub edge_label: Option<Label<'a, E>>,
+    /// Positions of the vertices, from one of the layouts of
+    /// [`crate::layout`], written as pinned `pos` attributes that
+    /// `neato` and `fdp` keep. Vertices missing from it get none.
+    pub positions: Option<&'a Layout>,
+}
+
+impl<'a, V: std::fmt::Debug, E> Default for DotConfig<'a, V, E> {
+    /// Directed, with vertices labelled by their [`Debug`](std::fmt::Debug)
+    /// form and no edge labels.
+    fn default() -> Self {
+        DotConfig {
+            name: String::from("G"),
+            directed: true,
+            vertex_label: Box::new(|v| format!("{:?}", v)),
+            edge_label: None,
+            positions: None,
+        }
+    }
+}
+
+/// Quotes a DOT identifier.
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// DOT text of the graph. Vertices are named `n0`, `n1` and so on in
+/// arena order, and edges are sorted by those numbers.
+///
+/// ```
+/// use graph::Graph;
+/// use graph::io::dot::{to_dot, DotConfig};
+///
+/// let mut graph = Graph::<&str, u32>::new();
+/// let a = graph.add_vertex("a");
+/// let b = graph.add_vertex("b");
+/// graph.add_edge((a, b), 3).unwrap();
+///
+/// let config = DotConfig {
+///     vertex_label: Box::new(|v: &&str| v.to_uppercase()),
+///     edge_label: Some(Box::new(|e: &u32| e.to_string())),
+///     ..DotConfig::default()
+/// };
+/// assert_eq!(
+///     to_dot(&graph, &config),
+///     "digraph \"G\" {\n    n0 [label=\"A\"];\n    n1 [label=\"B\"];\n    n0 -> n1 [label=\"3\"];\n}\n",
+/// );
+/// ```
+pub fn to_dot<V: std::fmt::Debug, E>(graph: &Graph<V, E>, config: &DotConfig<V, E>) -> String {
+    let (keyword, arrow) = if config.directed { ("digraph", "->") } else { ("graph", "--") };
+    let mut out = format!("{} {} {{\n", keyword, quote(&config.name));
+
+    let mut position: HashMap<VertexId, usize> = HashMap::with_capacity(graph.vertex_count());
+    for (i, (id, data)) in graph.vertices().enumerate() {
+        position.insert(id, i);
+        out += &format!("    n{} [label={}", i, quote(&(config.vertex_label)(data)));
+        if let Some(&(x, y)) = config.positions.and_then(|positions| positions.get(&id)) {
+            out += &format!(", pos=\"{},{}!\"", x, y);
+        }
+        out += "];\n";
+    }
+
+    let mut edges: Vec<(usize, usize, &E)> = graph.edges()
+        .filter(|(&(from, to), _)| {
+            config.directed || position[&from] <= position[&to] || graph.get_edge((to, from)).is_none()
+        })
+        .map(|(&(from, to), data)| (position[&from], position[&to], data))
+        .collect();
+    edges.sort_unstable_by_key(|&(from, to, _)| (from, to));
+
+    for (from, to, data) in edges {
+        out += &format!("    n{} {} n{}", from, arrow, to);
+        if let Some(label) = &config.edge_label {
+            out += &format!(" [label={}]", quote(&label(data)));
+        }
+        out += ";\n";
+    }
+    out += "}\n";
+    out
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// DOT text of the graph with the default [`DotConfig`].
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&DotConfig::default())
+    }
+
+    /// DOT text of the graph, see [`to_dot`].
+    pub fn to_dot_with(&self, config: &DotConfig<V, E>) -> String {
+        to_dot(&self.graph, config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_dot, DotConfig};
+    use crate::layout::Layout;
+    use crate::Graph;
+
+    #[test]
+    fn positions_are_pinned() {
+        let mut graph = Graph::<&str, ()>::new();
+        let a = graph.add_vertex("a");
+        let b = graph.add_vertex("b");
+        graph.add_vertex("c");
+        graph.add_edge((a, b), ()).unwrap();
+
+        let mut layout = Layout::new();
+        layout.insert(a, (0.0, 1.5));
+        layout.insert(b, (-2.0, 0.25));
+        let config = DotConfig {
+            positions: Some(&layout),
+            ..DotConfig::default()
+        };
+        let dot = to_dot(&graph, &config);
+        assert!(dot.contains("n0 [label=\"\\\"a\\\"\", pos=\"0,1.5!\"];\n"));
+        assert!(dot.contains("n1 [label=\"\\\"b\\\"\", pos=\"-2,0.25!\"];\n"));
+        assert!(dot.contains("n2 [label=\"\\\"c\\\"\"];\n"));
+
+        let grid = to_dot(&graph, &DotConfig {
+            positions: Some(&graph.grid_layout(2.0)),
+            ..DotConfig::default()
+        });
+        assert_eq!(grid.matches("pos=").count(), 3);
+    }
+}
@@ -0,0 +1,174 @@
+//! Edge lists with configurable separators and optional weights.
+//!
+//! ```text
+//! # comment
+//! 1 2 0.5
+//! 2 3
+//! 4
+//! ```
+//!
+//! Every line is `<from> <to> [weight]`, a line with a single vertex adds
+//! it alone. The fields are separated by [`EdgeListConfig::separator`],
+//! any whitespace by default, and trimmed. Blank lines and comment lines
+//! are skipped.
+
+use super::Error;
+use crate::GraphMap;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+/// Options of [`read_edge_list`] and [`write_edge_list`].
+#[derive(Clone, Debug)]
+pub struct EdgeListConfig<E> {
+    /// Character between fields, or `None` for any amount of whitespace.
+    pub separator: Option<char>,
+    /// Lines starting with this character are skipped.
+    pub comment: Option<char>,
+    /// When false, every edge read is added in both directions, and a
+    /// pair of opposite edges with the same weight is written once.
+    pub directed: bool,
+    /// Weight of the edges without one. `None` makes weights required.
+    pub default_weight: Option<E>,
+}
+
+impl<E> Default for EdgeListConfig<E> {
+    /// Whitespace separated, `#` comments, directed, weights required.
+    fn default() -> Self {
+        EdgeListConfig {
+            separator: None,
+            comment: Some('#'),
+            directed: true,
+            default_weight: None,
+        }
+    }
+}
+
+impl<E> EdgeListConfig<E> {
+    fn split<'a>(&self, line: &'a str) -> Vec<&'a str> {
+        match self.separator {
+            Some(separator) => line.split(separator).map(str::trim).collect(),
+            None => line.split_whitespace().collect(),
+        }
+    }
+}
+
+/// Reads an edge list, see the [module](self) documentation.
+///
+/// ```
+/// use graph::io::{read_edge_list, EdgeListConfig};
+///
+/// let config = EdgeListConfig {
+///     separator: Some(','),
+///     directed: false,
+///     default_weight: Some(1),
+///     ..EdgeListConfig::default()
+/// };
+/// let graph = read_edge_list::<u32, u32, _>("1,2,5\n2,3\n4\n".as_bytes(), &config).unwrap();
+/// assert_eq!(graph.vertex_count(), 4);
+/// assert_eq!(graph.get_edge((2, 1)), Some(&5));
+/// assert_eq!(graph.get_edge((3, 2)), Some(&1));
+///
+/// let err = read_edge_list::<u32, u32, _>("1 2\n".as_bytes(), &EdgeListConfig::default()).unwrap_err();
+/// assert_eq!(err.to_string(), "line 1: missing weight");
+/// ```
+pub fn read_edge_list<V, E, R>(reader: R, config: &EdgeListConfig<E>) -> Result<GraphMap<V, E>, Error>
+where
+    V: FromStr + Eq + Hash + Clone + std::fmt::Debug,
+    E: FromStr + Clone,
+    R: BufRead,
+{
+    let mut graph = GraphMap::new();
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let number = number + 1;
+        let trimmed = line.trim();
+        let comment = match config.comment {
+            Some(comment) => trimmed.starts_with(comment),
+            None => false,
+        };
+        if trimmed.is_empty() || comment {
+            continue;
+        }
+
+        let parse = |name: &str| {
+            name.parse::<V>()
+                .map_err(|_| Error::parse(number, format!("invalid vertex `{}`", name)))
+        };
+        let (from, to, weight) = match config.split(trimmed).as_slice() {
+            [vertex] => {
+                graph.add_vertex(parse(vertex)?);
+                continue;
+            }
+            [from, to] => match &config.default_weight {
+                Some(weight) => (parse(from)?, parse(to)?, weight.clone()),
+                None => return Err(Error::parse(number, "missing weight")),
+            },
+            [from, to, weight] => {
+                let weight = weight.parse::<E>()
+                    .map_err(|_| Error::parse(number, format!("invalid weight `{}`", weight)))?;
+                (parse(from)?, parse(to)?, weight)
+            }
+            _ => return Err(Error::parse(number, "expected `<from> <to> [weight]`")),
+        };
+        if !config.directed {
+            graph.add_edge((to.clone(), from.clone()), weight.clone());
+        }
+        graph.add_edge((from, to), weight);
+    }
+    Ok(graph)
+}
+
+/// Writes the graph in the format read by [`read_edge_list`], always
+/// with the weights. Isolated vertices get a line of their own.
+pub fn write_edge_list<V, E, W>(writer: &mut W, graph: &GraphMap<V, E>, config: &EdgeListConfig<E>) -> std::io::Result<()>
+where
+    V: Display + Eq + Hash + Clone + std::fmt::Debug,
+    E: Display + PartialEq,
+    W: Write,
+{
+    let separator = config.separator.unwrap_or(' ');
+    let mut written: HashSet<(&V, &V)> = HashSet::new();
+    for ((from, to), weight) in graph.edges() {
+        if !config.directed {
+            if written.contains(&(to, from)) && graph.get_edge((to.clone(), from.clone())) == Some(weight) {
+                continue;
+            }
+            written.insert((from, to));
+        }
+        writeln!(writer, "{}{}{}{}{}", from, separator, to, separator, weight)?;
+    }
+    for vertex in graph.vertices() {
+        if graph.indegree(vertex.clone()) == 0 && graph.outdegree(vertex.clone()) == 0 {
+            writeln!(writer, "{}", vertex)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undirected_round_trip_writes_pairs_once() {
+        let config = EdgeListConfig {
+            separator: Some('\t'),
+            directed: false,
+            ..EdgeListConfig::default()
+        };
+        let graph = read_edge_list::<String, f64, _>("a\tb\t1.5\nb\tc\t2\n# skipped\n\nd\n".as_bytes(), &config).unwrap();
+        assert_eq!(graph.edge_count(), 4);
+
+        let mut out = Vec::new();
+        write_edge_list(&mut out, &graph, &config).unwrap();
+        assert_eq!(String::from_utf8(out.clone()).unwrap().lines().count(), 3);
+        let read = read_edge_list::<String, f64, _>(out.as_slice(), &config).unwrap();
+        assert_eq!(read.vertex_count(), 4);
+        assert_eq!(read.edge_count(), 4);
+        assert_eq!(read.get_edge(("c".to_string(), "b".to_string())), Some(&2.0));
+    }
+}
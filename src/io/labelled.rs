@@ -0,0 +1,153 @@
+//! Edge lists whose vertices are named by arbitrary strings.
+//!
+//! ```text
+//! # comment
+//! alice bob 2.5
+//! bob "New York"
+//! carol
+//! ```
+//!
+//! Every line is `<from> <to> [weight]`, with the weight defaulting to 1.
+//! A line with a single name adds an isolated vertex. Names containing
+//! whitespace, quotes or a leading `#` are written between double quotes,
+//! with `\"` and `\\` as escapes. Blank lines and lines starting with `#`
+//! are skipped.
+
+use super::Error;
+use crate::GraphMap;
+use std::io::{BufRead, Write};
+
+/// Reads an edge list with string vertex names and optional weights.
+///
+/// ```
+/// use graph::io::read_labelled_edge_list;
+///
+/// let text = "alice bob 2.5\nbob \"New York\"\ncarol\n";
+/// let graph = read_labelled_edge_list(text.as_bytes()).unwrap();
+///
+/// assert_eq!(graph.vertex_count(), 4);
+/// assert_eq!(graph.get_edge(("alice".into(), "bob".into())), Some(&2.5));
+/// assert_eq!(graph.get_edge(("bob".into(), "New York".into())), Some(&1.0));
+/// ```
+pub fn read_labelled_edge_list<R: BufRead>(reader: R) -> Result<GraphMap<String, f64>, Error> {
+    let mut graph = GraphMap::new();
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let number = number + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let tokens = tokenize(trimmed).ok_or_else(|| Error::parse(number, "unterminated quote"))?;
+        match tokens.as_slice() {
+            [vertex] => graph.add_vertex(vertex.clone()),
+            [from, to] => graph.add_edge((from.clone(), to.clone()), 1.0),
+            [from, to, weight] => {
+                let weight = weight.parse::<f64>()
+                    .map_err(|_| Error::parse(number, format!("invalid weight `{}`", weight)))?;
+                graph.add_edge((from.clone(), to.clone()), weight);
+            }
+            _ => return Err(Error::parse(number, "expected `<from> <to> [weight]`")),
+        }
+    }
+    Ok(graph)
+}
+
+/// Writes the graph in the format read by [`read_labelled_edge_list`].
+/// Isolated vertices get a line of their own.
+pub fn write_labelled_edge_list<W: Write>(writer: &mut W, graph: &GraphMap<String, f64>) -> std::io::Result<()> {
+    for ((from, to), weight) in graph.edges() {
+        writeln!(writer, "{} {} {}", quote(from), quote(to), weight)?;
+    }
+    for vertex in graph.vertices() {
+        if graph.indegree(vertex.clone()) == 0 && graph.outdegree(vertex.clone()) == 0 {
+            writeln!(writer, "{}", quote(vertex))?;
+        }
+    }
+    Ok(())
+}
+
+/// Splits a line on whitespace, keeping quoted names together.
+/// Returns `None` on an unterminated quote.
+fn tokenize(line: &str) -> Option<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut token = String::new();
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => token.push(chars.next()?),
+                    c => token.push(c),
+                }
+            }
+            tokens.push(token);
+        } else {
+            let mut token = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+            tokens.push(token);
+        }
+    }
+    Some(tokens)
+}
+
+fn quote(name: &str) -> String {
+    let plain = !name.is_empty()
+        && !name.starts_with('#')
+        && !name.chars().any(|c| c.is_whitespace() || c == '"' || c == '\\');
+    if plain {
+        return name.to_string();
+    }
+    let mut quoted = String::from("\"");
+    for c in name.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_keeps_names_and_isolated_vertices() {
+        let mut graph = GraphMap::<String, f64>::new();
+        graph.add_edge(("a \"b\"".to_string(), "#c".to_string()), 0.5);
+        graph.add_edge(("d".to_string(), "".to_string()), 3.0);
+        graph.add_vertex("lonely one".to_string());
+
+        let mut out = Vec::new();
+        write_labelled_edge_list(&mut out, &graph).unwrap();
+        let read = read_labelled_edge_list(out.as_slice()).unwrap();
+
+        assert_eq!(read.vertex_count(), 5);
+        assert_eq!(read.edge_count(), 2);
+        assert_eq!(read.get_edge(("a \"b\"".to_string(), "#c".to_string())), Some(&0.5));
+        assert_eq!(read.get_edge(("d".to_string(), "".to_string())), Some(&3.0));
+    }
+
+    #[test]
+    fn reports_line_of_malformed_input() {
+        match read_labelled_edge_list("a b\n\na b c\n".as_bytes()) {
+            Err(Error::Parse { line, .. }) => assert_eq!(line, 3),
+            other => panic!("unexpected {:?}", other.map(|g| g.edge_count())),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Sparse matrices in the Matrix Market coordinate format, as the
+//! SuiteSparse collection publishes them.
+//!
+//! ```text
+//! %%MatrixMarket matrix coordinate real symmetric
+//! % comment
+//! 3 3 2
+//! 2 1 0.5
+//! 3 2 1.5
+//! ```
+//!
+//! The banner names the field, `real`, `integer` or `pattern`, and the
+//! symmetry, `general` or `symmetric`. Then comes the size line
+//! `<rows> <columns> <entries>`, and a line `<row> <column> [value]` for
+//! every entry, without a value for `pattern` matrices. The matrix must
+//! be square. Row `i` and column `i` are the vertex `i - 1`, and every
+//! entry is an edge from its row to its column. Only the vertices with
+//! an entry are in the graph, so a size line claiming billions of rows
+//! costs nothing. Symmetric matrices only
+//! store one triangle, the other direction is added for them.
+
+use super::Error;
+use crate::GraphMap;
+use std::io::BufRead;
+use std::str::FromStr;
+
+/// The size line sizes the graph up front, but a wrong one can't make
+/// it reserve more than this many vertices or edges.
+const MAX_RESERVED: usize = 1 << 20;
+
+/// Reads a graph in the Matrix Market format, see the
+/// [module](self) documentation. The entries of `pattern` matrices
+/// get `weight`, the others are parsed as `W`. The indices must be
+/// within the size of the matrix, but rows and columns without entries
+/// don't become vertices.
+///
+/// ```
+/// use graph::io::read_matrix_market;
+///
+/// let text = "%%MatrixMarket matrix coordinate real symmetric\n3 3 2\n2 1 0.5\n3 2 1.5\n";
+/// let graph = read_matrix_market::<f64, _>(text.as_bytes(), 1.0).unwrap();
+/// assert_eq!(graph.vertex_count(), 3);
+/// assert_eq!(graph.get_edge((0, 1)), Some(&0.5));
+/// assert_eq!(graph.get_edge((2, 1)), Some(&1.5));
+/// ```
+pub fn read_matrix_market<W, R>(mut reader: R, weight: W) -> Result<GraphMap<u32, W>, Error>
+where
+    W: FromStr + Clone,
+    R: BufRead,
+{
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let banner = line.to_lowercase();
+    let (pattern, symmetric) = match banner.split_whitespace().collect::<Vec<_>>().as_slice() {
+        ["%%matrixmarket", "matrix", "coordinate", field, symmetry] => {
+            let pattern = match *field {
+                "real" | "integer" => false,
+                "pattern" => true,
+                _ => return Err(Error::parse(1, format!("unsupported field `{}`", field))),
+            };
+            let symmetric = match *symmetry {
+                "general" => false,
+                "symmetric" => true,
+                _ => return Err(Error::parse(1, format!("unsupported symmetry `{}`", symmetry))),
+            };
+            (pattern, symmetric)
+        }
+        _ => return Err(Error::parse(1, "expected `%%MatrixMarket matrix coordinate <field> <symmetry>`")),
+    };
+
+    let mut number = 1;
+    let mut size = None;
+    let mut graph = GraphMap::new();
+    let mut entries = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        number += 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('%') {
+            continue;
+        }
+        let fields: Vec<&str> = trimmed.split_whitespace().collect();
+
+        let (order, expected) = match size {
+            Some(size) => size,
+            None => {
+                let parsed = match fields.as_slice() {
+                    [rows, columns, entries] => match (rows.parse::<u32>(), columns.parse::<u32>(), entries.parse::<usize>()) {
+                        (Ok(rows), Ok(columns), Ok(entries)) => Some((rows, columns, entries)),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                let (rows, columns, entries) = match parsed {
+                    Some(parsed) => parsed,
+                    None => return Err(Error::parse(number, "size line must be `<rows> <columns> <entries>`")),
+                };
+                if rows != columns {
+                    return Err(Error::parse(number, format!("matrix must be square, found {}x{}", rows, columns)));
+                }
+                let edges = if symmetric { entries.saturating_mul(2) } else { entries };
+                // Only the vertices with entries are added, at most two per entry.
+                let vertices = (rows as usize).min(edges.saturating_mul(2));
+                graph = GraphMap::with_capacity(vertices.min(MAX_RESERVED), edges.min(MAX_RESERVED));
+                size = Some((rows, entries));
+                continue;
+            }
+        };
+
+        let vertex = |word: &str| match word.parse::<u32>() {
+            Ok(index) if index >= 1 && index <= order => Ok(index - 1),
+            _ => Err(Error::parse(number, format!("invalid index `{}`", word))),
+        };
+        let (row, column, value) = match (fields.as_slice(), pattern) {
+            ([row, column], true) => (vertex(row)?, vertex(column)?, weight.clone()),
+            ([row, column, value], false) => {
+                let value = value.parse::<W>()
+                    .map_err(|_| Error::parse(number, format!("invalid value `{}`", value)))?;
+                (vertex(row)?, vertex(column)?, value)
+            }
+            (_, true) => return Err(Error::parse(number, "expected `<row> <column>`")),
+            (_, false) => return Err(Error::parse(number, "expected `<row> <column> <value>`")),
+        };
+        if symmetric && row != column {
+            graph.add_edge((column, row), value.clone());
+        }
+        graph.add_edge((row, column), value);
+        entries += 1;
+        if entries > expected {
+            return Err(Error::parse(number, format!("size line says {} entries, found more", expected)));
+        }
+    }
+
+    match size {
+        None => Err(Error::parse(number, "missing size line")),
+        Some((_, expected)) if entries != expected => {
+            Err(Error::parse(number, format!("size line says {} entries, found {}", expected, entries)))
+        }
+        Some(_) => Ok(graph),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_matrix_market;
+
+    #[test]
+    fn pattern_matrices_use_the_given_weight() {
+        let text = "%%MatrixMarket matrix coordinate pattern general\n% a comment\n4 4 3\n1 2\n2 3\n3 3\n";
+        let graph = read_matrix_market(text.as_bytes(), 7u32).unwrap();
+        assert_eq!(graph.vertex_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.get_edge((2, 2)), Some(&7));
+        assert!(!graph.contains_edge((1, 0)));
+
+        let rectangular = "%%MatrixMarket matrix coordinate real general\n2 3 1\n1 1 1.0\n";
+        let err = read_matrix_market(rectangular.as_bytes(), 1.0).unwrap_err();
+        assert_eq!(err.to_string(), "line 2: matrix must be square, found 2x3");
+
+        let short = "%%MatrixMarket matrix coordinate real general\n2 2 2\n1 2 1.0\n";
+        assert!(read_matrix_market(short.as_bytes(), 1.0).is_err());
+        let out_of_range = "%%MatrixMarket matrix coordinate real general\n2 2 1\n3 1 1.0\n";
+        assert!(read_matrix_market(out_of_range.as_bytes(), 1.0).is_err());
+
+        let huge = "%%MatrixMarket matrix coordinate real general\n4294967295 4294967295 1\n4294967295 1 1.0\n";
+        let graph = read_matrix_market(huge.as_bytes(), 1.0).unwrap();
+        assert_eq!(graph.vertex_count(), 2);
+        assert_eq!(graph.get_edge((4294967294, 0)), Some(&1.0));
+    }
+}
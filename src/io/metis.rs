@@ -0,0 +1,163 @@
+//! Undirected graphs in the METIS format, used by the graph
+//! partitioning tools and the DIMACS challenges.
+//!
+//! ```text
+//! % comment
+//! 3 2 1
+//! 2 5
+//! 1 5 3 2
+//! 2 2
+//! ```
+//!
+//! The header is `<vertices> <edges> [fmt [ncon]]`, then the line `i`
+//! lists the neighbours of the vertex `i`, counting from 1, so a vertex
+//! without neighbours has an empty line. `fmt` is up to three digits:
+//! the last one means every neighbour is followed by the weight of the
+//! edge, the middle one that lines start with `ncon` vertex weights,
+//! and the first one that they start with a vertex size. Vertex sizes
+//! and weights are skipped. Lines starting with `%` are comments.
+
+use super::Error;
+use crate::GraphMap;
+use std::io::BufRead;
+use std::str::FromStr;
+
+/// The header sizes the graph up front, but a wrong one can't make it
+/// reserve more than this many vertices or edges.
+const MAX_RESERVED: usize = 1 << 20;
+
+/// Reads a graph in the METIS format, see the [module](self)
+/// documentation. The vertex `i` of the file is `i - 1` in the graph,
+/// and every edge is added in both directions, as the file lists it
+/// on the lines of both ends. Vertices are added as their lines are
+/// read, so a header claiming more vertices than the file has fails
+/// without allocating them. Without edge weights in the file, the
+/// edges get `weight`.
+///
+/// ```
+/// use graph::io::read_metis;
+///
+/// let graph = read_metis::<u32, _>("3 2 1\n2 5\n1 5 3 2\n2 2\n".as_bytes(), 1).unwrap();
+/// assert_eq!(graph.vertex_count(), 3);
+/// assert_eq!(graph.edge_count(), 4);
+/// assert_eq!(graph.get_edge((2, 1)), Some(&2));
+/// ```
+pub fn read_metis<W, R>(mut reader: R, weight: W) -> Result<GraphMap<u32, W>, Error>
+where
+    W: FromStr + Clone,
+    R: BufRead,
+{
+    let mut line = String::new();
+    let mut number = 0;
+    let header = loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(Error::parse(number + 1, "missing header"));
+        }
+        number += 1;
+        if !line.trim_start().starts_with('%') {
+            break line.split_whitespace().map(str::to_string).collect::<Vec<_>>();
+        }
+    };
+    let usage = "header must be `<vertices> <edges> [fmt [ncon]]`";
+    let (vertices, edges, fmt, ncon) = match header.as_slice() {
+        [vertices, edges, rest @ ..] if rest.len() <= 2 => {
+            let fmt = rest.first().map(String::as_str).unwrap_or("0");
+            if fmt.len() > 3 || fmt.chars().any(|c| c != '0' && c != '1') {
+                return Err(Error::parse(number, format!("invalid fmt `{}`", fmt)));
+            }
+            let fmt = format!("{:0>3}", fmt);
+            let ncon = match rest.get(1).map(|n| n.parse::<usize>()) {
+                None => 1,
+                Some(Ok(ncon)) => ncon,
+                Some(Err(_)) => return Err(Error::parse(number, usage)),
+            };
+            match (vertices.parse::<u32>(), edges.parse::<usize>()) {
+                (Ok(vertices), Ok(edges)) => (vertices, edges, fmt, ncon),
+                _ => return Err(Error::parse(number, usage)),
+            }
+        }
+        _ => return Err(Error::parse(number, usage)),
+    };
+    let has_size = &fmt[0..1] == "1";
+    let has_vertex_weights = &fmt[1..2] == "1";
+    let has_edge_weights = &fmt[2..3] == "1";
+    let skipped = has_size as usize + if has_vertex_weights { ncon } else { 0 };
+
+    let mut graph = GraphMap::with_capacity((vertices as usize).min(MAX_RESERVED), edges.saturating_mul(2).min(MAX_RESERVED));
+    let mut from = 0;
+    let mut entries = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        number += 1;
+        let trimmed = line.trim();
+        if trimmed.starts_with('%') {
+            continue;
+        }
+        if from == vertices {
+            if trimmed.is_empty() {
+                continue;
+            }
+            return Err(Error::parse(number, format!("header says {} vertices, found more", vertices)));
+        }
+
+        graph.add_vertex(from);
+        let mut fields = trimmed.split_whitespace();
+        for _ in 0..skipped {
+            if fields.next().is_none() {
+                return Err(Error::parse(number, "missing vertex weight"));
+            }
+        }
+        while let Some(word) = fields.next() {
+            let to = match word.parse::<u32>() {
+                Ok(to) if to >= 1 && to <= vertices => to - 1,
+                _ => return Err(Error::parse(number, format!("invalid vertex `{}`", word))),
+            };
+            let weight = if has_edge_weights {
+                let word = fields.next()
+                    .ok_or_else(|| Error::parse(number, format!("missing weight of the edge to {}", word)))?;
+                word.parse::<W>()
+                    .map_err(|_| Error::parse(number, format!("invalid weight `{}`", word)))?
+            } else {
+                weight.clone()
+            };
+            graph.add_edge((from, to), weight);
+            entries += 1;
+        }
+        from += 1;
+    }
+
+    if from != vertices {
+        return Err(Error::parse(number, format!("header says {} vertices, found {}", vertices, from)));
+    }
+    if entries != edges.saturating_mul(2) {
+        return Err(Error::parse(number, format!("header says {} edges, found {} neighbours instead of twice that", edges, entries)));
+    }
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_metis;
+
+    #[test]
+    fn vertex_weights_are_skipped() {
+        // fmt 11 with two vertex weights, and an isolated vertex 4
+        let text = "% weighted\n4 2 11 2\n5 1 2 3\n0 0 1 3 3 4\n1 1 2 4\n7 7\n";
+        let graph = read_metis::<u32, _>(text.as_bytes(), 0).unwrap();
+        assert_eq!(graph.vertex_count(), 4);
+        assert_eq!(graph.edge_count(), 4);
+        assert_eq!(graph.get_edge((0, 1)), Some(&3));
+        assert_eq!(graph.get_edge((2, 1)), Some(&4));
+        assert_eq!(graph.outdegree(3), 0);
+
+        let unweighted = read_metis("3 1\n2\n1\n\n".as_bytes(), 1u32).unwrap();
+        assert_eq!(unweighted.edge_count(), 2);
+        assert!(read_metis("3 2\n2\n1\n\n".as_bytes(), 1u32).is_err());
+        assert!(read_metis("2 1\n2\n1\n1\n".as_bytes(), 1u32).is_err());
+        assert!(read_metis("4294967295 0\n\n".as_bytes(), 1u32).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+//! Reading and writing graphs in text and binary formats.
+
+mod labelled;
+mod adjacency;
+mod stable;
+mod edge_list;
+mod snap;
+mod matrix_market;
+mod metis;
+pub mod simple_text;
+pub mod dot;
+pub mod binary;
+pub mod compressed;
+
+pub use labelled::{read_labelled_edge_list, write_labelled_edge_list};
+pub use adjacency::{read_adjacency_list, write_adjacency_list};
+pub use stable::StableIds;
+pub use edge_list::{read_edge_list, write_edge_list, EdgeListConfig};
+pub use snap::read_snap;
+pub use matrix_market::read_matrix_market;
+pub use metis::read_metis;
+
+use std::fmt;
+
+/// Error while reading a graph.
+#[derive(Debug)]
+pub enum Error {
+    /// The underlying reader or writer failed.
+    Io(std::io::Error),
+    /// The input is malformed. Lines are numbered from 1.
+    Parse { line: usize, message: String },
+}
+
+impl Error {
+    pub(crate) fn parse<S: Into<String>>(line: usize, message: S) -> Self {
+        Error::Parse {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
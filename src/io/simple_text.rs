@@ -0,0 +1,243 @@
+//! The plain text format of the shell, for graphs with `u32` vertices.
+//!
+//! ```text
+//! file     = header { line }
+//! header   = vertices " " edges "\n"
+//! line     = edge | isolated | empty
+//! edge     = from " " to " " weight "\n"
+//! isolated = vertex "\n"
+//! ```
+//!
+//! `vertices` and `edges` are the number of vertices and of edge lines.
+//! Vertices are the ones named by the edge and isolated lines. Files
+//! written without isolated lines name fewer vertices than the header
+//! says, and then every vertex from `0` to `vertices - 1` is added too.
+//! Fields are separated by any amount of whitespace.
+//!
+//! Undirected graphs store every edge in both directions, and write each
+//! pair of edges with the same weight once.
+
+use super::Error;
+use crate::GraphMap;
+use std::fmt::Display;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+/// Number of lines between two calls of the progress callback of
+/// [`read_graph_with_progress`].
+pub const PROGRESS_INTERVAL: usize = 1 << 16;
+
+/// The header sizes the graph up front, but a wrong header can't make
+/// it reserve more than this many vertices or edges.
+const MAX_RESERVED: usize = 1 << 26;
+
+/// How far a read got, given to the callback of [`read_graph_with_progress`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// Lines read, the header included.
+    pub lines: usize,
+    /// Bytes read, line breaks included.
+    pub bytes: u64,
+    /// Edge lines read.
+    pub edges: usize,
+    /// Edge lines announced by the header.
+    pub expected_edges: usize,
+}
+
+/// Reads a graph in the simple text format, line by line.
+/// When `undirected`, every edge is added in both directions.
+/// See [`read_graph_with_progress`].
+///
+/// ```
+/// use graph::io::simple_text::read_graph;
+///
+/// let text = "4 2\n0 1 5\n1 2 7\n3\n";
+/// let graph = read_graph::<_, u32>(text.as_bytes(), true).unwrap();
+/// assert_eq!(graph.vertex_count(), 4);
+/// assert_eq!(graph.get_edge((2, 1)), Some(&7));
+///
+/// let err = read_graph::<_, u32>("2 1\n0 x 5\n".as_bytes(), false).unwrap_err();
+/// assert_eq!(err.to_string(), "line 2: invalid vertex `x`");
+/// ```
+pub fn read_graph<R: BufRead, E: FromStr + Clone>(reader: R, undirected: bool) -> Result<GraphMap<u32, E>, Error> {
+    read_graph_with_progress(reader, undirected, |_| ())
+}
+
+/// Reads a graph like [`read_graph`], calling `progress` every
+/// [`PROGRESS_INTERVAL`] lines and once at the end. The input is
+/// streamed through a single line buffer, so only the graph itself
+/// grows with the file, and the graph is sized from the header
+/// before the first edge is added.
+///
+/// ```
+/// use graph::io::simple_text::read_graph_with_progress;
+///
+/// let text = "3 2\n0 1 5\n1 2 7\n";
+/// let mut last = None;
+/// let graph = read_graph_with_progress::<_, u32, _>(text.as_bytes(), false, |p| last = Some(*p)).unwrap();
+/// let last = last.unwrap();
+/// assert_eq!((last.lines, last.bytes, last.edges), (3, text.len() as u64, 2));
+/// assert_eq!(graph.edge_count(), 2);
+/// ```
+// `is_multiple_of` needs Rust 1.87.
+#[allow(clippy::manual_is_multiple_of)]
+pub fn read_graph_with_progress<R, E, F>(mut reader: R, undirected: bool, mut progress: F) -> Result<GraphMap<u32, E>, Error>
+where
+    R: BufRead,
+    E: FromStr + Clone,
+    F: FnMut(&Progress),
+{
+    let mut line = String::new();
+    let mut status = Progress {
+        lines: 0,
+        bytes: 0,
+        edges: 0,
+        expected_edges: 0,
+    };
+
+    let read = reader.read_line(&mut line)?;
+    if read == 0 {
+        return Err(Error::parse(1, "missing header"));
+    }
+    status.lines = 1;
+    status.bytes = read as u64;
+    let mut header = line.split_whitespace();
+    let (vertices, edges) = match (header.next(), header.next(), header.next()) {
+        (Some(vertices), Some(edges), None) => match (vertices.parse::<u32>(), edges.parse::<usize>()) {
+            (Ok(vertices), Ok(edges)) => (vertices, edges),
+            _ => return Err(Error::parse(1, "header must be `<vertices> <edges>`")),
+        },
+        _ => return Err(Error::parse(1, "header must be `<vertices> <edges>`")),
+    };
+    status.expected_edges = edges;
+    let stored_edges = if undirected { edges.saturating_mul(2) } else { edges };
+    let mut graph = GraphMap::with_capacity((vertices as usize).min(MAX_RESERVED), stored_edges.min(MAX_RESERVED));
+
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        if read == 0 {
+            break;
+        }
+        status.lines += 1;
+        status.bytes += read as u64;
+        let number = status.lines;
+        let vertex = |word: &str| {
+            word.parse::<u32>().map_err(|_| Error::parse(number, format!("invalid vertex `{}`", word)))
+        };
+
+        let mut fields = line.split_whitespace();
+        match (fields.next(), fields.next(), fields.next(), fields.next()) {
+            (None, ..) => (),
+            (Some(isolated), None, ..) => {
+                graph.add_vertex(vertex(isolated)?);
+            }
+            (Some(from), Some(to), Some(weight), None) => {
+                let (from, to) = (vertex(from)?, vertex(to)?);
+                let weight: E = weight.parse()
+                    .map_err(|_| Error::parse(number, format!("invalid weight `{}`", weight)))?;
+                if undirected {
+                    graph.add_edge((to, from), weight.clone());
+                }
+                graph.add_edge((from, to), weight);
+                status.edges += 1;
+            }
+            _ => {
+                let found = line.trim_end();
+                return Err(Error::parse(number, format!("expected an edge or a vertex, found `{}`", found)));
+            }
+        }
+        if status.lines % PROGRESS_INTERVAL == 0 {
+            progress(&status);
+        }
+    }
+    progress(&status);
+
+    if status.edges != edges {
+        return Err(Error::parse(1, format!("header says {} edges, found {}", edges, status.edges)));
+    }
+    if graph.vertex_count() < vertices as usize {
+        for vertex in 0..vertices {
+            graph.add_vertex(vertex);
+        }
+    }
+    Ok(graph)
+}
+
+/// Writes a graph in the simple text format, edges sorted by their ends.
+/// When `undirected`, an edge is skipped if the reverse edge has the same
+/// weight and was already written.
+pub fn write_graph<W: Write, E: Display + PartialEq>(writer: &mut W, graph: &GraphMap<u32, E>, undirected: bool) -> std::io::Result<()> {
+    let mut edges: Vec<((u32, u32), &E)> = graph.edges()
+        .map(|((&from, &to), weight)| ((from, to), weight))
+        .filter(|&((from, to), weight)| {
+            !undirected || from <= to || graph.get_edge((to, from)) != Some(weight)
+        })
+        .collect();
+    edges.sort_unstable_by_key(|&(edge, _)| edge);
+
+    let mut isolated: Vec<u32> = graph.vertices()
+        .copied()
+        .filter(|&v| graph.outdegree(v) == 0 && graph.indegree(v) == 0)
+        .collect();
+    isolated.sort_unstable();
+
+    writeln!(writer, "{} {}", graph.vertex_count(), edges.len())?;
+    for ((from, to), weight) in edges {
+        writeln!(writer, "{} {} {}", from, to, weight)?;
+    }
+    for vertex in isolated {
+        writeln!(writer, "{}", vertex)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_graph, read_graph_with_progress, write_graph, PROGRESS_INTERVAL};
+    use crate::GraphMap;
+
+    #[test]
+    fn round_trip_keeps_isolated_vertices() {
+        let mut graph = GraphMap::<u32, u32>::new();
+        graph.add_edge((0, 1), 3);
+        graph.add_edge((1, 0), 3);
+        graph.add_edge((1, 2), 4);
+        graph.add_vertex(7);
+
+        let mut text = Vec::new();
+        write_graph(&mut text, &graph, true).unwrap();
+        assert_eq!(String::from_utf8(text.clone()).unwrap(), "4 2\n0 1 3\n1 2 4\n7\n");
+
+        let read = read_graph::<_, u32>(text.as_slice(), true).unwrap();
+        assert_eq!(read.vertex_count(), 4);
+        assert_eq!(read.edge_count(), 4);
+        assert!(read.vertices().any(|&v| v == 7));
+        assert!(!read.vertices().any(|&v| v == 3));
+    }
+
+    #[test]
+    fn legacy_files_number_vertices_from_zero() {
+        let read = read_graph::<_, u32>("3 1\n0 1 5\n".as_bytes(), false).unwrap();
+        assert_eq!(read.vertex_count(), 3);
+        assert_eq!(read.outdegree(2), 0);
+
+        assert!(read_graph::<_, u32>("3 2\n0 1 5\n".as_bytes(), false).is_err());
+        assert!(read_graph::<_, u32>("3 1\n0 1\n".as_bytes(), false).is_err());
+    }
+
+    #[test]
+    fn progress_is_reported_every_interval() {
+        let lines = 2 * PROGRESS_INTERVAL + 10;
+        let mut text = format!("{} {}\r\n", lines, lines - 1);
+        for i in 1..lines {
+            text += &format!("{} {}  {}\r\n", i - 1, i, i);
+        }
+        let mut reports = Vec::new();
+        let graph = read_graph_with_progress::<_, u32, _>(text.as_bytes(), false, |p| reports.push(*p)).unwrap();
+        assert_eq!(graph.edge_count(), lines - 1);
+        assert_eq!(reports.iter().map(|p| p.lines).collect::<Vec<_>>(), vec![PROGRESS_INTERVAL, 2 * PROGRESS_INTERVAL, lines]);
+        assert_eq!(reports[2].bytes, text.len() as u64);
+        assert_eq!(reports[0].expected_edges, lines - 1);
+    }
+}
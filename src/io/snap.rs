@@ -0,0 +1,109 @@
+//! Edge lists of the Stanford Large Network Dataset Collection (SNAP).
+//!
+//! ```text
+//! # Directed graph (each unordered pair of nodes is saved once): Wiki-Vote.txt
+//! # Nodes: 7115 Edges: 103689
+//! # FromNodeId ToNodeId
+//! 30 1412
+//! 30 3352
+//! ```
+//!
+//! Every line is `<from> <to>`, separated by tabs or spaces, and lines
+//! starting with `#` are comments. Further columns, like the timestamps
+//! of the temporal datasets, are ignored. The `Nodes: .. Edges: ..`
+//! comment, when there is one, sizes the graph up front.
+
+use super::Error;
+use crate::GraphMap;
+use std::io::BufRead;
+
+/// The header comment sizes the graph up front, but a wrong one can't
+/// make it reserve more than this many vertices or edges.
+const MAX_RESERVED: usize = 1 << 26;
+
+/// Vertex and edge counts of a `# Nodes: 7115 Edges: 103689` comment.
+fn counts(comment: &str) -> Option<(usize, usize)> {
+    let mut words = comment.trim_start_matches('#').split_whitespace();
+    let mut vertices = None;
+    let mut edges = None;
+    while let Some(word) = words.next() {
+        match word {
+            "Nodes:" => vertices = words.next().and_then(|n| n.parse().ok()),
+            "Edges:" => edges = words.next().and_then(|n| n.parse().ok()),
+            _ => (),
+        }
+    }
+    Some((vertices?, edges?))
+}
+
+/// Reads a SNAP edge list, see the [module](self) documentation. Every
+/// edge gets `weight`. When `undirected`, every edge is also added in the
+/// opposite direction, for the datasets saving each pair once.
+///
+/// ```
+/// use graph::io::read_snap;
+///
+/// let text = "# Undirected graph: example.txt\n# Nodes: 3 Edges: 2\n# FromNodeId\tToNodeId\n0\t1\n1\t2\n";
+/// let graph = read_snap(text.as_bytes(), true, ()).unwrap();
+/// assert_eq!(graph.vertex_count(), 3);
+/// assert_eq!(graph.edge_count(), 4);
+/// assert!(graph.contains_edge((2, 1)));
+/// ```
+pub fn read_snap<W: Clone, R: BufRead>(mut reader: R, undirected: bool, weight: W) -> Result<GraphMap<u32, W>, Error> {
+    let mut graph = GraphMap::new();
+    let mut line = String::new();
+    let mut number = 0;
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        number += 1;
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            if graph.vertex_count() == 0 {
+                if let Some((vertices, edges)) = counts(trimmed) {
+                    let edges = if undirected { edges.saturating_mul(2) } else { edges };
+                    graph = GraphMap::with_capacity(vertices.min(MAX_RESERVED), edges.min(MAX_RESERVED));
+                }
+            }
+            continue;
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let vertex = |word: &str| {
+            word.parse::<u32>().map_err(|_| Error::parse(number, format!("invalid vertex `{}`", word)))
+        };
+        let mut fields = trimmed.split_whitespace();
+        let (from, to) = match (fields.next(), fields.next()) {
+            (Some(from), Some(to)) => (vertex(from)?, vertex(to)?),
+            _ => return Err(Error::parse(number, "expected `<from> <to>`")),
+        };
+        if undirected {
+            graph.add_edge((to, from), weight.clone());
+        }
+        graph.add_edge((from, to), weight.clone());
+    }
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_snap;
+
+    #[test]
+    fn temporal_columns_are_ignored() {
+        let text = "# Directed graph\n# Nodes: 4 Edges: 3\n1 2 1217567877\n2 3 1217573801\n\n3 1 1217606247\n";
+        let graph = read_snap(text.as_bytes(), false, 1.0).unwrap();
+        assert_eq!(graph.vertex_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.get_edge((3, 1)), Some(&1.0));
+        assert!(!graph.contains_edge((1, 3)));
+
+        let err = read_snap("# comment\n1\n".as_bytes(), false, ()).unwrap_err();
+        assert_eq!(err.to_string(), "line 2: expected `<from> <to>`");
+    }
+}
@@ -0,0 +1,208 @@
+//! Saving graphs with vertex ids that survive a reload.
+//!
+//! [`VertexId`]s depend on the order the vertices were added and removed,
+//! so they change when a graph is saved and loaded again. [`StableIds`]
+//! gives every vertex a `u64` that is written along with the graph and
+//! read back with it, so it can be referenced from other files or
+//! databases.
+//!
+//! ```text
+//! next <first stable id not given yet>
+//! v <stable id> <vertex data>
+//! e <from stable id> <to stable id> <edge data>
+//! ```
+//!
+//! The `next` line keeps the ids of the vertices removed before saving
+//! from being given again after a reload. Without it, the next id is
+//! the one after the largest read.
+//!
+//! Data is written with [`Display`] and read with [`FromStr`],
+//! with backslashes and line breaks escaped.
+
+use super::Error;
+use crate::{Graph, VertexId};
+use crate::wal::{escape, unescape};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+/// Two-way mapping between vertex ids and stable `u64` ids.
+/// Stable ids are never reused.
+#[derive(Clone, Debug, Default)]
+pub struct StableIds {
+    stable: HashMap<VertexId, u64>,
+    vertices: HashMap<u64, VertexId>,
+    next: u64,
+}
+
+impl StableIds {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Numbers the vertices of the graph from 0, in arena order.
+    pub fn assign<V: std::fmt::Debug, E>(graph: &Graph<V, E>) -> Self {
+        let mut ids = StableIds::new();
+        for (id, _) in graph.vertices() {
+            ids.insert(id);
+        }
+        ids
+    }
+
+    /// Stable id of the vertex, assigning the next free one if it has none.
+    pub fn insert(&mut self, vertex: VertexId) -> u64 {
+        if let Some(&stable) = self.stable.get(&vertex) {
+            return stable;
+        }
+        let stable = self.next;
+        self.next = self.next.checked_add(1).expect("stable ids exhausted");
+        self.stable.insert(vertex, stable);
+        self.vertices.insert(stable, vertex);
+        stable
+    }
+
+    /// Forgets a removed vertex, returning its stable id.
+    pub fn remove(&mut self, vertex: VertexId) -> Option<u64> {
+        let stable = self.stable.remove(&vertex)?;
+        self.vertices.remove(&stable);
+        Some(stable)
+    }
+
+    /// Stable id of the vertex.
+    pub fn get(&self, vertex: VertexId) -> Option<u64> {
+        self.stable.get(&vertex).copied()
+    }
+
+    /// Vertex with the stable id.
+    pub fn vertex(&self, stable: u64) -> Option<VertexId> {
+        self.vertices.get(&stable).copied()
+    }
+
+    /// Number of mapped vertices.
+    pub fn len(&self) -> usize {
+        self.stable.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stable.is_empty()
+    }
+}
+
+impl<V: std::fmt::Debug + Display, E: Display> Graph<V, E> {
+
+    /// Writes the graph with the stable id of every vertex.
+    /// Vertices without one are given the next free id.
+    pub fn write_stable<W: Write>(&self, writer: &mut W, ids: &mut StableIds) -> std::io::Result<()> {
+        let mut vertices: Vec<(u64, &V)> = self.vertices()
+            .map(|(id, data)| (ids.insert(id), data))
+            .collect();
+        vertices.sort_unstable_by_key(|&(stable, _)| stable);
+        writeln!(writer, "next {}", ids.next)?;
+        for (stable, data) in vertices {
+            writeln!(writer, "v {} {}", stable, escape(data))?;
+        }
+        for (&(from, to), data) in self.edges() {
+            writeln!(writer, "e {} {} {}", ids.insert(from), ids.insert(to), escape(data))?;
+        }
+        Ok(())
+    }
+}
+
+impl<V: std::fmt::Debug + FromStr, E: FromStr> Graph<V, E> {
+
+    /// Reads a graph written by [`Graph::write_stable`], along with the
+    /// mapping from the stable ids to the new vertex ids.
+    ///
+    /// ```
+    /// use graph::Graph;
+    /// use graph::io::StableIds;
+    ///
+    /// let mut graph = Graph::<String, u32>::new();
+    /// let a = graph.add_vertex("a".to_string());
+    /// let b = graph.add_vertex("b".to_string());
+    /// graph.add_edge((b, a), 4).unwrap();
+    /// graph.remove_vertex(a);
+    /// let c = graph.add_vertex("c".to_string());
+    /// graph.add_edge((b, c), 5).unwrap();
+    ///
+    /// let mut ids = StableIds::new();
+    /// ids.insert(b);
+    /// ids.insert(c);
+    /// let removed = ids.insert(graph.add_vertex("d".to_string()));
+    /// graph.remove_vertex(ids.vertex(removed).unwrap());
+    /// ids.remove(ids.vertex(removed).unwrap());
+    /// let mut saved = Vec::new();
+    /// graph.write_stable(&mut saved, &mut ids).unwrap();
+    ///
+    /// let (loaded, loaded_ids) = Graph::<String, u32>::read_stable(saved.as_slice()).unwrap();
+    /// let b2 = loaded_ids.vertex(ids.get(b).unwrap()).unwrap();
+    /// let c2 = loaded_ids.vertex(ids.get(c).unwrap()).unwrap();
+    /// assert_eq!(loaded.get_vertex(c2).map(|s| s.as_str()), Some("c"));
+    /// assert_eq!(loaded.get_edge((b2, c2)), Some(&5));
+    ///
+    /// // The id of the removed vertex is not given again.
+    /// let (mut loaded, mut loaded_ids) = (loaded, loaded_ids);
+    /// let e = loaded.add_vertex("e".to_string());
+    /// assert_eq!(loaded_ids.insert(e), removed + 1);
+    ///
+    /// let last = format!("v {} x", u64::MAX);
+    /// match Graph::<String, u32>::read_stable(last.as_bytes()) {
+    ///     Err(graph::io::Error::Parse { line, .. }) => assert_eq!(line, 1),
+    ///     _ => panic!("the last id must be refused"),
+    /// }
+    /// ```
+    pub fn read_stable<R: BufRead>(reader: R) -> Result<(Self, StableIds), Error> {
+        let mut graph = Graph::new();
+        let mut ids = StableIds::new();
+
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let number = number + 1;
+            let invalid = || Error::parse(number, format!("invalid entry `{}`", line));
+            let stable = |text: Option<&str>| -> Result<u64, Error> {
+                text.and_then(|t| t.parse().ok()).ok_or_else(invalid)
+            };
+
+            let mut parts = line.splitn(2, ' ');
+            match parts.next() {
+                Some("next") => ids.next = ids.next.max(stable(parts.next())?),
+                Some("v") => {
+                    let mut parts = parts.next().ok_or_else(invalid)?.splitn(2, ' ');
+                    let id = stable(parts.next())?;
+                    let data = parts.next()
+                        .and_then(unescape)
+                        .and_then(|d| d.parse().ok())
+                        .ok_or_else(invalid)?;
+                    if ids.vertex(id).is_some() {
+                        return Err(Error::parse(number, format!("duplicate vertex {}", id)));
+                    }
+                    let vertex = graph.add_vertex(data);
+                    ids.stable.insert(vertex, id);
+                    ids.vertices.insert(id, vertex);
+                    let next = id.checked_add(1).ok_or_else(|| {
+                        Error::parse(number, format!("stable id {} leaves none for new vertices", id))
+                    })?;
+                    ids.next = ids.next.max(next);
+                }
+                Some("e") => {
+                    let mut parts = parts.next().ok_or_else(invalid)?.splitn(3, ' ');
+                    let from = stable(parts.next())?;
+                    let to = stable(parts.next())?;
+                    let data = parts.next()
+                        .and_then(unescape)
+                        .and_then(|d| d.parse().ok())
+                        .ok_or_else(invalid)?;
+                    let unknown = |id| Error::parse(number, format!("unknown vertex {}", id));
+                    let from = ids.vertex(from).ok_or_else(|| unknown(from))?;
+                    let to = ids.vertex(to).ok_or_else(|| unknown(to))?;
+                    graph.insert_edge((from, to), data);
+                }
+                _ if line.trim().is_empty() => (),
+                _ => return Err(invalid()),
+            }
+        }
+        Ok((graph, ids))
+    }
+}
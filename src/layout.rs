@@ -0,0 +1,544 @@
+//! Positions in the plane for drawing a graph: force-directed,
+//! circular, grid, spectral and layered layouts.
+
+use super::{Graph, GraphMap, VertexId};
+use super::algorithms::Indexed;
+use rand::Rng;
+use std::hash::Hash;
+use std::collections::HashMap;
+
+/// Coordinates of a vertex in the plane.
+pub type Position = (f64, f64);
+
+/// Position of every vertex of a graph.
+pub type Layout = HashMap<VertexId, Position>;
+
+/// Parameters of the force-directed layout.
+#[derive(Clone, Copy, Debug)]
+pub struct ForceDirected {
+    /// Number of iterations to run.
+    pub iterations: usize,
+    /// Width of the drawing area.
+    pub width: f64,
+    /// Height of the drawing area.
+    pub height: f64,
+}
+
+impl Default for ForceDirected {
+    fn default() -> Self {
+        ForceDirected {
+            iterations: 100,
+            width: 1.0,
+            height: 1.0,
+        }
+    }
+}
+
+/// Parameters of the layered (Sugiyama) layout.
+#[derive(Clone, Copy, Debug)]
+pub struct Layered {
+    /// Distance between two consecutive layers.
+    pub layer_spacing: f64,
+    /// Distance between two vertices on the same layer.
+    pub vertex_spacing: f64,
+    /// Number of up and down barycenter sweeps used
+    /// to reduce the edge crossings.
+    pub sweeps: usize,
+}
+
+impl Default for Layered {
+    fn default() -> Self {
+        Layered {
+            layer_spacing: 1.0,
+            vertex_spacing: 1.0,
+            sweeps: 8,
+        }
+    }
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Fruchterman-Reingold force-directed layout.
+    /// Edges are treated as undirected springs and every pair of vertices
+    /// repels each other. Starting positions are drawn from `rng`, so a
+    /// seeded rng always gives the same layout.
+    /// Time complexity: O(iterations * (V^2 + E))
+    pub fn force_directed_layout<R: Rng>(&self, config: &ForceDirected, rng: &mut R) -> Layout {
+        let Indexed { ids, edges, .. } = self.indexed();
+        let n = ids.len();
+        if n == 0 {
+            return Layout::new();
+        }
+
+        let ForceDirected { iterations, width, height } = *config;
+        let k = (width * height / n as f64).sqrt();
+        let mut pos: Vec<Position> = (0..n)
+            .map(|_| (rng.gen::<f64>() * width, rng.gen::<f64>() * height))
+            .collect();
+        let mut temperature = width.max(height) / 10.0;
+        let cooling = temperature / (iterations as f64 + 1.0);
+
+        for _ in 0..iterations {
+            let mut disp = vec![(0.0, 0.0); n];
+
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    let (dx, dy, dist) = delta(pos[i], pos[j]);
+                    let force = k * k / dist;
+                    disp[i].0 += dx / dist * force;
+                    disp[i].1 += dy / dist * force;
+                    disp[j].0 -= dx / dist * force;
+                    disp[j].1 -= dy / dist * force;
+                }
+            }
+
+            for &(i, j) in edges.iter() {
+                let (dx, dy, dist) = delta(pos[i], pos[j]);
+                let force = dist * dist / k;
+                disp[i].0 -= dx / dist * force;
+                disp[i].1 -= dy / dist * force;
+                disp[j].0 += dx / dist * force;
+                disp[j].1 += dy / dist * force;
+            }
+
+            for (p, d) in pos.iter_mut().zip(disp.iter()) {
+                let len = (d.0 * d.0 + d.1 * d.1).sqrt().max(f64::EPSILON);
+                let step = len.min(temperature);
+                p.0 = (p.0 + d.0 / len * step).clamp(0.0, width);
+                p.1 = (p.1 + d.1 / len * step).clamp(0.0, height);
+            }
+
+            temperature -= cooling;
+        }
+
+        ids.into_iter().zip(pos).collect()
+    }
+
+    /// Places the vertices evenly on a circle centered in the origin,
+    /// in arena order.
+    pub fn circular_layout(&self, radius: f64) -> Layout {
+        let n = self.vertex_count() as f64;
+        self.vertices()
+            .enumerate()
+            .map(|(i, (id, _))| {
+                let angle = 2.0 * std::f64::consts::PI * i as f64 / n;
+                (id, (radius * angle.cos(), radius * angle.sin()))
+            })
+            .collect()
+    }
+
+    /// Places the vertices on a square grid, row by row, in arena order.
+    pub fn grid_layout(&self, spacing: f64) -> Layout {
+        let columns = (self.vertex_count() as f64).sqrt().ceil().max(1.0) as usize;
+        self.vertices()
+            .enumerate()
+            .map(|(i, (id, _))| {
+                let x = (i % columns) as f64 * spacing;
+                let y = (i / columns) as f64 * spacing;
+                (id, (x, y))
+            })
+            .collect()
+    }
+
+    /// Spectral layout: the coordinates of each vertex are its entries in
+    /// the eigenvectors of the 2 smallest nonzero eigenvalues of the
+    /// Laplacian matrix, scaled to fit in `[-1, 1]`. A vertex gets 0 for
+    /// a missing eigenvalue, and without edges the layout is circular.
+    /// Edge directions and weights are ignored.
+    ///
+    /// A disconnected graph has a zero eigenvalue per component, which
+    /// are skipped, but each of the other eigenvectors is only nonzero on
+    /// a single component: laying out the components one by one draws
+    /// them better.
+    /// Time complexity: O(V^3)
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// let mut graph = Graph::<u32, ()>::new();
+    /// let ids: Vec<_> = (0..6).map(|i| graph.add_vertex(i)).collect();
+    /// for pair in ids.windows(2) {
+    ///     graph.add_edge((pair[0], pair[1]), ()).unwrap();
+    /// }
+    ///
+    /// // The first coordinate orders the vertices along the path.
+    /// let layout = graph.spectral_layout();
+    /// let x: Vec<f64> = ids.iter().map(|id| layout[id].0).collect();
+    /// let increasing = x.windows(2).all(|w| w[0] < w[1]);
+    /// let decreasing = x.windows(2).all(|w| w[0] > w[1]);
+    /// assert!(increasing || decreasing);
+    /// assert!(x.iter().all(|x| x.abs() <= 1.0));
+    /// ```
+    pub fn spectral_layout(&self) -> Layout {
+        let n = self.vertex_count();
+        if n < 3 {
+            return self.circular_layout(1.0);
+        }
+
+        let (ids, laplacian) = self.to_laplacian_matrix().into_parts();
+        let (values, vectors) = symmetric_eigen(laplacian);
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap());
+
+        let epsilon = 1e-9 * values[order[n - 1]].max(1.0);
+        let mut nonzero = order.into_iter().filter(|&k| values[k] > epsilon);
+        let (x, y) = match (nonzero.next(), nonzero.next()) {
+            (Some(x), y) => (x, y),
+            (None, _) => return self.circular_layout(1.0),
+        };
+        let max = |k: usize| (0..n).map(|i| vectors[i][k].abs()).fold(f64::EPSILON, f64::max);
+        let coordinate = |i: usize, k: Option<usize>| k.map_or(0.0, |k| vectors[i][k] / max(k));
+
+        ids.into_iter()
+            .enumerate()
+            .map(|(i, id)| (id, (coordinate(i, Some(x)), coordinate(i, y))))
+            .collect()
+    }
+
+    /// Layered (Sugiyama) layout, meant for DAGs.
+    /// Edges point downwards: sources are on the first layer (`y = 0`) and
+    /// every edge goes to a later layer. If the graph has cycles, the edges
+    /// closing them are reversed first.
+    ///
+    /// The steps are cycle removal, longest path layering, adding dummy
+    /// vertices on long edges, barycenter crossing minimization and finally
+    /// centering every layer on `x = 0`.
+    pub fn layered_layout(&self, config: &Layered) -> Layout {
+        let Indexed { ids, edges } = self.indexed();
+        let n = ids.len();
+
+        let edges = acyclic_edges(n, edges);
+        let mut layer = longest_path_layers(n, &edges);
+
+        // split edges longer than one layer with dummy vertices
+        let mut up = vec![Vec::new(); n];
+        let mut down = vec![Vec::new(); n];
+        for (from, to) in edges {
+            let mut prev = from;
+            for l in (layer[from] + 1)..layer[to] {
+                let dummy = layer.len();
+                layer.push(l);
+                up.push(vec![prev]);
+                down.push(Vec::new());
+                down[prev].push(dummy);
+                prev = dummy;
+            }
+            down[prev].push(to);
+            up[to].push(prev);
+        }
+
+        let layer_count = layer.iter().map(|&l| l + 1).max().unwrap_or(0);
+        let mut layers = vec![Vec::new(); layer_count];
+        for (v, &l) in layer.iter().enumerate() {
+            layers[l].push(v);
+        }
+
+        let mut position = vec![0; layer.len()];
+        for nodes in layers.iter() {
+            for (i, &v) in nodes.iter().enumerate() {
+                position[v] = i;
+            }
+        }
+
+        for _ in 0..config.sweeps {
+            for nodes in layers.iter_mut().skip(1) {
+                order_by_barycenter(nodes, &up, &mut position);
+            }
+            for nodes in layers.iter_mut().rev().skip(1) {
+                order_by_barycenter(nodes, &down, &mut position);
+            }
+        }
+
+        let mut layout = Layout::new();
+        for (l, nodes) in layers.iter().enumerate() {
+            let offset = (nodes.len() as f64 - 1.0) / 2.0;
+            for (i, &v) in nodes.iter().enumerate() {
+                if v < n {
+                    let x = (i as f64 - offset) * config.vertex_spacing;
+                    let y = l as f64 * config.layer_spacing;
+                    layout.insert(ids[v], (x, y));
+                }
+            }
+        }
+        layout
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// Circular layout, see [`Graph::circular_layout`].
+    pub fn circular_layout(&self, radius: f64) -> HashMap<V, Position> {
+        self.layout_by_vertex(self.graph.circular_layout(radius))
+    }
+
+    /// Grid layout, see [`Graph::grid_layout`].
+    pub fn grid_layout(&self, spacing: f64) -> HashMap<V, Position> {
+        self.layout_by_vertex(self.graph.grid_layout(spacing))
+    }
+
+    /// Spectral layout, see [`Graph::spectral_layout`].
+    pub fn spectral_layout(&self) -> HashMap<V, Position> {
+        self.layout_by_vertex(self.graph.spectral_layout())
+    }
+
+    /// Layered layout, see [`Graph::layered_layout`].
+    pub fn layered_layout(&self, config: &Layered) -> HashMap<V, Position> {
+        self.layout_by_vertex(self.graph.layered_layout(config))
+    }
+
+    /// Fruchterman-Reingold force-directed layout,
+    /// see [`Graph::force_directed_layout`].
+    pub fn force_directed_layout<R: Rng>(&self, config: &ForceDirected, rng: &mut R) -> HashMap<V, Position> {
+        self.layout_by_vertex(self.graph.force_directed_layout(config, rng))
+    }
+
+    /// Rekeys a layout of the inner graph by vertex data.
+    pub(crate) fn layout_by_vertex(&self, layout: Layout) -> HashMap<V, Position> {
+        layout.into_iter()
+            .map(|(id, p)| (self.graph.get_vertex(id).unwrap().clone(), p))
+            .collect()
+    }
+}
+
+/// Difference between two positions and the distance between them,
+/// never exactly 0 so it can be divided by.
+fn delta(a: Position, b: Position) -> (f64, f64, f64) {
+    let dx = a.0 - b.0;
+    let dy = a.1 - b.1;
+    let dist = (dx * dx + dy * dy).sqrt().max(0.01);
+    (dx, dy, dist)
+}
+
+/// Reverses the back edges found by a DFS so the graph becomes acyclic.
+/// Duplicate edges created by the reversal are merged.
+fn acyclic_edges(n: usize, edges: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    let mut adj = vec![Vec::new(); n];
+    for &(from, to) in edges.iter() {
+        adj[from].push(to);
+    }
+
+    // 0 = not visited, 1 = on the stack, 2 = done
+    let mut state = vec![0u8; n];
+    let mut back = std::collections::HashSet::new();
+    for root in 0..n {
+        if state[root] != 0 {
+            continue;
+        }
+        let mut stack = vec![(root, 0)];
+        state[root] = 1;
+        while let Some(&mut (v, ref mut next)) = stack.last_mut() {
+            if *next < adj[v].len() {
+                let w = adj[v][*next];
+                *next += 1;
+                match state[w] {
+                    0 => {
+                        state[w] = 1;
+                        stack.push((w, 0));
+                    }
+                    1 => {
+                        back.insert((v, w));
+                    }
+                    _ => (),
+                }
+            } else {
+                state[v] = 2;
+                stack.pop();
+            }
+        }
+    }
+
+    let mut edges: Vec<(usize, usize)> = edges.into_iter()
+        .map(|(from, to)| if back.contains(&(from, to)) { (to, from) } else { (from, to) })
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+    edges
+}
+
+/// Puts every vertex on the layer after its deepest predecessor.
+fn longest_path_layers(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut adj = vec![Vec::new(); n];
+    let mut indegree = vec![0; n];
+    for &(from, to) in edges.iter() {
+        adj[from].push(to);
+        indegree[to] += 1;
+    }
+
+    let mut layer = vec![0; n];
+    let mut queue: std::collections::VecDeque<usize> = (0..n).filter(|&v| indegree[v] == 0).collect();
+    while let Some(v) = queue.pop_front() {
+        for &w in adj[v].iter() {
+            layer[w] = layer[w].max(layer[v] + 1);
+            indegree[w] -= 1;
+            if indegree[w] == 0 {
+                queue.push_back(w);
+            }
+        }
+    }
+    layer
+}
+
+/// Sorts a layer by the mean position of each vertex's neighbours
+/// on the adjacent layer. Vertices without neighbours keep their place.
+fn order_by_barycenter(nodes: &mut [usize], neighbours: &[Vec<usize>], position: &mut [usize]) {
+    let mut keyed: Vec<(f64, usize)> = nodes.iter()
+        .map(|&v| {
+            let adj = &neighbours[v];
+            let center = if adj.is_empty() {
+                position[v] as f64
+            } else {
+                adj.iter().map(|&w| position[w] as f64).sum::<f64>() / adj.len() as f64
+            };
+            (center, v)
+        })
+        .collect();
+    keyed.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    for (i, (node, (_, v))) in nodes.iter_mut().zip(keyed).enumerate() {
+        *node = v;
+        position[v] = i;
+    }
+}
+
+/// Eigenvalues and eigenvectors of a symmetric matrix, using the cyclic
+/// Jacobi method. Column `k` of the returned matrix is the eigenvector
+/// of the `k`-th eigenvalue.
+fn symmetric_eigen(mut a: Vec<Vec<f64>>) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = a.len();
+    let mut v = vec![vec![0.0; n]; n];
+    for (i, row) in v.iter_mut().enumerate() {
+        row[i] = 1.0;
+    }
+
+    for _ in 0..100 {
+        let off: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j])
+            .sum();
+        if off < 1e-18 {
+            break;
+        }
+
+        for p in 0..n {
+            for q in (p + 1)..n {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let t = if theta == 0.0 { 1.0 } else { t };
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (head, tail) = a.split_at_mut(q);
+                for (apk, aqk) in head[p].iter_mut().zip(tail[0].iter_mut()) {
+                    let (x, y) = (*apk, *aqk);
+                    *apk = c * x - s * y;
+                    *aqk = s * x + c * y;
+                }
+                for row in v.iter_mut() {
+                    let (vkp, vkq) = (row[p], row[q]);
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+
+    let values = (0..n).map(|i| a[i][i]).collect();
+    (values, v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ForceDirected, Layered};
+    use crate::Graph;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn force_directed_layout_is_seeded() {
+        let mut graph = Graph::<u32, ()>::new();
+        let ids: Vec<_> = (0..12).map(|i| graph.add_vertex(i)).collect();
+        for i in 0..12 {
+            graph.add_edge((ids[i], ids[(i + 1) % 12]), ()).unwrap();
+            graph.add_edge((ids[i], ids[(i * 5) % 12]), ()).unwrap();
+        }
+        let config = ForceDirected {
+            iterations: 50,
+            width: 4.0,
+            height: 3.0,
+        };
+        let first = graph.force_directed_layout(&config, &mut StdRng::seed_from_u64(5));
+        let second = graph.force_directed_layout(&config, &mut StdRng::seed_from_u64(5));
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 12);
+        assert!(first.values().all(|&(x, y)| (0.0..=4.0).contains(&x) && (0.0..=3.0).contains(&y)));
+        assert_ne!(first, graph.force_directed_layout(&config, &mut StdRng::seed_from_u64(6)));
+    }
+
+    #[test]
+    fn layers_follow_the_edges() {
+        let mut graph = Graph::<u32, ()>::new();
+        let ids: Vec<_> = (0..7).map(|i| graph.add_vertex(i)).collect();
+        let edges = [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (0, 4), (5, 6), (6, 4)];
+        for &(a, b) in edges.iter() {
+            graph.add_edge((ids[a], ids[b]), ()).unwrap();
+        }
+        let config = Layered {
+            layer_spacing: 2.0,
+            ..Layered::default()
+        };
+        let layout = graph.layered_layout(&config);
+        assert_eq!(layout.len(), 7);
+        for &(a, b) in edges.iter() {
+            assert!(layout[&ids[a]].1 < layout[&ids[b]].1, "edge {} -> {} goes up", a, b);
+        }
+        // sources on the first layer, the longest path sets the depth
+        assert_eq!(layout[&ids[0]].1, 0.0);
+        assert_eq!(layout[&ids[5]].1, 0.0);
+        assert_eq!(layout[&ids[4]].1, 6.0);
+
+        // A cycle has one edge reversed, the others still go down.
+        graph.add_edge((ids[4], ids[0]), ()).unwrap();
+        let layout = graph.layered_layout(&config);
+        let down = graph.edges().filter(|&(&(a, b), _)| layout[&a].1 < layout[&b].1).count();
+        assert_eq!(down, graph.edge_count() - 1);
+    }
+
+    #[test]
+    fn spectral_layout_spreads_every_component() {
+        // two paths of three vertices
+        let mut graph = Graph::<u32, ()>::new();
+        let ids: Vec<_> = (0..6).map(|i| graph.add_vertex(i)).collect();
+        for &(a, b) in [(0, 1), (1, 2), (3, 4), (4, 5)].iter() {
+            graph.add_edge((ids[a], ids[b]), ()).unwrap();
+        }
+        let layout = graph.spectral_layout();
+        assert_eq!(layout.len(), 6);
+        assert!(layout.values().all(|&(x, y)| x.is_finite() && y.is_finite()));
+
+        // The eigenvectors of the zero eigenvalues are constant on each
+        // path, and would put it on a single point.
+        let mut points: Vec<(i64, i64)> = layout.values()
+            .map(|&(x, y)| ((x * 1e6).round() as i64, (y * 1e6).round() as i64))
+            .collect();
+        points.sort_unstable();
+        points.dedup();
+        assert!(points.len() > 2);
+        let middle = layout[&ids[1]];
+        assert_ne!(layout[&ids[0]], middle);
+        assert_ne!(layout[&ids[2]], middle);
+
+        let empty = Graph::<u32, ()>::new();
+        assert!(empty.spectral_layout().is_empty());
+        let mut isolated = Graph::<u32, ()>::new();
+        for i in 0..4 {
+            isolated.add_vertex(i);
+        }
+        assert!(isolated.spectral_layout().values().all(|&(x, y)| (x * x + y * y - 1.0).abs() < 1e-9));
+    }
+}
@@ -7,37 +7,104 @@
 //!
 //! I also implemented [`GraphMap`], which identifies the nodes by
 //! the data they hold, instead of [`VertexId`].
+//!
+//! # Iteration order
+//!
+//! Vertices are iterated in the order of their slots in the arena, the
+//! insertion order as long as none was removed. Edges and adjacency are
+//! iterated in the order of the hash maps, which is random for every run.
+//! With the `deterministic` feature the maps use [`DefaultHashBuilder`]
+//! with fixed keys, so the same operations iterate in the same order on
+//! every run, and so do the algorithms built on the iteration.
+//! Other hashers, faster for integer keys, are plugged in with
+//! [`Graph::with_hasher`] and [`GraphMap::with_hasher`].
 pub mod traversal;
+pub mod tree;
+pub mod temporal;
+pub mod layout;
+pub mod matrix;
+pub mod render;
+pub mod query;
+pub mod algorithms;
+pub mod index;
+pub mod wal;
+pub mod io;
+pub mod generate;
+pub mod sampling;
+pub mod ch;
+pub mod degree_sequence;
+pub mod frozen;
+pub mod graph_ref;
+pub mod compact;
+pub mod dense;
+pub mod transform;
+pub mod property;
+pub mod components;
+pub mod transaction;
+pub mod cli;
+pub mod undirected;
+pub mod multigraph;
+#[cfg(feature = "disk")]
+pub mod disk;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+#[cfg(feature = "serde")]
+mod serialize;
+mod error;
+mod arena;
+
+pub use error::GraphError;
+pub use arena::{VertexId, Vertices};
 
-use generational_arena::{ Arena, Index };
+use arena::Arena;
 use std::vec::IntoIter;
-use std::hash::Hash;
+use std::hash::{BuildHasher, Hash};
+use std::ops::Add;
 use std::collections::{
     HashMap, 
-    hash_set::HashSet,
+    hash_set::{self, HashSet},
     hash_map,
 };
 
-pub type VertexId = Index;
 pub type EdgeId = (VertexId, VertexId);
 
+/// Builds the hashers of the maps inside the graphs, random
+/// for every map.
+#[cfg(not(feature = "deterministic"))]
+pub type DefaultHashBuilder = hash_map::RandomState;
+
+/// Builds the hashers of the maps inside the graphs, with fixed keys
+/// so the iteration order is the same on every run.
+#[cfg(feature = "deterministic")]
+pub type DefaultHashBuilder = std::hash::BuildHasherDefault<hash_map::DefaultHasher>;
+
+pub(crate) type Map<K, T> = HashMap<K, T, DefaultHashBuilder>;
+pub(crate) type Set<T> = HashSet<T, DefaultHashBuilder>;
+
 /// Graph data structure. [`V`] is the Vertex data,
-/// and [`E`] is the Edge data.
+/// and [`E`] is the Edge data. [`S`] builds the hashers of the
+/// maps inside, see [`Graph::with_hasher`].
 #[derive(Clone, Debug)]
-pub struct Graph<V, E> {
+pub struct Graph<V, E, S = DefaultHashBuilder> {
     arena: Arena<V>,
-    inbound: HashMap<VertexId, HashSet<VertexId>>,
-    outbound: HashMap<VertexId, HashSet<VertexId>>,
-    edges: HashMap<EdgeId, E>,
+    inbound: HashMap<VertexId, HashSet<VertexId, S>, S>,
+    outbound: HashMap<VertexId, HashSet<VertexId, S>, S>,
+    edges: HashMap<EdgeId, E, S>,
+}
+
+impl<V: std::fmt::Debug, E> Default for Graph<V, E> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<V: std::fmt::Debug,E> Graph<V, E> {
 
     pub fn new() -> Self {
         let arena = Arena::new();
-        let inbound = HashMap::new();
-        let outbound = HashMap::new();
-        let edges = HashMap::new();
+        let inbound = Map::default();
+        let outbound = Map::default();
+        let edges = Map::default();
         Graph {
             arena,
             inbound,
@@ -45,6 +112,93 @@ impl<V: std::fmt::Debug,E> Graph<V, E> {
             edges,
         }
     }
+
+    /// Empty graph with room for the given number of vertices and
+    /// edges, so adding them does not reallocate.
+    pub fn with_capacity(vertices: usize, edges: usize) -> Self {
+        Graph {
+            arena: Arena::with_capacity(vertices),
+            inbound: Map::with_capacity_and_hasher(vertices, Default::default()),
+            outbound: Map::with_capacity_and_hasher(vertices, Default::default()),
+            edges: Map::with_capacity_and_hasher(edges, Default::default()),
+        }
+    }
+
+    /// Graph of the given vertices and of edges between their positions
+    /// in `vertices`. Returns the ids of the vertices, in order.
+    /// Panics if an edge refers to a missing position.
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// let (graph, ids) = Graph::from_edges(vec!["a", "b", "c"], vec![(0, 1, 5), (1, 2, 7)]);
+    /// assert_eq!(graph.get_edge((ids[1], ids[2])), Some(&7));
+    /// assert_eq!(graph.edge_count(), 2);
+    /// ```
+    pub fn from_edges<I>(vertices: Vec<V>, edges: I) -> (Self, Vec<VertexId>)
+    where
+        I: IntoIterator<Item = (usize, usize, E)>,
+    {
+        let edges = edges.into_iter();
+        let mut graph = Graph::with_capacity(vertices.len(), edges.size_hint().0);
+        let ids: Vec<VertexId> = vertices.into_iter().map(|v| graph.add_vertex(v)).collect();
+        for (from, to, weight) in edges {
+            graph.insert_edge((ids[from], ids[to]), weight);
+        }
+        (graph, ids)
+    }
+}
+
+impl<V: std::fmt::Debug, E, S: BuildHasher + Default> Graph<V, E, S> {
+
+    /// Empty graph whose maps use hashers built by `hasher`, like
+    /// [`HashMap::with_hasher`]. The algorithms outside this module,
+    /// other than the generic ones of [`traversal`], need the
+    /// [`DefaultHashBuilder`].
+    ///
+    /// ```
+    /// use graph::Graph;
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::BuildHasherDefault;
+    ///
+    /// let mut graph = Graph::<&str, u32, _>::with_hasher(BuildHasherDefault::<DefaultHasher>::default());
+    /// let a = graph.add_vertex("a");
+    /// let b = graph.add_vertex("b");
+    /// graph.add_edge((a, b), 5).unwrap();
+    /// assert_eq!(graph.get_edge((a, b)), Some(&5));
+    /// assert_eq!(graph::traversal::bfs(&graph, a), vec![a, b]);
+    /// ```
+    pub fn with_hasher(hasher: S) -> Self
+    where
+        S: Clone,
+    {
+        Graph::with_capacity_and_hasher(0, 0, hasher)
+    }
+
+    /// Like [`Graph::with_capacity`], with the hashers of [`Graph::with_hasher`].
+    pub fn with_capacity_and_hasher(vertices: usize, edges: usize, hasher: S) -> Self
+    where
+        S: Clone,
+    {
+        Graph {
+            arena: Arena::with_capacity(vertices),
+            inbound: HashMap::with_capacity_and_hasher(vertices, hasher.clone()),
+            outbound: HashMap::with_capacity_and_hasher(vertices, hasher.clone()),
+            edges: HashMap::with_capacity_and_hasher(edges, hasher),
+        }
+    }
+
+    /// Adds all the edges, see [`Graph::add_edge`], reserving
+    /// room for them first. Stops at the first edge with a vertex
+    /// not in the graph, keeping the edges added before it.
+    pub fn extend_with_edges<I: IntoIterator<Item = (EdgeId, E)>>(&mut self, edges: I) -> Result<(), GraphError> {
+        let edges = edges.into_iter();
+        self.edges.reserve(edges.size_hint().0);
+        for (edge, weight) in edges {
+            self.add_edge(edge, weight)?;
+        }
+        Ok(())
+    }
     
     /// Adds a vertes to the graph, and returns an Id.
     /// Only way to get Id.
@@ -59,68 +213,294 @@ impl<V: std::fmt::Debug,E> Graph<V, E> {
     pub fn get_vertex(&self, vertex: VertexId) -> Option<&V> {
         self.arena.get(vertex)
     }
+
+    /// Returns the data in the vertex, mutably.
+    pub fn get_vertex_mut(&mut self, vertex: VertexId) -> Option<&mut V> {
+        self.arena.get_mut(vertex)
+    }
     
-    /// Adds an edge, or modifies the existing one.
-    pub fn add_edge(&mut self, edge: EdgeId, weight: E) {
-        self.edges.insert(edge, weight);
+    /// Checks that the vertex is in the graph.
+    pub fn contains_vertex(&self, vertex: VertexId) -> bool {
+        self.arena.contains(vertex)
+    }
+
+    /// Adds an edge, or modifies the existing one, returning its previous
+    /// data. Fails if either vertex is not in the graph, an id from a
+    /// removed vertex included.
+    ///
+    /// ```
+    /// use graph::{Graph, GraphError};
+    ///
+    /// let mut graph = Graph::<&str, u32>::new();
+    /// let a = graph.add_vertex("a");
+    /// let b = graph.add_vertex("b");
+    /// assert_eq!(graph.add_edge((a, b), 1), Ok(None));
+    /// assert_eq!(graph.add_edge((a, b), 2), Ok(Some(1)));
+    ///
+    /// graph.remove_vertex(b);
+    /// assert!(!graph.contains_vertex(b));
+    /// assert_eq!(graph.add_edge((a, b), 3), Err(GraphError::VertexNotFound));
+    /// assert_eq!(graph.edge_count(), 0);
+    /// ```
+    pub fn add_edge(&mut self, edge: EdgeId, weight: E) -> Result<Option<E>, GraphError> {
         let (from, to) = edge;
-        self.outbound.entry(from).or_default().insert(to);
-        self.inbound.entry(to).or_default().insert(from);
+        if !self.arena.contains(from) || !self.arena.contains(to) {
+            return Err(GraphError::VertexNotFound);
+        }
+        Ok(self.insert_edge(edge, weight))
+    }
+
+    /// Adds an edge without checking its vertices, for callers that
+    /// just got them from the graph.
+    pub(crate) fn insert_edge(&mut self, edge: EdgeId, weight: E) -> Option<E> {
+        let previous = self.edges.insert(edge, weight);
+        if previous.is_none() {
+            let (from, to) = edge;
+            self.outbound.entry(from).or_default().insert(to);
+            self.inbound.entry(to).or_default().insert(from);
+        }
+        previous
     }
     
     /// Get the edge.
     pub fn get_edge(&self, edge: EdgeId) -> Option<&E> {
         self.edges.get(&edge) 
     }
+
+    /// Get the edge, mutably.
+    pub fn get_edge_mut(&mut self, edge: EdgeId) -> Option<&mut E> {
+        self.edges.get_mut(&edge)
+    }
+
+    /// The edge, present or not, for in place updates with a single
+    /// lookup, like [`HashMap::entry`]. Fails if one of the vertices is
+    /// not in the graph, like [`Graph::add_edge`].
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// let mut graph = Graph::<&str, u32>::new();
+    /// let a = graph.add_vertex("a");
+    /// let b = graph.add_vertex("b");
+    /// for _ in 0..3 {
+    ///     graph.edge_entry((a, b)).unwrap().and_modify(|count| *count += 1).or_insert(1);
+    /// }
+    /// assert_eq!(graph.get_edge((a, b)), Some(&3));
+    /// assert_eq!(graph.outdegree(a), 1);
+    ///
+    /// graph.remove_vertex(b);
+    /// assert!(graph.edge_entry((a, b)).is_err());
+    /// ```
+    pub fn edge_entry(&mut self, edge: EdgeId) -> Result<EdgeEntry<'_, E, S>, GraphError> {
+        let (from, to) = edge;
+        if !self.arena.contains(from) || !self.arena.contains(to) {
+            return Err(GraphError::VertexNotFound);
+        }
+        Ok(self.entry(edge))
+    }
+
+    /// [`Graph::edge_entry`] without checking the vertices.
+    fn entry(&mut self, edge: EdgeId) -> EdgeEntry<'_, E, S> {
+        EdgeEntry {
+            entry: self.edges.entry(edge),
+            inbound: &mut self.inbound,
+            outbound: &mut self.outbound,
+        }
+    }
     
-    /// Removes the vertes.
-    /// Time complexity: O(outdegree(v))
+    /// Removes the vertes. Does nothing if it is not in the graph.
+    /// Time complexity: O(degree(v))
     pub fn remove_vertex(&mut self, vertex: VertexId) {
-        self.arena.remove(vertex);
+        let _ = self.try_remove_vertex(vertex);
+    }
+
+    /// Removes the vertex and its edges, returning its data.
+    /// Time complexity: O(degree(v))
+    pub fn try_remove_vertex(&mut self, vertex: VertexId) -> Result<V, GraphError> {
+        let data = self.arena.remove(vertex).ok_or(GraphError::VertexNotFound)?;
+        self.detach(vertex);
+        Ok(data)
+    }
+
+    /// Removes the edges of a vertex already removed from the arena.
+    fn detach(&mut self, vertex: VertexId) {
         let from = vertex;
 
-        for &to in self.outbound[&from].iter() {
+        for to in self.outbound.remove(&from).unwrap_or_default() {
             self.edges.remove(&(from,to));
-            self.inbound.get_mut(&to).unwrap().remove(&from);
+            if let Some(set) = self.inbound.get_mut(&to) {
+                set.remove(&from);
+            }
         }
 
         let to = from;
-        for &from in self.inbound[&to].iter() {
+        for from in self.inbound.remove(&to).unwrap_or_default() {
             self.edges.remove(&(from,to));
+            if let Some(set) = self.outbound.get_mut(&from) {
+                set.remove(&to);
+            }
         }
-        
-        self.inbound.remove(&from);
-        self.outbound.remove(&from);
+    }
+
+    /// Removes the edges from the adjacency sets, after their data.
+    fn unlink(&mut self, edges: Vec<EdgeId>) {
+        for (from, to) in edges {
+            if let Some(set) = self.outbound.get_mut(&from) {
+                set.remove(&to);
+            }
+            if let Some(set) = self.inbound.get_mut(&to) {
+                set.remove(&from);
+            }
+        }
+    }
+
+    /// Keeps only the vertices for which `keep` returns true, removing
+    /// the others with their edges.
+    /// Time complexity: O(V + sum of the degrees of the removed vertices)
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// let mut graph = Graph::<u32, ()>::new();
+    /// let ids: Vec<_> = (0..4).map(|i| graph.add_vertex(i)).collect();
+    /// graph.add_edge((ids[0], ids[1]), ()).unwrap();
+    /// graph.add_edge((ids[1], ids[2]), ()).unwrap();
+    /// graph.add_edge((ids[2], ids[3]), ()).unwrap();
+    ///
+    /// graph.retain_vertices(|_, &v| v != 1);
+    /// assert_eq!(graph.vertex_count(), 3);
+    /// assert_eq!(graph.edge_count(), 1);
+    /// assert_eq!(graph.degree(ids[0]), 0);
+    /// ```
+    pub fn retain_vertices<F: FnMut(VertexId, &V) -> bool>(&mut self, mut keep: F) {
+        let mut removed = Vec::new();
+        self.arena.retain(|id, vertex| keep(id, vertex) || {
+            removed.push(id);
+            false
+        });
+        for vertex in removed {
+            self.detach(vertex);
+        }
+    }
+
+    /// Keeps only the edges for which `keep` returns true.
+    /// Time complexity: O(E)
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// let mut graph = Graph::<u32, f64>::new();
+    /// let a = graph.add_vertex(0);
+    /// let b = graph.add_vertex(1);
+    /// graph.add_edge((a, b), 0.1).unwrap();
+    /// graph.add_edge((b, a), 0.9).unwrap();
+    ///
+    /// graph.retain_edges(|_, &weight| weight > 0.5);
+    /// assert_eq!(graph.edge_count(), 1);
+    /// assert_eq!(graph.outdegree(a), 0);
+    /// assert_eq!(graph.indegree(a), 1);
+    /// ```
+    pub fn retain_edges<F: FnMut(EdgeId, &E) -> bool>(&mut self, mut keep: F) {
+        let mut removed = Vec::new();
+        self.edges.retain(|&edge, weight| keep(edge, weight) || {
+            removed.push(edge);
+            false
+        });
+        self.unlink(removed);
     }
     
-    /// Remove an edge
+    /// Estimates the memory taken by the graph, see [`MemoryFootprint`].
+    /// Time complexity: O(V)
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// let mut graph = Graph::<u32, u64>::new();
+    /// let ids: Vec<_> = (0..100).map(|i| graph.add_vertex(i)).collect();
+    /// for i in 0..100 {
+    ///     for j in 0..100 {
+    ///         graph.add_edge((ids[i], ids[j]), 1).unwrap();
+    ///     }
+    /// }
+    /// let before = graph.memory_footprint();
+    ///
+    /// graph.retain_edges(|(from, to), _| from == to);
+    /// graph.shrink_to_fit();
+    /// let after = graph.memory_footprint();
+    /// assert!(after.edges * 50 < before.edges);
+    /// assert!(after.total() < before.total());
+    /// assert_eq!(after.vertices, before.vertices);
+    /// ```
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        let sets: usize = self.inbound.values()
+            .chain(self.outbound.values())
+            .map(|set| table_bytes::<VertexId>(set.capacity()))
+            .sum();
+        let maps = table_bytes::<(VertexId, HashSet<VertexId, S>)>(self.inbound.capacity())
+            + table_bytes::<(VertexId, HashSet<VertexId, S>)>(self.outbound.capacity());
+        MemoryFootprint {
+            vertices: self.arena.footprint(),
+            adjacency: maps + sets,
+            edges: table_bytes::<(EdgeId, E)>(self.edges.capacity()),
+        }
+    }
+
+    /// Shrinks the maps to fit the vertices and edges left after
+    /// removals, giving the memory back to the allocator. The slots of
+    /// removed vertices stay in the arena, and are reused by the next
+    /// vertices added.
+    /// Time complexity: O(V + E)
+    pub fn shrink_to_fit(&mut self) {
+        self.edges.shrink_to_fit();
+        self.inbound.shrink_to_fit();
+        self.outbound.shrink_to_fit();
+        for set in self.inbound.values_mut().chain(self.outbound.values_mut()) {
+            set.shrink_to_fit();
+        }
+    }
+
+    /// Remove an edge. Does nothing if it is not in the graph.
     /// Time complexity: O(1)
     pub fn remove_edge(&mut self, edge: EdgeId) {
-        self.edges.remove(&edge);              
+        let _ = self.try_remove_edge(edge);
+    }
+
+    /// Removes an edge, returning its data.
+    /// Time complexity: O(1)
+    pub fn try_remove_edge(&mut self, edge: EdgeId) -> Result<E, GraphError> {
         let (from, to) = edge;
-        self.outbound.get_mut(&from).unwrap().remove(&to);
-        self.inbound.get_mut(&to).unwrap().remove(&from);
+        if let Some(set) = self.outbound.get_mut(&from) {
+            set.remove(&to);
+        }
+        if let Some(set) = self.inbound.get_mut(&to) {
+            set.remove(&from);
+        }
+        self.edges.remove(&edge).ok_or(GraphError::EdgeNotFound)
     }
     
-    /// Returns an iterator over outbound edges
-    pub fn adj_out(&self, vertex: VertexId) -> Option<IntoIter<(VertexId, &E)>> {
+    /// Returns an iterator over outbound edges, as pairs of
+    /// (target, weight). Borrows the graph, without allocating.
+    pub fn adj_out(&self, vertex: VertexId) -> Option<AdjOut<'_, E, S>> {
         let outbound = self.outbound.get(&vertex)?;
-        let vec: Vec<(VertexId, &E)> = outbound.iter().map(|&target| {
-            (target, self.edges.get(&(vertex,target)).unwrap())
-        }).collect();
-        Some(vec.into_iter())
+        Some(AdjOut {
+            vertex,
+            targets: outbound.iter(),
+            edges: &self.edges,
+        })
     }
 
-    /// Returns an iterator over inbound edges
-    pub fn adj_in(&self, vertex: VertexId) -> Option<IntoIter<(VertexId, &E)>> {
+    /// Returns an iterator over inbound edges, as pairs of
+    /// (source, weight). Borrows the graph, without allocating.
+    pub fn adj_in(&self, vertex: VertexId) -> Option<AdjIn<'_, E, S>> {
         let inbound = self.inbound.get(&vertex)?;
-        let vec: Vec<(VertexId, &E)> = inbound.iter().map(|&target| {
-            (target, self.edges.get(&(target,vertex)).unwrap())
-        }).collect();
-        Some(vec.into_iter())
+        Some(AdjIn {
+            vertex,
+            sources: inbound.iter(),
+            edges: &self.edges,
+        })
     }
     
-    /// Indegree of the vertex
+    /// Indegree of the vertex, or 0 if it is not in the graph.
     pub fn indegree(&self, vertex: VertexId) -> usize {
         match self.inbound.get(&vertex) {
             Some(set) => set.len(),
@@ -128,7 +508,7 @@ impl<V: std::fmt::Debug,E> Graph<V, E> {
         }
     }
     
-    /// Outdegree of the vertex
+    /// Outdegree of the vertex, or 0 if it is not in the graph.
     pub fn outdegree(&self, vertex: VertexId) -> usize {
         match self.outbound.get(&vertex) {
             Some(set) => set.len(),
@@ -136,6 +516,40 @@ impl<V: std::fmt::Debug,E> Graph<V, E> {
         }
     }
 
+    /// Indegree plus outdegree, so a self loop counts twice.
+    pub fn degree(&self, vertex: VertexId) -> usize {
+        self.indegree(vertex) + self.outdegree(vertex)
+    }
+
+    /// Returns an iterator over the vertices joined to this one by an
+    /// edge in either direction, each once. Borrows the graph, without
+    /// allocating.
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// let mut graph = Graph::<&str, ()>::new();
+    /// let a = graph.add_vertex("a");
+    /// let b = graph.add_vertex("b");
+    /// let c = graph.add_vertex("c");
+    /// graph.add_edge((a, b), ()).unwrap();
+    /// graph.add_edge((b, a), ()).unwrap();
+    /// graph.add_edge((c, a), ()).unwrap();
+    ///
+    /// let mut neighbors: Vec<_> = graph.neighbors(a).unwrap().collect();
+    /// neighbors.sort();
+    /// assert_eq!(neighbors, vec![b, c]);
+    /// assert_eq!(graph.degree(a), 3);
+    /// ```
+    pub fn neighbors(&self, vertex: VertexId) -> Option<AllNeighbors<'_, S>> {
+        let outbound = self.outbound.get(&vertex)?;
+        Some(AllNeighbors {
+            outbound,
+            targets: outbound.iter(),
+            sources: self.inbound.get(&vertex)?.iter(),
+        })
+    }
+
     /// Number of vertices
     pub fn vertex_count(&self) -> usize {
         self.arena.len()
@@ -147,37 +561,370 @@ impl<V: std::fmt::Debug,E> Graph<V, E> {
     }
     
     /// Iterator over the vertices
-    pub fn vertices(&self) -> generational_arena::Iter<V> {
-        self.arena.iter()
+    pub fn vertices(&self) -> Vertices<'_, V> {
+        Vertices::new(&self.arena)
     }
     
     /// Iterator over the edges
-    pub fn edges(&self) -> hash_map::Iter<EdgeId, E> {
+    pub fn edges(&self) -> hash_map::Iter<'_, EdgeId, E> {
         self.edges.iter()
     }
 
+    /// Iterator over the ids of the vertices.
+    pub fn vertex_ids(&self) -> impl Iterator<Item = VertexId> + '_ {
+        self.arena.iter().map(|(id, _)| id)
+    }
+
+    /// Iterator over the ids of the edges.
+    pub fn edge_ids(&self) -> impl Iterator<Item = EdgeId> + '_ {
+        self.edges.keys().copied()
+    }
+
+    /// Iterator over the vertex ids with their degree, see [`Graph::degree`].
+    pub fn vertices_with_degree(&self) -> impl Iterator<Item = (VertexId, usize)> + '_ {
+        self.vertex_ids().map(move |id| (id, self.degree(id)))
+    }
+
+    /// Iterator over the edges with the data of both ends, as
+    /// (from, from data, to, to data, edge data).
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// let mut graph = Graph::<&str, u32>::new();
+    /// let a = graph.add_vertex("a");
+    /// let b = graph.add_vertex("b");
+    /// graph.add_edge((a, b), 1).unwrap();
+    ///
+    /// for (_, from, _, to, weight) in graph.edges_mut() {
+    ///     *weight += from.len() as u32 + to.len() as u32;
+    /// }
+    /// let edges: Vec<_> = graph.edges_with_data().map(|(_, &from, _, &to, &w)| (from, to, w)).collect();
+    /// assert_eq!(edges, vec![("a", "b", 3)]);
+    /// ```
+    pub fn edges_with_data(&self) -> impl Iterator<Item = (VertexId, &V, VertexId, &V, &E)> {
+        let arena = &self.arena;
+        self.edges.iter().map(move |(&(from, to), weight)| (from, &arena[from], to, &arena[to], weight))
+    }
+
+    /// Like [`Graph::edges_with_data`], with the edge data mutable.
+    pub fn edges_mut(&mut self) -> impl Iterator<Item = (VertexId, &V, VertexId, &V, &mut E)> {
+        let arena = &self.arena;
+        self.edges.iter_mut().map(move |(&(from, to), weight)| (from, &arena[from], to, &arena[to], weight))
+    }
+
+}
+
+impl<V: std::fmt::Debug, E: Copy + Add<Output = E> + Default, S: BuildHasher + Default> Graph<V, E, S> {
+
+    /// Sum of the data of the inbound edges, the default value if
+    /// the vertex is not in the graph.
+    /// Time complexity: O(indegree(v))
+    pub fn weighted_indegree(&self, vertex: VertexId) -> E {
+        self.adj_in(vertex)
+            .map(|sources| sources.fold(E::default(), |sum, (_, &weight)| sum + weight))
+            .unwrap_or_default()
+    }
+
+    /// Sum of the data of the outbound edges, the default value if
+    /// the vertex is not in the graph.
+    /// Time complexity: O(outdegree(v))
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// let mut graph = Graph::<&str, f64>::new();
+    /// let a = graph.add_vertex("a");
+    /// let b = graph.add_vertex("b");
+    /// let c = graph.add_vertex("c");
+    /// graph.add_edge((a, b), 1.5).unwrap();
+    /// graph.add_edge((a, c), 2.0).unwrap();
+    /// graph.add_edge((c, a), 0.5).unwrap();
+    ///
+    /// assert_eq!(graph.weighted_outdegree(a), 3.5);
+    /// assert_eq!(graph.weighted_indegree(a), 0.5);
+    /// assert_eq!(graph.weighted_degree(a), 4.0);
+    /// assert_eq!(graph.weighted_outdegree(b), 0.0);
+    /// ```
+    pub fn weighted_outdegree(&self, vertex: VertexId) -> E {
+        self.adj_out(vertex)
+            .map(|targets| targets.fold(E::default(), |sum, (_, &weight)| sum + weight))
+            .unwrap_or_default()
+    }
+
+    /// Sum of the data of all the edges of the vertex, its strength.
+    /// A self loop is counted twice, like in [`Graph::degree`].
+    /// Time complexity: O(degree(v))
+    pub fn weighted_degree(&self, vertex: VertexId) -> E {
+        self.weighted_indegree(vertex) + self.weighted_outdegree(vertex)
+    }
+}
+
+/// Estimated heap memory of a graph, in bytes, see
+/// [`Graph::memory_footprint`]. It counts the room reserved by the
+/// containers, used or not, but not the heap memory owned by the vertex
+/// and edge data, like the buffer of a `String`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryFootprint {
+    /// The arena of the vertex data, and for a [`GraphMap`] the map
+    /// from the data to the ids.
+    pub vertices: usize,
+    /// The inbound and outbound adjacency sets, and the maps holding them.
+    pub adjacency: usize,
+    /// The map from the edges to their data.
+    pub edges: usize,
+}
+
+impl MemoryFootprint {
+
+    /// Bytes of the whole graph.
+    pub fn total(&self) -> usize {
+        self.vertices + self.adjacency + self.edges
+    }
+}
+
+/// Bytes of a hash table with room for `capacity` entries of `T`: a
+/// power of two of buckets, each with the entry and a control byte, and
+/// a group of 16 control bytes more. The room taken by removed entries
+/// is not in the capacity; rounding up to the next power of two makes
+/// up for it.
+fn table_bytes<T>(capacity: usize) -> usize {
+    let buckets = match capacity {
+        0 => return 0,
+        1..=7 => capacity + 1,
+        _ => capacity * 8 / 7,
+    };
+    buckets.next_power_of_two() * (std::mem::size_of::<T>() + 1) + 16
+}
+
+/// Iterator over the outbound edges of a vertex, see [`Graph::adj_out`].
+#[derive(Clone, Debug)]
+pub struct AdjOut<'a, E, S = DefaultHashBuilder> {
+    vertex: VertexId,
+    targets: hash_set::Iter<'a, VertexId>,
+    edges: &'a HashMap<EdgeId, E, S>,
+}
+
+impl<'a, E, S: BuildHasher> Iterator for AdjOut<'a, E, S> {
+    type Item = (VertexId, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let &target = self.targets.next()?;
+        Some((target, &self.edges[&(self.vertex, target)]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.targets.size_hint()
+    }
+}
+
+impl<'a, E, S: BuildHasher> ExactSizeIterator for AdjOut<'a, E, S> {}
+
+/// Iterator over the inbound edges of a vertex, see [`Graph::adj_in`].
+#[derive(Clone, Debug)]
+pub struct AdjIn<'a, E, S = DefaultHashBuilder> {
+    vertex: VertexId,
+    sources: hash_set::Iter<'a, VertexId>,
+    edges: &'a HashMap<EdgeId, E, S>,
+}
+
+impl<'a, E, S: BuildHasher> Iterator for AdjIn<'a, E, S> {
+    type Item = (VertexId, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let &source = self.sources.next()?;
+        Some((source, &self.edges[&(source, self.vertex)]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sources.size_hint()
+    }
+}
+
+impl<'a, E, S: BuildHasher> ExactSizeIterator for AdjIn<'a, E, S> {}
+
+/// Iterator over the neighbours of a vertex in both directions,
+/// see [`Graph::neighbors`].
+#[derive(Clone, Debug)]
+pub struct AllNeighbors<'a, S = DefaultHashBuilder> {
+    outbound: &'a HashSet<VertexId, S>,
+    targets: hash_set::Iter<'a, VertexId>,
+    sources: hash_set::Iter<'a, VertexId>,
+}
+
+impl<'a, S: BuildHasher> Iterator for AllNeighbors<'a, S> {
+    type Item = VertexId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(&target) = self.targets.next() {
+            return Some(target);
+        }
+        let outbound = self.outbound;
+        self.sources.by_ref().copied().find(|source| !outbound.contains(source))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let targets = self.targets.len();
+        (targets, Some(targets + self.sources.len()))
+    }
+}
+
+/// An edge that may or may not be in the graph, see [`Graph::edge_entry`].
+#[derive(Debug)]
+pub struct EdgeEntry<'a, E, S = DefaultHashBuilder> {
+    entry: hash_map::Entry<'a, EdgeId, E>,
+    inbound: &'a mut HashMap<VertexId, HashSet<VertexId, S>, S>,
+    outbound: &'a mut HashMap<VertexId, HashSet<VertexId, S>, S>,
+}
+
+impl<'a, E, S: BuildHasher + Default> EdgeEntry<'a, E, S> {
+
+    /// The two ends of the edge.
+    pub fn key(&self) -> EdgeId {
+        *self.entry.key()
+    }
+
+    /// Calls `f` on the data if the edge is in the graph.
+    pub fn and_modify<F: FnOnce(&mut E)>(mut self, f: F) -> Self {
+        self.entry = self.entry.and_modify(f);
+        self
+    }
+
+    /// Data of the edge, adding it with `weight` if missing.
+    pub fn or_insert(self, weight: E) -> &'a mut E {
+        self.or_insert_with(|| weight)
+    }
+
+    /// Data of the edge, adding it with the result of `f` if missing.
+    pub fn or_insert_with<F: FnOnce() -> E>(self, f: F) -> &'a mut E {
+        match self.entry {
+            hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            hash_map::Entry::Vacant(entry) => {
+                let (from, to) = *entry.key();
+                self.outbound.entry(from).or_default().insert(to);
+                self.inbound.entry(to).or_default().insert(from);
+                entry.insert(f())
+            }
+        }
+    }
+
+    /// Data of the edge, adding it with `E::default()` if missing.
+    pub fn or_default(self) -> &'a mut E
+    where
+        E: Default,
+    {
+        self.or_insert_with(E::default)
+    }
+}
+
+/// [`AdjOut`] or [`AdjIn`] giving the vertex data instead of the ids,
+/// see [`GraphMap::adj_out`].
+#[derive(Clone, Debug)]
+pub struct MapAdj<'a, V, I> {
+    arena: &'a Arena<V>,
+    inner: I,
+}
+
+impl<'a, V, E: 'a, I: Iterator<Item = (VertexId, &'a E)>> Iterator for MapAdj<'a, V, I> {
+    type Item = (&'a V, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (id, e) = self.inner.next()?;
+        Some((&self.arena[id], e))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
+impl<'a, V, E: 'a, I: ExactSizeIterator<Item = (VertexId, &'a E)>> ExactSizeIterator for MapAdj<'a, V, I> {}
+
 
 /// Wrapper around the [`Graph`] that allows you
 /// to identify the vertices by their data.
 /// [`V`] needs to be [`Hash`].
 #[derive(Clone, Debug)]
-pub struct GraphMap<V: Eq + Hash + Clone, E> {
-    graph: Graph<V, E>,
-    map: HashMap<V, VertexId>,
+pub struct GraphMap<V: Eq + Hash + Clone, E, S = DefaultHashBuilder> {
+    graph: Graph<V, E, S>,
+    map: HashMap<V, VertexId, S>,
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> Default for GraphMap<V, E> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V,E> {
     pub fn new() -> Self {
         let graph = Graph::new();
-        let map = HashMap::new();
+        let map = Map::default();
         GraphMap {
             graph,
             map
         }
     }
 
+    /// Empty graph with room for the given number of vertices and
+    /// edges, so adding them does not reallocate.
+    pub fn with_capacity(vertices: usize, edges: usize) -> Self {
+        GraphMap {
+            graph: Graph::with_capacity(vertices, edges),
+            map: Map::with_capacity_and_hasher(vertices, Default::default()),
+        }
+    }
+
+    /// Graph of the given edges, adding their ends as vertices.
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut graph = GraphMap::from_edges(vec![(("a", "b"), 1), (("b", "c"), 2)]);
+    /// graph.extend_with_edges(vec![(("c", "a"), 3)]);
+    /// assert_eq!(graph.vertex_count(), 3);
+    /// assert_eq!(graph.get_edge(("c", "a")), Some(&3));
+    /// ```
+    pub fn from_edges<I: IntoIterator<Item = ((V, V), E)>>(edges: I) -> Self {
+        let mut graph = GraphMap::new();
+        graph.extend_with_edges(edges);
+        graph
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E, S: BuildHasher + Default> GraphMap<V, E, S> {
+
+    /// Empty graph whose maps use hashers built by `hasher`,
+    /// see [`Graph::with_hasher`].
+    pub fn with_hasher(hasher: S) -> Self
+    where
+        S: Clone,
+    {
+        GraphMap::with_capacity_and_hasher(0, 0, hasher)
+    }
+
+    /// Like [`GraphMap::with_capacity`], with the hashers of [`Graph::with_hasher`].
+    pub fn with_capacity_and_hasher(vertices: usize, edges: usize, hasher: S) -> Self
+    where
+        S: Clone,
+    {
+        GraphMap {
+            graph: Graph::with_capacity_and_hasher(vertices, edges, hasher.clone()),
+            map: HashMap::with_capacity_and_hasher(vertices, hasher),
+        }
+    }
+
+    /// Adds all the edges, see [`GraphMap::add_edge`], reserving room
+    /// for them first. The number of new vertices is not known, so use
+    /// [`GraphMap::with_capacity`] to reserve them too.
+    pub fn extend_with_edges<I: IntoIterator<Item = ((V, V), E)>>(&mut self, edges: I) {
+        let edges = edges.into_iter();
+        self.graph.edges.reserve(edges.size_hint().0);
+        for (edge, weight) in edges {
+            self.add_edge(edge, weight);
+        }
+    }
+
     fn add_or_get_vertex(&mut self, vertex: V) -> VertexId {
 
         match self.map.get(&vertex) {
@@ -195,7 +942,7 @@ impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V,E> {
     /// do nothing
     pub fn add_vertex(&mut self, vertex: V) {
         match self.map.get(&vertex) {
-            Some(_) => return (),
+            Some(_) => (),
             None => {
                 let id = self.graph.add_vertex(vertex.clone());
                 self.map.insert(vertex, id);
@@ -210,7 +957,7 @@ impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V,E> {
 
         let from = self.add_or_get_vertex(from);
         let to = self.add_or_get_vertex(to);
-        self.graph.add_edge((from,to), weight);
+        self.graph.insert_edge((from,to), weight);
     }
     
     ///Get an edge
@@ -221,64 +968,246 @@ impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V,E> {
         self.graph.get_edge((from,to)) 
     }
 
-    pub fn contains_edge(&self, edge: (V,V)) -> bool {
-        !self.get_edge(edge).is_none()
+    /// Get an edge, mutably.
+    pub fn get_edge_mut(&mut self, edge: (V, V)) -> Option<&mut E> {
+        let (from, to) = edge;
+        let from = *self.map.get(&from)?;
+        let to = *self.map.get(&to)?;
+        self.graph.get_edge_mut((from, to))
     }
 
-    /// Removes the vertes.
-    /// Time complexity: O(outdegree(v))
-    pub fn remove_vertex(&mut self, vertex: V) -> bool {
-        let id = match self.map.remove(&vertex) {
-            Some(id) => id,
+    /// See [`Graph::edge_entry`]. Adds the vertices if they are
+    /// missing, like [`GraphMap::add_edge`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut cooccurrences = GraphMap::<&str, u32>::new();
+    /// for pair in [("rust", "graph"), ("rust", "graph"), ("graph", "data")].iter() {
+    ///     *cooccurrences.edge_entry(*pair).or_default() += 1;
+    /// }
+    /// assert_eq!(cooccurrences.get_edge(("rust", "graph")), Some(&2));
+    /// assert_eq!(cooccurrences.vertex_count(), 3);
+    /// ```
+    pub fn edge_entry(&mut self, edge: (V, V)) -> EdgeEntry<'_, E, S> {
+        let (from, to) = edge;
+        let from = self.add_or_get_vertex(from);
+        let to = self.add_or_get_vertex(to);
+        self.graph.entry((from, to))
+    }
+
+    /// Modifies the vertex with `update`, keeping its edges. Returns
+    /// false, leaving the graph unchanged, if the vertex is not in the
+    /// graph or if the modified vertex is equal to another one.
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut graph = GraphMap::<String, u32>::new();
+    /// graph.add_edge(("a".to_string(), "b".to_string()), 1);
+    /// assert!(graph.update_vertex("a".to_string(), |v| v.push('2')));
+    /// assert_eq!(graph.get_edge(("a2".to_string(), "b".to_string())), Some(&1));
+    /// assert!(!graph.update_vertex("a2".to_string(), |v| *v = "b".to_string()));
+    /// ```
+    pub fn update_vertex<F: FnOnce(&mut V)>(&mut self, vertex: V, update: F) -> bool {
+        let id = match self.map.get(&vertex) {
+            Some(&id) => id,
             None => return false,
         };
-        self.graph.remove_vertex(id);
+        let mut updated = vertex.clone();
+        update(&mut updated);
+        if updated != vertex && self.map.contains_key(&updated) {
+            return false;
+        }
+        self.map.remove(&vertex);
+        self.map.insert(updated.clone(), id);
+        *self.graph.get_vertex_mut(id).unwrap() = updated;
         true
     }
+
+    pub fn contains_edge(&self, edge: (V,V)) -> bool {
+        self.get_edge(edge).is_some()
+    }
+
+    /// Removes the vertes. Returns false if it is not in the graph.
+    /// Time complexity: O(degree(v))
+    pub fn remove_vertex(&mut self, vertex: V) -> bool {
+        self.try_remove_vertex(vertex).is_ok()
+    }
+
+    /// Removes the vertex and its edges, returning the stored vertex.
+    /// Time complexity: O(degree(v))
+    pub fn try_remove_vertex(&mut self, vertex: V) -> Result<V, GraphError> {
+        let id = self.map.remove(&vertex).ok_or(GraphError::VertexNotFound)?;
+        self.graph.try_remove_vertex(id)
+    }
     
-    /// Removes an edge.
+    /// Keeps only the vertices for which `keep` returns true,
+    /// see [`Graph::retain_vertices`].
+    pub fn retain_vertices<F: FnMut(&V) -> bool>(&mut self, mut keep: F) {
+        let mut removed = Vec::new();
+        self.graph.retain_vertices(|_, vertex| keep(vertex) || {
+            removed.push(vertex.clone());
+            false
+        });
+        for vertex in removed {
+            self.map.remove(&vertex);
+        }
+    }
+
+    /// Keeps only the edges for which `keep` returns true,
+    /// see [`Graph::retain_edges`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut graph = GraphMap::<&str, u32>::new();
+    /// graph.add_edge(("a", "b"), 1);
+    /// graph.add_edge(("b", "c"), 5);
+    /// graph.add_edge(("c", "d"), 2);
+    ///
+    /// graph.retain_edges(|(from, _), &weight| weight > 1 && *from != "c");
+    /// assert_eq!(graph.edge_count(), 1);
+    /// assert!(graph.contains_edge(("b", "c")));
+    ///
+    /// graph.retain_vertices(|&v| v != "b");
+    /// assert_eq!(graph.vertex_count(), 3);
+    /// assert_eq!(graph.edge_count(), 0);
+    /// assert_eq!(graph.id_of(&"b"), None);
+    /// ```
+    pub fn retain_edges<F: FnMut((&V, &V), &E) -> bool>(&mut self, mut keep: F) {
+        let arena = &self.graph.arena;
+        let mut removed = Vec::new();
+        self.graph.edges.retain(|&(from, to), weight| keep((&arena[from], &arena[to]), weight) || {
+            removed.push((from, to));
+            false
+        });
+        self.graph.unlink(removed);
+    }
+
+    /// Replaces the vertex `old` with `new`, keeping its edges, and
+    /// returns the old vertex. Fails if `old` is not in the graph, or if
+    /// `new` is another vertex of the graph.
+    /// Time complexity: O(1)
+    ///
+    /// ```
+    /// use graph::{GraphError, GraphMap};
+    ///
+    /// let mut graph = GraphMap::<String, u32>::new();
+    /// graph.add_edge(("a".to_string(), "b".to_string()), 1);
+    /// graph.add_edge(("b".to_string(), "a".to_string()), 2);
+    ///
+    /// let old = graph.replace_vertex(&"a".to_string(), "c".to_string());
+    /// assert_eq!(old, Ok("a".to_string()));
+    /// assert_eq!(graph.get_edge(("c".to_string(), "b".to_string())), Some(&1));
+    /// assert_eq!(graph.get_edge(("b".to_string(), "c".to_string())), Some(&2));
+    /// assert_eq!(graph.id_of(&"a".to_string()), None);
+    ///
+    /// let err = graph.replace_vertex(&"c".to_string(), "b".to_string());
+    /// assert_eq!(err, Err(GraphError::VertexExists));
+    /// ```
+    pub fn replace_vertex(&mut self, old: &V, new: V) -> Result<V, GraphError> {
+        let id = *self.map.get(old).ok_or(GraphError::VertexNotFound)?;
+        if new != *old && self.map.contains_key(&new) {
+            return Err(GraphError::VertexExists);
+        }
+        self.map.remove(old);
+        self.map.insert(new.clone(), id);
+        Ok(std::mem::replace(&mut self.graph.arena[id], new))
+    }
+
+    /// See [`Graph::memory_footprint`].
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        let mut footprint = self.graph.memory_footprint();
+        footprint.vertices += table_bytes::<(V, VertexId)>(self.map.capacity());
+        footprint
+    }
+
+    /// Shrinks the maps to fit the vertices left after removals,
+    /// see [`Graph::shrink_to_fit`].
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit();
+        self.graph.shrink_to_fit();
+    }
+
+    /// Removes an edge. Returns false if it is not in the graph.
     pub fn remove_edge(&mut self, edge: (V,V) ) -> bool {
+        self.try_remove_edge(edge).is_ok()
+    }
+
+    /// Removes an edge, returning its data.
+    ///
+    /// ```
+    /// use graph::{GraphError, GraphMap};
+    ///
+    /// let mut graph = GraphMap::<u32, u32>::new();
+    /// graph.add_edge((1, 2), 5);
+    /// assert_eq!(graph.try_remove_edge((1, 2)), Ok(5));
+    /// assert_eq!(graph.try_remove_edge((1, 2)), Err(GraphError::EdgeNotFound));
+    /// assert_eq!(graph.try_remove_edge((1, 3)), Err(GraphError::VertexNotFound));
+    /// assert_eq!(graph.try_indegree(3), Err(GraphError::VertexNotFound));
+    /// assert_eq!(graph.indegree(3), 0);
+    /// ```
+    pub fn try_remove_edge(&mut self, edge: (V,V) ) -> Result<E, GraphError> {
         let (from, to) = edge;
-        let from = *match self.map.get(&from) {
-            Some(id) => id,
-            None => return false,
-        };
-        let to = *match self.map.get(&to) {
-            Some(id) => id,
-            None => return false,
-        };
-        self.graph.remove_edge((from,to));
-        true
+        let from = *self.map.get(&from).ok_or(GraphError::VertexNotFound)?;
+        let to = *self.map.get(&to).ok_or(GraphError::VertexNotFound)?;
+        self.graph.try_remove_edge((from,to))
     }
     
     /// Iterate over the outbound nodes.
     /// Returns pairs of (vertex, weight).
-    pub fn adj_out(&self, vertex: V) -> Option<IntoIter<(&V, &E)>> {
+    pub fn adj_out(&self, vertex: V) -> Option<MapAdj<'_, V, AdjOut<'_, E, S>>> {
         let id = *self.map.get(&vertex)?;
-        let vec: Vec<(&V,&E)> = self.graph.adj_out(id)?.map(|(id, e)| {
-            (self.graph.get_vertex(id).unwrap(), e)
-        }).collect();
-        Some(vec.into_iter())
+        Some(MapAdj {
+            arena: &self.graph.arena,
+            inner: self.graph.adj_out(id)?,
+        })
     }
 
     /// Iterate over the inbound nodes.
     /// Returns pairs of (vertex, weight).
-    pub fn adj_in(&self, vertex: V) -> Option<IntoIter<(&V, &E)>> {
+    pub fn adj_in(&self, vertex: V) -> Option<MapAdj<'_, V, AdjIn<'_, E, S>>> {
         let id = *self.map.get(&vertex)?;
-        let vec: Vec<(&V,&E)> = self.graph.adj_in(id)?.map(|(id, e)| {
-            (self.graph.get_vertex(id).unwrap(), e)
-        }).collect();
-        Some(vec.into_iter())
+        Some(MapAdj {
+            arena: &self.graph.arena,
+            inner: self.graph.adj_in(id)?,
+        })
     }
     
-    /// Indegree of the node
+    /// Indegree of the node, or 0 if it is not in the graph.
     pub fn indegree(&self, vertex: V) -> usize {
-        self.graph.indegree(self.map[&vertex])
+        self.try_indegree(vertex).unwrap_or(0)
     }
 
-    /// Outdegree of the node
+    /// Outdegree of the node, or 0 if it is not in the graph.
     pub fn outdegree(&self, vertex: V) -> usize {
-        self.graph.outdegree(self.map[&vertex])
+        self.try_outdegree(vertex).unwrap_or(0)
+    }
+
+    /// Indegree plus outdegree of the node, or 0 if it is not in the graph.
+    pub fn degree(&self, vertex: V) -> usize {
+        self.indegree(vertex.clone()) + self.outdegree(vertex)
+    }
+
+    /// Iterate over the nodes joined to this one in either direction,
+    /// each once. See [`Graph::neighbors`].
+    pub fn neighbors(&self, vertex: V) -> Option<impl Iterator<Item = &V>> {
+        let id = *self.map.get(&vertex)?;
+        let arena = &self.graph.arena;
+        Some(self.graph.neighbors(id)?.map(move |other| &arena[other]))
+    }
+
+    /// Indegree of the node.
+    pub fn try_indegree(&self, vertex: V) -> Result<usize, GraphError> {
+        let id = self.map.get(&vertex).ok_or(GraphError::VertexNotFound)?;
+        Ok(self.graph.indegree(*id))
+    }
+
+    /// Outdegree of the node.
+    pub fn try_outdegree(&self, vertex: V) -> Result<usize, GraphError> {
+        let id = self.map.get(&vertex).ok_or(GraphError::VertexNotFound)?;
+        Ok(self.graph.outdegree(*id))
     }
 
     pub fn vertex_count(&self) -> usize {
@@ -289,20 +1218,131 @@ impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V,E> {
         self.graph.edge_count()
     }
 
-    pub fn vertices(&self) -> hash_map::Keys<V, VertexId> {
+    pub fn vertices(&self) -> hash_map::Keys<'_, V, VertexId> {
         self.map.keys()
     }
 
+    /// Id of the vertex in the underlying [`Graph`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut graph = GraphMap::<&str, u32>::new();
+    /// graph.add_edge(("a", "b"), 1);
+    ///
+    /// let a = graph.id_of(&"a").unwrap();
+    /// let b = graph.id_of(&"b").unwrap();
+    /// assert_eq!(graph.as_graph().get_edge((a, b)), Some(&1));
+    /// assert_eq!(graph.vertex_of(b), Some(&"b"));
+    /// assert_eq!(graph.edge_ids().collect::<Vec<_>>(), vec![(a, b)]);
+    /// assert_eq!(graph.id_of(&"c"), None);
+    /// ```
+    pub fn id_of(&self, vertex: &V) -> Option<VertexId> {
+        self.map.get(vertex).copied()
+    }
+
+    /// Vertex with the id, see [`GraphMap::id_of`].
+    pub fn vertex_of(&self, id: VertexId) -> Option<&V> {
+        self.graph.get_vertex(id)
+    }
+
+    /// Whether the vertex is in the graph.
+    /// Time complexity: O(1)
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut graph = GraphMap::<String, ()>::new();
+    /// graph.add_vertex("a".to_string());
+    /// assert!(graph.contains_vertex(&"a".to_string()));
+    /// assert!(!graph.contains_vertex(&"b".to_string()));
+    ///
+    /// let stored = graph.get_vertex(&"a".to_string()).unwrap();
+    /// assert_eq!(graph.vertex_id(stored), graph.id_of(stored));
+    /// graph.remove_vertex("a".to_string());
+    /// assert_eq!(graph.get_vertex(&"a".to_string()), None);
+    /// ```
+    pub fn contains_vertex(&self, vertex: &V) -> bool {
+        self.map.contains_key(vertex)
+    }
+
+    /// The vertex stored in the graph equal to `vertex`, borrowed for as
+    /// long as the graph.
+    /// Time complexity: O(1)
+    pub fn get_vertex(&self, vertex: &V) -> Option<&V> {
+        self.map.get_key_value(vertex).map(|(stored, _)| stored)
+    }
+
+    /// Id of the vertex, the same as [`GraphMap::id_of`].
+    pub fn vertex_id(&self, vertex: &V) -> Option<VertexId> {
+        self.id_of(vertex)
+    }
+
+    /// The underlying [`Graph`], for the algorithms working on ids.
+    pub fn as_graph(&self) -> &Graph<V, E, S> {
+        &self.graph
+    }
+
+    /// Iterator over the ids of the vertices, see [`GraphMap::id_of`].
+    pub fn vertex_ids(&self) -> impl Iterator<Item = VertexId> + '_ {
+        self.graph.vertex_ids()
+    }
+
+    /// Iterator over the ids of the edges, see [`GraphMap::id_of`].
+    pub fn edge_ids(&self) -> impl Iterator<Item = EdgeId> + '_ {
+        self.graph.edge_ids()
+    }
+
+    /// Iterator over the vertices with their degree, see [`Graph::degree`].
+    pub fn vertices_with_degree(&self) -> impl Iterator<Item = (&V, usize)> {
+        self.graph.vertices().map(move |(id, vertex)| (vertex, self.graph.degree(id)))
+    }
+
+    /// Iterate over the edges, with the data mutable.
+    pub fn edges_mut(&mut self) -> impl Iterator<Item = ((&V, &V), &mut E)> {
+        self.graph.edges_mut().map(|(_, from, _, to, weight)| ((from, to), weight))
+    }
+
     pub fn edges(&self) -> IntoIter<((&V,&V), &E)> {
-        let edges = self.graph.edges();
-        let vec: Vec<((&V,&V), &E)> = edges.map(|((from,to), e)| {
-            // println!("{:?} {:?}", self.graph.get_vertex(*from), self.graph.get_vertex(*to));
-            ((self.graph.get_vertex(*from).unwrap(), self.graph.get_vertex(*to).unwrap()), e)
-        }).collect();
+        let vec: Vec<((&V,&V), &E)> = self.graph.edges_with_data()
+            .map(|(_, from, _, to, e)| ((from, to), e))
+            .collect();
         vec.into_iter()
     }
 }
 
+impl<V, E, S> GraphMap<V, E, S>
+where
+    V: Eq + Hash + Clone + std::fmt::Debug,
+    E: Copy + Add<Output = E> + Default,
+    S: BuildHasher + Default,
+{
+    /// See [`Graph::weighted_indegree`].
+    pub fn weighted_indegree(&self, vertex: V) -> E {
+        self.map.get(&vertex).map(|&id| self.graph.weighted_indegree(id)).unwrap_or_default()
+    }
+
+    /// See [`Graph::weighted_outdegree`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let graph = GraphMap::from_edges(vec![((1, 2), 3), ((1, 3), 4), ((2, 1), 1)]);
+    /// assert_eq!(graph.weighted_outdegree(1), 7);
+    /// assert_eq!(graph.weighted_indegree(1), 1);
+    /// assert_eq!(graph.weighted_degree(1), 8);
+    /// assert_eq!(graph.weighted_degree(4), 0);
+    /// ```
+    pub fn weighted_outdegree(&self, vertex: V) -> E {
+        self.map.get(&vertex).map(|&id| self.graph.weighted_outdegree(id)).unwrap_or_default()
+    }
+
+    /// See [`Graph::weighted_degree`].
+    pub fn weighted_degree(&self, vertex: V) -> E {
+        self.map.get(&vertex).map(|&id| self.graph.weighted_degree(id)).unwrap_or_default()
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -310,4 +1350,79 @@ mod tests {
     fn it_works() {
         assert_eq!(2 + 2, 4);
     }
+
+    #[test]
+    fn removing_a_vertex_removes_its_inbound_edges() {
+        let mut graph = crate::Graph::<&str, u32>::new();
+        let a = graph.add_vertex("a");
+        let b = graph.add_vertex("b");
+        let c = graph.add_vertex("c");
+        graph.add_edge((a, b), 1).unwrap();
+        graph.add_edge((c, b), 2).unwrap();
+        graph.add_edge((b, a), 3).unwrap();
+
+        graph.remove_vertex(b);
+        assert_eq!(graph.edge_count(), 0);
+        for &v in [a, c].iter() {
+            assert_eq!(graph.outdegree(v), 0);
+            assert_eq!(graph.indegree(v), 0);
+            assert_eq!(graph.adj_out(v).unwrap().count(), 0);
+        }
+
+        let d = graph.add_vertex("d");
+        graph.add_edge((a, d), 4).unwrap();
+        assert_eq!(graph.adj_out(a).unwrap().collect::<Vec<_>>(), vec![(d, &4)]);
+        assert_eq!(graph.try_remove_edge((c, b)), Err(crate::GraphError::EdgeNotFound));
+    }
+
+    #[test]
+    fn no_edge_entry_for_a_removed_vertex() {
+        let mut graph = crate::Graph::<&str, u32>::new();
+        let a = graph.add_vertex("a");
+        let b = graph.add_vertex("b");
+        graph.remove_vertex(a);
+        assert_eq!(graph.edge_entry((a, b)).err(), Some(crate::GraphError::VertexNotFound));
+        assert_eq!(graph.edge_entry((b, a)).err(), Some(crate::GraphError::VertexNotFound));
+        assert_eq!(graph.edge_count(), 0);
+        assert_eq!(graph.edges_with_data().count(), 0);
+
+        *graph.edge_entry((b, b)).unwrap().or_default() += 2;
+        assert_eq!(graph.get_edge((b, b)), Some(&2));
+    }
+
+    #[cfg(feature = "deterministic")]
+    #[test]
+    fn iteration_is_the_same_every_time() {
+        let build = || {
+            let mut graph = crate::GraphMap::<u32, u32>::new();
+            for i in 0..100 {
+                graph.add_edge((i % 17, i * 7 % 31), i);
+            }
+            graph
+        };
+        let (a, b) = (build(), build());
+        let edges = |graph: &crate::GraphMap<u32, u32>| graph.edges()
+            .map(|((&from, &to), &weight)| (from, to, weight))
+            .collect::<Vec<_>>();
+        assert_eq!(edges(&a), edges(&b));
+        assert!(a.vertices().eq(b.vertices()));
+    }
+
+    #[test]
+    fn shrinking_lowers_the_footprint() {
+        let mut graph = crate::GraphMap::<u32, u32>::new();
+        for i in 0..1000 {
+            graph.add_edge((i, (i + 1) % 1000), i);
+        }
+        let full = graph.memory_footprint();
+        assert!(full.edges >= 1000 * std::mem::size_of::<(crate::EdgeId, u32)>());
+        assert!(full.vertices > graph.graph.memory_footprint().vertices);
+
+        graph.retain_vertices(|&v| v < 10);
+        graph.shrink_to_fit();
+        let shrunk = graph.memory_footprint();
+        assert!(shrunk.adjacency * 10 < full.adjacency);
+        assert!(shrunk.edges * 10 < full.edges);
+        assert!(shrunk.total() < full.total());
+    }
 }
@@ -1,234 +1,174 @@
 use rustyline::error::ReadlineError;
 use rustyline::Editor;
 use graph::*;
-use rand::{ Rng, seq::IteratorRandom };
+use graph::io::{compressed, simple_text};
+use graph::cli::{CommandEngine, Output};
+use graph::wal::PersistentGraph;
 use std::path::Path;
-use std::collections::HashSet;
 
 
-fn random_graph(vertices: u32, edges: u32) -> GraphMap<u32,u32> {
-
-    let mut graph = GraphMap::<u32, u32>::new();
-    let mut rng = rand::thread_rng();
-    
-    if edges > vertices*vertices {
-        println!("Impossible");
-        return graph;
-    }
-
-    let mut edge_list = Vec::new();
-    for i in 0..vertices {
-        for j in 0..vertices {
-            edge_list.push((i,j));
-        }
-    }
-
-    for &(from, to) in edge_list.iter().choose_multiple(&mut rng, edges as usize) {
-        let weight: u32= rng.gen_range(0..100);
-        graph.add_edge((from,to), weight);
-    }
-    graph
-}
-
 fn read_graph(path: &Path, undirected: bool) -> GraphMap<u32, u32> {
-    let mut graph = GraphMap::<u32,u32>::new();
-    let contents = std::fs::read_to_string(path).unwrap();
-    
-    let lines: Vec<&str> = contents.lines().collect();
-    let first_line: Vec<&str> = lines[0].split(" ").collect();
-    let nodes = str::parse::<u32>(first_line[0]).unwrap();
-    // let edges = str::parse::<u32>(first_line[1]).unwrap();
-
-    for v in 0..nodes {
-        graph.add_vertex(v);
-    }
-
-    for line in lines[1..].iter() {
-        let line: Vec<&str> = line.split(" ").collect();
-        let origin = str::parse::<u32>(line[0]).unwrap();
-        let target = str::parse::<u32>(line[1]).unwrap();
-        let cost = str::parse::<u32>(line[2]).unwrap();
-        graph.add_edge((origin,target), cost);
-        if undirected {
-            graph.add_edge((target,origin), cost);
+    let graph = compressed::open(path)
+        .map_err(graph::io::Error::from)
+        .and_then(|reader| simple_text::read_graph(reader, undirected));
+    match graph {
+        Ok(graph) => graph,
+        Err(err) => {
+            println!("Could not read {}: {}", path.display(), err);
+            GraphMap::new()
         }
     }
-    graph
 }
 
-fn write_graph<W: std::io::Write>(writer: &mut W, graph: &GraphMap<u32,u32>, undirected: bool ) {
-
-    let mut printed = HashSet::<(u32,u32,u32)>::new();
-    
-    for ((&origin, &target), &cost) in graph.edges() {
-
-        if !undirected {
-            printed.insert((origin, target, cost));
-        }
+const USAGE: &str = "Usage: graph [--json] [--undirected] [--input <file>] [--output <file>] [--persist <file>] [run <script>]";
+
+/// Options from the command line.
+struct Args {
+    json: bool,
+    undirected: bool,
+    input: String,
+    output: String,
+    /// Snapshot of a persistent session, with its log next to it.
+    persist: Option<String>,
+    script: Option<String>,
+}
 
-        if undirected && !printed.contains(&(target, origin, cost)) {
-            printed.insert((origin, target, cost));
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        json: false,
+        undirected: false,
+        input: "graph1k.txt".to_string(),
+        output: "graph1k_modif.txt".to_string(),
+        persist: None,
+        script: None,
+    };
+    let mut words = std::env::args().skip(1);
+    while let Some(word) = words.next() {
+        let mut value = || words.next().ok_or_else(|| format!("{} needs a file", word));
+        match word.as_str() {
+            "--json" => args.json = true,
+            "--undirected" => args.undirected = true,
+            "--input" => args.input = value()?,
+            "--output" => args.output = value()?,
+            "--persist" => args.persist = Some(value()?),
+            "run" => args.script = Some(value()?),
+            _ => return Err(format!("unknown argument {}", word)),
         }
     }
+    Ok(args)
+}
 
-    write!(writer, "{} {}\n", graph.vertex_count(), printed.len()).unwrap();
-
-    for (o, t, c) in printed.iter() {
-        write!(writer, "{} {} {}\n", o, t, c).unwrap();
+/// Opens the persistent graph, starting from the input file
+/// the first time.
+fn open_persistent(snapshot: &Path, args: &Args) -> std::io::Result<PersistentGraph<u32, u32>> {
+    let log = snapshot.with_extension("log");
+    let fresh = !snapshot.exists() && !log.exists();
+    let mut graph = PersistentGraph::open(snapshot, log)?;
+    if fresh {
+        graph.replace(read_graph(Path::new(&args.input), args.undirected))?;
     }
+    Ok(graph)
+}
 
-    for &v in graph.vertices() {
-        if graph.outdegree(v) == 0 && graph.indegree(v) == 0 {
-            write!(writer, "{}\n", v).unwrap();
+/// Runs the script and exits, with status 1 if a command failed.
+fn run_script(engine: &mut CommandEngine, path: &str) {
+    let script = match std::fs::File::open(path) {
+        Ok(file) => std::io::BufReader::new(file),
+        Err(err) => {
+            eprintln!("Could not read {}: {}", path, err);
+            std::process::exit(2);
         }
+    };
+    let stdout = std::io::stdout();
+    let failed = engine.run_script(script, &mut stdout.lock()).unwrap_or_else(|err| {
+        eprintln!("Error: {}", err);
+        1
+    });
+    if let Err(err) = engine.save() {
+        eprintln!("Could not save: {}", err);
+        std::process::exit(1);
     }
-}
-
-
-fn show_help() {
-    println!("add_edge <origin> <dest> <cost>");
-    println!("remove_edge <origin> <dest>");
-    println!("get_edge <origin> <dest>");
-    println!("remove_node <vertex>");
-    println!("add_vertex <vertex>");
-    println!("indegree <vertex>");
-    println!("outdegree <vertex>");
-    println!("inbound <vertex>");
-    println!("outbound <vertex>");
-    println!("vertex_count");
-    println!("edge_count");
-    println!("print_graph");
-    println!("contains_edge");
-    println!("connected_components");
-    println!("dijkstra <origin> <dest>");
+    std::process::exit(if failed > 0 { 1 } else { 0 });
 }
 
 fn main() {
-    let undirected = false;
-    let in_file: &str = "graph1k.txt";   
-    let out_file: &str = "graph1k_modif.txt";
-    let mut graph = read_graph(Path::new(in_file), undirected);
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let mut engine = match &args.persist {
+        Some(snapshot) => match open_persistent(Path::new(snapshot), &args) {
+            Ok(graph) => CommandEngine::persistent(graph, &args.output, args.undirected),
+            Err(err) => {
+                eprintln!("Could not open {}: {}", snapshot, err);
+                std::process::exit(2);
+            }
+        },
+        None => {
+            let graph = read_graph(Path::new(&args.input), args.undirected);
+            CommandEngine::new(graph, &args.output, args.undirected)
+        }
+    };
+    engine.set_json(args.json);
+    if let Some(script) = &args.script {
+        run_script(&mut engine, script);
+    }
 
     let mut rl = Editor::<()>::new();
 
-    if rl.load_history("history.txt").is_err() {
+    if rl.load_history("history.txt").is_err() && !engine.is_json() {
         println!("No previous history.");
     }
 
     loop {
-        let readline = rl.readline(">> ");
+        let readline = rl.readline(if engine.is_json() { "" } else { ">> " });
         match readline {
             Ok(line) => {
-                
-                let line_split: Vec<&str> = line.split(" ").collect();
-                match line_split[0] {
-                    "help" => show_help(),
-                    "add_vertex" => {
-                        let first = str::parse::<u32>(line_split[1]).unwrap();
-                        graph.add_vertex(first);
-                    }
-                    "add_edge" => {
-                        let first = str::parse::<u32>(line_split[1]).unwrap();
-                        let second = str::parse::<u32>(line_split[2]).unwrap();
-                        let third = str::parse::<u32>(line_split[3]).unwrap();
-                        graph.add_edge((first,second),third);                     
-                    },
-                    "get_edge" => {
-                        let first = str::parse::<u32>(line_split[1]).unwrap();
-                        let second = str::parse::<u32>(line_split[2]).unwrap();
-                        println!("{:?}", graph.get_edge((first,second)));
-                    }
-                    "remove_edge" => {
-                        let first = str::parse::<u32>(line_split[1]).unwrap();
-                        let second = str::parse::<u32>(line_split[2]).unwrap();
-                        graph.remove_edge((first,second));
-                    },
-                    "remove_node" => {
-                        let first = str::parse::<u32>(line_split[1]).unwrap();
-                        graph.remove_vertex(first);
-                    },
-                    "indegree" => {
-                        let first = str::parse::<u32>(line_split[1]).unwrap();
-                        println!("{}", graph.indegree(first));
-                    },
-                    "outdegree" => {
-                        let first = str::parse::<u32>(line_split[1]).unwrap();
-                        println!("{}", graph.outdegree(first));
-                    },
-                    "outbound" => {
-                        let first = str::parse::<u32>(line_split[1]).unwrap();
-                        for (v, w) in graph.adj_out(first).unwrap() {
-                            println!("{} {}", v, w);
-                        }
-                    },
-                    "inbound" => {
-                        let first = str::parse::<u32>(line_split[1]).unwrap();
-                        for (v, w) in graph.adj_in(first).unwrap() {
-                            println!("{} {}", v, w);
-                        }
-                    },
-                    "vertex_count" => {
-                        println!("{}", graph.vertex_count());
-                    },
-                    "edge_count" => {
-                        println!("{}", graph.edge_count());
-                    },                
-                    "print_graph" => {
-                        write_graph(&mut std::io::stdout(), &graph, undirected);
-                    },
-                    "contains_edge" => {
-                        let first = str::parse::<u32>(line_split[1]).unwrap();
-                        let second = str::parse::<u32>(line_split[2]).unwrap();
-                        println!("{}", graph.contains_edge((first,second)));
-                    }
-                    "connected_components" => {
-                        let components = graph.connected_components();
-                        for g in components.iter() {
-
-                            println!("Component: ");
-                            write_graph(&mut std::io::stdout(), &g, undirected);
-                            // for v in g.vertices() {
-                            //     print!("{} ", v);
-                            // }
-                            // println!("");
-                        }
-                    }
-                    "dijkstra" => {
-                        let first = str::parse::<u32>(line_split[1]).unwrap();
-                        let second = str::parse::<u32>(line_split[2]).unwrap();
-
-                        let (path, cost) = graph.dijkstra(first, second).unwrap();
-                        print!("Path: ");
-                        for node in path.iter() {
-                            print!("{} ", node);
+                let words: Vec<&str> = line.split_whitespace().collect();
+                match words.first() {
+                    Some(&"trace") => {
+                        let stdin = std::io::stdin();
+                        let stdout = std::io::stdout();
+                        if let Err(err) = engine.trace(&line, &mut stdin.lock(), &mut stdout.lock()) {
+                            print!("{}", engine.render(&Output::Error(format!("Error: {}", err))));
                         }
-                        println!("\nTotal cost: {}", cost);
                     }
                     _ => {
-                        println!("No such command");
-                        continue;
+                        let output = engine.execute(&line);
+                        print!("{}", engine.render(&output));
+                        if let Err(err) = engine.autosave() {
+                            print!("{}", engine.render(&Output::Error(format!("Autosave failed: {}", err))));
+                        }
                     }
                 }
-                
+
                 rl.add_history_entry(line.as_str());
             },
             Err(ReadlineError::Interrupted) => {
-                println!("CTRL-C");
+                if !engine.is_json() {
+                    println!("CTRL-C");
+                }
                 break
             },
             Err(ReadlineError::Eof) => {
-                println!("CTRL-D");
+                if !engine.is_json() {
+                    println!("CTRL-D");
+                }
                 break
             },
             Err(err) => {
-                println!("Error: {:?}", err);
+                print!("{}", engine.render(&Output::Error(format!("Error: {:?}", err))));
                 break
             }
         }
     }
     rl.save_history("history.txt").unwrap();
 
-    let mut file = std::fs::File::create(Path::new(out_file)).unwrap();
-    write_graph(&mut file, &graph, undirected);
+    if let Err(err) = engine.save() {
+        print!("{}", engine.render(&Output::Error(format!("Could not save {}: {}", args.output, err))));
+    }
 
 }
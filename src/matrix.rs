@@ -0,0 +1,199 @@
+//! Dense matrices of a graph, for spectral methods: the adjacency
+//! matrix and the Laplacians. Rows and columns follow the order of
+//! [`Matrix::order`], which is the arena order.
+
+use super::{Graph, GraphMap, VertexId};
+use super::algorithms::Indexed;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Square matrix with a row and a column for every vertex.
+/// `T` identifies the vertices, [`VertexId`] or the vertex data.
+#[derive(Clone, Debug)]
+pub struct Matrix<T> {
+    /// Vertex of every row and column.
+    order: Vec<T>,
+    /// Row and column of every vertex.
+    index: HashMap<T, usize>,
+    /// Rows of the matrix, `values[i][j]` being the value at the row
+    /// of `order()[i]` and the column of `order()[j]`.
+    pub values: Vec<Vec<f64>>,
+}
+
+impl<T: Eq + Hash + Clone> Matrix<T> {
+
+    fn new(order: Vec<T>, values: Vec<Vec<f64>>) -> Self {
+        let index = order.iter().cloned().enumerate().map(|(i, v)| (v, i)).collect();
+        Matrix {
+            order,
+            index,
+            values,
+        }
+    }
+
+    fn map<U: Eq + Hash + Clone, F: Fn(T) -> U>(self, f: F) -> Matrix<U> {
+        Matrix::new(self.order.into_iter().map(f).collect(), self.values)
+    }
+}
+
+impl<T: Eq + Hash> Matrix<T> {
+
+    /// Vertex of every row and column.
+    pub fn order(&self) -> &[T] {
+        &self.order
+    }
+
+    /// Row and column of every vertex.
+    pub fn index(&self) -> &HashMap<T, usize> {
+        &self.index
+    }
+
+    /// Takes the matrix apart into [`Matrix::order`] and the values.
+    pub fn into_parts(self) -> (Vec<T>, Vec<Vec<f64>>) {
+        (self.order, self.values)
+    }
+
+    /// Value at the row of `from` and the column of `to`.
+    /// Time complexity: O(1)
+    pub fn get(&self, from: &T, to: &T) -> Option<f64> {
+        let row = *self.index.get(from)?;
+        let column = *self.index.get(to)?;
+        Some(self.values[row][column])
+    }
+}
+
+impl<T: PartialEq> PartialEq for Matrix<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.order == other.order && self.values == other.values
+    }
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Adjacency matrix with 1 at `(from, to)` for every edge, so it is
+    /// symmetric only if the graph is.
+    /// Time complexity: O(V^2 + E)
+    pub fn to_adjacency_matrix(&self) -> Matrix<VertexId> {
+        self.to_weighted_adjacency_matrix(|_| 1.0)
+    }
+
+    /// Adjacency matrix with `weight` of the edge at `(from, to)`.
+    /// Time complexity: O(V^2 + E)
+    pub fn to_weighted_adjacency_matrix<F: FnMut(&E) -> f64>(&self, mut weight: F) -> Matrix<VertexId> {
+        let order: Vec<VertexId> = self.vertices().map(|(id, _)| id).collect();
+        let index: HashMap<VertexId, usize> = order.iter()
+            .enumerate()
+            .map(|(i, &id)| (id, i))
+            .collect();
+        let mut values = vec![vec![0.0; order.len()]; order.len()];
+        for (&(from, to), data) in self.edges() {
+            values[index[&from]][index[&to]] = weight(data);
+        }
+        Matrix::new(order, values)
+    }
+
+    /// Laplacian matrix `D - A` of the graph with the edge directions
+    /// ignored: `A` has 1 for every pair of vertices joined either way,
+    /// and `D` the number of neighbours on the diagonal. Self loops are
+    /// left out. Symmetric, with rows summing to 0.
+    /// Time complexity: O(V^2 + E log E)
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let graph = GraphMap::<&str, ()>::from_edges(vec![(("a", "b"), ()), (("b", "a"), ()), (("b", "c"), ())]);
+    /// let laplacian = graph.to_laplacian_matrix();
+    /// assert_eq!(laplacian.order(), &[&"a", &"b", &"c"]);
+    /// assert_eq!(laplacian.values, vec![
+    ///     vec![1.0, -1.0, 0.0],
+    ///     vec![-1.0, 2.0, -1.0],
+    ///     vec![0.0, -1.0, 1.0],
+    /// ]);
+    /// assert_eq!(laplacian.get(&&"c", &&"b"), Some(-1.0));
+    /// ```
+    pub fn to_laplacian_matrix(&self) -> Matrix<VertexId> {
+        let Indexed { ids, edges } = self.indexed();
+        let n = ids.len();
+        let mut values = vec![vec![0.0; n]; n];
+        for &(i, j) in edges.iter() {
+            if values[i][j] == 0.0 {
+                values[i][j] = -1.0;
+                values[j][i] = -1.0;
+                values[i][i] += 1.0;
+                values[j][j] += 1.0;
+            }
+        }
+        Matrix::new(ids, values)
+    }
+
+    /// Normalized Laplacian `I - D^-1/2 A D^-1/2`, with `A` and `D` as in
+    /// [`Graph::to_laplacian_matrix`]. Its eigenvalues are between 0 and
+    /// 2. Vertices without neighbours get a row and a column of zeros.
+    /// Time complexity: O(V^2 + E log E)
+    pub fn to_normalized_laplacian_matrix(&self) -> Matrix<VertexId> {
+        let mut laplacian = self.to_laplacian_matrix();
+        let scale: Vec<f64> = (0..laplacian.order.len())
+            .map(|i| laplacian.values[i][i])
+            .map(|degree| if degree > 0.0 { 1.0 / degree.sqrt() } else { 0.0 })
+            .collect();
+        for (i, row) in laplacian.values.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value *= scale[i] * scale[j];
+            }
+        }
+        laplacian
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::to_adjacency_matrix`].
+    pub fn to_adjacency_matrix(&self) -> Matrix<&V> {
+        self.graph.to_adjacency_matrix().map(|id| &self.graph.arena[id])
+    }
+
+    /// See [`Graph::to_weighted_adjacency_matrix`].
+    pub fn to_weighted_adjacency_matrix<F: FnMut(&E) -> f64>(&self, weight: F) -> Matrix<&V> {
+        self.graph.to_weighted_adjacency_matrix(weight).map(|id| &self.graph.arena[id])
+    }
+
+    /// See [`Graph::to_laplacian_matrix`].
+    pub fn to_laplacian_matrix(&self) -> Matrix<&V> {
+        self.graph.to_laplacian_matrix().map(|id| &self.graph.arena[id])
+    }
+
+    /// See [`Graph::to_normalized_laplacian_matrix`].
+    pub fn to_normalized_laplacian_matrix(&self) -> Matrix<&V> {
+        self.graph.to_normalized_laplacian_matrix().map(|id| &self.graph.arena[id])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+
+    #[test]
+    fn matrices_of_a_star() {
+        let mut graph = GraphMap::<u32, u32>::from_edges(vec![((0, 1), 2), ((0, 2), 3), ((0, 3), 4), ((3, 0), 5)]);
+        graph.add_vertex(4);
+
+        let adjacency = graph.to_weighted_adjacency_matrix(|&w| w as f64);
+        assert_eq!(adjacency.get(&&0, &&3), Some(4.0));
+        assert_eq!(adjacency.get(&&3, &&0), Some(5.0));
+        assert_eq!(adjacency.get(&&1, &&0), Some(0.0));
+        assert_eq!(adjacency.get(&&1, &&7), None);
+        assert_eq!(graph.to_adjacency_matrix().get(&&0, &&2), Some(1.0));
+
+        let normalized = graph.to_normalized_laplacian_matrix();
+        let index = normalized.index();
+        let expected = -1.0 / 3f64.sqrt();
+        assert!((normalized.get(&&0, &&1).unwrap() - expected).abs() < 1e-12);
+        assert!((normalized.get(&&0, &&0).unwrap() - 1.0).abs() < 1e-12);
+        assert!(normalized.values[index[&&4]].iter().all(|&v| v == 0.0));
+        for i in 0..5 {
+            for j in 0..5 {
+                assert_eq!(normalized.values[i][j], normalized.values[j][i]);
+            }
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Directed graph with parallel edges.
+//!
+//! [`Graph`](crate::Graph) keys its edges by their two ends, so adding a
+//! second edge between the same vertices replaces the first one.
+//! [`MultiGraph`] keeps its edges in an arena instead, and every edge gets
+//! its own [`MultiEdgeId`], so any number of them can join the same pair.
+
+use super::{GraphError, VertexId, Vertices};
+use crate::arena::{Arena, Key};
+use generational_arena::Index;
+use std::collections::{
+    HashMap,
+    HashSet,
+    hash_set,
+};
+
+/// Id of an edge of a [`MultiGraph`], like a [`VertexId`] for the edges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MultiEdgeId(Index);
+
+impl Key for MultiEdgeId {
+    fn from_index(index: Index) -> Self {
+        MultiEdgeId(index)
+    }
+
+    fn index(self) -> Index {
+        self.0
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Edge<E> {
+    from: VertexId,
+    to: VertexId,
+    data: E,
+}
+
+/// Directed graph data structure allowing parallel edges.
+/// [`V`] is the Vertex data, and [`E`] is the Edge data.
+///
+/// ```
+/// use graph::multigraph::MultiGraph;
+///
+/// let mut graph = MultiGraph::<&str, &str>::new();
+/// let a = graph.add_vertex("a");
+/// let b = graph.add_vertex("b");
+/// let bus = graph.add_edge((a, b), "bus 12").unwrap();
+/// graph.add_edge((a, b), "tram 3").unwrap();
+///
+/// assert_eq!(graph.edge_count(), 2);
+/// assert_eq!(graph.edges_between((a, b)).len(), 2);
+/// assert_eq!(graph.remove_edge(bus), Some("bus 12"));
+/// let routes: Vec<_> = graph.adj_out(a).unwrap().map(|(_, to, route)| (to, *route)).collect();
+/// assert_eq!(routes, vec![(b, "tram 3")]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MultiGraph<V, E> {
+    arena: Arena<V>,
+    edges: Arena<Edge<E>, MultiEdgeId>,
+    inbound: HashMap<VertexId, HashSet<MultiEdgeId>>,
+    outbound: HashMap<VertexId, HashSet<MultiEdgeId>>,
+}
+
+impl<V: std::fmt::Debug, E> MultiGraph<V, E> {
+
+    pub fn new() -> Self {
+        MultiGraph {
+            arena: Arena::new(),
+            edges: Arena::new(),
+            inbound: HashMap::new(),
+            outbound: HashMap::new(),
+        }
+    }
+
+    /// Adds a vertex to the graph, and returns its id.
+    pub fn add_vertex(&mut self, vertex: V) -> VertexId {
+        let id = self.arena.insert(vertex);
+        self.inbound.entry(id).or_default();
+        self.outbound.entry(id).or_default();
+        id
+    }
+
+    /// Returns the data in the vertex.
+    pub fn get_vertex(&self, vertex: VertexId) -> Option<&V> {
+        self.arena.get(vertex)
+    }
+
+    /// Returns the data in the vertex, mutably.
+    pub fn get_vertex_mut(&mut self, vertex: VertexId) -> Option<&mut V> {
+        self.arena.get_mut(vertex)
+    }
+
+    /// Adds a new edge, even if the vertices are already joined,
+    /// and returns its id. Fails if either vertex is not in the graph.
+    pub fn add_edge(&mut self, edge: (VertexId, VertexId), data: E) -> Result<MultiEdgeId, GraphError> {
+        let (from, to) = edge;
+        if !self.arena.contains(from) || !self.arena.contains(to) {
+            return Err(GraphError::VertexNotFound);
+        }
+        let id = self.edges.insert(Edge { from, to, data });
+        self.outbound.entry(from).or_default().insert(id);
+        self.inbound.entry(to).or_default().insert(id);
+        Ok(id)
+    }
+
+    /// Get the edge.
+    pub fn get_edge(&self, edge: MultiEdgeId) -> Option<&E> {
+        self.edges.get(edge).map(|edge| &edge.data)
+    }
+
+    /// Get the edge, mutably.
+    pub fn get_edge_mut(&mut self, edge: MultiEdgeId) -> Option<&mut E> {
+        self.edges.get_mut(edge).map(|edge| &mut edge.data)
+    }
+
+    /// The two ends of the edge, as (from, to).
+    pub fn endpoints(&self, edge: MultiEdgeId) -> Option<(VertexId, VertexId)> {
+        self.edges.get(edge).map(|edge| (edge.from, edge.to))
+    }
+
+    /// Ids of all the edges going from the first vertex to the second.
+    /// Time complexity: O(outdegree(from))
+    pub fn edges_between(&self, edge: (VertexId, VertexId)) -> Vec<MultiEdgeId> {
+        let (from, to) = edge;
+        match self.outbound.get(&from) {
+            Some(set) => set.iter().copied().filter(|&id| self.edges[id].to == to).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Removes the vertex and its edges, returning its data.
+    /// Time complexity: O(degree(v))
+    pub fn remove_vertex(&mut self, vertex: VertexId) -> Option<V> {
+        let data = self.arena.remove(vertex)?;
+        let outbound = self.outbound.remove(&vertex).unwrap_or_default();
+        let inbound = self.inbound.remove(&vertex).unwrap_or_default();
+        for id in outbound.into_iter().chain(inbound) {
+            if let Some(edge) = self.edges.remove(id) {
+                if let Some(set) = self.inbound.get_mut(&edge.to) {
+                    set.remove(&id);
+                }
+                if let Some(set) = self.outbound.get_mut(&edge.from) {
+                    set.remove(&id);
+                }
+            }
+        }
+        Some(data)
+    }
+
+    /// Removes an edge, returning its data.
+    /// Time complexity: O(1)
+    pub fn remove_edge(&mut self, edge: MultiEdgeId) -> Option<E> {
+        let Edge { from, to, data } = self.edges.remove(edge)?;
+        self.outbound.get_mut(&from).unwrap().remove(&edge);
+        self.inbound.get_mut(&to).unwrap().remove(&edge);
+        Some(data)
+    }
+
+    /// Returns an iterator over outbound edges, as
+    /// (edge id, target, data), one item per parallel edge.
+    pub fn adj_out(&self, vertex: VertexId) -> Option<MultiAdj<'_, E>> {
+        let outbound = self.outbound.get(&vertex)?;
+        Some(MultiAdj {
+            ids: outbound.iter(),
+            edges: &self.edges,
+            outbound: true,
+        })
+    }
+
+    /// Returns an iterator over inbound edges, as
+    /// (edge id, source, data), one item per parallel edge.
+    pub fn adj_in(&self, vertex: VertexId) -> Option<MultiAdj<'_, E>> {
+        let inbound = self.inbound.get(&vertex)?;
+        Some(MultiAdj {
+            ids: inbound.iter(),
+            edges: &self.edges,
+            outbound: false,
+        })
+    }
+
+    /// Indegree of the vertex, counting parallel edges.
+    pub fn indegree(&self, vertex: VertexId) -> usize {
+        match self.inbound.get(&vertex) {
+            Some(set) => set.len(),
+            None => 0,
+        }
+    }
+
+    /// Outdegree of the vertex, counting parallel edges.
+    pub fn outdegree(&self, vertex: VertexId) -> usize {
+        match self.outbound.get(&vertex) {
+            Some(set) => set.len(),
+            None => 0,
+        }
+    }
+
+    /// Number of vertices
+    pub fn vertex_count(&self) -> usize {
+        self.arena.len()
+    }
+
+    /// Number of edges, counting parallel edges.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Iterator over the vertices
+    pub fn vertices(&self) -> Vertices<'_, V> {
+        Vertices::new(&self.arena)
+    }
+
+    /// Iterator over the edges, as (edge id, (from, to), data).
+    pub fn edges(&self) -> impl Iterator<Item = (MultiEdgeId, (VertexId, VertexId), &E)> {
+        self.edges.iter().map(|(id, edge)| (id, (edge.from, edge.to), &edge.data))
+    }
+}
+
+/// Iterator over the edges of a vertex, see [`MultiGraph::adj_out`]
+/// and [`MultiGraph::adj_in`].
+#[derive(Clone, Debug)]
+pub struct MultiAdj<'a, E> {
+    ids: hash_set::Iter<'a, MultiEdgeId>,
+    edges: &'a Arena<Edge<E>, MultiEdgeId>,
+    outbound: bool,
+}
+
+impl<'a, E> Iterator for MultiAdj<'a, E> {
+    type Item = (MultiEdgeId, VertexId, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let &id = self.ids.next()?;
+        let edge = &self.edges[id];
+        let other = if self.outbound { edge.to } else { edge.from };
+        Some((id, other, &edge.data))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+impl<'a, E> ExactSizeIterator for MultiAdj<'a, E> {}
+
+impl<V: std::fmt::Debug, E> Default for MultiGraph<V, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultiGraph;
+
+    #[test]
+    fn parallel_edges_are_kept() {
+        let mut graph = MultiGraph::<u32, u32>::new();
+        let ids: Vec<_> = (0..3).map(|i| graph.add_vertex(i)).collect();
+        let first = graph.add_edge((ids[0], ids[1]), 1).unwrap();
+        let second = graph.add_edge((ids[0], ids[1]), 2).unwrap();
+        graph.add_edge((ids[1], ids[0]), 3).unwrap();
+        graph.add_edge((ids[1], ids[2]), 4).unwrap();
+        graph.add_edge((ids[2], ids[2]), 5).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(graph.edge_count(), 5);
+        assert_eq!(graph.outdegree(ids[0]), 2);
+        assert_eq!(graph.indegree(ids[1]), 2);
+        assert_eq!(graph.endpoints(second), Some((ids[0], ids[1])));
+        let mut weights: Vec<u32> = graph.adj_in(ids[1]).unwrap().map(|(_, _, &w)| w).collect();
+        weights.sort_unstable();
+        assert_eq!(weights, vec![1, 2]);
+
+        *graph.get_edge_mut(first).unwrap() = 10;
+        assert_eq!(graph.get_edge(first), Some(&10));
+        assert_eq!(graph.remove_vertex(ids[2]), Some(2));
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.outdegree(ids[1]), 1);
+        assert_eq!(graph.remove_vertex(ids[1]), Some(1));
+        assert_eq!(graph.edge_count(), 0);
+        assert_eq!(graph.get_edge(first), None);
+        assert_eq!(graph.outdegree(ids[0]), 0);
+        assert!(graph.add_edge((ids[0], ids[1]), 6).is_err());
+        assert_eq!(graph.edge_count(), 0);
+    }
+}
@@ -0,0 +1,222 @@
+//! Parallel algorithms for large graphs, behind the `rayon` feature.
+//!
+//! Vertices are tracked by their arena slot, so the shared state is a
+//! vector of atomics instead of a locked map. Results are the same as
+//! those of the sequential versions, up to the order inside a level or
+//! a component.
+
+use crate::algorithms::centrality::PageRankConfig;
+use crate::property::VertexMap;
+use crate::{Graph, GraphMap, VertexId};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Dense number of the vertex, below the arena capacity.
+fn slot(id: VertexId) -> usize {
+    <(usize, u64)>::from(id).0
+}
+
+/// Root of the set of `x`, halving the path on the way up. A parent
+/// slot is never larger than its child's, so the walk always ends.
+fn find(parent: &[AtomicUsize], mut x: usize) -> usize {
+    loop {
+        let p = parent[x].load(Ordering::Acquire);
+        if p == x {
+            return x;
+        }
+        let grandparent = parent[p].load(Ordering::Acquire);
+        // Losing the race only skips the shortcut.
+        let _ = parent[x].compare_exchange(p, grandparent, Ordering::AcqRel, Ordering::Acquire);
+        x = grandparent;
+    }
+}
+
+/// Merges the sets of `a` and `b`, the larger root linking to the smaller.
+fn union(parent: &[AtomicUsize], mut a: usize, mut b: usize) {
+    loop {
+        a = find(parent, a);
+        b = find(parent, b);
+        let (low, high) = match a.cmp(&b) {
+            std::cmp::Ordering::Equal => return,
+            std::cmp::Ordering::Less => (a, b),
+            std::cmp::Ordering::Greater => (b, a),
+        };
+        // Fails if `high` got a parent meanwhile, then both are found again.
+        if parent[high].compare_exchange(high, low, Ordering::AcqRel, Ordering::Acquire).is_ok() {
+            return;
+        }
+    }
+}
+
+impl<V: std::fmt::Debug + Sync, E: Sync> Graph<V, E> {
+
+    /// Level synchronous breadth first search: every level is expanded in
+    /// parallel. Returns the vertices by distance from `start`, the first
+    /// level being `start` alone. Empty if `start` is not in the graph.
+    /// Time complexity: O((V + E) / threads + depth)
+    pub fn par_bfs(&self, start: VertexId) -> Vec<Vec<VertexId>> {
+        if self.get_vertex(start).is_none() {
+            return Vec::new();
+        }
+        let visited: Vec<AtomicBool> = (0..self.arena.capacity()).map(|_| AtomicBool::new(false)).collect();
+        visited[slot(start)].store(true, Ordering::Relaxed);
+        let mut levels = vec![vec![start]];
+        loop {
+            let next: Vec<VertexId> = levels.last().unwrap()
+                .par_iter()
+                .flat_map_iter(|v| self.outbound[v].iter().copied())
+                .filter(|&to| !visited[slot(to)].swap(true, Ordering::Relaxed))
+                .collect();
+            if next.is_empty() {
+                return levels;
+            }
+            levels.push(next);
+        }
+    }
+
+    /// Weakly connected components with a concurrent union-find: the
+    /// edges are merged in parallel, every root linking to the smaller
+    /// of the two slots with a compare and swap. Components are sorted
+    /// by their first vertex in arena order, and vertices inside them by
+    /// arena order.
+    /// Time complexity: O((V + E) log V / threads)
+    pub fn par_connected_components(&self) -> Vec<Vec<VertexId>> {
+        let ids: Vec<VertexId> = self.vertices().map(|(id, _)| id).collect();
+        let parent: Vec<AtomicUsize> = (0..self.arena.capacity()).map(AtomicUsize::new).collect();
+        ids.par_iter().for_each(|&v| {
+            for &w in self.outbound[&v].iter() {
+                union(&parent, slot(v), slot(w));
+            }
+        });
+
+        let mut components: Vec<Vec<VertexId>> = Vec::new();
+        let mut position: HashMap<usize, usize> = HashMap::new();
+        for id in ids {
+            let label = find(&parent, slot(id));
+            let i = *position.entry(label).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[i].push(id);
+        }
+        components
+    }
+
+    /// PageRank with every round computed in parallel, pulling the rank
+    /// of the inbound neighbours. See [`Graph::pagerank`].
+    /// Time complexity: O((V + E) / threads * iterations)
+    pub fn par_pagerank(&self, config: PageRankConfig) -> VertexMap<f64> {
+        let indexed = self.indexed();
+        let n = indexed.ids.len();
+        if n == 0 {
+            return VertexMap::new();
+        }
+        let mut sources = vec![Vec::new(); n];
+        let mut outdegree = vec![0usize; n];
+        for &(from, to) in indexed.edges.iter() {
+            sources[to].push(from);
+            outdegree[from] += 1;
+        }
+
+        let mut rank = vec![1.0 / n as f64; n];
+        for _ in 0..config.max_iterations {
+            let dangling: f64 = rank.par_iter()
+                .zip(outdegree.par_iter())
+                .filter(|(_, &degree)| degree == 0)
+                .map(|(&r, _)| r)
+                .sum();
+            let base = (1.0 - config.damping + config.damping * dangling) / n as f64;
+            let next: Vec<f64> = sources.par_iter()
+                .map(|sources| {
+                    let pulled: f64 = sources.iter().map(|&u| rank[u] / outdegree[u] as f64).sum();
+                    base + config.damping * pulled
+                })
+                .collect();
+            let change: f64 = rank.par_iter().zip(next.par_iter()).map(|(a, b)| (a - b).abs()).sum();
+            rank = next;
+            if change < config.tolerance {
+                break;
+            }
+        }
+        indexed.ids.into_iter().zip(rank).collect()
+    }
+}
+
+impl<V, E> GraphMap<V, E>
+where
+    V: Eq + Hash + Clone + std::fmt::Debug + Sync,
+    E: Sync,
+{
+
+    fn vertex(&self, id: VertexId) -> &V {
+        self.graph.get_vertex(id).unwrap()
+    }
+
+    /// See [`Graph::par_bfs`].
+    pub fn par_bfs(&self, start: &V) -> Vec<Vec<&V>> {
+        match self.map.get(start) {
+            Some(&id) => self.graph.par_bfs(id).into_iter()
+                .map(|level| level.into_iter().map(|v| self.vertex(v)).collect())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// See [`Graph::par_connected_components`].
+    pub fn par_connected_components(&self) -> Vec<Vec<&V>> {
+        self.graph.par_connected_components().into_iter()
+            .map(|component| component.into_iter().map(|v| self.vertex(v)).collect())
+            .collect()
+    }
+
+    /// See [`Graph::par_pagerank`].
+    pub fn par_pagerank(&self, config: PageRankConfig) -> HashMap<&V, f64> {
+        self.graph.par_pagerank(config).into_iter()
+            .map(|(id, rank)| (self.vertex(id), rank))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::centrality::PageRankConfig;
+    use crate::generate::gnm;
+    use crate::traversal::{bfs, connected_components};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn parallel_versions_agree() {
+        let mut rng = StdRng::seed_from_u64(8);
+        let graph = gnm(300, 350, &mut rng, |_| 1u32).unwrap();
+        let start = graph.map[&0];
+
+        let levels = graph.graph.par_bfs(start);
+        let mut reached: Vec<_> = levels.concat();
+        let mut expected = bfs(&graph, start);
+        reached.sort();
+        expected.sort();
+        assert_eq!(reached, expected);
+
+        let mut components = graph.graph.par_connected_components();
+        let mut sequential = connected_components(&graph);
+        for component in components.iter_mut().chain(sequential.iter_mut()) {
+            component.sort();
+        }
+        components.sort();
+        sequential.sort();
+        assert_eq!(components, sequential);
+
+        // Two long paths, which label propagation needed thousands of rounds for.
+        let paths = crate::GraphMap::from_edges((0..5000u32).chain(10000..12000).map(|i| ((i, i + 1), ())));
+        let components = paths.par_connected_components();
+        assert_eq!(components.len(), 2);
+        assert_eq!((components[0].len(), components[1].len()), (5001, 2001));
+
+        let config = PageRankConfig::default();
+        let parallel = graph.graph.par_pagerank(config);
+        let rank = graph.graph.pagerank(config);
+        assert!(rank.iter().all(|(&id, r)| (parallel[id] - r).abs() < 1e-9));
+    }
+}
@@ -0,0 +1,287 @@
+//! Attributes attached to vertices and edges from outside the graph.
+//!
+//! [`VertexMap`] and [`EdgeMap`] key their values by id, so colors, flags
+//! or algorithm results can be kept without changing `V` or `E`. Ids are
+//! generational: when a vertex is removed and its slot reused, the new
+//! vertex gets a different id, so an old value never shows up on it.
+//! Values of removed vertices and edges stay until
+//! [`VertexMap::remove_stale`] or [`EdgeMap::remove_stale`] is called.
+
+use crate::{EdgeId, Graph, Map, VertexId};
+use std::collections::{hash_map, HashMap};
+use std::iter::FromIterator;
+use std::ops::Index;
+
+/// Values of type `T` attached to some vertices of a graph.
+///
+/// ```
+/// use graph::Graph;
+///
+/// let mut graph = Graph::<&str, ()>::new();
+/// let a = graph.add_vertex("a");
+/// let b = graph.add_vertex("b");
+///
+/// let mut color = graph.vertex_map::<u8>();
+/// color.insert(a, 1);
+/// graph.remove_vertex(a);
+/// let c = graph.add_vertex("c");
+/// assert_eq!(color.get(c), None);
+///
+/// color.remove_stale(&graph);
+/// assert!(color.is_empty());
+///
+/// let length = graph.vertex_map_with(|_, name| name.len());
+/// assert_eq!(length[b], 1);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VertexMap<T> {
+    values: Map<VertexId, T>,
+}
+
+impl<T> VertexMap<T> {
+
+    pub fn new() -> Self {
+        VertexMap {
+            values: Map::default(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        VertexMap {
+            values: Map::with_capacity_and_hasher(capacity, Default::default()),
+        }
+    }
+
+    /// Sets the value of the vertex, returning the previous one.
+    pub fn insert(&mut self, vertex: VertexId, value: T) -> Option<T> {
+        self.values.insert(vertex, value)
+    }
+
+    pub fn get(&self, vertex: VertexId) -> Option<&T> {
+        self.values.get(&vertex)
+    }
+
+    pub fn get_mut(&mut self, vertex: VertexId) -> Option<&mut T> {
+        self.values.get_mut(&vertex)
+    }
+
+    /// Value of the vertex, inserting `T::default()` if it has none.
+    pub fn get_or_default(&mut self, vertex: VertexId) -> &mut T
+    where
+        T: Default,
+    {
+        self.values.entry(vertex).or_default()
+    }
+
+    pub fn remove(&mut self, vertex: VertexId) -> Option<T> {
+        self.values.remove(&vertex)
+    }
+
+    pub fn contains(&self, vertex: VertexId) -> bool {
+        self.values.contains_key(&vertex)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Iterator over the vertices with a value.
+    pub fn iter(&self) -> hash_map::Iter<'_, VertexId, T> {
+        self.values.iter()
+    }
+
+    /// Drops the values of the vertices no longer in the graph.
+    pub fn remove_stale<V: std::fmt::Debug, E>(&mut self, graph: &Graph<V, E>) {
+        self.values.retain(|&id, _| graph.get_vertex(id).is_some());
+    }
+}
+
+/// Values of type `T` attached to some edges of a graph, see [`VertexMap`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EdgeMap<T> {
+    values: Map<EdgeId, T>,
+}
+
+impl<T> EdgeMap<T> {
+
+    pub fn new() -> Self {
+        EdgeMap {
+            values: Map::default(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        EdgeMap {
+            values: Map::with_capacity_and_hasher(capacity, Default::default()),
+        }
+    }
+
+    /// Sets the value of the edge, returning the previous one.
+    pub fn insert(&mut self, edge: EdgeId, value: T) -> Option<T> {
+        self.values.insert(edge, value)
+    }
+
+    pub fn get(&self, edge: EdgeId) -> Option<&T> {
+        self.values.get(&edge)
+    }
+
+    pub fn get_mut(&mut self, edge: EdgeId) -> Option<&mut T> {
+        self.values.get_mut(&edge)
+    }
+
+    /// Value of the edge, inserting `T::default()` if it has none.
+    pub fn get_or_default(&mut self, edge: EdgeId) -> &mut T
+    where
+        T: Default,
+    {
+        self.values.entry(edge).or_default()
+    }
+
+    pub fn remove(&mut self, edge: EdgeId) -> Option<T> {
+        self.values.remove(&edge)
+    }
+
+    pub fn contains(&self, edge: EdgeId) -> bool {
+        self.values.contains_key(&edge)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Iterator over the edges with a value.
+    pub fn iter(&self) -> hash_map::Iter<'_, EdgeId, T> {
+        self.values.iter()
+    }
+
+    /// Drops the values of the edges no longer in the graph. An edge
+    /// removed and added again between the same vertices keeps its value.
+    pub fn remove_stale<V: std::fmt::Debug, E>(&mut self, graph: &Graph<V, E>) {
+        self.values.retain(|&edge, _| graph.get_edge(edge).is_some());
+    }
+}
+
+impl<T> Default for VertexMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Default for EdgeMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<VertexId> for VertexMap<T> {
+    type Output = T;
+
+    /// Panics if the vertex has no value.
+    fn index(&self, vertex: VertexId) -> &T {
+        &self.values[&vertex]
+    }
+}
+
+impl<T> Index<EdgeId> for EdgeMap<T> {
+    type Output = T;
+
+    /// Panics if the edge has no value.
+    fn index(&self, edge: EdgeId) -> &T {
+        &self.values[&edge]
+    }
+}
+
+impl<T> FromIterator<(VertexId, T)> for VertexMap<T> {
+    fn from_iter<I: IntoIterator<Item = (VertexId, T)>>(iter: I) -> Self {
+        VertexMap {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> FromIterator<(EdgeId, T)> for EdgeMap<T> {
+    fn from_iter<I: IntoIterator<Item = (EdgeId, T)>>(iter: I) -> Self {
+        EdgeMap {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> From<HashMap<VertexId, T>> for VertexMap<T> {
+    fn from(values: HashMap<VertexId, T>) -> Self {
+        VertexMap { values: values.into_iter().collect() }
+    }
+}
+
+impl<T> From<HashMap<EdgeId, T>> for EdgeMap<T> {
+    fn from(values: HashMap<EdgeId, T>) -> Self {
+        EdgeMap { values: values.into_iter().collect() }
+    }
+}
+
+impl<T> IntoIterator for VertexMap<T> {
+    type Item = (VertexId, T);
+    type IntoIter = hash_map::IntoIter<VertexId, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl<T> IntoIterator for EdgeMap<T> {
+    type Item = (EdgeId, T);
+    type IntoIter = hash_map::IntoIter<EdgeId, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a VertexMap<T> {
+    type Item = (&'a VertexId, &'a T);
+    type IntoIter = hash_map::Iter<'a, VertexId, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a EdgeMap<T> {
+    type Item = (&'a EdgeId, &'a T);
+    type IntoIter = hash_map::Iter<'a, EdgeId, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Empty [`VertexMap`], with room for every vertex.
+    pub fn vertex_map<T>(&self) -> VertexMap<T> {
+        VertexMap::with_capacity(self.vertex_count())
+    }
+
+    /// [`VertexMap`] with the value `f` gives every vertex.
+    pub fn vertex_map_with<T, F: FnMut(VertexId, &V) -> T>(&self, mut f: F) -> VertexMap<T> {
+        self.vertices().map(|(id, vertex)| (id, f(id, vertex))).collect()
+    }
+
+    /// Empty [`EdgeMap`], with room for every edge.
+    pub fn edge_map<T>(&self) -> EdgeMap<T> {
+        EdgeMap::with_capacity(self.edge_count())
+    }
+
+    /// [`EdgeMap`] with the value `f` gives every edge.
+    pub fn edge_map_with<T, F: FnMut(EdgeId, &E) -> T>(&self, mut f: F) -> EdgeMap<T> {
+        self.edges().map(|(&edge, weight)| (edge, f(edge, weight))).collect()
+    }
+}
@@ -0,0 +1,239 @@
+
+//! Chainable traversal queries, in the style of Gremlin.
+//!
+//! ```
+//! use graph::GraphMap;
+//!
+//! let mut graph = GraphMap::<u32, u32>::new();
+//! graph.add_edge((0, 1), 5);
+//! graph.add_edge((1, 2), 2);
+//! graph.add_edge((1, 3), 7);
+//!
+//! let targets: Vec<&u32> = graph.v(&0).unwrap()
+//!     .out()
+//!     .out_e()
+//!     .has_weight(|&w| w > 3)
+//!     .target()
+//!     .values();
+//! assert_eq!(targets, vec![&3]);
+//! ```
+//!
+//! Every step is lazy, nothing is computed until the query
+//! is collected or iterated.
+//!
+//! The [`text`] module adds a small textual language on top.
+pub mod text;
+
+use super::{Graph, GraphMap, VertexId, EdgeId};
+use std::hash::Hash;
+use std::collections::HashSet;
+
+/// A query whose current position is a stream of vertices.
+pub struct VertexQuery<'a, V, E> {
+    graph: &'a Graph<V, E>,
+    iter: Box<dyn Iterator<Item = VertexId> + 'a>,
+}
+
+/// A query whose current position is a stream of edges.
+pub struct EdgeQuery<'a, V, E> {
+    graph: &'a Graph<V, E>,
+    iter: Box<dyn Iterator<Item = (EdgeId, &'a E)> + 'a>,
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Starts a query on a single vertex.
+    pub fn v(&self, start: VertexId) -> VertexQuery<'_, V, E> {
+        let iter = self.arena.contains(start).then_some(start).into_iter();
+        VertexQuery::with(self, iter)
+    }
+
+    /// Starts a query on all the vertices.
+    pub fn vs(&self) -> VertexQuery<'_, V, E> {
+        VertexQuery::with(self, self.arena.iter().map(|(id, _)| id))
+    }
+
+    /// Starts a query on all the edges.
+    pub fn es(&self) -> EdgeQuery<'_, V, E> {
+        EdgeQuery::with(self, self.edges.iter().map(|(&edge, e)| (edge, e)))
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// Starts a query on a single vertex.
+    /// Returns `None` if the vertex is not in the graph.
+    pub fn v(&self, start: &V) -> Option<VertexQuery<'_, V, E>> {
+        let id = *self.map.get(start)?;
+        Some(self.graph.v(id))
+    }
+
+    /// Starts a query on all the vertices.
+    pub fn vs(&self) -> VertexQuery<'_, V, E> {
+        self.graph.vs()
+    }
+
+    /// Starts a query on all the edges.
+    pub fn es(&self) -> EdgeQuery<'_, V, E> {
+        self.graph.es()
+    }
+}
+
+impl<'a, V: 'a, E: 'a> VertexQuery<'a, V, E> {
+
+    fn with<I: Iterator<Item = VertexId> + 'a>(graph: &'a Graph<V, E>, iter: I) -> Self {
+        VertexQuery {
+            graph,
+            iter: Box::new(iter),
+        }
+    }
+
+    /// Moves to the targets of the outbound edges.
+    pub fn out(self) -> Self {
+        let graph = self.graph;
+        let iter = self.iter.flat_map(move |v| graph.outbound[&v].iter().copied());
+        VertexQuery::with(graph, iter)
+    }
+
+    /// Moves to the sources of the inbound edges.
+    pub fn in_(self) -> Self {
+        let graph = self.graph;
+        let iter = self.iter.flat_map(move |v| graph.inbound[&v].iter().copied());
+        VertexQuery::with(graph, iter)
+    }
+
+    /// Moves to all the neighbours, regardless of direction.
+    pub fn both(self) -> Self {
+        let graph = self.graph;
+        let iter = self.iter.flat_map(move |v| {
+            graph.outbound[&v].iter().chain(graph.inbound[&v].iter()).copied()
+        });
+        VertexQuery::with(graph, iter)
+    }
+
+    /// Moves to the outbound edges.
+    pub fn out_e(self) -> EdgeQuery<'a, V, E> {
+        let graph = self.graph;
+        let iter = self.iter.flat_map(move |from| {
+            graph.outbound[&from].iter().map(move |&to| ((from, to), &graph.edges[&(from, to)]))
+        });
+        EdgeQuery::with(graph, iter)
+    }
+
+    /// Moves to the inbound edges.
+    pub fn in_e(self) -> EdgeQuery<'a, V, E> {
+        let graph = self.graph;
+        let iter = self.iter.flat_map(move |to| {
+            graph.inbound[&to].iter().map(move |&from| ((from, to), &graph.edges[&(from, to)]))
+        });
+        EdgeQuery::with(graph, iter)
+    }
+
+    /// Keeps the vertices whose data matches the predicate.
+    pub fn filter<F: Fn(&V) -> bool + 'a>(self, predicate: F) -> Self {
+        let graph = self.graph;
+        let iter = self.iter.filter(move |&v| predicate(&graph.arena[v]));
+        VertexQuery::with(graph, iter)
+    }
+
+    /// Keeps the vertices whose id matches the predicate.
+    pub fn filter_id<F: Fn(VertexId) -> bool + 'a>(self, predicate: F) -> Self {
+        let iter = self.iter.filter(move |&v| predicate(v));
+        VertexQuery::with(self.graph, iter)
+    }
+
+    /// Drops the vertices that were already seen in this stream.
+    pub fn dedup(self) -> Self {
+        let mut seen = HashSet::new();
+        let iter = self.iter.filter(move |&v| seen.insert(v));
+        VertexQuery::with(self.graph, iter)
+    }
+
+    /// Keeps at most `n` vertices.
+    pub fn limit(self, n: usize) -> Self {
+        let iter = self.iter.take(n);
+        VertexQuery::with(self.graph, iter)
+    }
+
+    /// Runs the query, returning the ids of the vertices reached.
+    pub fn collect(self) -> Vec<VertexId> {
+        self.iter.collect()
+    }
+
+    /// Runs the query, returning the data of the vertices reached.
+    pub fn values(self) -> Vec<&'a V> {
+        let graph = self.graph;
+        self.iter.map(|v| &graph.arena[v]).collect()
+    }
+
+    /// Runs the query, returning the number of vertices reached.
+    pub fn count(self) -> usize {
+        self.iter.count()
+    }
+}
+
+impl<'a, V: 'a, E: 'a> EdgeQuery<'a, V, E> {
+
+    fn with<I: Iterator<Item = (EdgeId, &'a E)> + 'a>(graph: &'a Graph<V, E>, iter: I) -> Self {
+        EdgeQuery {
+            graph,
+            iter: Box::new(iter),
+        }
+    }
+
+    /// Keeps the edges whose weight matches the predicate.
+    pub fn has_weight<F: Fn(&E) -> bool + 'a>(self, predicate: F) -> Self {
+        let iter = self.iter.filter(move |&(_, e)| predicate(e));
+        EdgeQuery::with(self.graph, iter)
+    }
+
+    /// Keeps the edges matching the predicate.
+    pub fn filter<F: Fn(EdgeId, &E) -> bool + 'a>(self, predicate: F) -> Self {
+        let iter = self.iter.filter(move |&(edge, e)| predicate(edge, e));
+        EdgeQuery::with(self.graph, iter)
+    }
+
+    /// Keeps at most `n` edges.
+    pub fn limit(self, n: usize) -> Self {
+        let iter = self.iter.take(n);
+        EdgeQuery::with(self.graph, iter)
+    }
+
+    /// Moves to the vertices the edges point to.
+    pub fn target(self) -> VertexQuery<'a, V, E> {
+        VertexQuery::with(self.graph, self.iter.map(|((_, to), _)| to))
+    }
+
+    /// Moves to the vertices the edges start from.
+    pub fn source(self) -> VertexQuery<'a, V, E> {
+        VertexQuery::with(self.graph, self.iter.map(|((from, _), _)| from))
+    }
+
+    /// Runs the query, returning the edges reached and their data.
+    pub fn collect(self) -> Vec<(EdgeId, &'a E)> {
+        self.iter.collect()
+    }
+
+    /// Runs the query, returning the number of edges reached.
+    pub fn count(self) -> usize {
+        self.iter.count()
+    }
+}
+
+impl<'a, V, E> IntoIterator for VertexQuery<'a, V, E> {
+    type Item = VertexId;
+    type IntoIter = Box<dyn Iterator<Item = VertexId> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter
+    }
+}
+
+impl<'a, V, E> IntoIterator for EdgeQuery<'a, V, E> {
+    type Item = (EdgeId, &'a E);
+    type IntoIter = Box<dyn Iterator<Item = (EdgeId, &'a E)> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter
+    }
+}
@@ -0,0 +1,506 @@
+//! A tiny Cypher-like query language.
+//!
+//! ```text
+//! match (a)-[w]->(b)<-[x]-(c:7) where w > 5 and a != 3 return a, w, b
+//! ```
+//!
+//! * `(a)` binds a vertex to `a`, `(a:7)` also requires its data to be `7`.
+//! * `-[w]->` follows an outbound edge, `<-[w]-` an inbound one,
+//!   the name of the edge is optional: `-[]->`.
+//! * `where` takes conditions joined by `and`, each comparing a
+//!   variable with a literal using `=`, `!=`, `<`, `<=`, `>` or `>=`.
+//!   Quoted literals (`"New York"`) may contain spaces.
+//! * `return` lists the variables to output, one row per match.
+//!
+//! A variable used twice must bind the same vertex, so
+//! `match (a)-[]->(b)-[]->(a) return a, b` finds the 2-cycles.
+//!
+//! Queries are run one hop at a time with the [query DSL](super).
+
+use crate::{Graph, GraphMap, VertexId, EdgeId};
+use std::fmt;
+use std::hash::Hash;
+use std::str::FromStr;
+use std::collections::HashMap;
+
+/// Error returned for queries that can not be parsed or
+/// refer to unknown variables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryError(pub String);
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "query error: {}", self.0)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// A value in a result row.
+#[derive(Debug, PartialEq)]
+pub enum Value<'a, V, E> {
+    Vertex(VertexId, &'a V),
+    Edge(EdgeId, &'a E),
+}
+
+impl<'a, V: fmt::Display, E: fmt::Display> fmt::Display for Value<'a, V, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Vertex(_, v) => write!(f, "{}", v),
+            Value::Edge(_, e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Result of a query: the returned variable names,
+/// and one row of values per match.
+#[derive(Debug)]
+pub struct QueryResult<'a, V, E> {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value<'a, V, E>>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn test<T: PartialOrd>(self, a: &T, b: &T) -> bool {
+        match self {
+            Op::Eq => a == b,
+            Op::Ne => a != b,
+            Op::Lt => a < b,
+            Op::Le => a <= b,
+            Op::Gt => a > b,
+            Op::Ge => a >= b,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct NodePattern {
+    name: Option<String>,
+    value: Option<String>,
+}
+
+#[derive(Debug)]
+struct EdgePattern {
+    name: Option<String>,
+    outbound: bool,
+}
+
+#[derive(Debug)]
+struct Condition {
+    variable: String,
+    op: Op,
+    literal: String,
+}
+
+#[derive(Debug)]
+struct Query {
+    nodes: Vec<NodePattern>,
+    edges: Vec<EdgePattern>,
+    conditions: Vec<Condition>,
+    returns: Vec<String>,
+}
+
+/// A partial match: the vertices and edges bound so far, in pattern order.
+#[derive(Clone)]
+struct Row {
+    nodes: Vec<VertexId>,
+    edges: Vec<EdgeId>,
+}
+
+type Predicate<'a, T> = Box<dyn Fn(&T) -> bool + 'a>;
+
+impl<V, E> Graph<V, E>
+where
+    V: std::fmt::Debug + FromStr + PartialOrd,
+    E: FromStr + PartialOrd,
+{
+    /// Runs a textual query, see the [module](self) documentation
+    /// for the syntax.
+    pub fn query(&self, text: &str) -> Result<QueryResult<'_, V, E>, QueryError> {
+        let query = parse(text)?;
+
+        // where each variable is bound: (is_node, position in the pattern)
+        let mut bound = HashMap::new();
+        for (i, node) in query.nodes.iter().enumerate() {
+            if let Some(name) = &node.name {
+                bound.entry(name.clone()).or_insert((true, i));
+            }
+        }
+        for (i, edge) in query.edges.iter().enumerate() {
+            if let Some(name) = &edge.name {
+                if bound.insert(name.clone(), (false, i)).is_some() {
+                    return Err(QueryError(format!("variable `{}` is bound twice", name)));
+                }
+            }
+        }
+        for variable in query.conditions.iter().map(|c| &c.variable).chain(query.returns.iter()) {
+            if !bound.contains_key(variable) {
+                return Err(QueryError(format!("unknown variable `{}`", variable)));
+            }
+        }
+
+        let mut node_filters: Vec<Vec<Predicate<'_, V>>> = query.nodes.iter().map(|_| Vec::new()).collect();
+        let mut edge_filters: Vec<Vec<Predicate<'_, E>>> = query.edges.iter().map(|_| Vec::new()).collect();
+        for (i, node) in query.nodes.iter().enumerate() {
+            if let Some(literal) = &node.value {
+                let value = parse_literal::<V>(literal)?;
+                node_filters[i].push(Box::new(move |v: &V| *v == value));
+            }
+        }
+        for condition in query.conditions.iter() {
+            let op = condition.op;
+            match bound[&condition.variable] {
+                (true, _) => {
+                    // a node variable can appear several times, filter all of them
+                    for (i, node) in query.nodes.iter().enumerate() {
+                        if node.name.as_ref() == Some(&condition.variable) {
+                            let value = parse_literal::<V>(&condition.literal)?;
+                            node_filters[i].push(Box::new(move |v: &V| op.test(v, &value)));
+                        }
+                    }
+                }
+                (false, i) => {
+                    let value = parse_literal::<E>(&condition.literal)?;
+                    edge_filters[i].push(Box::new(move |e: &E| op.test(e, &value)));
+                }
+            }
+        }
+
+        let first = &node_filters[0];
+        let mut rows: Vec<Row> = self.vs()
+            .filter(move |v| first.iter().all(|f| f(v)))
+            .collect()
+            .into_iter()
+            .map(|v| Row { nodes: vec![v], edges: Vec::new() })
+            .collect();
+
+        for (hop, edge) in query.edges.iter().enumerate() {
+            let filters = &edge_filters[hop];
+            let next_filters = &node_filters[hop + 1];
+            let same_as = query.nodes[hop + 1].name.as_ref()
+                .and_then(|name| query.nodes[..=hop].iter().position(|n| n.name.as_ref() == Some(name)));
+
+            let mut next_rows = Vec::new();
+            for row in rows {
+                let current = *row.nodes.last().unwrap();
+                let step = if edge.outbound { self.v(current).out_e() } else { self.v(current).in_e() };
+                let found = step.has_weight(move |e| filters.iter().all(|f| f(e))).collect();
+                for ((from, to), _) in found {
+                    let next = if edge.outbound { to } else { from };
+                    if let Some(i) = same_as {
+                        if row.nodes[i] != next {
+                            continue;
+                        }
+                    }
+                    if !next_filters.iter().all(|f| f(&self.arena[next])) {
+                        continue;
+                    }
+                    let mut row = row.clone();
+                    row.nodes.push(next);
+                    row.edges.push((from, to));
+                    next_rows.push(row);
+                }
+            }
+            rows = next_rows;
+        }
+
+        let rows = rows.into_iter()
+            .map(|row| {
+                query.returns.iter()
+                    .map(|name| match bound[name] {
+                        (true, i) => Value::Vertex(row.nodes[i], &self.arena[row.nodes[i]]),
+                        (false, i) => Value::Edge(row.edges[i], &self.edges[&row.edges[i]]),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(QueryResult {
+            columns: query.returns,
+            rows,
+        })
+    }
+}
+
+impl<V, E> GraphMap<V, E>
+where
+    V: Eq + Hash + Clone + std::fmt::Debug + FromStr + PartialOrd,
+    E: FromStr + PartialOrd,
+{
+    /// Runs a textual query, see [`Graph::query`].
+    pub fn query(&self, text: &str) -> Result<QueryResult<'_, V, E>, QueryError> {
+        self.graph.query(text)
+    }
+}
+
+fn parse_literal<T: FromStr>(literal: &str) -> Result<T, QueryError> {
+    literal.parse()
+        .map_err(|_| QueryError(format!("invalid literal `{}`", literal)))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Symbol(&'static str),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, QueryError> {
+    const SYMBOLS: [&str; 14] = ["->", "<-", "<=", ">=", "!=", "(", ")", "[", "]", "-", ",", ":", "<", ">"];
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+
+    while !rest.is_empty() {
+        if let Some(stripped) = rest.strip_prefix('"') {
+            let end = stripped.find('"')
+                .ok_or_else(|| QueryError(String::from("unterminated string")))?;
+            tokens.push(Token::Quoted(stripped[..end].to_string()));
+            rest = &stripped[end + 1..];
+        } else if let Some(stripped) = rest.strip_prefix('=') {
+            tokens.push(Token::Symbol("="));
+            rest = stripped;
+        } else if let Some(&symbol) = SYMBOLS.iter().find(|&&s| rest.starts_with(s)) {
+            // a minus directly followed by a digit is a negative number, not an edge
+            let negative = symbol == "-" && rest[1..].starts_with(|c: char| c.is_ascii_digit())
+                && !matches!(tokens.last(), Some(Token::Symbol("]")));
+            if negative {
+                let end = rest[1..].find(|c: char| !(c.is_alphanumeric() || c == '.' || c == '_'))
+                    .map_or(rest.len(), |i| i + 1);
+                tokens.push(Token::Word(rest[..end].to_string()));
+                rest = &rest[end..];
+            } else {
+                tokens.push(Token::Symbol(symbol));
+                rest = &rest[symbol.len()..];
+            }
+        } else {
+            let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '.' || c == '_'))
+                .unwrap_or(rest.len());
+            if end == 0 {
+                return Err(QueryError(format!("unexpected character `{}`", rest.chars().next().unwrap())));
+            }
+            tokens.push(Token::Word(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, symbol: &'static str) -> Result<(), QueryError> {
+        match self.next() {
+            Some(Token::Symbol(s)) if s == symbol => Ok(()),
+            other => Err(QueryError(format!("expected `{}`, found {}", symbol, describe(other.as_ref())))),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String, QueryError> {
+        match self.next() {
+            Some(Token::Word(w)) if w.starts_with(|c: char| c.is_alphabetic() || c == '_') => Ok(w),
+            other => Err(QueryError(format!("expected a variable, found {}", describe(other.as_ref())))),
+        }
+    }
+
+    fn literal(&mut self) -> Result<String, QueryError> {
+        match self.next() {
+            Some(Token::Word(w)) | Some(Token::Quoted(w)) => Ok(w),
+            other => Err(QueryError(format!("expected a value, found {}", describe(other.as_ref())))),
+        }
+    }
+
+    fn node(&mut self) -> Result<NodePattern, QueryError> {
+        self.expect("(")?;
+        let name = match self.peek() {
+            Some(Token::Word(_)) => Some(self.identifier()?),
+            _ => None,
+        };
+        let value = if self.peek() == Some(&Token::Symbol(":")) {
+            self.position += 1;
+            Some(self.literal()?)
+        } else {
+            None
+        };
+        self.expect(")")?;
+        Ok(NodePattern { name, value })
+    }
+
+    fn edge_name(&mut self) -> Result<Option<String>, QueryError> {
+        self.expect("[")?;
+        let name = match self.peek() {
+            Some(Token::Word(_)) => Some(self.identifier()?),
+            _ => None,
+        };
+        self.expect("]")?;
+        Ok(name)
+    }
+
+    fn edge(&mut self) -> Result<Option<EdgePattern>, QueryError> {
+        match self.peek() {
+            Some(Token::Symbol("-")) => {
+                self.position += 1;
+                let name = self.edge_name()?;
+                self.expect("->")?;
+                Ok(Some(EdgePattern { name, outbound: true }))
+            }
+            Some(Token::Symbol("<-")) => {
+                self.position += 1;
+                let name = self.edge_name()?;
+                self.expect("-")?;
+                Ok(Some(EdgePattern { name, outbound: false }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn condition(&mut self) -> Result<Condition, QueryError> {
+        let variable = self.identifier()?;
+        let op = match self.next() {
+            Some(Token::Symbol("=")) => Op::Eq,
+            Some(Token::Symbol("!=")) => Op::Ne,
+            Some(Token::Symbol("<")) => Op::Lt,
+            Some(Token::Symbol("<=")) => Op::Le,
+            Some(Token::Symbol(">")) => Op::Gt,
+            Some(Token::Symbol(">=")) => Op::Ge,
+            other => return Err(QueryError(format!("expected a comparison, found {}", describe(other.as_ref())))),
+        };
+        let literal = self.literal()?;
+        Ok(Condition { variable, op, literal })
+    }
+}
+
+fn describe(token: Option<&Token>) -> String {
+    match token {
+        Some(Token::Word(w)) => format!("`{}`", w),
+        Some(Token::Quoted(w)) => format!("\"{}\"", w),
+        Some(Token::Symbol(s)) => format!("`{}`", s),
+        None => String::from("end of query"),
+    }
+}
+
+fn parse(text: &str) -> Result<Query, QueryError> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        position: 0,
+    };
+
+    if !parser.keyword("match") {
+        return Err(QueryError(String::from("queries start with `match`")));
+    }
+
+    let mut nodes = vec![parser.node()?];
+    let mut edges = Vec::new();
+    while let Some(edge) = parser.edge()? {
+        edges.push(edge);
+        nodes.push(parser.node()?);
+    }
+
+    let mut conditions = Vec::new();
+    if parser.keyword("where") {
+        conditions.push(parser.condition()?);
+        while parser.keyword("and") {
+            conditions.push(parser.condition()?);
+        }
+    }
+
+    if !parser.keyword("return") {
+        return Err(QueryError(format!("expected `return`, found {}", describe(parser.peek()))));
+    }
+    let mut returns = vec![parser.identifier()?];
+    while parser.peek() == Some(&Token::Symbol(",")) {
+        parser.position += 1;
+        returns.push(parser.identifier()?);
+    }
+
+    if let Some(token) = parser.peek() {
+        return Err(QueryError(format!("unexpected {} after `return`", describe(Some(token)))));
+    }
+
+    Ok(Query {
+        nodes,
+        edges,
+        conditions,
+        returns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+
+    fn sample() -> GraphMap<u32, u32> {
+        let mut graph = GraphMap::new();
+        graph.add_edge((0, 1), 7);
+        graph.add_edge((1, 2), 3);
+        graph.add_edge((1, 0), 9);
+        graph.add_edge((2, 3), 6);
+        graph
+    }
+
+    fn run(graph: &GraphMap<u32, u32>, text: &str) -> Vec<Vec<String>> {
+        let mut rows: Vec<Vec<String>> = graph.query(text).unwrap()
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|v| v.to_string()).collect())
+            .collect();
+        rows.sort();
+        rows
+    }
+
+    #[test]
+    fn weight_filter() {
+        let graph = sample();
+        let rows = run(&graph, "match (a)-[w]->(b) where w > 5 return a, b");
+        assert_eq!(rows, vec![vec!["0", "1"], vec!["1", "0"], vec!["2", "3"]]);
+    }
+
+    #[test]
+    fn repeated_variable_and_inbound() {
+        let graph = sample();
+        assert_eq!(run(&graph, "match (a)-[]->(b)-[]->(a) where a < 1 return b"), vec![vec!["1"]]);
+        assert_eq!(run(&graph, "MATCH (a:2)<-[w]-(b) RETURN b, w"), vec![vec!["1", "3"]]);
+    }
+
+    #[test]
+    fn errors() {
+        let graph = sample();
+        assert!(graph.query("match (a) return b").is_err());
+        assert!(graph.query("match (a)-[w]-(b) return a").is_err());
+        assert!(graph.query("match (a) where a > x return a").is_err());
+    }
+}
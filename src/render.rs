@@ -0,0 +1,245 @@
+//! Text renderings of small graphs, for the terminal: an adjacency
+//! matrix, a drawing on a character canvas, and the tree of a breadth
+//! first search.
+
+use super::{Graph, GraphMap, VertexId};
+use std::fmt::Display;
+use std::hash::Hash;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+impl<V: std::fmt::Debug + Display, E: Display> Graph<V, E> {
+
+    /// Renders the graph as an adjacency matrix, rows and columns in
+    /// arena order. Cell `(row, column)` holds the weight of the edge
+    /// from `row` to `column`, or `·` if there is none.
+    pub fn render_matrix(&self) -> String {
+        let ids: Vec<VertexId> = self.vertices().map(|(id, _)| id).collect();
+        let labels: Vec<String> = self.vertices().map(|(_, v)| v.to_string()).collect();
+        let cells: Vec<Vec<String>> = ids.iter()
+            .map(|&from| {
+                ids.iter()
+                    .map(|&to| match self.get_edge((from, to)) {
+                        Some(e) => e.to_string(),
+                        None => String::from("·"),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let label_width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..ids.len())
+            .map(|j| {
+                cells.iter()
+                    .map(|row| row[j].chars().count())
+                    .chain(std::iter::once(labels[j].chars().count()))
+                    .max()
+                    .unwrap()
+            })
+            .collect();
+
+        let mut out = format!("{:w$} │", "", w = label_width);
+        for (label, &w) in labels.iter().zip(widths.iter()) {
+            out += &format!(" {:>w$}", label, w = w);
+        }
+        out.push('\n');
+        out += &"─".repeat(label_width + 1);
+        out.push('┼');
+        out += &"─".repeat(widths.iter().map(|w| w + 1).sum());
+        out.push('\n');
+
+        for (label, row) in labels.iter().zip(cells.iter()) {
+            out += &format!("{:>w$} │", label, w = label_width);
+            for (cell, &w) in row.iter().zip(widths.iter()) {
+                out += &format!(" {:>w$}", cell, w = w);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Draws the graph on a `width` x `height` character canvas.
+    /// Vertices are placed on a circle and edges are drawn as straight
+    /// lines, with an arrow head next to the target vertex.
+    /// Meant for small graphs, bigger ones get cluttered quickly.
+    pub fn render_drawing(&self, width: usize, height: usize) -> String {
+        let width = width.max(3);
+        let height = height.max(3);
+        let layout = self.circular_layout(1.0);
+        let cell: HashMap<VertexId, (i64, i64)> = layout.into_iter()
+            .map(|(id, (x, y))| {
+                let col = ((x + 1.0) / 2.0 * (width - 1) as f64).round() as i64;
+                let row = ((1.0 - y) / 2.0 * (height - 1) as f64).round() as i64;
+                (id, (col, row))
+            })
+            .collect();
+
+        let mut canvas = vec![vec![' '; width]; height];
+
+        for (&(from, to), _) in self.edges() {
+            if from == to {
+                continue;
+            }
+            let line = bresenham(cell[&from], cell[&to]);
+            for pair in line.windows(2) {
+                let (c0, r0) = pair[0];
+                let (c1, r1) = pair[1];
+                let ch = match (c1 - c0, r1 - r0) {
+                    (0, _) => '│',
+                    (_, 0) => '─',
+                    (dc, dr) if (dc > 0) == (dr > 0) => '╲',
+                    _ => '╱',
+                };
+                canvas[r1 as usize][c1 as usize] = ch;
+            }
+            if line.len() >= 3 {
+                let (c0, r0) = line[line.len() - 3];
+                let (c1, r1) = line[line.len() - 2];
+                let head = match (c1 - c0, r1 - r0) {
+                    (dc, _) if dc > 0 => '>',
+                    (dc, _) if dc < 0 => '<',
+                    (_, dr) if dr > 0 => 'v',
+                    _ => '^',
+                };
+                canvas[r1 as usize][c1 as usize] = head;
+            }
+        }
+
+        for (id, v) in self.vertices() {
+            let (col, row) = cell[&id];
+            let label = v.to_string();
+            let start = (col as usize).min(width.saturating_sub(label.chars().count()));
+            for (i, ch) in label.chars().enumerate().take(width - start) {
+                canvas[row as usize][start + i] = ch;
+            }
+        }
+
+        canvas.into_iter()
+            .map(|row| row.into_iter().collect::<String>().trim_end().to_string() + "\n")
+            .collect()
+    }
+
+    /// Renders the tree of a breadth first search from `root`, one
+    /// vertex per line, indented under its parent with the weight of
+    /// the tree edge. Children are in arena order. Returns `None` if
+    /// the root is not in the graph.
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let graph = GraphMap::from_edges(vec![((1, 2), 5), ((1, 3), 2), ((2, 4), 1), ((3, 4), 7)]);
+    /// assert_eq!(graph.render_tree(&1).unwrap(), "\
+    /// 1
+    /// ├── 2 (5)
+    /// │   └── 4 (1)
+    /// └── 3 (2)
+    /// ");
+    /// ```
+    pub fn render_tree(&self, root: VertexId) -> Option<String> {
+        let mut children: HashMap<VertexId, Vec<(VertexId, &E)>> = HashMap::new();
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+        visited.insert(root);
+        queue.push_back(root);
+        let mut out = format!("{}\n", self.get_vertex(root)?);
+
+        while let Some(current) = queue.pop_front() {
+            let mut next: Vec<(VertexId, &E)> = self.adj_out(current).unwrap()
+                .filter(|&(to, _)| visited.insert(to))
+                .collect();
+            next.sort_unstable_by_key(|&(to, _)| to);
+            queue.extend(next.iter().map(|&(to, _)| to));
+            children.insert(current, next);
+        }
+
+        // (vertex, indentation, weight of the tree edge, last child)
+        let mut stack: Vec<(VertexId, String, &E, bool)> = Vec::new();
+        let push = |stack: &mut Vec<_>, vertex: VertexId, indent: String| {
+            let next = &children[&vertex];
+            for (i, &(child, weight)) in next.iter().enumerate().rev() {
+                stack.push((child, indent.clone(), weight, i + 1 == next.len()));
+            }
+        };
+        push(&mut stack, root, String::new());
+        while let Some((vertex, indent, weight, last)) = stack.pop() {
+            let branch = if last { "└──" } else { "├──" };
+            out += &format!("{}{} {} ({})\n", indent, branch, self.arena[vertex], weight);
+            push(&mut stack, vertex, indent + if last { "    " } else { "│   " });
+        }
+        Some(out)
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug + Display, E: Display> GraphMap<V, E> {
+
+    /// Adjacency matrix view, see [`Graph::render_matrix`].
+    pub fn render_matrix(&self) -> String {
+        self.graph.render_matrix()
+    }
+
+    /// Character drawing, see [`Graph::render_drawing`].
+    pub fn render_drawing(&self, width: usize, height: usize) -> String {
+        self.graph.render_drawing(width, height)
+    }
+
+    /// Breadth first search tree, see [`Graph::render_tree`].
+    pub fn render_tree(&self, root: &V) -> Option<String> {
+        self.graph.render_tree(*self.map.get(root)?)
+    }
+}
+
+/// Cells on the line between two points, both ends included.
+fn bresenham(from: (i64, i64), to: (i64, i64)) -> Vec<(i64, i64)> {
+    let (mut x, mut y) = from;
+    let dx = (to.0 - x).abs();
+    let dy = -(to.1 - y).abs();
+    let sx = if x < to.0 { 1 } else { -1 };
+    let sy = if y < to.1 { 1 } else { -1 };
+    let mut err = dx + dy;
+    let mut cells = vec![(x, y)];
+    while (x, y) != to {
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+        cells.push((x, y));
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+
+    #[test]
+    fn matrix_and_drawing_snapshots() {
+        let mut graph = Graph::<&str, u32>::new();
+        let a = graph.add_vertex("a");
+        let b = graph.add_vertex("b");
+        let c = graph.add_vertex("long");
+        graph.add_edge((a, b), 5).unwrap();
+        graph.add_edge((b, c), 12).unwrap();
+        graph.add_edge((c, c), 1).unwrap();
+        assert_eq!(graph.render_matrix(), concat!(
+            "     │ a b long\n",
+            "─────┼─────────\n",
+            "   a │ · 5    ·\n",
+            "   b │ · ·   12\n",
+            "long │ · ·    1\n",
+        ));
+        assert_eq!(graph.render_drawing(16, 8), concat!(
+            "    b<\n",
+            "    │ ──╲\n",
+            "    │    ─╲\n",
+            "    │      ──╲\n",
+            "    │         ─a\n",
+            "    │\n",
+            "    v\n",
+            "    long\n",
+        ));
+    }
+}
@@ -0,0 +1,423 @@
+//! Sampling smaller graphs out of big ones, and random walks.
+//!
+//! The [`Graph`] methods return the sampled vertices or edges, the
+//! [`GraphMap`] methods build the sampled graph. Vertex samples give
+//! induced subgraphs, with every edge between two sampled vertices.
+//! Walks and neighbourhood samples, meant for training graph embeddings,
+//! follow the edge directions and return the vertices or edges on both.
+//! Sampling is deterministic for a given seed.
+
+use super::{Graph, GraphMap, VertexId, EdgeId};
+use rand::Rng;
+use rand::seq::{IteratorRandom, SliceRandom};
+use std::hash::Hash;
+use std::collections::{
+    HashMap,
+    HashSet,
+    VecDeque,
+};
+
+/// Probability of jumping back to the start vertex in
+/// [`Graph::random_walk_sample`].
+const RESTART_PROBABILITY: f64 = 0.15;
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    fn sorted_vertex_ids(&self) -> Vec<VertexId> {
+        let mut ids: Vec<VertexId> = self.vertices().map(|(id, _)| id).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// `size` vertices picked uniformly at random,
+    /// or all of them if there are fewer.
+    pub fn sample_vertices<R: Rng>(&self, size: usize, rng: &mut R) -> Vec<VertexId> {
+        self.sorted_vertex_ids().into_iter().choose_multiple(rng, size)
+    }
+
+    /// `size` edges picked uniformly at random,
+    /// or all of them if there are fewer.
+    pub fn sample_edges<R: Rng>(&self, size: usize, rng: &mut R) -> Vec<EdgeId> {
+        let mut edges: Vec<EdgeId> = self.edges().map(|(&edge, _)| edge).collect();
+        edges.sort_unstable();
+        edges.into_iter().choose_multiple(rng, size)
+    }
+
+    /// Forest fire sampling. A fire starts at a random vertex and every
+    /// burning vertex spreads it to a geometric number of its unburned
+    /// out-neighbours with mean `forward / (1 - forward)`, and likewise to
+    /// its in-neighbours with `backward`. When the fire dies out a new one
+    /// starts somewhere else, until `size` vertices are burned.
+    ///
+    /// Keeps the degree distribution and the community structure
+    /// of the graph better than uniform sampling.
+    /// Panics if a probability is not in `[0, 1)`.
+    pub fn forest_fire_sample<R: Rng>(&self, size: usize, forward: f64, backward: f64, rng: &mut R) -> Vec<VertexId> {
+        assert!((0.0..1.0).contains(&forward), "forward burning probability must be in [0, 1)");
+        assert!((0.0..1.0).contains(&backward), "backward burning probability must be in [0, 1)");
+        let size = size.min(self.vertex_count());
+        let mut unburned = self.sorted_vertex_ids();
+        let mut burned = HashSet::new();
+        let mut sample = Vec::new();
+
+        while sample.len() < size {
+            unburned.retain(|id| !burned.contains(id));
+            let seed = *unburned.choose(rng).unwrap();
+            let mut fire = VecDeque::new();
+            burned.insert(seed);
+            sample.push(seed);
+            fire.push_back(seed);
+
+            while let Some(vertex) = fire.pop_front() {
+                for &(neighbours, probability) in [(&self.outbound, forward), (&self.inbound, backward)].iter() {
+                    let mut candidates: Vec<VertexId> = neighbours[&vertex].iter()
+                        .copied()
+                        .filter(|id| !burned.contains(id))
+                        .collect();
+                    candidates.sort_unstable();
+                    let mut count = 0;
+                    while rng.gen_bool(probability) {
+                        count += 1;
+                    }
+                    for next in candidates.into_iter().choose_multiple(rng, count) {
+                        if sample.len() == size {
+                            return sample;
+                        }
+                        burned.insert(next);
+                        sample.push(next);
+                        fire.push_back(next);
+                    }
+                }
+            }
+        }
+        sample
+    }
+
+    /// Random walk sampling. Walks from a random vertex, following edges
+    /// in both directions and going back to the start with probability
+    /// 0.15 at each step, until `size` distinct vertices were visited.
+    /// When the component of the start has no vertex left to visit, or
+    /// the walk finds none in 100 steps per vertex of the component, it
+    /// restarts from a random vertex not visited yet.
+    pub fn random_walk_sample<R: Rng>(&self, size: usize, rng: &mut R) -> Vec<VertexId> {
+        let size = size.min(self.vertex_count());
+        let adjacency: HashMap<VertexId, Vec<VertexId>> = self.undirected_adjacency()
+            .into_iter()
+            .map(|(id, set)| {
+                let mut neighbours: Vec<VertexId> = set.into_iter().collect();
+                neighbours.sort_unstable();
+                (id, neighbours)
+            })
+            .collect();
+        // Unvisited vertices, and their position in the vector.
+        let mut unvisited = self.sorted_vertex_ids();
+        let mut position: HashMap<VertexId, usize> = unvisited.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+        let mut sample = Vec::new();
+
+        while sample.len() < size {
+            let start = *unvisited.choose(rng).unwrap();
+            let component = component_of(&adjacency, start);
+            let mut remaining = component.iter().filter(|id| position.contains_key(id)).count();
+            let patience = 100 * component.len();
+            let mut current = start;
+            let mut idle = 0;
+            while sample.len() < size && remaining > 0 && idle < patience {
+                if let Some(i) = position.remove(&current) {
+                    unvisited.swap_remove(i);
+                    if let Some(&moved) = unvisited.get(i) {
+                        position.insert(moved, i);
+                    }
+                    sample.push(current);
+                    remaining -= 1;
+                    idle = 0;
+                } else {
+                    idle += 1;
+                }
+                current = match adjacency[&current].choose(rng) {
+                    Some(&next) if !rng.gen_bool(RESTART_PROBABILITY) => next,
+                    _ => start,
+                };
+            }
+        }
+        sample
+    }
+}
+
+/// Vertices connected to `start`, itself included.
+fn component_of(adjacency: &HashMap<VertexId, Vec<VertexId>>, start: VertexId) -> Vec<VertexId> {
+    let mut seen = HashSet::new();
+    seen.insert(start);
+    let mut component = vec![start];
+    let mut next = 0;
+    while next < component.len() {
+        for &other in adjacency[&component[next]].iter() {
+            if seen.insert(other) {
+                component.push(other);
+            }
+        }
+        next += 1;
+    }
+    component
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    fn sorted_successors(&self, vertex: VertexId) -> Vec<VertexId> {
+        let mut next: Vec<VertexId> = self.outbound[&vertex].iter().copied().collect();
+        next.sort_unstable();
+        next
+    }
+
+    /// Walk of up to `length` vertices from `start`, `start` included,
+    /// going to a uniformly random out-neighbour at every step. Stops
+    /// early at a vertex without outbound edges, and is empty if `start`
+    /// is not in the graph.
+    /// Time complexity: O(length * max degree)
+    pub fn random_walk<R: Rng>(&self, start: VertexId, length: usize, rng: &mut R) -> Vec<VertexId> {
+        let mut walk = Vec::with_capacity(length);
+        if self.get_vertex(start).is_none() || length == 0 {
+            return walk;
+        }
+        walk.push(start);
+        while walk.len() < length {
+            match self.sorted_successors(*walk.last().unwrap()).choose(rng) {
+                Some(&next) => walk.push(next),
+                None => break,
+            }
+        }
+        walk
+    }
+
+    /// Biased walk of node2vec, like [`Graph::random_walk`] but with the
+    /// step from `v`, having come from `t`, weighted by where it goes:
+    /// `1 / p` back to `t`, 1 to a vertex with an edge from `t`, and `1 / q`
+    /// to any other. A low `p` keeps the walk near its start, a low `q`
+    /// sends it further away, and `p = q = 1` is the uniform walk.
+    /// Panics if `p` or `q` is not positive.
+    /// Time complexity: O(length * max degree)
+    pub fn node2vec_walk<R: Rng>(&self, start: VertexId, length: usize, p: f64, q: f64, rng: &mut R) -> Vec<VertexId> {
+        assert!(p > 0.0, "return parameter p must be positive");
+        assert!(q > 0.0, "in-out parameter q must be positive");
+        let mut walk = self.random_walk(start, length.min(2), rng);
+        while walk.len() >= 2 && walk.len() < length {
+            let previous = walk[walk.len() - 2];
+            let next = self.sorted_successors(walk[walk.len() - 1]);
+            let step = next.choose_weighted(rng, |&x| {
+                if x == previous {
+                    1.0 / p
+                } else if self.outbound[&previous].contains(&x) {
+                    1.0
+                } else {
+                    1.0 / q
+                }
+            });
+            match step {
+                Ok(&x) => walk.push(x),
+                Err(_) => break,
+            }
+        }
+        walk
+    }
+
+    /// Neighbourhood sample of GraphSAGE: `fanout` outbound edges picked
+    /// at random from `vertex`, then `fanout` from each of their targets,
+    /// and so on `depth` times. Element `k` of the result has the edges
+    /// of hop `k + 1`. A vertex reached several times in a hop is only
+    /// expanded once, and one with fewer edges gives all of them.
+    /// Empty if `vertex` is not in the graph.
+    /// Time complexity: O(depth * V * max degree) at worst
+    pub fn neighborhood_sample<R: Rng>(&self, vertex: VertexId, depth: usize, fanout: usize, rng: &mut R) -> Vec<Vec<EdgeId>> {
+        if self.get_vertex(vertex).is_none() {
+            return Vec::new();
+        }
+        let mut hops = Vec::with_capacity(depth);
+        let mut frontier = vec![vertex];
+        for _ in 0..depth {
+            let mut edges = Vec::new();
+            for &from in frontier.iter() {
+                for to in self.sorted_successors(from).into_iter().choose_multiple(rng, fanout) {
+                    edges.push((from, to));
+                }
+            }
+            let mut seen = HashSet::new();
+            frontier = edges.iter().map(|&(_, to)| to).filter(|&to| seen.insert(to)).collect();
+            hops.push(edges);
+        }
+        hops
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::random_walk`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    /// use rand::{SeedableRng, rngs::StdRng};
+    ///
+    /// let graph = GraphMap::<&str, ()>::from_edges(vec![(("a", "b"), ()), (("b", "c"), ()), (("c", "a"), ()), (("c", "d"), ())]);
+    /// let mut rng = StdRng::seed_from_u64(5);
+    ///
+    /// let walk = graph.random_walk(&"a", 10, &mut rng);
+    /// assert_eq!(walk[..3], [&"a", &"b", &"c"]);
+    /// for pair in walk.windows(2) {
+    ///     assert!(graph.get_edge((*pair[0], *pair[1])).is_some());
+    /// }
+    /// assert_eq!(graph.random_walk(&"d", 10, &mut rng), vec![&"d"]);
+    /// ```
+    pub fn random_walk<R: Rng>(&self, start: &V, length: usize, rng: &mut R) -> Vec<&V> {
+        match self.map.get(start) {
+            Some(&start) => self.graph.random_walk(start, length, rng).into_iter().map(|id| &self.graph.arena[id]).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// See [`Graph::node2vec_walk`].
+    pub fn node2vec_walk<R: Rng>(&self, start: &V, length: usize, p: f64, q: f64, rng: &mut R) -> Vec<&V> {
+        match self.map.get(start) {
+            Some(&start) => self.graph.node2vec_walk(start, length, p, q, rng).into_iter().map(|id| &self.graph.arena[id]).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// See [`Graph::neighborhood_sample`].
+    pub fn neighborhood_sample<R: Rng>(&self, vertex: &V, depth: usize, fanout: usize, rng: &mut R) -> Vec<Vec<(&V, &V)>> {
+        let vertex = match self.map.get(vertex) {
+            Some(&vertex) => vertex,
+            None => return Vec::new(),
+        };
+        self.graph.neighborhood_sample(vertex, depth, fanout, rng)
+            .into_iter()
+            .map(|hop| hop.into_iter().map(|(from, to)| (&self.graph.arena[from], &self.graph.arena[to])).collect())
+            .collect()
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E: Clone> GraphMap<V, E> {
+
+    /// Subgraph induced by the vertices.
+    fn induced(&self, vertices: &[VertexId]) -> GraphMap<V, E> {
+        let keep: HashSet<VertexId> = vertices.iter().copied().collect();
+        let mut sample = GraphMap::new();
+        for &id in vertices.iter() {
+            sample.add_vertex(self.graph.get_vertex(id).unwrap().clone());
+        }
+        for &id in vertices.iter() {
+            for (to, weight) in self.graph.adj_out(id).unwrap() {
+                if keep.contains(&to) {
+                    let from = self.graph.get_vertex(id).unwrap().clone();
+                    let to = self.graph.get_vertex(to).unwrap().clone();
+                    sample.add_edge((from, to), weight.clone());
+                }
+            }
+        }
+        sample
+    }
+
+    /// Subgraph induced by `size` uniformly sampled vertices.
+    pub fn sample_vertices<R: Rng>(&self, size: usize, rng: &mut R) -> GraphMap<V, E> {
+        self.induced(&self.graph.sample_vertices(size, rng))
+    }
+
+    /// Graph made of `size` uniformly sampled edges and their ends.
+    pub fn sample_edges<R: Rng>(&self, size: usize, rng: &mut R) -> GraphMap<V, E> {
+        let mut sample = GraphMap::new();
+        for (from, to) in self.graph.sample_edges(size, rng) {
+            let weight = self.graph.get_edge((from, to)).unwrap().clone();
+            let from = self.graph.get_vertex(from).unwrap().clone();
+            let to = self.graph.get_vertex(to).unwrap().clone();
+            sample.add_edge((from, to), weight);
+        }
+        sample
+    }
+
+    /// Induced subgraph of a forest fire sample, see [`Graph::forest_fire_sample`].
+    pub fn forest_fire_sample<R: Rng>(&self, size: usize, forward: f64, backward: f64, rng: &mut R) -> GraphMap<V, E> {
+        self.induced(&self.graph.forest_fire_sample(size, forward, backward, rng))
+    }
+
+    /// Induced subgraph of a random walk sample, see [`Graph::random_walk_sample`].
+    pub fn random_walk_sample<R: Rng>(&self, size: usize, rng: &mut R) -> GraphMap<V, E> {
+        self.induced(&self.graph.random_walk_sample(size, rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GraphMap;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn samples_have_target_size() {
+        let mut graph = GraphMap::<u32, u32>::new();
+        for i in 0..30 {
+            graph.add_edge((i, (i + 1) % 30), i);
+            graph.add_edge((i, (i * 7) % 30), i);
+        }
+        // Two vertices the walk can't reach from the rest.
+        graph.add_edge((100, 101), 0);
+
+        let mut rng = StdRng::seed_from_u64(11);
+        assert_eq!(graph.sample_vertices(10, &mut rng).vertex_count(), 10);
+        assert_eq!(graph.sample_edges(10, &mut rng).edge_count(), 10);
+        assert_eq!(graph.forest_fire_sample(12, 0.7, 0.3, &mut rng).vertex_count(), 12);
+        assert_eq!(graph.random_walk_sample(32, &mut rng).vertex_count(), 32);
+
+        let sample = graph.forest_fire_sample(15, 0.5, 0.0, &mut rng);
+        for ((from, to), weight) in sample.edges() {
+            assert_eq!(graph.get_edge((*from, *to)), Some(weight));
+        }
+
+        // Isolated vertices end their walk at once, instead of idling.
+        for v in 1000..3000 {
+            graph.add_vertex(v);
+        }
+        let all = graph.vertex_count();
+        assert_eq!(graph.random_walk_sample(all, &mut rng).vertex_count(), all);
+    }
+    #[test]
+    fn walks_follow_edges() {
+        // a grid of 10 x 10 with edges both ways
+        let mut graph = GraphMap::<(u32, u32), ()>::new();
+        for x in 0..10 {
+            for y in 0..10 {
+                for &(dx, dy) in [(1, 0), (0, 1)].iter() {
+                    if x + dx < 10 && y + dy < 10 {
+                        graph.add_edge(((x, y), (x + dx, y + dy)), ());
+                        graph.add_edge(((x + dx, y + dy), (x, y)), ());
+                    }
+                }
+            }
+        }
+        let mut rng = StdRng::seed_from_u64(2);
+        let distance = |walk: &[&(u32, u32)]| {
+            let &&(x, y) = walk.last().unwrap();
+            x + y
+        };
+
+        let mut near = 0;
+        let mut far = 0;
+        for _ in 0..200 {
+            let homing = graph.node2vec_walk(&(0, 0), 20, 0.05, 10.0, &mut rng);
+            let roaming = graph.node2vec_walk(&(0, 0), 20, 10.0, 0.05, &mut rng);
+            assert_eq!(homing.len(), 20);
+            for pair in homing.windows(2).chain(roaming.windows(2)) {
+                assert!(graph.get_edge((*pair[0], *pair[1])).is_some());
+            }
+            near += distance(&homing);
+            far += distance(&roaming);
+        }
+        assert!(near < far);
+
+        let hops = graph.neighborhood_sample(&(5, 5), 3, 2, &mut rng);
+        assert_eq!(hops.len(), 3);
+        assert_eq!(hops[0].len(), 2);
+        assert!(hops[1].len() <= 4);
+        for (k, hop) in hops.iter().enumerate().skip(1) {
+            for (from, _) in hop.iter() {
+                assert!(hops[k - 1].iter().any(|(_, to)| to == from));
+            }
+        }
+        assert!(graph.neighborhood_sample(&(50, 50), 3, 2, &mut rng).is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+//! [`Serialize`] and [`Deserialize`] for [`Graph`] and [`GraphMap`],
+//! behind the `serde` feature.
+//!
+//! [`VertexId`]s are arena indices, only valid in the graph that made
+//! them, so they are not written. Vertices are written as a list, and
+//! edges refer to their positions in it:
+//!
+//! ```text
+//! { "vertices": [v0, v1, ...], "edges": [[from, to, data], ...] }
+//! ```
+//!
+//! Deserializing adds the vertices in order, so the `i`th vertex of the
+//! list gets the `i`th new id. The new ids are different from the old
+//! ones; use [`StableIds`](crate::io::StableIds) to keep references
+//! to vertices across a save.
+//!
+//! A [`VertexId`] on its own, in data kept next to the graph, is written
+//! as its `[slot, generation]` pair.
+
+use crate::{Graph, GraphMap, Map, VertexId};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Serialize)]
+struct SerializedGraph<'a, V, E> {
+    vertices: Vec<&'a V>,
+    edges: Vec<(usize, usize, &'a E)>,
+}
+
+#[derive(Deserialize)]
+struct DeserializedGraph<V, E> {
+    vertices: Vec<V>,
+    edges: Vec<(usize, usize, E)>,
+}
+
+impl<'a, V: std::fmt::Debug, E> SerializedGraph<'a, V, E> {
+    fn new(graph: &'a Graph<V, E>) -> Self {
+        let mut position = HashMap::with_capacity(graph.vertex_count());
+        let vertices = graph.vertices()
+            .enumerate()
+            .map(|(i, (id, data))| {
+                position.insert(id, i);
+                data
+            })
+            .collect();
+        let edges = graph.edges()
+            .map(|(&(from, to), data)| (position[&from], position[&to], data))
+            .collect();
+        SerializedGraph {
+            vertices,
+            edges,
+        }
+    }
+}
+
+impl<V: std::fmt::Debug, E> DeserializedGraph<V, E> {
+    /// Builds the graph, failing on edges to missing vertices.
+    fn build(self) -> Result<Graph<V, E>, String> {
+        let mut graph = Graph::new();
+        let ids: Vec<VertexId> = self.vertices.into_iter().map(|v| graph.add_vertex(v)).collect();
+        for (from, to, data) in self.edges {
+            match (ids.get(from), ids.get(to)) {
+                (Some(&from), Some(&to)) => {
+                    graph.insert_edge((from, to), data);
+                }
+                _ => return Err(format!("edge ({}, {}) refers to a missing vertex", from, to)),
+            }
+        }
+        Ok(graph)
+    }
+}
+
+impl<V: std::fmt::Debug + Serialize, E: Serialize> Serialize for Graph<V, E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedGraph::new(self).serialize(serializer)
+    }
+}
+
+impl<'de, V: std::fmt::Debug + Deserialize<'de>, E: Deserialize<'de>> Deserialize<'de> for Graph<V, E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        DeserializedGraph::deserialize(deserializer)?.build().map_err(D::Error::custom)
+    }
+}
+
+impl<V, E> Serialize for GraphMap<V, E>
+where
+    V: Eq + Hash + Clone + std::fmt::Debug + Serialize,
+    E: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.graph.serialize(serializer)
+    }
+}
+
+impl<'de, V, E> Deserialize<'de> for GraphMap<V, E>
+where
+    V: Eq + Hash + Clone + std::fmt::Debug + Deserialize<'de>,
+    E: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let graph: Graph<V, E> = Graph::deserialize(deserializer)?;
+        let mut map = Map::with_capacity_and_hasher(graph.vertex_count(), Default::default());
+        for (id, data) in graph.vertices() {
+            if map.insert(data.clone(), id).is_some() {
+                return Err(D::Error::custom(format!("duplicate vertex {:?}", data)));
+            }
+        }
+        Ok(GraphMap {
+            graph,
+            map,
+        })
+    }
+}
+
+impl Serialize for VertexId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        <(usize, u64)>::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for VertexId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <(usize, u64)>::deserialize(deserializer).map(VertexId::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Graph, GraphMap, VertexId};
+
+    #[test]
+    fn round_trip_remaps_ids() {
+        let mut graph = Graph::<String, u32>::new();
+        let a = graph.add_vertex("a".to_string());
+        let b = graph.add_vertex("b".to_string());
+        let c = graph.add_vertex("c".to_string());
+        graph.add_edge((a, c), 1).unwrap();
+        graph.remove_vertex(b);
+        let d = graph.add_vertex("d".to_string());
+        graph.add_edge((d, a), 2).unwrap();
+
+        let json = serde_json::to_string(&graph).unwrap();
+        let loaded: Graph<String, u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.vertex_count(), 3);
+        let id = |name: &str| loaded.vertices().find(|(_, v)| v.as_str() == name).unwrap().0;
+        assert_eq!(loaded.get_edge((id("a"), id("c"))), Some(&1));
+        assert_eq!(loaded.get_edge((id("d"), id("a"))), Some(&2));
+
+        let map: GraphMap<String, u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(map.get_edge(("d".to_string(), "a".to_string())), Some(&2));
+
+        assert!(serde_json::from_str::<Graph<String, u32>>(r#"{"vertices":["a"],"edges":[[0,1,5]]}"#).is_err());
+        assert!(serde_json::from_str::<GraphMap<String, u32>>(r#"{"vertices":["a","a"],"edges":[]}"#).is_err());
+
+        assert_eq!(serde_json::to_string(&d).unwrap(), "[1,1]");
+        assert_eq!(serde_json::from_str::<VertexId>("[1,1]").unwrap(), d);
+    }
+}
@@ -0,0 +1,245 @@
+//! Temporal graphs, whose edges hold the times of their connections,
+//! and the journeys that respect them.
+
+use super::{Graph, VertexId};
+use std::cmp::Reverse;
+use std::collections::{
+    BinaryHeap,
+    HashMap,
+};
+
+/// A single connection along an edge: it leaves the origin
+/// at `departure` and reaches the target at `arrival`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Contact<T> {
+    pub departure: T,
+    pub arrival: T,
+}
+
+impl<T> Contact<T> {
+    pub fn new(departure: T, arrival: T) -> Self {
+        Contact {
+            departure,
+            arrival,
+        }
+    }
+}
+
+/// Graph whose edges are only usable at given times.
+/// Every edge holds all the connections between its two vertices.
+pub type TemporalGraph<V, T> = Graph<V, Vec<Contact<T>>>;
+
+/// A journey through a temporal graph, as the list of
+/// `(from, to, contact)` hops taken.
+pub type Journey<T> = Vec<(VertexId, VertexId, Contact<T>)>;
+
+impl<V: std::fmt::Debug, T: Ord + Copy> Graph<V, Vec<Contact<T>>> {
+
+    /// Earliest time each vertex can be reached from `start`,
+    /// leaving no sooner than `window.0` and arriving no later than `window.1`.
+    /// Vertices that can't be reached in the window are left out.
+    ///
+    /// ```
+    /// use graph::temporal::{Contact, TemporalGraph};
+    ///
+    /// let mut graph = TemporalGraph::<&str, u32>::new();
+    /// let a = graph.add_vertex("a");
+    /// let b = graph.add_vertex("b");
+    /// let c = graph.add_vertex("c");
+    /// graph.add_edge((a, b), vec![Contact::new(1, 2), Contact::new(5, 6)]).unwrap();
+    /// graph.add_edge((b, c), vec![Contact::new(3, 4)]).unwrap();
+    ///
+    /// let arrivals = graph.earliest_arrivals(a, (0, 10));
+    /// assert_eq!(arrivals[&b], 2);
+    /// assert_eq!(arrivals[&c], 4);
+    ///
+    /// // Leaving at 2 misses the first contact, and the one to c is gone by 6.
+    /// let arrivals = graph.earliest_arrivals(a, (2, 10));
+    /// assert_eq!(arrivals[&b], 6);
+    /// assert!(!arrivals.contains_key(&c));
+    /// ```
+    pub fn earliest_arrivals(&self, start: VertexId, window: (T, T)) -> HashMap<VertexId, T> {
+        self.earliest_arrival_search(start, window)
+            .into_iter()
+            .map(|(v, (time, _))| (v, time))
+            .collect()
+    }
+
+    /// Journey from `start` to `end` arriving as soon as possible,
+    /// using only contacts inside the time window.
+    /// Returns the hops taken and the arrival time,
+    /// or `None` if `end` can't be reached in the window.
+    ///
+    /// ```
+    /// use graph::temporal::{Contact, TemporalGraph};
+    ///
+    /// let mut graph = TemporalGraph::<&str, u32>::new();
+    /// let a = graph.add_vertex("a");
+    /// let b = graph.add_vertex("b");
+    /// let c = graph.add_vertex("c");
+    /// graph.add_edge((a, b), vec![Contact::new(1, 2)]).unwrap();
+    /// // The direct contact arrives later than waiting at b for the 5 to 6 one.
+    /// graph.add_edge((a, c), vec![Contact::new(0, 9)]).unwrap();
+    /// graph.add_edge((b, c), vec![Contact::new(5, 6)]).unwrap();
+    ///
+    /// let (journey, arrival) = graph.earliest_arrival_path(a, c, (0, 10)).unwrap();
+    /// assert_eq!(journey, vec![(a, b, Contact::new(1, 2)), (b, c, Contact::new(5, 6))]);
+    /// assert_eq!(arrival, 6);
+    ///
+    /// // Nothing gets to c by 5.
+    /// assert_eq!(graph.earliest_arrival_path(a, c, (0, 5)), None);
+    /// // Leaving at 2 misses the contact to b.
+    /// assert_eq!(graph.earliest_arrival_path(a, c, (2, 10)), None);
+    /// assert_eq!(graph.earliest_arrival_path(c, a, (0, 10)), None);
+    /// ```
+    pub fn earliest_arrival_path(&self, start: VertexId, end: VertexId, window: (T, T)) -> Option<(Journey<T>, T)> {
+        let reached = self.earliest_arrival_search(start, window);
+        let &(arrival, _) = reached.get(&end)?;
+
+        let mut path = Vec::new();
+        let mut curr = end;
+        while let Some(&(_, Some((prev, contact)))) = reached.get(&curr) {
+            path.push((prev, curr, contact));
+            curr = prev;
+        }
+        path.reverse();
+
+        Some((path, arrival))
+    }
+
+    /// Latest time one can leave `start` and still reach `end`
+    /// by `window.1`, without leaving before `window.0`.
+    /// Returns `None` if `end` can't be reached in the window.
+    ///
+    /// ```
+    /// use graph::temporal::{Contact, TemporalGraph};
+    ///
+    /// let mut graph = TemporalGraph::<&str, u32>::new();
+    /// let a = graph.add_vertex("a");
+    /// let b = graph.add_vertex("b");
+    /// let c = graph.add_vertex("c");
+    /// graph.add_edge((a, b), vec![Contact::new(1, 2), Contact::new(5, 6)]).unwrap();
+    /// graph.add_edge((b, c), vec![Contact::new(3, 4), Contact::new(8, 9)]).unwrap();
+    ///
+    /// assert_eq!(graph.latest_departure(a, c, (0, 10)), Some(5));
+    /// // The 8 to 9 contact arrives too late, so a has to leave at 1.
+    /// assert_eq!(graph.latest_departure(a, c, (0, 5)), Some(1));
+    /// assert_eq!(graph.latest_departure(a, c, (2, 5)), None);
+    /// assert_eq!(graph.latest_departure(c, a, (0, 10)), None);
+    /// ```
+    pub fn latest_departure(&self, start: VertexId, end: VertexId, window: (T, T)) -> Option<T> {
+        let (begin, finish) = window;
+        self.get_vertex(end)?;
+
+        let mut queue = BinaryHeap::new();
+        let mut latest = HashMap::<VertexId, T>::new();
+
+        latest.insert(end, finish);
+        queue.push((finish, end));
+
+        while let Some((time, node)) = queue.pop() {
+            if latest[&node] > time {
+                continue;
+            }
+            if node == start {
+                break;
+            }
+
+            for (prev, contacts) in self.adj_in(node)? {
+                let best = contacts.iter()
+                    .filter(|c| c.departure >= begin && c.arrival <= time)
+                    .map(|c| c.departure)
+                    .max();
+                if let Some(departure) = best {
+                    let better = match latest.get(&prev) {
+                        Some(&t) => departure > t,
+                        None => true,
+                    };
+                    if better {
+                        latest.insert(prev, departure);
+                        queue.push((departure, prev));
+                    }
+                }
+            }
+        }
+
+        if start == end {
+            return Some(finish);
+        }
+        latest.get(&start).copied()
+    }
+
+    /// Dijkstra-like search on arrival times. For every reached vertex
+    /// stores the arrival time and the hop used to get there.
+    #[allow(clippy::type_complexity)]
+    fn earliest_arrival_search(&self, start: VertexId, window: (T, T)) -> HashMap<VertexId, (T, Option<(VertexId, Contact<T>)>)> {
+        let (begin, finish) = window;
+        let mut queue = BinaryHeap::new();
+        let mut reached = HashMap::new();
+
+        if self.get_vertex(start).is_none() {
+            return reached;
+        }
+
+        reached.insert(start, (begin, None));
+        queue.push(Reverse((begin, start)));
+
+        while let Some(Reverse((time, node))) = queue.pop() {
+            if reached[&node].0 < time {
+                continue;
+            }
+
+            for (next, contacts) in self.adj_out(node).unwrap() {
+                let best = contacts.iter()
+                    .filter(|c| c.departure >= time && c.arrival <= finish)
+                    .min_by_key(|c| c.arrival);
+                if let Some(&contact) = best {
+                    let better = match reached.get(&next) {
+                        Some(&(t, _)) => contact.arrival < t,
+                        None => true,
+                    };
+                    if better {
+                        reached.insert(next, (contact.arrival, Some((node, contact))));
+                        queue.push(Reverse((contact.arrival, next)));
+                    }
+                }
+            }
+        }
+
+        reached
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Contact, TemporalGraph};
+
+    #[test]
+    fn journeys_wait_for_later_contacts() {
+        let mut graph = TemporalGraph::<u32, u32>::new();
+        let ids: Vec<_> = (0..5).map(|i| graph.add_vertex(i)).collect();
+        graph.add_edge((ids[0], ids[1]), vec![Contact::new(2, 3)]).unwrap();
+        // Leaves before anyone can be at 1, then a usable one after waiting.
+        graph.add_edge((ids[1], ids[2]), vec![Contact::new(1, 2), Contact::new(7, 8)]).unwrap();
+        graph.add_edge((ids[2], ids[3]), vec![Contact::new(8, 10)]).unwrap();
+        graph.add_edge((ids[3], ids[0]), vec![Contact::new(11, 12)]).unwrap();
+
+        let arrivals = graph.earliest_arrivals(ids[0], (0, 20));
+        assert_eq!(arrivals[&ids[0]], 0);
+        assert_eq!(arrivals[&ids[2]], 8);
+        assert_eq!(arrivals[&ids[3]], 10);
+        assert!(!arrivals.contains_key(&ids[4]));
+
+        let (journey, arrival) = graph.earliest_arrival_path(ids[0], ids[3], (0, 20)).unwrap();
+        assert_eq!(arrival, 10);
+        assert_eq!(journey.len(), 3);
+        assert!(journey.windows(2).all(|hops| hops[0].2.arrival <= hops[1].2.departure));
+        assert_eq!(graph.earliest_arrival_path(ids[0], ids[4], (0, 20)), None);
+        assert_eq!(graph.earliest_arrival_path(ids[0], ids[3], (0, 9)), None);
+
+        assert_eq!(graph.latest_departure(ids[0], ids[3], (0, 20)), Some(2));
+        assert_eq!(graph.latest_departure(ids[0], ids[3], (3, 20)), None);
+        assert_eq!(graph.latest_departure(ids[4], ids[3], (0, 20)), None);
+        assert_eq!(graph.latest_departure(ids[2], ids[2], (0, 20)), Some(20));
+    }
+}
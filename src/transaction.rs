@@ -0,0 +1,293 @@
+//! Batches of mutations applied all together or not at all.
+//!
+//! [`Graph::transaction`] and [`GraphMap::transaction`] run a closure on
+//! a transaction, which mutates the graph and remembers how to undo
+//! every change. If the closure returns an error, the changes are undone
+//! in reverse order and the graph is left as it was, adjacency included.
+//! The transaction dereferences to the graph, so the closure reads the
+//! changes made so far.
+
+use crate::{EdgeId, Graph, GraphError, GraphMap, VertexId};
+use std::hash::{BuildHasher, Hash};
+use std::ops::Deref;
+
+/// How to undo a change of a [`Transaction`].
+#[derive(Debug)]
+enum Undo<E> {
+    AddedVertex(VertexId),
+    /// The edge and its data before the change.
+    Edge(EdgeId, Option<E>),
+}
+
+/// Mutations of a [`Graph`], see [`Graph::transaction`].
+///
+/// A removed vertex can't get its id back, so vertex removals are
+/// only applied when the transaction commits: until then the vertex
+/// and its edges are still in the graph.
+#[derive(Debug)]
+pub struct Transaction<'g, V, E, S> {
+    graph: &'g mut Graph<V, E, S>,
+    undo: Vec<Undo<E>>,
+    removed: Vec<VertexId>,
+}
+
+impl<'g, V: std::fmt::Debug, E, S: BuildHasher + Default> Transaction<'g, V, E, S> {
+
+    /// Adds a vertex, see [`Graph::add_vertex`].
+    pub fn add_vertex(&mut self, vertex: V) -> VertexId {
+        let id = self.graph.add_vertex(vertex);
+        self.undo.push(Undo::AddedVertex(id));
+        id
+    }
+
+    /// Adds an edge or modifies the existing one, see [`Graph::add_edge`].
+    pub fn add_edge(&mut self, edge: EdgeId, weight: E) -> Result<(), GraphError> {
+        let previous = self.graph.add_edge(edge, weight)?;
+        self.undo.push(Undo::Edge(edge, previous));
+        Ok(())
+    }
+
+    /// Removes an edge, see [`Graph::try_remove_edge`].
+    pub fn remove_edge(&mut self, edge: EdgeId) -> Result<(), GraphError> {
+        let weight = self.graph.try_remove_edge(edge)?;
+        self.undo.push(Undo::Edge(edge, Some(weight)));
+        Ok(())
+    }
+
+    /// Removes the vertex and its edges when the transaction commits.
+    pub fn remove_vertex(&mut self, vertex: VertexId) -> Result<(), GraphError> {
+        if !self.graph.arena.contains(vertex) {
+            return Err(GraphError::VertexNotFound);
+        }
+        self.removed.push(vertex);
+        Ok(())
+    }
+
+    fn commit(self) {
+        for vertex in self.removed {
+            self.graph.remove_vertex(vertex);
+        }
+    }
+
+    fn rollback(self) {
+        for undo in self.undo.into_iter().rev() {
+            match undo {
+                Undo::AddedVertex(id) => self.graph.remove_vertex(id),
+                Undo::Edge(edge, Some(weight)) => {
+                    self.graph.insert_edge(edge, weight);
+                }
+                Undo::Edge(edge, None) => self.graph.remove_edge(edge),
+            }
+        }
+    }
+}
+
+impl<'g, V, E, S> Deref for Transaction<'g, V, E, S> {
+    type Target = Graph<V, E, S>;
+
+    fn deref(&self) -> &Graph<V, E, S> {
+        self.graph
+    }
+}
+
+impl<V: std::fmt::Debug, E, S: BuildHasher + Default> Graph<V, E, S> {
+
+    /// Runs `f` on a [`Transaction`], keeping its changes if it returns
+    /// `Ok` and undoing them if it returns `Err`.
+    ///
+    /// ```
+    /// use graph::{Graph, GraphError};
+    ///
+    /// let mut graph = Graph::<&str, u32>::new();
+    /// let a = graph.add_vertex("a");
+    /// let b = graph.add_vertex("b");
+    /// graph.add_edge((a, b), 1).unwrap();
+    ///
+    /// let result: Result<(), GraphError> = graph.transaction(|tx| {
+    ///     let c = tx.add_vertex("c");
+    ///     tx.add_edge((b, c), 2)?;
+    ///     tx.add_edge((a, b), 5)?;
+    ///     tx.remove_vertex(a)?;
+    ///     tx.remove_edge((c, a))
+    /// });
+    /// assert_eq!(result, Err(GraphError::EdgeNotFound));
+    /// assert_eq!(graph.vertex_count(), 2);
+    /// assert_eq!(graph.get_edge((a, b)), Some(&1));
+    /// assert_eq!(graph.outdegree(b), 0);
+    ///
+    /// let c = graph.transaction(|tx| {
+    ///     let c = tx.add_vertex("c");
+    ///     tx.add_edge((b, c), 2)?;
+    ///     tx.remove_vertex(a)?;
+    ///     Ok::<_, GraphError>(c)
+    /// }).unwrap();
+    /// assert_eq!(graph.vertex_count(), 2);
+    /// assert_eq!(graph.get_edge((b, c)), Some(&2));
+    /// assert_eq!(graph.indegree(b), 0);
+    /// ```
+    pub fn transaction<T, Err, F>(&mut self, f: F) -> Result<T, Err>
+    where
+        F: FnOnce(&mut Transaction<V, E, S>) -> Result<T, Err>,
+    {
+        let mut tx = Transaction {
+            graph: self,
+            undo: Vec::new(),
+            removed: Vec::new(),
+        };
+        match f(&mut tx) {
+            Ok(value) => {
+                tx.commit();
+                Ok(value)
+            }
+            Err(err) => {
+                tx.rollback();
+                Err(err)
+            }
+        }
+    }
+}
+
+/// How to undo a change of a [`MapTransaction`].
+#[derive(Debug)]
+enum MapUndo<V, E> {
+    AddedVertex(V),
+    /// The edge and its data before the change.
+    Edge((V, V), Option<E>),
+    /// The vertex and all its edges.
+    RemovedVertex(V, Vec<((V, V), E)>),
+}
+
+/// Mutations of a [`GraphMap`], see [`GraphMap::transaction`].
+/// Unlike [`Transaction`], every change is applied right away.
+#[derive(Debug)]
+pub struct MapTransaction<'g, V: Eq + Hash + Clone, E, S> {
+    graph: &'g mut GraphMap<V, E, S>,
+    undo: Vec<MapUndo<V, E>>,
+}
+
+impl<'g, V, E, S> MapTransaction<'g, V, E, S>
+where
+    V: Eq + Hash + Clone + std::fmt::Debug,
+    S: BuildHasher + Default,
+{
+    fn add_missing(&mut self, vertex: &V) {
+        if !self.graph.contains_vertex(vertex) {
+            self.graph.add_vertex(vertex.clone());
+            self.undo.push(MapUndo::AddedVertex(vertex.clone()));
+        }
+    }
+
+    /// Adds a vertex, see [`GraphMap::add_vertex`].
+    pub fn add_vertex(&mut self, vertex: V) {
+        self.add_missing(&vertex);
+    }
+
+    /// Adds an edge and the missing vertices, or modifies the
+    /// existing edge, see [`GraphMap::add_edge`].
+    pub fn add_edge(&mut self, edge: (V, V), weight: E) {
+        let (from, to) = &edge;
+        self.add_missing(from);
+        self.add_missing(to);
+        let previous = self.graph.try_remove_edge(edge.clone()).ok();
+        self.graph.add_edge(edge.clone(), weight);
+        self.undo.push(MapUndo::Edge(edge, previous));
+    }
+
+    /// Removes an edge, see [`GraphMap::try_remove_edge`].
+    pub fn remove_edge(&mut self, edge: (V, V)) -> Result<(), GraphError> {
+        let weight = self.graph.try_remove_edge(edge.clone())?;
+        self.undo.push(MapUndo::Edge(edge, Some(weight)));
+        Ok(())
+    }
+
+    /// Removes the vertex and its edges, see [`GraphMap::try_remove_vertex`].
+    pub fn remove_vertex(&mut self, vertex: V) -> Result<(), GraphError> {
+        let id = *self.graph.map.get(&vertex).ok_or(GraphError::VertexNotFound)?;
+        let graph = &mut self.graph.graph;
+        let mut incident: Vec<EdgeId> = graph.outbound[&id].iter().map(|&to| (id, to)).collect();
+        incident.extend(graph.inbound[&id].iter().filter(|&&from| from != id).map(|&from| (from, id)));
+        let mut edges = Vec::with_capacity(incident.len());
+        for (from, to) in incident {
+            let weight = graph.edges.remove(&(from, to)).unwrap();
+            edges.push(((graph.arena[from].clone(), graph.arena[to].clone()), weight));
+        }
+        let vertex = self.graph.try_remove_vertex(vertex)?;
+        self.undo.push(MapUndo::RemovedVertex(vertex, edges));
+        Ok(())
+    }
+
+    fn rollback(self) {
+        for undo in self.undo.into_iter().rev() {
+            match undo {
+                MapUndo::AddedVertex(vertex) => {
+                    self.graph.remove_vertex(vertex);
+                }
+                MapUndo::Edge(edge, Some(weight)) => {
+                    self.graph.add_edge(edge, weight);
+                }
+                MapUndo::Edge(edge, None) => {
+                    self.graph.remove_edge(edge);
+                }
+                MapUndo::RemovedVertex(vertex, edges) => {
+                    self.graph.add_vertex(vertex);
+                    for (edge, weight) in edges {
+                        self.graph.add_edge(edge, weight);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'g, V: Eq + Hash + Clone, E, S> Deref for MapTransaction<'g, V, E, S> {
+    type Target = GraphMap<V, E, S>;
+
+    fn deref(&self) -> &GraphMap<V, E, S> {
+        self.graph
+    }
+}
+
+impl<V, E, S> GraphMap<V, E, S>
+where
+    V: Eq + Hash + Clone + std::fmt::Debug,
+    S: BuildHasher + Default,
+{
+    /// Runs `f` on a [`MapTransaction`], keeping its changes if it
+    /// returns `Ok` and undoing them if it returns `Err`.
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut graph = GraphMap::<&str, u32>::new();
+    /// graph.add_edge(("a", "b"), 1);
+    /// graph.add_edge(("b", "a"), 2);
+    ///
+    /// let result = graph.transaction(|tx| {
+    ///     tx.add_edge(("a", "c"), 3);
+    ///     tx.remove_vertex("b").map_err(|err| err.to_string())?;
+    ///     if tx.vertex_count() < 3 {
+    ///         return Err("too few vertices".to_string());
+    ///     }
+    ///     Ok(())
+    /// });
+    /// assert_eq!(result, Err("too few vertices".to_string()));
+    /// assert_eq!(graph.vertex_count(), 2);
+    /// assert_eq!(graph.get_edge(("b", "a")), Some(&2));
+    /// assert_eq!(graph.get_edge(("a", "c")), None);
+    /// assert_eq!(graph.degree("a"), 2);
+    /// ```
+    pub fn transaction<T, Err, F>(&mut self, f: F) -> Result<T, Err>
+    where
+        F: FnOnce(&mut MapTransaction<V, E, S>) -> Result<T, Err>,
+    {
+        let mut tx = MapTransaction {
+            graph: self,
+            undo: Vec::new(),
+        };
+        let result = f(&mut tx);
+        if result.is_err() {
+            tx.rollback();
+        }
+        result
+    }
+}
@@ -0,0 +1,406 @@
+//! Building new graphs out of existing ones.
+
+use super::{Graph, GraphMap, EdgeId, VertexId};
+use std::hash::Hash;
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E: Clone> GraphMap<V, E> {
+
+    /// Graph with every vertex renamed by `key`. Vertices whose new keys
+    /// collide are merged, and so are their edges: when two edges end up
+    /// between the same pair of keys, `merge` combines the weight already
+    /// there with the next one. Edges between two merged vertices become
+    /// self loops.
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut web = GraphMap::<&str, u32>::new();
+    /// web.add_edge(("a.com/x", "b.org/1"), 1);
+    /// web.add_edge(("a.com/y", "b.org/2"), 2);
+    /// web.add_edge(("a.com/x", "a.com/y"), 5);
+    ///
+    /// let domains = web.map_keys(|url| url.split('/').next().unwrap().to_string(), |a, b| a + b);
+    /// assert_eq!(domains.vertex_count(), 2);
+    /// assert_eq!(domains.get_edge(("a.com".into(), "b.org".into())), Some(&3));
+    /// assert_eq!(domains.get_edge(("a.com".into(), "a.com".into())), Some(&5));
+    /// ```
+    pub fn map_keys<K, F, M>(&self, key: F, mut merge: M) -> GraphMap<K, E>
+    where
+        K: Eq + Hash + Clone + std::fmt::Debug,
+        F: Fn(&V) -> K,
+        M: FnMut(E, E) -> E,
+    {
+        let mut mapped = GraphMap::new();
+        for vertex in self.vertices() {
+            mapped.add_vertex(key(vertex));
+        }
+        for ((from, to), weight) in self.edges() {
+            let edge = (mapped.map[&key(from)], mapped.map[&key(to)]);
+            let weight = match mapped.graph.edges.remove(&edge) {
+                Some(existing) => merge(existing, weight.clone()),
+                None => weight.clone(),
+            };
+            mapped.graph.insert_edge(edge, weight);
+        }
+        mapped
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// Adds all the vertices and edges of `other` to this graph. When both
+    /// graphs have the same edge, `resolve` is called with this graph's
+    /// weight first and the other's second, to pick the weight to keep.
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut left = GraphMap::<&str, u32>::new();
+    /// left.add_edge(("a", "b"), 3);
+    /// let mut right = GraphMap::<&str, u32>::new();
+    /// right.add_edge(("a", "b"), 5);
+    /// right.add_edge(("b", "c"), 1);
+    ///
+    /// left.merge_with(right, |w1, w2| w1.min(w2));
+    /// assert_eq!(left.get_edge(("a", "b")), Some(&3));
+    /// assert_eq!(left.get_edge(("b", "c")), Some(&1));
+    /// ```
+    pub fn merge_with<F>(&mut self, other: GraphMap<V, E>, mut resolve: F)
+    where
+        F: FnMut(E, E) -> E,
+    {
+        let GraphMap { graph, map } = other;
+        let mut ids = HashMap::with_capacity(map.len());
+        for (vertex, other_id) in map {
+            ids.insert(other_id, self.add_or_get_vertex(vertex));
+        }
+        for ((from, to), weight) in graph.edges {
+            let edge = (ids[&from], ids[&to]);
+            let weight = match self.graph.edges.remove(&edge) {
+                Some(existing) => resolve(existing, weight),
+                None => weight,
+            };
+            self.graph.insert_edge(edge, weight);
+        }
+    }
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Contracts the edge, replacing its two ends with one new vertex
+    /// holding `merge_vertex(from, to)`, and returns the new id. Edges
+    /// of both ends are moved to the new vertex, and when two of them end
+    /// up between the same vertices, `merge_edges` combines their weights.
+    /// All edges between the two ends, in either direction, are removed,
+    /// and their self loops stay self loops. Returns `None`, leaving the
+    /// graph as it is, if the edge does not exist. A self loop is only
+    /// removed, and its vertex keeps its id.
+    /// Time complexity: O(degree(from) + degree(to))
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// let mut graph = Graph::<u32, u32>::new();
+    /// let a = graph.add_vertex(1);
+    /// let b = graph.add_vertex(2);
+    /// let c = graph.add_vertex(4);
+    /// graph.add_edge((a, b), 1).unwrap();
+    /// graph.add_edge((a, c), 2).unwrap();
+    /// graph.add_edge((b, c), 3).unwrap();
+    ///
+    /// let ab = graph.contract_edge((a, b), |x, y| x + y, |w1, w2| w1 + w2).unwrap();
+    /// assert_eq!(graph.vertex_count(), 2);
+    /// assert_eq!(graph.get_vertex(ab), Some(&3));
+    /// assert_eq!(graph.get_edge((ab, c)), Some(&5));
+    /// assert_eq!(graph.edge_count(), 1);
+    /// ```
+    pub fn contract_edge<F, M>(&mut self, edge: EdgeId, merge_vertex: F, mut merge_edges: M) -> Option<VertexId>
+    where
+        F: FnOnce(V, V) -> V,
+        M: FnMut(E, E) -> E,
+    {
+        if !self.edges.contains_key(&edge) {
+            return None;
+        }
+        let (a, b) = edge;
+        if a == b {
+            self.remove_edge(edge);
+            return Some(a);
+        }
+
+        let mut incident: Vec<EdgeId> = Vec::new();
+        for &v in &[a, b] {
+            incident.extend(self.outbound[&v].iter().map(|&to| (v, to)));
+            incident.extend(self.inbound[&v].iter().map(|&from| (from, v)));
+        }
+        let mut moved = Vec::with_capacity(incident.len());
+        for edge in incident {
+            if let Some(weight) = self.edges.remove(&edge) {
+                moved.push((edge, weight));
+            }
+        }
+        for &v in &[a, b] {
+            for &to in &self.outbound[&v] {
+                self.inbound.get_mut(&to).unwrap().remove(&v);
+            }
+            for &from in &self.inbound[&v] {
+                self.outbound.get_mut(&from).unwrap().remove(&v);
+            }
+        }
+        for &v in &[a, b] {
+            self.inbound.remove(&v);
+            self.outbound.remove(&v);
+        }
+
+        let first = self.arena.remove(a).unwrap();
+        let second = self.arena.remove(b).unwrap();
+        let merged = self.add_vertex(merge_vertex(first, second));
+        let rename = |v: VertexId| if v == a || v == b { merged } else { v };
+        for ((from, to), weight) in moved {
+            let joins_ends = (from == a && to == b) || (from == b && to == a);
+            if joins_ends {
+                continue;
+            }
+            let edge = (rename(from), rename(to));
+            let weight = match self.edges.remove(&edge) {
+                Some(existing) => merge_edges(existing, weight),
+                None => weight,
+            };
+            self.insert_edge(edge, weight);
+        }
+        Some(merged)
+    }
+}
+
+impl<V: Clone + std::fmt::Debug, E: Clone> Graph<V, E> {
+
+    /// Induced subgraph of the vertices for which `keep` returns true,
+    /// with all the edges between them. The vertices keep their ids.
+    /// Time complexity: O(V + E)
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// let mut graph = Graph::<u32, &str>::new();
+    /// let ids: Vec<_> = (0..4).map(|i| graph.add_vertex(i)).collect();
+    /// graph.add_edge((ids[0], ids[2]), "kept").unwrap();
+    /// graph.add_edge((ids[0], ids[1]), "dropped").unwrap();
+    ///
+    /// let even = graph.filter(|_, &v| v % 2 == 0);
+    /// assert_eq!(even.vertex_count(), 2);
+    /// assert_eq!(even.edge_count(), 1);
+    /// assert_eq!(even.get_edge((ids[0], ids[2])), Some(&"kept"));
+    /// ```
+    pub fn filter<F: FnMut(VertexId, &V) -> bool>(&self, mut keep: F) -> Graph<V, E> {
+        let mut arena = self.arena.clone();
+        arena.retain(|id, vertex| keep(id, vertex));
+        let mut subgraph = Graph {
+            arena,
+            inbound: Default::default(),
+            outbound: Default::default(),
+            edges: Default::default(),
+        };
+        let ids: Vec<VertexId> = subgraph.arena.iter().map(|(id, _)| id).collect();
+        for id in ids {
+            subgraph.inbound.insert(id, Default::default());
+            subgraph.outbound.insert(id, Default::default());
+        }
+        for (&(from, to), weight) in self.edges.iter() {
+            if subgraph.arena.contains(from) && subgraph.arena.contains(to) {
+                subgraph.insert_edge((from, to), weight.clone());
+            }
+        }
+        subgraph
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E: Clone> GraphMap<V, E> {
+
+    /// Induced subgraph of the given vertices, with all the edges
+    /// between them. Vertices not in the graph are ignored.
+    /// Time complexity: O(V + E)
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut graph = GraphMap::<&str, u32>::new();
+    /// graph.add_edge(("a", "b"), 1);
+    /// graph.add_edge(("b", "c"), 2);
+    /// graph.add_edge(("c", "a"), 3);
+    ///
+    /// let subgraph = graph.subgraph(vec!["a", "b", "x"]);
+    /// assert_eq!(subgraph.vertex_count(), 2);
+    /// assert_eq!(subgraph.edge_count(), 1);
+    /// assert!(subgraph.contains_edge(("a", "b")));
+    /// ```
+    pub fn subgraph<I: IntoIterator<Item = V>>(&self, vertices: I) -> GraphMap<V, E> {
+        let selected: HashSet<VertexId> = vertices.into_iter()
+            .filter_map(|vertex| self.map.get(&vertex).copied())
+            .collect();
+        let graph = self.graph.filter(|id, _| selected.contains(&id));
+        let map = self.map.iter()
+            .filter(|(_, id)| selected.contains(id))
+            .map(|(vertex, &id)| (vertex.clone(), id))
+            .collect();
+        GraphMap {
+            graph,
+            map,
+        }
+    }
+}
+
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Graph with the same edges, and every vertex data replaced by
+    /// `f(id, data)`. The arena can't be rebuilt with the same ids, so
+    /// the vertices get new ones; the returned map gives the new id of
+    /// every old one.
+    /// Time complexity: O(V + E)
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// let mut graph = Graph::<&str, u32>::new();
+    /// let a = graph.add_vertex("a");
+    /// let b = graph.add_vertex("bb");
+    /// graph.add_edge((a, b), 1).unwrap();
+    ///
+    /// let (lengths, ids) = graph.map_vertices(|_, name| name.len());
+    /// assert_eq!(lengths.get_vertex(ids[&b]), Some(&2));
+    /// assert_eq!(lengths.get_edge((ids[&a], ids[&b])), Some(&1));
+    /// ```
+    pub fn map_vertices<V2, F>(&self, mut f: F) -> (Graph<V2, E>, HashMap<VertexId, VertexId>)
+    where
+        V2: std::fmt::Debug,
+        E: Clone,
+        F: FnMut(VertexId, &V) -> V2,
+    {
+        let mut mapped = Graph::new();
+        let mut ids = HashMap::with_capacity(self.vertex_count());
+        for (id, vertex) in self.vertices() {
+            ids.insert(id, mapped.add_vertex(f(id, vertex)));
+        }
+        for (&(from, to), weight) in self.edges.iter() {
+            mapped.insert_edge((ids[&from], ids[&to]), weight.clone());
+        }
+        (mapped, ids)
+    }
+
+    /// Graph with the same vertices and ids, and every edge data
+    /// replaced by `f(edge, data)`.
+    /// Time complexity: O(V + E)
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// let mut graph = Graph::<&str, u32>::new();
+    /// let a = graph.add_vertex("a");
+    /// let b = graph.add_vertex("b");
+    /// graph.add_edge((a, b), 4).unwrap();
+    ///
+    /// let halved = graph.map_edges(|_, &w| w as f64 / 2.0);
+    /// assert_eq!(halved.get_edge((a, b)), Some(&2.0));
+    /// ```
+    pub fn map_edges<E2, F>(&self, mut f: F) -> Graph<V, E2>
+    where
+        V: Clone,
+        F: FnMut(EdgeId, &E) -> E2,
+    {
+        Graph {
+            arena: self.arena.clone(),
+            inbound: self.inbound.clone(),
+            outbound: self.outbound.clone(),
+            edges: self.edges.iter().map(|(&edge, weight)| (edge, f(edge, weight))).collect(),
+        }
+    }
+
+    /// Flips the direction of every edge. The inbound and outbound sets
+    /// are swapped, and the edge map is refilled in its own allocation.
+    /// Time complexity: O(E)
+    pub fn reverse_in_place(&mut self) {
+        std::mem::swap(&mut self.inbound, &mut self.outbound);
+        let edges: Vec<(EdgeId, E)> = self.edges.drain().collect();
+        self.edges.extend(edges.into_iter().map(|((from, to), weight)| ((to, from), weight)));
+    }
+
+    /// Copy of the graph with every edge flipped, and the same ids.
+    /// Time complexity: O(V + E)
+    ///
+    /// ```
+    /// use graph::Graph;
+    ///
+    /// let mut graph = Graph::<&str, u32>::new();
+    /// let a = graph.add_vertex("a");
+    /// let b = graph.add_vertex("b");
+    /// graph.add_edge((a, b), 1).unwrap();
+    ///
+    /// let transposed = graph.transpose();
+    /// assert_eq!(transposed.get_edge((b, a)), Some(&1));
+    /// assert!(transposed.get_edge((a, b)).is_none());
+    /// assert_eq!(transposed.outdegree(b), 1);
+    /// ```
+    pub fn transpose(&self) -> Graph<V, E>
+    where
+        V: Clone,
+        E: Clone,
+    {
+        Graph {
+            arena: self.arena.clone(),
+            inbound: self.outbound.clone(),
+            outbound: self.inbound.clone(),
+            edges: self.edges.iter().map(|(&(from, to), weight)| ((to, from), weight.clone())).collect(),
+        }
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// Graph with the same vertices, and every edge data replaced
+    /// by `f(edge, data)`. See [`GraphMap::map_keys`] to change
+    /// the vertices.
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let mut graph = GraphMap::<&str, u32>::new();
+    /// graph.add_edge(("a", "b"), 3);
+    ///
+    /// let labels = graph.map_edges(|(from, to), w| format!("{}{}:{}", from, to, w));
+    /// assert_eq!(labels.get_edge(("a", "b")), Some(&"ab:3".to_string()));
+    /// ```
+    pub fn map_edges<E2, F>(&self, mut f: F) -> GraphMap<V, E2>
+    where
+        F: FnMut((&V, &V), &E) -> E2,
+    {
+        let graph = &self.graph;
+        GraphMap {
+            graph: graph.map_edges(|(from, to), weight| {
+                f((&graph.arena[from], &graph.arena[to]), weight)
+            }),
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::reverse_in_place`].
+    pub fn reverse_in_place(&mut self) {
+        self.graph.reverse_in_place();
+    }
+
+    /// See [`Graph::transpose`].
+    pub fn transpose(&self) -> GraphMap<V, E>
+    where
+        E: Clone,
+    {
+        GraphMap {
+            graph: self.graph.transpose(),
+            map: self.map.clone(),
+        }
+    }
+}
@@ -1,9 +1,12 @@
 
-use super::{GraphMap};
+use super::{Graph, GraphMap, VertexId};
+use super::graph_ref::{EdgeWeights, GraphBase, GraphRef, Neighbors};
+use super::EdgeId;
+use super::algorithms::bellman_ford::{ShortestPathResult, ShortestPaths};
 use std::hash::Hash;
+use std::ops::Add;
 use std::cmp::Reverse;
 use std::collections::{
-    VecDeque,
     BinaryHeap,
     HashSet,
     HashMap
@@ -13,103 +16,712 @@ impl<V: Eq + Hash + Clone + std::fmt::Debug + Ord, E: Clone + Ord + std::ops::Ad
     
     /// Performs a BFS starting on the given node.
     /// Returns a vector ov all nodes, in the order
-    /// they were traversed
+    /// they were traversed. See [`bfs`].
     pub fn bfs(&self, start: &V) -> Vec<V> {
-        let mut visited = HashSet::new();
-        let mut nodes = Vec::new();
-        let mut queue = VecDeque::new();
-        
-        queue.push_back(start.clone());
-        visited.insert(start.clone());
-        
-        while !queue.is_empty() {
-            let current = queue.pop_front().unwrap();
-
-            nodes.push(current.clone());
-
-            for (v, _) in self.adj_out(current).unwrap() {
-                if !visited.contains(v) {
-                    visited.insert(v.clone());
-                    queue.push_back(v.clone());
+        let start = match self.map.get(start) {
+            Some(&id) => id,
+            None => return Vec::new(),
+        };
+        bfs(self, start).into_iter()
+            .map(|id| self.graph.get_vertex(id).unwrap().clone())
+            .collect()
+    }
+
+    /// Finds all connected components, ignoring the direction of the edges.
+    /// Returns a vector of Graphs, each representing a different
+    /// connected component. See [`connected_components`].
+    pub fn connected_components(&self) -> Vec<GraphMap<V,E>> {
+        connected_components(self).into_iter()
+            .map(|component| {
+                let mut graph = GraphMap::<V,E>::new();
+                for &id in component.iter() {
+                    let node = self.graph.get_vertex(id).unwrap();
+                    graph.add_vertex(node.clone());
+                    for (adj, w) in self.graph.adj_out(id).unwrap() {
+                        let adj = self.graph.get_vertex(adj).unwrap();
+                        graph.add_edge((node.clone(), adj.clone()), w.clone());
+                    }
                 }
+                graph
+            })
+            .collect()
+    }
+
+}
+
+/// Breadth first search from `start`, returning the vertices in the
+/// order they were reached. Empty if `start` is not in the graph.
+///
+/// ```
+/// use graph::Graph;
+/// use graph::traversal::bfs;
+///
+/// let mut graph = Graph::<&str, u32>::new();
+/// let a = graph.add_vertex("a");
+/// let b = graph.add_vertex("b");
+/// let c = graph.add_vertex("c");
+/// graph.add_edge((a, b), 1).unwrap();
+/// graph.add_edge((b, c), 1).unwrap();
+///
+/// assert_eq!(bfs(&graph, a), vec![a, b, c]);
+/// assert_eq!(bfs(&graph.freeze(), b), vec![b, c]);
+/// ```
+pub fn bfs<G: Neighbors>(graph: &G, start: VertexId) -> Vec<VertexId> {
+    if !graph.contains_vertex(start) {
+        return Vec::new();
+    }
+    let mut visited = HashSet::new();
+    let mut order = vec![start];
+    visited.insert(start);
+    let mut next = 0;
+    while next < order.len() {
+        let current = order[next];
+        next += 1;
+        graph.visit_out(current, |to, _| {
+            if visited.insert(to) {
+                order.push(to);
             }
+        });
+    }
+    order
+}
+
+/// Weakly connected components: the vertices joined by a path when
+/// the direction of the edges is ignored. Every vertex is in exactly
+/// one component, in breadth first order from its first vertex.
+/// Time complexity: O(V + E)
+///
+/// ```
+/// use graph::undirected::UndirectedGraph;
+/// use graph::traversal::connected_components;
+///
+/// let mut graph = UndirectedGraph::<u32, ()>::new();
+/// let ids: Vec<_> = (0..5).map(|i| graph.add_vertex(i)).collect();
+/// graph.add_edge((ids[0], ids[1]), ()).unwrap();
+/// graph.add_edge((ids[2], ids[3]), ()).unwrap();
+///
+/// let mut sizes: Vec<_> = connected_components(&graph).iter().map(Vec::len).collect();
+/// sizes.sort_unstable();
+/// assert_eq!(sizes, vec![1, 2, 2]);
+/// ```
+pub fn connected_components<G: Neighbors>(graph: &G) -> Vec<Vec<VertexId>> {
+    let mut components = Vec::new();
+    let mut visited = HashSet::with_capacity(graph.vertex_count());
+    graph.visit_vertices(|start| {
+        if !visited.insert(start) {
+            return;
         }
-        nodes
+        let mut component = vec![start];
+        let mut next = 0;
+        while next < component.len() {
+            let current = component[next];
+            next += 1;
+            let mut reach = |other, _: &G::Edge| {
+                if visited.insert(other) {
+                    component.push(other);
+                }
+            };
+            graph.visit_out(current, &mut reach);
+            graph.visit_in(current, &mut reach);
+        }
+        components.push(component);
+    });
+    components
+}
+
+/// Whether `goal` can be reached from `start` following the edges,
+/// with a breadth first search that stops when it reaches `goal`. A
+/// vertex reaches itself. False if either vertex is not in the graph.
+/// Time complexity: O(V + E)
+///
+/// ```
+/// use graph::Graph;
+/// use graph::traversal::has_path;
+///
+/// let mut graph = Graph::<&str, ()>::new();
+/// let a = graph.add_vertex("a");
+/// let b = graph.add_vertex("b");
+/// let c = graph.add_vertex("c");
+/// graph.add_edge((a, b), ()).unwrap();
+/// graph.add_edge((b, c), ()).unwrap();
+///
+/// assert!(has_path(&graph, a, c));
+/// assert!(!has_path(&graph, c, a));
+/// assert!(has_path(&graph, c, c));
+/// ```
+pub fn has_path<G: Neighbors>(graph: &G, start: VertexId, goal: VertexId) -> bool {
+    if !graph.contains_vertex(start) || !graph.contains_vertex(goal) {
+        return false;
+    }
+    let mut visited = HashSet::new();
+    let mut queue = vec![start];
+    visited.insert(start);
+    let mut next = 0;
+    while next < queue.len() {
+        if queue[next] == goal {
+            return true;
+        }
+        graph.visit_out(queue[next], |to, _| {
+            if visited.insert(to) {
+                queue.push(to);
+            }
+        });
+        next += 1;
     }
+    false
+}
 
-    /// Finds all connected components.
-    /// Returns a vector of Graphs, each representing a different
-    /// connected component.
-    pub fn connected_components(&self) -> Vec<GraphMap<V,E>> {
-        let mut components = Vec::new();
-        let mut visited = HashSet::<V>::new();
+/// The paths from `start` to `goal` with at most `max_len` edges that
+/// go through no vertex twice, depth first. The paths are found one at
+/// a time as the iterator advances, keeping only the current one, so
+/// taking the first few is cheap even when there are many. Empty if
+/// `start == goal`, or if either vertex is not in the graph.
+/// Time complexity: O(d^max_len) for an outdegree d, in the worst case
+///
+/// ```
+/// use graph::Graph;
+/// use graph::traversal::all_simple_paths;
+///
+/// let mut graph = Graph::<&str, ()>::new();
+/// let a = graph.add_vertex("a");
+/// let b = graph.add_vertex("b");
+/// let c = graph.add_vertex("c");
+/// let d = graph.add_vertex("d");
+/// graph.add_edge((a, b), ()).unwrap();
+/// graph.add_edge((a, c), ()).unwrap();
+/// graph.add_edge((b, c), ()).unwrap();
+/// graph.add_edge((c, b), ()).unwrap();
+/// graph.add_edge((b, d), ()).unwrap();
+/// graph.add_edge((c, d), ()).unwrap();
+///
+/// let mut paths: Vec<_> = all_simple_paths(&graph, a, d, 3).collect();
+/// paths.sort();
+/// assert_eq!(paths, vec![vec![a, b, c, d], vec![a, b, d], vec![a, c, b, d], vec![a, c, d]]);
+/// assert_eq!(all_simple_paths(&graph, a, d, 2).count(), 2);
+/// assert_eq!(all_simple_paths(&graph, d, a, 3).count(), 0);
+/// ```
+pub fn all_simple_paths<G: Neighbors>(graph: &G, start: VertexId, goal: VertexId, max_len: usize) -> SimplePaths<'_, G> {
+    let mut paths = SimplePaths {
+        graph,
+        goal,
+        max_len,
+        path: Vec::new(),
+        on_path: HashSet::new(),
+        pending: Vec::new(),
+    };
+    if start != goal && graph.contains_vertex(start) && graph.contains_vertex(goal) {
+        paths.push(start);
+    }
+    paths
+}
 
-        for v in self.vertices() {
-            if !visited.contains(v) {
-                let component = self.bfs(v);
-                
+/// Iterator over the simple paths between two vertices, see
+/// [`all_simple_paths`].
+pub struct SimplePaths<'g, G> {
+    graph: &'g G,
+    goal: VertexId,
+    max_len: usize,
+    path: Vec<VertexId>,
+    on_path: HashSet<VertexId>,
+    /// Targets left to try from each vertex of the path, last first.
+    pending: Vec<Vec<VertexId>>,
+}
 
-                //make the graph
-                let mut graph = GraphMap::<V,E>::new();
+impl<'g, G: Neighbors> SimplePaths<'g, G> {
 
-                for node in component.iter() {
-                    //add it to the visited nodes
-                    visited.insert(node.clone());
-                    
-                    graph.add_vertex(node.clone());
+    fn push(&mut self, vertex: VertexId) {
+        let mut targets = Vec::new();
+        self.graph.visit_out(vertex, |to, _| targets.push(to));
+        targets.reverse();
+        self.path.push(vertex);
+        self.on_path.insert(vertex);
+        self.pending.push(targets);
+    }
+}
+
+impl<'g, G: Neighbors> Iterator for SimplePaths<'g, G> {
+    type Item = Vec<VertexId>;
 
-                    for (adj, w) in self.adj_out(node.clone()).unwrap() {
-                        graph.add_edge((node.clone(),adj.clone()), (*w).clone());
+    fn next(&mut self) -> Option<Vec<VertexId>> {
+        while let Some(targets) = self.pending.last_mut() {
+            // The path has `path.len() - 1` edges, the edge to `to` adds one.
+            match targets.pop() {
+                Some(to) if to == self.goal => {
+                    if self.path.len() <= self.max_len {
+                        let mut path = self.path.clone();
+                        path.push(to);
+                        return Some(path);
                     }
                 }
-                components.push(graph);
+                Some(to) => {
+                    if self.path.len() < self.max_len && !self.on_path.contains(&to) {
+                        self.push(to);
+                    }
+                }
+                None => {
+                    self.pending.pop();
+                    let last = self.path.pop().unwrap();
+                    self.on_path.remove(&last);
+                }
             }
         }
-
-        components
+        None
     }
+}
 
+/// Shortest path from `start` to `goal` with Dijkstra's algorithm,
+/// using the edges as lengths. Returns the path, both ends included,
+/// and its length. `None` if `goal` can't be reached.
+/// Time complexity: O((V + E) log V)
+///
+/// ```
+/// use graph::Graph;
+/// use graph::traversal::dijkstra;
+///
+/// let mut graph = Graph::<&str, u32>::new();
+/// let a = graph.add_vertex("a");
+/// let b = graph.add_vertex("b");
+/// let c = graph.add_vertex("c");
+/// graph.add_edge((a, b), 1).unwrap();
+/// graph.add_edge((b, c), 2).unwrap();
+/// graph.add_edge((a, c), 5).unwrap();
+///
+/// assert_eq!(dijkstra(&graph, a, c), Some((vec![a, b, c], 3)));
+/// assert_eq!(dijkstra(&graph.freeze(), c, a), None);
+/// ```
+pub fn dijkstra<G>(graph: &G, start: VertexId, goal: VertexId) -> Option<(Vec<VertexId>, G::Edge)>
+where
+    G: Neighbors,
+    G::Edge: Copy + Ord + Add<Output = G::Edge> + Default,
+{
+    search(graph, start, goal, |_| G::Edge::default())
 }
 
+/// Shortest paths from `start` to every vertex it reaches, with
+/// Dijkstra's algorithm, so a single run answers any number of targets.
+/// Empty if `start` is not in the graph.
+/// Time complexity: O((V + E) log V)
+///
+/// ```
+/// use graph::Graph;
+/// use graph::traversal::shortest_paths_from;
+///
+/// let mut graph = Graph::<&str, u32>::new();
+/// let a = graph.add_vertex("a");
+/// let b = graph.add_vertex("b");
+/// let c = graph.add_vertex("c");
+/// let d = graph.add_vertex("d");
+/// graph.add_edge((a, b), 1).unwrap();
+/// graph.add_edge((b, c), 2).unwrap();
+/// graph.add_edge((a, c), 5).unwrap();
+///
+/// let paths = shortest_paths_from(&graph, a);
+/// assert_eq!(paths.distance[&c], 3);
+/// assert_eq!(paths.path_to(c), Some(vec![a, b, c]));
+/// assert_eq!(paths.path_to(a), Some(vec![a]));
+/// assert_eq!(paths.path_to(d), None);
+/// ```
+pub fn shortest_paths_from<G>(graph: &G, start: VertexId) -> ShortestPathResult<G::Edge>
+where
+    G: Neighbors,
+    G::Edge: Copy + Ord + Add<Output = G::Edge> + Default,
+{
+    let mut paths = ShortestPaths {
+        distance: HashMap::new(),
+        predecessor: HashMap::new(),
+    };
+    if !graph.contains_vertex(start) {
+        return paths;
+    }
+    let mut queue = BinaryHeap::new();
+    paths.distance.insert(start, G::Edge::default());
+    queue.push(Reverse((G::Edge::default(), start)));
+
+    while let Some(Reverse((so_far, node))) = queue.pop() {
+        if so_far > paths.distance[&node] {
+            continue;
+        }
+        graph.visit_out(node, |next, &weight| {
+            let through = so_far + weight;
+            let better = match paths.distance.get(&next) {
+                Some(&d) => through < d,
+                None => true,
+            };
+            if better {
+                paths.distance.insert(next, through);
+                paths.predecessor.insert(next, node);
+                queue.push(Reverse((through, next)));
+            }
+        });
+    }
+    paths
+}
 
-impl GraphMap<u32, u32> {
+/// A* search from `start` to `goal`. `heuristic` estimates the cost
+/// from a vertex to `goal`; if it never overestimates it, the path
+/// found is a shortest one. A zero heuristic makes this [`dijkstra`].
+/// Returns the path, both ends included, and its cost.
+pub fn astar<G, H>(graph: &G, start: VertexId, goal: VertexId, heuristic: H) -> Option<(Vec<VertexId>, G::Edge)>
+where
+    G: GraphRef,
+    G::Edge: Copy + Ord + Add<Output = G::Edge> + Default,
+    H: Fn(&G::Vertex) -> G::Edge,
+{
+    search(graph, start, goal, |v| graph.with_vertex(v, &heuristic).unwrap())
+}
 
-    pub fn dijkstra(&self, start: u32, end: u32) -> Option<(Vec<u32>, u32)> {
-        let mut queue = BinaryHeap::new();
-        let mut dist = HashMap::<u32, u32>::new();
-        let mut next = HashMap::<u32, u32>::new();
+/// Best first search shared by [`dijkstra`] and [`astar`].
+fn search<G, H>(graph: &G, start: VertexId, goal: VertexId, estimate: H) -> Option<(Vec<VertexId>, G::Edge)>
+where
+    G: Neighbors,
+    G::Edge: Copy + Ord + Add<Output = G::Edge> + Default,
+    H: Fn(VertexId) -> G::Edge,
+{
+    if !graph.contains_vertex(start) || !graph.contains_vertex(goal) {
+        return None;
+    }
+    let mut cost = HashMap::<VertexId, G::Edge>::new();
+    let mut previous = HashMap::<VertexId, VertexId>::new();
+    let mut queue = BinaryHeap::new();
 
-        dist.insert(end, 0);
-        queue.push(Reverse((0, end)));
+    cost.insert(start, G::Edge::default());
+    queue.push(Reverse((estimate(start), G::Edge::default(), start)));
 
-        while !queue.is_empty() {
-            let Reverse((_, node)) = queue.pop().unwrap();       
+    while let Some(Reverse((_, so_far, node))) = queue.pop() {
+        if so_far > cost[&node] {
+            continue;
+        }
+        if node == goal {
+            let mut path = vec![goal];
+            let mut current = goal;
+            while let Some(&p) = previous.get(&current) {
+                path.push(p);
+                current = p;
+            }
+            path.reverse();
+            return Some((path, so_far));
+        }
+        graph.visit_out(node, |next, &weight| {
+            let through = so_far + weight;
+            let better = match cost.get(&next) {
+                Some(&c) => through < c,
+                None => true,
+            };
+            if better {
+                cost.insert(next, through);
+                previous.insert(next, node);
+                queue.push(Reverse((through + estimate(next), through, next)));
+            }
+        });
+    }
+    None
+}
 
+/// Up to `k` shortest paths from `start` to `goal` without repeated
+/// vertices, with Yen's algorithm. Sorted by cost, the first one being
+/// the [`dijkstra`] path.
+/// Time complexity: O(k V (V + E) log V)
+///
+/// ```
+/// use graph::Graph;
+/// use graph::traversal::k_shortest_paths;
+///
+/// let mut graph = Graph::<&str, u32>::new();
+/// let a = graph.add_vertex("a");
+/// let b = graph.add_vertex("b");
+/// let c = graph.add_vertex("c");
+/// let d = graph.add_vertex("d");
+/// graph.add_edge((a, b), 1).unwrap();
+/// graph.add_edge((b, d), 1).unwrap();
+/// graph.add_edge((a, c), 2).unwrap();
+/// graph.add_edge((c, d), 2).unwrap();
+/// graph.add_edge((b, c), 1).unwrap();
+///
+/// let paths = k_shortest_paths(&graph, a, d, 5);
+/// let costs: Vec<u32> = paths.iter().map(|&(_, cost)| cost).collect();
+/// assert_eq!(costs, vec![2, 4, 4]);
+/// assert_eq!(paths[0].0, vec![a, b, d]);
+/// ```
+pub fn k_shortest_paths<G>(graph: &G, start: VertexId, goal: VertexId, k: usize) -> Vec<(Vec<VertexId>, G::Edge)>
+where
+    G: Neighbors,
+    G::Edge: Copy + Ord + Add<Output = G::Edge> + Default,
+{
+    let mut found: Vec<(Vec<VertexId>, G::Edge)> = Vec::new();
+    if k == 0 {
+        return found;
+    }
+    match dijkstra(graph, start, goal) {
+        Some(first) => found.push(first),
+        None => return found,
+    }
+    let mut candidates = BinaryHeap::new();
+    let mut seen: HashSet<Vec<VertexId>> = HashSet::new();
+    seen.insert(found[0].0.clone());
 
-            for (&prev, &cost) in self.adj_in(node).unwrap() {
-                if !dist.contains_key(&prev) || dist[&node] + cost < dist[&prev] {
-                    dist.insert(prev, dist[&node] + cost);
-                    queue.push(Reverse((dist[&prev], prev)));
-                    next.insert(prev,node);
+    while found.len() < k {
+        let previous = found.last().unwrap().0.clone();
+        let mut root_cost = G::Edge::default();
+        for j in 0..previous.len() - 1 {
+            let root = &previous[..=j];
+            let mut without = Without {
+                graph,
+                vertices: root[..j].iter().copied().collect(),
+                edges: HashSet::new(),
+            };
+            for (path, _) in found.iter() {
+                if path.len() > j + 1 && &path[..=j] == root {
+                    without.edges.insert((path[j], path[j + 1]));
                 }
             }
+            if let Some((spur, spur_cost)) = dijkstra(&without, previous[j], goal) {
+                let mut path = root[..j].to_vec();
+                path.extend(spur);
+                if seen.insert(path.clone()) {
+                    candidates.push(Reverse((root_cost + spur_cost, path)));
+                }
+            }
+            root_cost = root_cost + graph.with_edge((previous[j], previous[j + 1]), |&cost| cost).unwrap();
+        }
+        match candidates.pop() {
+            Some(Reverse((cost, path))) => found.push((path, cost)),
+            None => break,
         }
+    }
+    found
+}
+
+/// A graph without some of its vertices and edges, where
+/// [`k_shortest_paths`] looks for deviations.
+struct Without<'g, G> {
+    graph: &'g G,
+    vertices: HashSet<VertexId>,
+    edges: HashSet<EdgeId>,
+}
+
+impl<'g, G: Neighbors> GraphBase for Without<'g, G> {
+
+    fn vertex_count(&self) -> usize {
+        self.graph.vertex_count() - self.vertices.len()
+    }
+
+    fn edge_count(&self) -> usize {
+        let mut count = 0;
+        self.visit_vertices(|v| self.visit_out(v, |_, _| count += 1));
+        count
+    }
+
+    fn contains_vertex(&self, vertex: VertexId) -> bool {
+        self.graph.contains_vertex(vertex) && !self.vertices.contains(&vertex)
+    }
+
+    fn visit_vertices<F: FnMut(VertexId)>(&self, mut visit: F) {
+        self.graph.visit_vertices(|v| {
+            if !self.vertices.contains(&v) {
+                visit(v);
+            }
+        })
+    }
+}
+
+impl<'g, G: Neighbors> EdgeWeights for Without<'g, G> {
+    type Edge = G::Edge;
 
-        if !dist.contains_key(&start) {
+    fn with_edge<R, F: FnOnce(&G::Edge) -> R>(&self, edge: EdgeId, visit: F) -> Option<R> {
+        let (from, to) = edge;
+        if self.edges.contains(&edge) || self.vertices.contains(&from) || self.vertices.contains(&to) {
             return None;
         }
+        self.graph.with_edge(edge, visit)
+    }
+}
+
+impl<'g, G: Neighbors> Neighbors for Without<'g, G> {
+
+    fn visit_out<F: FnMut(VertexId, &G::Edge)>(&self, vertex: VertexId, mut visit: F) {
+        if self.vertices.contains(&vertex) {
+            return;
+        }
+        self.graph.visit_out(vertex, |to, weight| {
+            if !self.vertices.contains(&to) && !self.edges.contains(&(vertex, to)) {
+                visit(to, weight);
+            }
+        })
+    }
+
+    fn visit_in<F: FnMut(VertexId, &G::Edge)>(&self, vertex: VertexId, mut visit: F) {
+        if self.vertices.contains(&vertex) {
+            return;
+        }
+        self.graph.visit_in(vertex, |from, weight| {
+            if !self.vertices.contains(&from) && !self.edges.contains(&(from, vertex)) {
+                visit(from, weight);
+            }
+        })
+    }
+}
 
-        let mut path = Vec::new();
-        let mut curr = start;
-        
-        while curr != end {
-            path.push(curr);    
-            curr = next[&curr];
+impl<V: std::fmt::Debug, E> Graph<V, E> {
+
+    /// Whether `goal` can be reached from `start`, see [`has_path`].
+    pub fn has_path(&self, start: VertexId, goal: VertexId) -> bool {
+        has_path(self, start, goal)
+    }
+
+    /// The paths from `start` to `goal` with at most `max_len` edges and
+    /// no repeated vertex, see [`all_simple_paths`].
+    pub fn all_simple_paths(&self, start: VertexId, goal: VertexId, max_len: usize) -> SimplePaths<'_, Self> {
+        all_simple_paths(self, start, goal, max_len)
+    }
+}
+
+impl<V: std::fmt::Debug, E: Copy + Ord + Add<Output = E> + Default> Graph<V, E> {
+
+    /// Shortest path from `start` to `goal`, see [`dijkstra`].
+    pub fn dijkstra(&self, start: VertexId, goal: VertexId) -> Option<(Vec<VertexId>, E)> {
+        dijkstra(self, start, goal)
+    }
+
+    /// Shortest paths from `start` to every vertex, see [`shortest_paths_from`].
+    pub fn shortest_paths_from(&self, start: VertexId) -> ShortestPathResult<E> {
+        shortest_paths_from(self, start)
+    }
+
+    /// Up to `k` loopless paths from `start` to `goal`, cheapest
+    /// first, see [`k_shortest_paths`].
+    pub fn k_shortest_paths(&self, start: VertexId, goal: VertexId, k: usize) -> Vec<(Vec<VertexId>, E)> {
+        k_shortest_paths(self, start, goal, k)
+    }
+
+
+    /// A* search from `start` to `goal`, see [`astar`].
+    pub fn astar<H>(&self, start: VertexId, goal: VertexId, heuristic: H) -> Option<(Vec<VertexId>, E)>
+    where
+        H: Fn(&V) -> E,
+    {
+        astar(self, start, goal, heuristic)
+    }
+}
+
+impl<V: Eq + Hash + Clone + std::fmt::Debug, E> GraphMap<V, E> {
+
+    /// See [`Graph::has_path`]. False if either vertex is missing.
+    pub fn has_path(&self, start: &V, goal: &V) -> bool {
+        match (self.map.get(start), self.map.get(goal)) {
+            (Some(&start), Some(&goal)) => has_path(self, start, goal),
+            _ => false,
         }
-        
-        path.push(end);
+    }
+
+    /// See [`Graph::all_simple_paths`]. Empty if either vertex is missing.
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// let workflow = GraphMap::<&str, ()>::from_edges(vec![
+    ///     (("draft", "review"), ()),
+    ///     (("review", "draft"), ()),
+    ///     (("review", "approved"), ()),
+    ///     (("draft", "approved"), ()),
+    ///     (("approved", "published"), ()),
+    /// ]);
+    /// assert!(workflow.has_path(&"draft", &"published"));
+    /// assert!(!workflow.has_path(&"published", &"draft"));
+    ///
+    /// let mut routes: Vec<_> = workflow.all_simple_paths(&"draft", &"published", 3).collect();
+    /// routes.sort();
+    /// assert_eq!(routes, vec![
+    ///     vec!["draft", "approved", "published"],
+    ///     vec!["draft", "review", "approved", "published"],
+    /// ]);
+    /// ```
+    pub fn all_simple_paths(&self, start: &V, goal: &V, max_len: usize) -> impl Iterator<Item = Vec<V>> + '_ {
+        let paths = match (self.map.get(start), self.map.get(goal)) {
+            (Some(&start), Some(&goal)) => Some(all_simple_paths(self, start, goal, max_len)),
+            _ => None,
+        };
+        paths.into_iter().flatten().map(move |path| {
+            path.into_iter()
+                .map(|id| self.graph.get_vertex(id).unwrap().clone())
+                .collect()
+        })
+    }
+}
+
+impl<V, E> GraphMap<V, E>
+where
+    V: Eq + Hash + Clone + std::fmt::Debug,
+    E: Copy + Ord + Add<Output = E> + Default,
+{
+
+    /// Shortest path from `start` to `end`, see [`dijkstra`].
+    /// `None` if either vertex is missing.
+    pub fn dijkstra(&self, start: V, end: V) -> Option<(Vec<V>, E)> {
+        let start = *self.map.get(&start)?;
+        let end = *self.map.get(&end)?;
+        let (path, cost) = dijkstra(self, start, end)?;
+        let path = path.into_iter()
+            .map(|id| self.graph.get_vertex(id).unwrap().clone())
+            .collect();
+        Some((path, cost))
+    }
 
-        Some((path, dist[&start]))
+    /// See [`Graph::shortest_paths_from`]. Empty if `start` is not in the graph.
+    pub fn shortest_paths_from(&self, start: &V) -> ShortestPaths<&V, E> {
+        let start = match self.map.get(start) {
+            Some(&id) => id,
+            None => return ShortestPaths {
+                distance: HashMap::new(),
+                predecessor: HashMap::new(),
+            },
+        };
+        shortest_paths_from(self, start).map(|id| self.graph.get_vertex(id).unwrap())
     }
 
+    /// See [`Graph::k_shortest_paths`]. Empty if either vertex is missing.
+    pub fn k_shortest_paths(&self, start: &V, goal: &V, k: usize) -> Vec<(Vec<V>, E)> {
+        let (start, goal) = match (self.map.get(start), self.map.get(goal)) {
+            (Some(&start), Some(&goal)) => (start, goal),
+            _ => return Vec::new(),
+        };
+        k_shortest_paths(self, start, goal, k).into_iter()
+            .map(|(path, cost)| {
+                let path = path.into_iter()
+                    .map(|id| self.graph.get_vertex(id).unwrap().clone())
+                    .collect();
+                (path, cost)
+            })
+            .collect()
+    }
+
+    /// See [`Graph::astar`].
+    ///
+    /// ```
+    /// use graph::GraphMap;
+    ///
+    /// // 4 x 4 grid, Manhattan distance to the corner.
+    /// let mut grid = GraphMap::<(u32, u32), u32>::new();
+    /// for x in 0..4 {
+    ///     for y in 0..4 {
+    ///         if x < 3 { grid.add_edge(((x, y), (x + 1, y)), 1); }
+    ///         if y < 3 { grid.add_edge(((x, y), (x, y + 1)), 1); }
+    ///     }
+    /// }
+    /// let (path, cost) = grid.astar(&(0, 0), &(3, 3), |&(x, y)| (3 - x) + (3 - y)).unwrap();
+    /// assert_eq!(cost, 6);
+    /// assert_eq!(path.len(), 7);
+    /// assert_eq!(path[0], (0, 0));
+    /// ```
+    pub fn astar<H>(&self, start: &V, goal: &V, heuristic: H) -> Option<(Vec<V>, E)>
+    where
+        H: Fn(&V) -> E,
+    {
+        let start = *self.map.get(start)?;
+        let goal = *self.map.get(goal)?;
+        let (path, cost) = self.graph.astar(start, goal, heuristic)?;
+        let path = path.into_iter()
+            .map(|id| self.graph.get_vertex(id).unwrap().clone())
+            .collect();
+        Some((path, cost))
+    }
 }
@@ -0,0 +1,297 @@
+//! Rooted trees stored as a [`Graph`] with edges from parent to child.
+
+use super::{Graph, VertexId, EdgeId};
+use std::collections::{
+    VecDeque,
+    HashSet,
+};
+
+/// Rooted tree built on top of [`Graph`].
+/// Edges always point from the parent to the child,
+/// so every vertex except the root has indegree 1.
+#[derive(Clone, Debug)]
+pub struct Tree<V, E = ()> {
+    graph: Graph<V, E>,
+    root: VertexId,
+}
+
+impl<V: std::fmt::Debug, E> Tree<V, E> {
+
+    /// Creates a tree containing only the root.
+    pub fn new(root: V) -> Self {
+        let mut graph = Graph::new();
+        let root = graph.add_vertex(root);
+        Tree {
+            graph,
+            root,
+        }
+    }
+
+    /// Turns a graph into a tree rooted in `root`.
+    /// The edges are looked at without direction and are flipped
+    /// so they point away from the root.
+    /// Returns `None` if the graph is not connected, has cycles,
+    /// self loops, or two edges between the same vertices.
+    pub fn from_graph(mut graph: Graph<V, E>, root: VertexId) -> Option<Self> {
+        graph.get_vertex(root)?;
+
+        if graph.edge_count() + 1 != graph.vertex_count() {
+            return None;
+        }
+
+        for &(from, to) in graph.edges.keys() {
+            if from == to || graph.edges.contains_key(&(to, from)) {
+                return None;
+            }
+        }
+
+        // connected with |V| - 1 edges, so it is a tree
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+        let mut flipped = Vec::new();
+
+        visited.insert(root);
+        queue.push_back(root);
+
+        while let Some(current) = queue.pop_front() {
+            for &child in graph.outbound[&current].iter() {
+                if visited.insert(child) {
+                    queue.push_back(child);
+                }
+            }
+            for &child in graph.inbound[&current].iter() {
+                if visited.insert(child) {
+                    queue.push_back(child);
+                    flipped.push((child, current));
+                }
+            }
+        }
+
+        if visited.len() != graph.vertex_count() {
+            return None;
+        }
+
+        for edge in flipped {
+            flip_edge(&mut graph, edge);
+        }
+
+        Some(Tree {
+            graph,
+            root,
+        })
+    }
+
+    /// The root of the tree.
+    pub fn root(&self) -> VertexId {
+        self.root
+    }
+
+    /// The underlying graph.
+    pub fn graph(&self) -> &Graph<V, E> {
+        &self.graph
+    }
+
+    /// Consumes the tree, returning the underlying graph.
+    pub fn into_graph(self) -> Graph<V, E> {
+        self.graph
+    }
+
+    /// Returns the data in the vertex.
+    pub fn get_vertex(&self, vertex: VertexId) -> Option<&V> {
+        self.graph.get_vertex(vertex)
+    }
+
+    /// Number of vertices in the tree.
+    pub fn vertex_count(&self) -> usize {
+        self.graph.vertex_count()
+    }
+
+    /// Adds a new leaf under `parent`.
+    /// Returns `None` if the parent is not in the tree.
+    pub fn add_child(&mut self, parent: VertexId, vertex: V, weight: E) -> Option<VertexId> {
+        self.graph.get_vertex(parent)?;
+        let child = self.graph.add_vertex(vertex);
+        self.graph.insert_edge((parent, child), weight);
+        Some(child)
+    }
+
+    /// Removes the vertex and everything under it.
+    /// The root can not be removed.
+    pub fn remove_subtree(&mut self, vertex: VertexId) -> bool {
+        if vertex == self.root || self.graph.get_vertex(vertex).is_none() {
+            return false;
+        }
+        for v in self.preorder(vertex) {
+            self.graph.remove_vertex(v);
+        }
+        true
+    }
+
+    /// Parent of the vertex. The root has no parent.
+    pub fn parent(&self, vertex: VertexId) -> Option<VertexId> {
+        self.graph.inbound.get(&vertex)?.iter().next().copied()
+    }
+
+    /// Children of the vertex.
+    pub fn children(&self, vertex: VertexId) -> Vec<VertexId> {
+        match self.graph.outbound.get(&vertex) {
+            Some(set) => set.iter().copied().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns true if the vertex has no children.
+    pub fn is_leaf(&self, vertex: VertexId) -> bool {
+        self.graph.outdegree(vertex) == 0
+    }
+
+    /// Distance from the root, in edges.
+    /// Time complexity: O(depth)
+    pub fn depth(&self, vertex: VertexId) -> Option<usize> {
+        self.graph.get_vertex(vertex)?;
+        let mut depth = 0;
+        let mut current = vertex;
+        while let Some(parent) = self.parent(current) {
+            current = parent;
+            depth += 1;
+        }
+        Some(depth)
+    }
+
+    /// Number of vertices in the subtree rooted in `vertex`,
+    /// including the vertex itself.
+    pub fn subtree_size(&self, vertex: VertexId) -> Option<usize> {
+        self.graph.get_vertex(vertex)?;
+        Some(self.preorder(vertex).len())
+    }
+
+    /// Height of the tree, the depth of the deepest vertex.
+    pub fn height(&self) -> usize {
+        let mut height = 0;
+        let mut queue = VecDeque::new();
+        queue.push_back((self.root, 0));
+        while let Some((current, depth)) = queue.pop_front() {
+            height = depth;
+            for &child in self.graph.outbound[&current].iter() {
+                queue.push_back((child, depth + 1));
+            }
+        }
+        height
+    }
+
+    /// Vertices of the subtree rooted in `vertex`, in preorder.
+    pub fn preorder(&self, vertex: VertexId) -> Vec<VertexId> {
+        let mut nodes = Vec::new();
+        if self.graph.get_vertex(vertex).is_none() {
+            return nodes;
+        }
+        let mut stack = vec![vertex];
+        while let Some(current) = stack.pop() {
+            nodes.push(current);
+            stack.extend(self.graph.outbound[&current].iter().copied());
+        }
+        nodes
+    }
+
+    /// Path from the root to the vertex, both included.
+    pub fn path_from_root(&self, vertex: VertexId) -> Option<Vec<VertexId>> {
+        self.graph.get_vertex(vertex)?;
+        let mut path = vec![vertex];
+        let mut current = vertex;
+        while let Some(parent) = self.parent(current) {
+            path.push(parent);
+            current = parent;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Makes `vertex` the new root, by flipping the edges
+    /// on the path between the old and the new root.
+    /// Time complexity: O(depth(vertex))
+    pub fn reroot(&mut self, vertex: VertexId) -> bool {
+        let path = match self.path_from_root(vertex) {
+            Some(path) => path,
+            None => return false,
+        };
+        for pair in path.windows(2) {
+            flip_edge(&mut self.graph, (pair[0], pair[1]));
+        }
+        self.root = vertex;
+        true
+    }
+
+    /// Checks that the underlying graph really is a tree rooted
+    /// in [`Tree::root`]: connected, acyclic, and every edge
+    /// pointing away from the root.
+    pub fn is_valid(&self) -> bool {
+        if self.graph.get_vertex(self.root).is_none()
+            || self.graph.indegree(self.root) != 0
+            || self.graph.edge_count() + 1 != self.graph.vertex_count() {
+            return false;
+        }
+        let mut visited = HashSet::new();
+        let mut stack = vec![self.root];
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current) {
+                return false;
+            }
+            stack.extend(self.graph.outbound[&current].iter().copied());
+        }
+        visited.len() == self.graph.vertex_count()
+    }
+}
+
+/// Reverses the direction of an existing edge, keeping its data.
+fn flip_edge<V, E>(graph: &mut Graph<V, E>, edge: EdgeId) {
+    let (from, to) = edge;
+    let weight = graph.edges.remove(&edge).unwrap();
+    graph.outbound.get_mut(&from).unwrap().remove(&to);
+    graph.inbound.get_mut(&to).unwrap().remove(&from);
+    graph.edges.insert((to, from), weight);
+    graph.outbound.get_mut(&to).unwrap().insert(from);
+    graph.inbound.get_mut(&from).unwrap().insert(to);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_graph_orients_and_reroots() {
+        let mut graph = Graph::<u32, ()>::new();
+        let a = graph.add_vertex(0);
+        let b = graph.add_vertex(1);
+        let c = graph.add_vertex(2);
+        let d = graph.add_vertex(3);
+        graph.add_edge((b, a), ()).unwrap();
+        graph.add_edge((b, c), ()).unwrap();
+        graph.add_edge((d, c), ()).unwrap();
+
+        let mut tree = Tree::from_graph(graph, a).unwrap();
+        assert!(tree.is_valid());
+        assert_eq!(tree.parent(b), Some(a));
+        assert_eq!(tree.depth(d), Some(3));
+        assert_eq!(tree.subtree_size(b), Some(3));
+
+        assert!(tree.reroot(c));
+        assert!(tree.is_valid());
+        assert_eq!(tree.root(), c);
+        assert_eq!(tree.depth(a), Some(2));
+        assert_eq!(tree.parent(c), None);
+    }
+
+    #[test]
+    fn from_graph_rejects_cycles() {
+        let mut graph = Graph::<u32, ()>::new();
+        let a = graph.add_vertex(0);
+        let b = graph.add_vertex(1);
+        let c = graph.add_vertex(2);
+        graph.add_edge((a, b), ()).unwrap();
+        graph.add_edge((b, a), ()).unwrap();
+        graph.add_vertex(3);
+        graph.add_edge((b, c), ()).unwrap();
+        assert!(Tree::from_graph(graph, a).is_none());
+    }
+}
@@ -0,0 +1,215 @@
+//! Graph with undirected edges.
+//!
+//! [`Graph`](crate::Graph) only has directed edges, and an undirected
+//! graph stored in it needs every edge twice. [`UndirectedGraph`] stores
+//! each edge once, under its two ends sorted, so `(a, b)` and `(b, a)`
+//! are the same edge.
+
+use super::{VertexId, Vertices, EdgeId, GraphError, Map, Set};
+use crate::arena::Arena;
+use std::collections::{
+    hash_map,
+    hash_set,
+};
+
+/// Canonical key of an undirected edge.
+fn key(edge: EdgeId) -> EdgeId {
+    let (a, b) = edge;
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+/// Undirected graph data structure. [`V`] is the Vertex data,
+/// and [`E`] is the Edge data.
+///
+/// ```
+/// use graph::undirected::UndirectedGraph;
+///
+/// let mut graph = UndirectedGraph::<&str, u32>::new();
+/// let a = graph.add_vertex("a");
+/// let b = graph.add_vertex("b");
+/// graph.add_edge((a, b), 5).unwrap();
+/// assert_eq!(graph.get_edge((b, a)), Some(&5));
+/// assert_eq!(graph.edge_count(), 1);
+/// assert_eq!(graph.degree(b), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct UndirectedGraph<V, E> {
+    arena: Arena<V>,
+    adj: Map<VertexId, Set<VertexId>>,
+    edges: Map<EdgeId, E>,
+}
+
+impl<V: std::fmt::Debug, E> UndirectedGraph<V, E> {
+
+    pub fn new() -> Self {
+        UndirectedGraph {
+            arena: Arena::new(),
+            adj: Map::default(),
+            edges: Map::default(),
+        }
+    }
+
+    /// Adds a vertex to the graph, and returns its id.
+    pub fn add_vertex(&mut self, vertex: V) -> VertexId {
+        let id = self.arena.insert(vertex);
+        self.adj.entry(id).or_default();
+        id
+    }
+
+    /// Returns the data in the vertex.
+    pub fn get_vertex(&self, vertex: VertexId) -> Option<&V> {
+        self.arena.get(vertex)
+    }
+
+    /// Returns the data in the vertex, mutably.
+    pub fn get_vertex_mut(&mut self, vertex: VertexId) -> Option<&mut V> {
+        self.arena.get_mut(vertex)
+    }
+
+    /// Adds an edge between the two vertices, in any order, or modifies
+    /// the existing one, returning its previous data. Fails if either
+    /// vertex is not in the graph, see [`Graph::add_edge`](crate::Graph::add_edge).
+    pub fn add_edge(&mut self, edge: EdgeId, weight: E) -> Result<Option<E>, GraphError> {
+        let (a, b) = edge;
+        if !self.arena.contains(a) || !self.arena.contains(b) {
+            return Err(GraphError::VertexNotFound);
+        }
+        self.adj.entry(a).or_default().insert(b);
+        self.adj.entry(b).or_default().insert(a);
+        Ok(self.edges.insert(key(edge), weight))
+    }
+
+    /// Get the edge between the two vertices, in any order.
+    pub fn get_edge(&self, edge: EdgeId) -> Option<&E> {
+        self.edges.get(&key(edge))
+    }
+
+    /// Get the edge between the two vertices, mutably.
+    pub fn get_edge_mut(&mut self, edge: EdgeId) -> Option<&mut E> {
+        self.edges.get_mut(&key(edge))
+    }
+
+    pub fn contains_edge(&self, edge: EdgeId) -> bool {
+        self.edges.contains_key(&key(edge))
+    }
+
+    /// Removes the vertex and its edges, returning its data.
+    /// Time complexity: O(degree(v))
+    pub fn remove_vertex(&mut self, vertex: VertexId) -> Option<V> {
+        let data = self.arena.remove(vertex)?;
+        for other in self.adj.remove(&vertex).unwrap_or_default() {
+            self.edges.remove(&key((vertex, other)));
+            if let Some(set) = self.adj.get_mut(&other) {
+                set.remove(&vertex);
+            }
+        }
+        Some(data)
+    }
+
+    /// Removes an edge, returning its data.
+    /// Time complexity: O(1)
+    pub fn remove_edge(&mut self, edge: EdgeId) -> Option<E> {
+        let weight = self.edges.remove(&key(edge))?;
+        let (a, b) = edge;
+        self.adj.get_mut(&a).unwrap().remove(&b);
+        self.adj.get_mut(&b).unwrap().remove(&a);
+        Some(weight)
+    }
+
+    /// Returns an iterator over the neighbours, as pairs
+    /// of (neighbour, weight).
+    pub fn adj(&self, vertex: VertexId) -> Option<Adj<'_, E>> {
+        let adj = self.adj.get(&vertex)?;
+        Some(Adj {
+            vertex,
+            others: adj.iter(),
+            edges: &self.edges,
+        })
+    }
+
+    /// Degree of the vertex. A self loop counts twice.
+    pub fn degree(&self, vertex: VertexId) -> usize {
+        match self.adj.get(&vertex) {
+            Some(set) => set.len() + set.contains(&vertex) as usize,
+            None => 0,
+        }
+    }
+
+    /// Number of vertices
+    pub fn vertex_count(&self) -> usize {
+        self.arena.len()
+    }
+
+    /// Number of edges, each counted once.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Iterator over the vertices
+    pub fn vertices(&self) -> Vertices<'_, V> {
+        Vertices::new(&self.arena)
+    }
+
+    /// Iterator over the edges, each once, with the smaller id first.
+    pub fn edges(&self) -> hash_map::Iter<'_, EdgeId, E> {
+        self.edges.iter()
+    }
+}
+
+/// Iterator over the edges of a vertex, see [`UndirectedGraph::adj`].
+#[derive(Clone, Debug)]
+pub struct Adj<'a, E> {
+    vertex: VertexId,
+    others: hash_set::Iter<'a, VertexId>,
+    edges: &'a Map<EdgeId, E>,
+}
+
+impl<'a, E> Iterator for Adj<'a, E> {
+    type Item = (VertexId, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let &other = self.others.next()?;
+        Some((other, &self.edges[&key((self.vertex, other))]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.others.size_hint()
+    }
+}
+
+impl<'a, E> ExactSizeIterator for Adj<'a, E> {}
+
+impl<V: std::fmt::Debug, E> Default for UndirectedGraph<V, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UndirectedGraph;
+
+    #[test]
+    fn edges_are_stored_once() {
+        let mut graph = UndirectedGraph::<u32, u32>::new();
+        let ids: Vec<_> = (0..3).map(|i| graph.add_vertex(i)).collect();
+        graph.add_edge((ids[0], ids[1]), 1).unwrap();
+        assert_eq!(graph.add_edge((ids[1], ids[0]), 2), Ok(Some(1)));
+        graph.add_edge((ids[2], ids[2]), 3).unwrap();
+        graph.add_edge((ids[1], ids[2]), 4).unwrap();
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.get_edge((ids[0], ids[1])), Some(&2));
+        assert_eq!(graph.degree(ids[2]), 3);
+        assert_eq!(graph.adj(ids[1]).unwrap().count(), 2);
+
+        assert_eq!(graph.remove_edge((ids[2], ids[1])), Some(4));
+        assert_eq!(graph.degree(ids[1]), 1);
+        assert_eq!(graph.remove_vertex(ids[2]), Some(2));
+        assert_eq!(graph.edge_count(), 1);
+        assert_eq!(graph.remove_vertex(ids[0]), Some(0));
+        assert_eq!(graph.degree(ids[1]), 0);
+        assert_eq!(graph.edge_count(), 0);
+        assert!(graph.add_edge((ids[0], ids[1]), 5).is_err());
+        assert!(graph.adj(ids[0]).is_none());
+    }
+}
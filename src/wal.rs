@@ -0,0 +1,502 @@
+//! Write-ahead log persistence.
+//!
+//! Every mutation of a [`WalGraph`] is appended to a log file before it is
+//! applied, so the graph can be rebuilt with [`Graph::replay`] after a crash,
+//! without rewriting the whole graph on every change.
+//!
+//! The log is a text file with one operation per line:
+//!
+//! ```text
+//! v <vertex data>
+//! rv <vertex id>
+//! e <from id> <to id> <edge data>
+//! re <from id> <to id>
+//! ```
+//!
+//! Vertex ids are written as `index.generation`. The arena hands out ids
+//! deterministically, so replaying the operations in order gives back the
+//! same ids. Data is written with [`Display`] and read with [`FromStr`],
+//! with backslashes and line breaks escaped.
+//!
+//! [`PersistentGraph`] does the same for a [`GraphMap`], logging the
+//! vertices themselves instead of ids, on top of a snapshot in the
+//! [binary](crate::io::binary) format. Compacting writes a new snapshot
+//! and empties the log, so the log doesn't grow forever.
+
+use super::{Graph, GraphError, GraphMap, VertexId, EdgeId};
+use crate::io::binary::{read_graph_map, write_graph_map};
+use crate::io::compressed::{self, Compression};
+use std::fmt::Display;
+use std::fs::{File, OpenOptions};
+use std::hash::Hash;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// [`Graph`] that logs every mutation to a file.
+#[derive(Debug)]
+pub struct WalGraph<V, E> {
+    graph: Graph<V, E>,
+    log: BufWriter<File>,
+}
+
+impl<V, E> WalGraph<V, E>
+where
+    V: std::fmt::Debug + Display + FromStr,
+    E: Display + FromStr,
+{
+    /// Opens the log at `path`, replaying it if it already exists,
+    /// and appends the following mutations to it.
+    /// A truncated last entry is dropped from the file.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let graph = if path.exists() {
+            let contents = std::fs::read(path)?;
+            let complete = contents.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+            if complete < contents.len() {
+                OpenOptions::new().write(true).open(path)?.set_len(complete as u64)?;
+            }
+            Graph::replay(path)?
+        } else {
+            Graph::new()
+        };
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(WalGraph {
+            graph,
+            log: BufWriter::new(file),
+        })
+    }
+
+    /// The graph built so far.
+    pub fn graph(&self) -> &Graph<V, E> {
+        &self.graph
+    }
+
+    /// Closes the log, returning the graph.
+    pub fn into_graph(mut self) -> io::Result<Graph<V, E>> {
+        self.log.flush()?;
+        Ok(self.graph)
+    }
+
+    /// Forces the log to disk. Every mutation is already handed to the
+    /// operating system, this also survives a power loss.
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.log.flush()?;
+        self.log.get_ref().sync_data()
+    }
+
+    fn append(&mut self, line: String) -> io::Result<()> {
+        self.log.write_all(line.as_bytes())?;
+        self.log.write_all(b"\n")?;
+        self.log.flush()
+    }
+
+    /// Logs and adds a vertex.
+    pub fn add_vertex(&mut self, vertex: V) -> io::Result<VertexId> {
+        self.append(format!("v {}", escape(&vertex)))?;
+        Ok(self.graph.add_vertex(vertex))
+    }
+
+    /// Logs and removes a vertex. Does nothing if the vertex is not
+    /// in the graph.
+    pub fn remove_vertex(&mut self, vertex: VertexId) -> io::Result<()> {
+        if self.graph.get_vertex(vertex).is_some() {
+            self.append(format!("rv {}", vertex))?;
+            self.graph.remove_vertex(vertex);
+        }
+        Ok(())
+    }
+
+    /// Logs and adds an edge, or modifies the existing one. Fails
+    /// without logging anything if either vertex is not in the graph.
+    pub fn add_edge(&mut self, edge: EdgeId, weight: E) -> io::Result<()> {
+        let (from, to) = edge;
+        if !self.graph.contains_vertex(from) || !self.graph.contains_vertex(to) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, GraphError::VertexNotFound));
+        }
+        self.append(format!("e {} {} {}", from, to, escape(&weight)))?;
+        self.graph.insert_edge(edge, weight);
+        Ok(())
+    }
+
+    /// Logs and removes an edge. Does nothing if the edge is not
+    /// in the graph.
+    pub fn remove_edge(&mut self, edge: EdgeId) -> io::Result<()> {
+        if self.graph.get_edge(edge).is_some() {
+            let (from, to) = edge;
+            self.append(format!("re {} {}", from, to))?;
+            self.graph.remove_edge(edge);
+        }
+        Ok(())
+    }
+}
+
+impl<V, E> Graph<V, E>
+where
+    V: std::fmt::Debug + FromStr,
+    E: FromStr,
+{
+    /// Rebuilds a graph from a write-ahead log, see [`WalGraph`].
+    /// A truncated last line, left by a crash in the middle of a
+    /// write, is ignored.
+    pub fn replay<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut graph = Graph::new();
+        let mut lines = reader.lines().peekable();
+        let mut number = 0;
+
+        while let Some(line) = lines.next() {
+            let line = line?;
+            number += 1;
+            let last = lines.peek().is_none();
+            match graph.apply(&line) {
+                Some(()) => (),
+                None if last => break,
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid log entry on line {}: {}", number, line),
+                    ));
+                }
+            }
+        }
+        Ok(graph)
+    }
+
+    fn apply(&mut self, line: &str) -> Option<()> {
+        let (op, rest) = line.split_once(' ')?;
+        match op {
+            "v" => {
+                self.add_vertex(unescape(rest)?.parse().ok()?);
+            }
+            "rv" => {
+                let id = parse_id(rest)?;
+                self.get_vertex(id)?;
+                self.remove_vertex(id);
+            }
+            "e" => {
+                let mut parts = rest.splitn(3, ' ');
+                let from = parse_id(parts.next()?)?;
+                let to = parse_id(parts.next()?)?;
+                let weight = unescape(parts.next()?)?.parse().ok()?;
+                self.get_vertex(from)?;
+                self.get_vertex(to)?;
+                self.insert_edge((from, to), weight);
+            }
+            "re" => {
+                let (from, to) = rest.split_once(' ')?;
+                let edge = (parse_id(from)?, parse_id(to)?);
+                self.get_edge(edge)?;
+                self.remove_edge(edge);
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+}
+
+/// [`GraphMap`] that logs every mutation to a file, on top of a
+/// snapshot, see the [module](self) documentation.
+///
+/// The log has the operations of [`WalGraph`], with vertices instead of
+/// ids, and spaces escaped as `\s`:
+///
+/// ```text
+/// v <vertex>
+/// rv <vertex>
+/// e <from> <to> <edge data>
+/// re <from> <to>
+/// ```
+///
+/// Every operation only says what the graph must have afterwards, so
+/// replaying the log over the snapshot it was compacted into gives the
+/// same graph: a crash in the middle of a compaction loses nothing.
+///
+/// ```
+/// use graph::wal::PersistentGraph;
+///
+/// let dir = std::env::temp_dir().join(format!("graph-doc-persistent-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let (snapshot, log) = (dir.join("graph.bin"), dir.join("graph.log"));
+///
+/// let mut graph = PersistentGraph::<String, u32>::open(&snapshot, &log).unwrap();
+/// graph.set_compact_every(3);
+/// graph.add_edge(("a".to_string(), "b c".to_string()), 1).unwrap();
+/// graph.add_edge(("b c".to_string(), "d".to_string()), 2).unwrap();
+/// graph.add_edge(("d".to_string(), "a".to_string()), 3).unwrap();
+/// graph.remove_vertex("d".to_string()).unwrap();
+/// drop(graph);
+///
+/// let mut graph = PersistentGraph::<String, u32>::open(&snapshot, &log).unwrap();
+/// assert_eq!(graph.log_len(), 1);
+/// assert_eq!(graph.graph().vertex_count(), 2);
+/// assert_eq!(graph.graph().get_edge(("a".to_string(), "b c".to_string())), Some(&1));
+///
+/// // Nothing to remove, nothing logged.
+/// assert!(!graph.remove_edge(("d".to_string(), "a".to_string())).unwrap());
+/// assert_eq!(graph.log_len(), 1);
+/// std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct PersistentGraph<V: Eq + Hash + Clone, E> {
+    graph: GraphMap<V, E>,
+    log: BufWriter<File>,
+    snapshot_path: PathBuf,
+    log_path: PathBuf,
+    entries: usize,
+    compact_every: usize,
+}
+
+impl<V, E> PersistentGraph<V, E>
+where
+    V: Eq + Hash + Clone + std::fmt::Debug + Display + FromStr,
+    E: Display + FromStr,
+{
+    /// Opens the graph, reading the snapshot and replaying the log if
+    /// they exist. A truncated last entry is dropped from the log.
+    /// Compacts every 10000 mutations until [`PersistentGraph::set_compact_every`]
+    /// changes it.
+    pub fn open<P: AsRef<Path>, Q: AsRef<Path>>(snapshot: P, log: Q) -> io::Result<Self> {
+        let (snapshot, log) = (snapshot.as_ref(), log.as_ref());
+        let mut graph = if snapshot.exists() {
+            read_graph_map(compressed::open(snapshot)?)?
+        } else {
+            GraphMap::new()
+        };
+        let mut entries = 0;
+        if log.exists() {
+            let contents = std::fs::read(log)?;
+            let complete = contents.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+            if complete < contents.len() {
+                OpenOptions::new().write(true).open(log)?.set_len(complete as u64)?;
+            }
+            for (number, line) in contents[..complete].split(|&b| b == b'\n').enumerate() {
+                if line.is_empty() {
+                    continue;
+                }
+                let line = String::from_utf8_lossy(line);
+                apply_to_map(&mut graph, &line).ok_or_else(|| io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid log entry on line {}: {}", number + 1, line),
+                ))?;
+                entries += 1;
+            }
+        }
+        let file = OpenOptions::new().create(true).append(true).open(log)?;
+        Ok(PersistentGraph {
+            graph,
+            log: BufWriter::new(file),
+            snapshot_path: snapshot.to_path_buf(),
+            log_path: log.to_path_buf(),
+            entries,
+            compact_every: 10_000,
+        })
+    }
+
+    /// The graph built so far.
+    pub fn graph(&self) -> &GraphMap<V, E> {
+        &self.graph
+    }
+
+    /// Closes the log, returning the graph.
+    pub fn into_graph(mut self) -> io::Result<GraphMap<V, E>> {
+        self.log.flush()?;
+        Ok(self.graph)
+    }
+
+    /// Number of entries in the log, since the last compaction.
+    pub fn log_len(&self) -> usize {
+        self.entries
+    }
+
+    /// Compacts when the log reaches `entries`, or never if 0.
+    pub fn set_compact_every(&mut self, entries: usize) {
+        self.compact_every = entries;
+    }
+
+    /// Forces the log to disk, see [`WalGraph::sync`].
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.log.flush()?;
+        self.log.get_ref().sync_data()
+    }
+
+    /// Writes the graph to a new snapshot, replacing the old one
+    /// atomically, and empties the log.
+    pub fn compact(&mut self) -> io::Result<()> {
+        let tmp = self.snapshot_path.with_extension("tmp");
+        let mut file = compressed::Writer::new(File::create(&tmp)?, Compression::from_path(&self.snapshot_path))?;
+        write_graph_map(&mut file, &self.graph)?;
+        file.finish()?.sync_data()?;
+        std::fs::rename(&tmp, &self.snapshot_path)?;
+
+        self.log.flush()?;
+        self.log = BufWriter::new(File::create(&self.log_path)?);
+        self.entries = 0;
+        Ok(())
+    }
+
+    /// Replaces the whole graph, and compacts.
+    pub fn replace(&mut self, graph: GraphMap<V, E>) -> io::Result<()> {
+        self.graph = graph;
+        self.compact()
+    }
+
+    /// Writes an entry, before the change it records is applied.
+    fn append(&mut self, line: String) -> io::Result<()> {
+        self.log.write_all(line.as_bytes())?;
+        self.log.write_all(b"\n")?;
+        self.log.flush()?;
+        self.entries += 1;
+        Ok(())
+    }
+
+    /// Compacts if the log is due, once the change is applied, so the
+    /// snapshot has it.
+    fn compact_if_due(&mut self) -> io::Result<()> {
+        if self.compact_every > 0 && self.entries >= self.compact_every {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    /// Logs and adds a vertex, see [`GraphMap::add_vertex`].
+    pub fn add_vertex(&mut self, vertex: V) -> io::Result<()> {
+        self.append(format!("v {}", escape_field(&vertex)))?;
+        self.graph.add_vertex(vertex);
+        self.compact_if_due()
+    }
+
+    /// Logs and removes a vertex. Returns false if it is not in the graph.
+    pub fn remove_vertex(&mut self, vertex: V) -> io::Result<bool> {
+        if !self.graph.contains_vertex(&vertex) {
+            return Ok(false);
+        }
+        self.append(format!("rv {}", escape_field(&vertex)))?;
+        self.graph.remove_vertex(vertex);
+        self.compact_if_due()?;
+        Ok(true)
+    }
+
+    /// Logs and adds an edge, see [`GraphMap::add_edge`].
+    pub fn add_edge(&mut self, edge: (V, V), weight: E) -> io::Result<()> {
+        let (from, to) = &edge;
+        self.append(format!("e {} {} {}", escape_field(from), escape_field(to), escape_field(&weight)))?;
+        self.graph.add_edge(edge, weight);
+        self.compact_if_due()
+    }
+
+    /// Logs and removes an edge. Returns false if it is not in the graph.
+    pub fn remove_edge(&mut self, edge: (V, V)) -> io::Result<bool> {
+        if !self.graph.contains_edge(edge.clone()) {
+            return Ok(false);
+        }
+        let (from, to) = &edge;
+        self.append(format!("re {} {}", escape_field(from), escape_field(to)))?;
+        self.graph.remove_edge(edge);
+        self.compact_if_due()?;
+        Ok(true)
+    }
+}
+
+/// Applies an entry of a [`PersistentGraph`] log.
+fn apply_to_map<V, E>(graph: &mut GraphMap<V, E>, line: &str) -> Option<()>
+where
+    V: Eq + Hash + Clone + std::fmt::Debug + FromStr,
+    E: FromStr,
+{
+    let mut parts = line.split(' ');
+    match (parts.next()?, parts.next(), parts.next(), parts.next(), parts.next()) {
+        ("v", Some(vertex), None, None, None) => graph.add_vertex(unescape(vertex)?.parse().ok()?),
+        ("rv", Some(vertex), None, None, None) => {
+            graph.remove_vertex(unescape(vertex)?.parse().ok()?);
+        }
+        ("e", Some(from), Some(to), Some(weight), None) => {
+            let edge = (unescape(from)?.parse().ok()?, unescape(to)?.parse().ok()?);
+            graph.add_edge(edge, unescape(weight)?.parse().ok()?);
+        }
+        ("re", Some(from), Some(to), None, None) => {
+            graph.remove_edge((unescape(from)?.parse().ok()?, unescape(to)?.parse().ok()?));
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+/// [`escape`] for a field of a line separated by spaces.
+fn escape_field<T: Display>(value: &T) -> String {
+    escape(value).replace(' ', "\\s")
+}
+
+fn parse_id(text: &str) -> Option<VertexId> {
+    let (index, generation) = text.split_once('.')?;
+    Some(VertexId::from((index.parse().ok()?, generation.parse().ok()?)))
+}
+
+pub(crate) fn escape<T: Display>(value: &T) -> String {
+    let text = value.to_string();
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub(crate) fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next()? {
+                '\\' => unescaped.push('\\'),
+                'n' => unescaped.push('\n'),
+                'r' => unescaped.push('\r'),
+                's' => unescaped.push(' '),
+                _ => return None,
+            }
+        } else {
+            unescaped.push(c);
+        }
+    }
+    Some(unescaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_restores_graph_and_ids() {
+        let path = std::env::temp_dir().join(format!("graph-wal-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut wal = WalGraph::<String, u32>::open(&path).unwrap();
+        let a = wal.add_vertex(String::from("a b\nc")).unwrap();
+        let b = wal.add_vertex(String::from("b")).unwrap();
+        let c = wal.add_vertex(String::from("c")).unwrap();
+        wal.add_edge((a, b), 3).unwrap();
+        wal.add_edge((b, c), 4).unwrap();
+        wal.remove_vertex(c).unwrap();
+        let d = wal.add_vertex(String::from("d")).unwrap();
+        wal.add_edge((d, a), 5).unwrap();
+        drop(wal);
+
+        let graph = Graph::<String, u32>::replay(&path).unwrap();
+        assert_eq!(graph.get_vertex(a).map(|s| s.as_str()), Some("a b\nc"));
+        assert_eq!(graph.get_vertex(c), None);
+        assert_eq!(graph.get_edge((d, a)), Some(&5));
+        assert_eq!(graph.edge_count(), 2);
+
+        let mut wal = WalGraph::<String, u32>::open(&path).unwrap();
+        wal.remove_edge((a, b)).unwrap();
+        let graph = wal.into_graph().unwrap();
+        assert_eq!(graph.edge_count(), 1);
+        assert_eq!(Graph::<String, u32>::replay(&path).unwrap().edge_count(), 1);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}